@@ -0,0 +1,44 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+};
+
+use super::schema::SchemaProvider;
+
+/// A named collection of schemas.
+#[derive(Debug, Default)]
+pub struct CatalogProvider {
+    schemas: RwLock<HashMap<String, Arc<SchemaProvider>>>,
+}
+
+impl CatalogProvider {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers `schema` under `name`, returning the schema it replaced.
+    pub fn register_schema(
+        &self,
+        name: impl Into<String>,
+        schema: Arc<SchemaProvider>,
+    ) -> Option<Arc<SchemaProvider>> {
+        self.schemas.write().unwrap().insert(name.into(), schema)
+    }
+
+    pub fn schema(&self, name: &str) -> Option<Arc<SchemaProvider>> {
+        self.schemas.read().unwrap().get(name).cloned()
+    }
+
+    /// Returns the names of all schemas in alphabetical order.
+    pub fn schema_names(&self) -> Vec<String> {
+        let mut names = self
+            .schemas
+            .read()
+            .unwrap()
+            .keys()
+            .cloned()
+            .collect::<Vec<_>>();
+        names.sort();
+        names
+    }
+}
@@ -0,0 +1,39 @@
+mod catalog_provider;
+mod schema;
+mod table_reference;
+
+pub use catalog_provider::CatalogProvider;
+pub use schema::SchemaProvider;
+pub use table_reference::TableReference;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_table_reference() -> anyhow::Result<()> {
+        let table = TableReference::parse("orders")?;
+        assert_eq!(table, TableReference::bare("orders"));
+        assert_eq!(
+            table.resolve("default", "public"),
+            ("default", "public", "orders")
+        );
+
+        let table = TableReference::parse("sales.orders")?;
+        assert_eq!(
+            table.resolve("default", "public"),
+            ("default", "sales", "orders")
+        );
+        assert_eq!(table.to_string(), "sales.orders");
+
+        let table = TableReference::parse("db.sales.orders")?;
+        assert_eq!(
+            table.resolve("default", "public"),
+            ("db", "sales", "orders")
+        );
+
+        assert!(TableReference::parse("a.b.c.d").is_err());
+        assert!(TableReference::parse("a..b").is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,48 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+};
+
+use crate::datasource::DataSource;
+
+/// A named collection of tables.
+#[derive(Debug, Default)]
+pub struct SchemaProvider {
+    tables: RwLock<HashMap<String, Arc<dyn DataSource>>>,
+}
+
+impl SchemaProvider {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers `source` under `name`, returning the table it replaced.
+    pub fn register_table(
+        &self,
+        name: impl Into<String>,
+        source: Arc<dyn DataSource>,
+    ) -> Option<Arc<dyn DataSource>> {
+        self.tables.write().unwrap().insert(name.into(), source)
+    }
+
+    pub fn deregister_table(&self, name: &str) -> Option<Arc<dyn DataSource>> {
+        self.tables.write().unwrap().remove(name)
+    }
+
+    pub fn table(&self, name: &str) -> Option<Arc<dyn DataSource>> {
+        self.tables.read().unwrap().get(name).cloned()
+    }
+
+    /// Returns the names of all tables in alphabetical order.
+    pub fn table_names(&self) -> Vec<String> {
+        let mut names = self
+            .tables
+            .read()
+            .unwrap()
+            .keys()
+            .cloned()
+            .collect::<Vec<_>>();
+        names.sort();
+        names
+    }
+}
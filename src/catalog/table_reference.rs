@@ -0,0 +1,92 @@
+use std::fmt::Display;
+
+use crate::error::{Result, plan_err};
+
+/// A possibly qualified table name such as `orders`, `public.orders` or
+/// `default.public.orders`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum TableReference {
+    Bare {
+        table: String,
+    },
+    Partial {
+        schema: String,
+        table: String,
+    },
+    Full {
+        catalog: String,
+        schema: String,
+        table: String,
+    },
+}
+
+impl TableReference {
+    pub fn bare(table: impl Into<String>) -> Self {
+        TableReference::Bare {
+            table: table.into(),
+        }
+    }
+
+    /// Parses a dot separated table name.
+    pub fn parse(name: &str) -> Result<Self> {
+        let parts = name.split('.').collect::<Vec<_>>();
+        if parts.iter().any(|p| p.is_empty()) {
+            return plan_err!("invalid table name '{}'", name);
+        }
+        Ok(match parts.as_slice() {
+            [table] => TableReference::Bare {
+                table: table.to_string(),
+            },
+            [schema, table] => TableReference::Partial {
+                schema: schema.to_string(),
+                table: table.to_string(),
+            },
+            [catalog, schema, table] => TableReference::Full {
+                catalog: catalog.to_string(),
+                schema: schema.to_string(),
+                table: table.to_string(),
+            },
+            _ => return plan_err!("invalid table name '{}'", name),
+        })
+    }
+
+    pub fn table(&self) -> &str {
+        match self {
+            TableReference::Bare { table }
+            | TableReference::Partial { table, .. }
+            | TableReference::Full { table, .. } => table,
+        }
+    }
+
+    /// Returns `(catalog, schema, table)`, filling in the missing parts from
+    /// the defaults.
+    pub fn resolve<'a>(
+        &'a self,
+        default_catalog: &'a str,
+        default_schema: &'a str,
+    ) -> (&'a str, &'a str, &'a str) {
+        match self {
+            TableReference::Bare { table } => (default_catalog, default_schema, table),
+            TableReference::Partial { schema, table } => (default_catalog, schema, table),
+            TableReference::Full {
+                catalog,
+                schema,
+                table,
+            } => (catalog, schema, table),
+        }
+    }
+}
+
+impl Display for TableReference {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TableReference::Bare { table } => write!(f, "{}", table),
+            TableReference::Partial { schema, table } => write!(f, "{}.{}", schema, table),
+            TableReference::Full {
+                catalog,
+                schema,
+                table,
+            } => write!(f, "{}.{}.{}", catalog, schema, table),
+        }
+    }
+}
@@ -1,10 +1,15 @@
 use std::{fs::File, sync::Arc};
 
-use crate::error::Result;
-use arrow::{array::RecordBatch, datatypes::SchemaRef};
+use crate::{
+    error::{DBError, Result},
+    physical::plan::{RecordBatchStream, limit_stream},
+};
+use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
 use derive_builder::Builder;
 
-use super::DataSource;
+use super::{DataSource, projection_indices};
+
+const DEFAULT_BATCH_SIZE: usize = 1024;
 
 #[derive(Debug, Default, Builder, Clone)]
 #[builder(default)]
@@ -16,6 +21,9 @@ pub struct CsvReadOptions {
     delimiter: u8,
     /// The character used for quoting fields.
     quote: u8,
+    /// Types that replace the inferred types of the named columns, e.g.
+    /// `Decimal128(12, 2)` for amounts of money that `Float64` would round.
+    column_types: Vec<(String, DataType)>,
 }
 
 #[derive(Debug, Clone)]
@@ -32,6 +40,7 @@ impl CsvDataSource {
             Some(schema) => schema.clone(),
             None => Self::infer_schema(&path, &options)?,
         };
+        let schema = Self::override_types(schema, &options.column_types)?;
         Ok(Self {
             path: path.to_string(),
             schema,
@@ -48,6 +57,21 @@ impl CsvDataSource {
         let (schema, _) = format.infer_schema(file, Some(10))?;
         Ok(Arc::new(schema))
     }
+
+    fn override_types(schema: SchemaRef, column_types: &[(String, DataType)]) -> Result<SchemaRef> {
+        if column_types.is_empty() {
+            return Ok(schema);
+        }
+        let mut fields = schema.fields().iter().cloned().collect::<Vec<_>>();
+        for (name, data_type) in column_types {
+            let index = schema
+                .index_of(name)
+                .map_err(|_| DBError::column_not_found(name.clone(), &schema))?;
+            let field = Field::clone(&fields[index]).with_data_type(data_type.clone());
+            fields[index] = Arc::new(field);
+        }
+        Ok(Arc::new(Schema::new(fields)))
+    }
 }
 
 impl DataSource for CsvDataSource {
@@ -55,22 +79,39 @@ impl DataSource for CsvDataSource {
         self.schema.clone()
     }
 
-    fn scan(&self, projection: Option<Vec<String>>) -> Result<RecordBatch> {
+    fn scan(
+        &self,
+        projection: Option<Vec<String>>,
+        limit: Option<usize>,
+    ) -> Result<RecordBatchStream> {
+        let indices = projection_indices(&self.schema, projection.as_ref())?;
         let file: File = File::open(self.path.as_str())?;
-        let mut csv = arrow::csv::ReaderBuilder::new(self.schema.clone())
-            .with_batch_size(1024)
+        // Don't parse more rows than needed to satisfy a small limit.
+        let batch_size = limit.map_or(DEFAULT_BATCH_SIZE, |limit| {
+            limit.clamp(1, DEFAULT_BATCH_SIZE)
+        });
+        let mut builder = arrow::csv::ReaderBuilder::new(self.schema.clone())
+            .with_batch_size(batch_size)
             .with_header(self.options.has_header)
             .with_delimiter(self.options.delimiter)
-            .with_quote(self.options.quote)
-            .build(file)?;
-        let batch = csv.next().unwrap()?;
-        Ok(batch)
+            .with_quote(self.options.quote);
+        if let Some(indices) = indices {
+            builder = builder.with_projection(indices);
+        }
+        let csv = builder.build(file)?;
+        Ok(limit_stream(
+            Box::new(csv.map(|batch| Ok(batch?))),
+            0,
+            limit,
+        ))
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use arrow::util::pretty;
+    use std::io::Write;
+
+    use arrow::{array::AsArray, util::pretty};
 
     use super::*;
     #[test]
@@ -82,9 +123,122 @@ mod tests {
             .quote(b'"')
             .build()?;
 
-        let csv_source = CsvDataSource::try_new(path, opts);
-        let ret = csv_source.unwrap().scan(None)?;
-        pretty::print_batches(&[ret]);
+        let csv_source = CsvDataSource::try_new(path, opts)?;
+        let ret = csv_source.scan(None, None)?.collect::<Result<Vec<_>>>()?;
+        assert_eq!(ret.len(), 1);
+        assert_eq!(ret[0].num_rows(), 6);
+        let results = pretty::pretty_format_batches(&ret)?.to_string();
+        assert_eq!(results.trim().lines().collect::<Vec<_>>(), vec![
+            "+----+----+----+",
+            "| c1 | c2 | c3 |",
+            "+----+----+----+",
+            "| a  | 1  | 2  |",
+            "| b  | 2  | 3  |",
+            "| c  | 3  | 4  |",
+            "| d  | 4  | 5  |",
+            "| e  | 5  | 6  |",
+            "| f  | 6  | 7  |",
+            "+----+----+----+",
+        ]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_scan_projection() -> anyhow::Result<()> {
+        let opts = CsvReadOptionsBuilder::default()
+            .has_header(true)
+            .delimiter(b',')
+            .quote(b'"')
+            .build()?;
+        let csv_source = CsvDataSource::try_new("testdata/csv/simple.csv", opts)?;
+
+        let projection = Some(vec!["c3".to_string(), "c1".to_string()]);
+        let ret = csv_source
+            .scan(projection, None)?
+            .collect::<Result<Vec<_>>>()?;
+        let schema = ret[0].schema();
+        let names = schema.fields().iter().map(|f| f.name()).collect::<Vec<_>>();
+        assert_eq!(names, vec!["c3", "c1"]);
+        assert_eq!(ret[0].num_rows(), 6);
+
+        let Err(err) = csv_source.scan(Some(vec!["c4".to_string()]), None) else {
+            panic!("expected an unknown column error");
+        };
+        assert_eq!(
+            err.to_string(),
+            "column 'c4' not found, available columns: c1, c2, c3"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_column_types() -> anyhow::Result<()> {
+        let decimal = DataType::Decimal128(10, 2);
+        let opts = CsvReadOptionsBuilder::default()
+            .has_header(true)
+            .delimiter(b',')
+            .quote(b'"')
+            .column_types(vec![("amount".to_string(), decimal.clone())])
+            .build()?;
+        let csv_source = CsvDataSource::try_new("testdata/csv/payments.csv", opts.clone())?;
+        let schema = csv_source.schema();
+        assert_eq!(schema.field(2).data_type(), &decimal);
+        assert_eq!(schema.field(3).data_type(), &DataType::Float64);
+
+        let ret = csv_source
+            .scan(Some(vec!["amount".to_string()]), None)?
+            .collect::<Result<Vec<_>>>()?;
+        let amounts = ret[0]
+            .column(0)
+            .as_primitive::<arrow::datatypes::Decimal128Type>();
+        assert_eq!(amounts.values(), &[1999, 10, 20, 123456]);
+
+        let opts = CsvReadOptionsBuilder::default()
+            .has_header(true)
+            .delimiter(b',')
+            .column_types(vec![("total".to_string(), decimal)])
+            .build()?;
+        let err = CsvDataSource::try_new("testdata/csv/payments.csv", opts).unwrap_err();
+        assert!(matches!(err, DBError::ColumnNotFound { .. }));
+        Ok(())
+    }
+
+    #[test]
+    fn test_scan_multiple_batches() -> anyhow::Result<()> {
+        // Concurrent test runs must not share the file.
+        let path = std::env::temp_dir().join(format!(
+            "study_query_engine_scan_multiple_batches_{}.csv",
+            std::process::id()
+        ));
+        let mut file = File::create(&path)?;
+        writeln!(file, "c1,c2")?;
+        for i in 0..2500 {
+            writeln!(file, "{},{}", i, i * 2)?;
+        }
+        drop(file);
+
+        let opts = CsvReadOptionsBuilder::default()
+            .has_header(true)
+            .delimiter(b',')
+            .quote(b'"')
+            .build()?;
+        let csv_source = CsvDataSource::try_new(path.to_str().unwrap(), opts)?;
+        let batches = csv_source.scan(None, None)?.collect::<Result<Vec<_>>>()?;
+
+        assert_eq!(batches.len(), 3);
+        assert_eq!(batches.iter().map(|b| b.num_rows()).sum::<usize>(), 2500);
+
+        let batches = csv_source
+            .scan(None, Some(10))?
+            .collect::<Result<Vec<_>>>()?;
+        assert_eq!(batches.len(), 1);
+        assert_eq!(batches[0].num_rows(), 10);
+
+        let batches = csv_source
+            .scan(None, Some(2000))?
+            .collect::<Result<Vec<_>>>()?;
+        assert_eq!(batches.iter().map(|b| b.num_rows()).sum::<usize>(), 2000);
+        std::fs::remove_file(&path)?;
         Ok(())
     }
 }
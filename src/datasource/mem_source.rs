@@ -1,38 +1,83 @@
-use std::sync::Arc;
+use arrow::{array::RecordBatch, datatypes::SchemaRef};
 
-use arrow::array::{RecordBatch, record_batch};
-
-use super::DataSource;
+use super::{DataSource, projection_indices};
+use crate::{
+    error::{Result, schema_err},
+    physical::plan::{RecordBatchStream, limit_stream},
+};
 
+/// A table backed by record batches held in memory.
 #[derive(Debug, Clone)]
-pub struct MemDataSource {}
+pub struct MemDataSource {
+    schema: SchemaRef,
+    batches: Vec<RecordBatch>,
+}
 
 impl MemDataSource {
-    pub fn new() -> Self {
-        Self {}
-    }
-
-    pub fn new_arc() -> Arc<Self> {
-        Arc::new(Self::new())
+    pub fn try_new(schema: SchemaRef, batches: Vec<RecordBatch>) -> Result<Self> {
+        for batch in batches.iter() {
+            if batch.schema().fields() != schema.fields() {
+                return schema_err!(
+                    "batch schema {:?} does not match table schema {:?}",
+                    batch.schema(),
+                    schema
+                );
+            }
+        }
+        Ok(Self { schema, batches })
     }
 }
 
 impl DataSource for MemDataSource {
-    fn schema(&self) -> arrow::datatypes::SchemaRef {
-        Arc::new(arrow::datatypes::Schema::new(vec![
-            arrow::datatypes::Field::new("c1", arrow::datatypes::DataType::Utf8, false),
-            arrow::datatypes::Field::new("c2", arrow::datatypes::DataType::Int32, false),
-            arrow::datatypes::Field::new("c3", arrow::datatypes::DataType::Int32, false),
-        ]))
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
     }
 
-    fn scan(&self, projection: Option<Vec<String>>) -> crate::error::Result<RecordBatch> {
+    fn scan(
+        &self,
+        projection: Option<Vec<String>>,
+        limit: Option<usize>,
+    ) -> Result<RecordBatchStream> {
+        let indices = projection_indices(&self.schema, projection.as_ref())?;
+        let batches = self.batches.clone();
+        let stream = Box::new(batches.into_iter().map(move |batch| match &indices {
+            Some(indices) => Ok(batch.project(indices)?),
+            None => Ok(batch),
+        }));
+        Ok(limit_stream(stream, 0, limit))
+    }
+}
+
+#[cfg(test)]
+impl MemDataSource {
+    /// A small `c1: Utf8, c2: Int32, c3: Int32` table shared by unit tests.
+    pub fn new_arc() -> std::sync::Arc<Self> {
+        use arrow::array::record_batch;
+
         let batch = record_batch!(
             ("c1", Utf8, ["alpha", "beta", "gamma"]),
             ("c2", Int32, [1, 2, 3]),
             ("c3", Int32, [Some(10), Some(20), Some(30)])
-        )?;
-        // batch.project(indices)
-        Ok(batch)
+        )
+        .unwrap();
+        std::sync::Arc::new(Self::try_new(batch.schema(), vec![batch]).unwrap())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scan_projection() -> Result<()> {
+        let source = MemDataSource::new_arc();
+        let ret = source
+            .scan(Some(vec!["c2".to_string()]), None)?
+            .collect::<Result<Vec<_>>>()?;
+        assert_eq!(ret[0].num_columns(), 1);
+        assert_eq!(ret[0].schema().field(0).name(), "c2");
+
+        assert!(source.scan(Some(vec!["c4".to_string()]), None).is_err());
+        Ok(())
     }
 }
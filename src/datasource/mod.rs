@@ -3,13 +3,59 @@ mod mem_source;
 
 use std::fmt::Debug;
 
-use crate::error::Result;
-use arrow::{array::RecordBatch, datatypes::SchemaRef};
+use crate::{
+    error::{DBError, Result},
+    physical::plan::RecordBatchStream,
+};
+use std::sync::Arc;
+
+use arrow::datatypes::{Schema, SchemaRef};
 pub use csv_source::*;
 pub use mem_source::*;
 
-pub trait DataSource: Debug {
+pub trait DataSource: Debug + Send + Sync {
     fn schema(&self) -> SchemaRef;
-    /// Creates an [`ExecutionPlan`] to scan the [`DataSource`].
-    fn scan(&self, projection: Option<Vec<String>>) -> Result<RecordBatch>;
+    /// Returns a [`RecordBatchStream`] that lazily reads the [`DataSource`].
+    /// When `projection` is given, only those columns are read and the
+    /// batches contain them in projection order. When `limit` is given, the
+    /// source stops reading once that many rows have been returned.
+    fn scan(
+        &self,
+        projection: Option<Vec<String>>,
+        limit: Option<usize>,
+    ) -> Result<RecordBatchStream>;
+}
+
+/// Returns the fields of `schema` named by `projection`, in projection order.
+/// Unknown names are skipped.
+pub fn project_schema(schema: &SchemaRef, projection: Option<&Vec<String>>) -> SchemaRef {
+    match projection {
+        None => schema.clone(),
+        Some(projection) => {
+            let fields = projection
+                .iter()
+                .filter_map(|name| schema.field_with_name(name).ok().cloned())
+                .collect::<Vec<_>>();
+            Arc::new(Schema::new_with_metadata(fields, schema.metadata().clone()))
+        }
+    }
+}
+
+/// Resolves projected column names to their indices in `schema`.
+pub fn projection_indices(
+    schema: &SchemaRef,
+    projection: Option<&Vec<String>>,
+) -> Result<Option<Vec<usize>>> {
+    let Some(projection) = projection else {
+        return Ok(None);
+    };
+    let indices = projection
+        .iter()
+        .map(|name| {
+            schema
+                .index_of(name)
+                .map_err(|_| DBError::column_not_found(name.clone(), schema))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(Some(indices))
 }
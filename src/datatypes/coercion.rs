@@ -0,0 +1,526 @@
+use arrow_schema::{
+    DECIMAL128_MAX_PRECISION, DECIMAL128_MAX_SCALE, DataType, IntervalUnit, TimeUnit,
+};
+
+use super::operator::{Operator, UnaryOperator};
+use crate::error::{Result, internal_err, plan_err};
+
+/// Returns the type both operands of `lhs op rhs` must be cast to before the
+/// operator is evaluated.
+pub fn binary_operand_type(lhs: &DataType, op: &Operator, rhs: &DataType) -> Result<DataType> {
+    use DataType::*;
+    use Operator::*;
+
+    let common = match op {
+        And | Or => match (lhs, rhs) {
+            (Boolean | Null, Boolean | Null) => Some(Boolean),
+            _ => None,
+        },
+        Eq | NotEq | Lt | LtEq | Gt | GtEq | IsDistinctFrom | IsNotDistinctFrom => {
+            comparison_type(lhs, rhs)
+        }
+        Plus | Minus | Multiply | Divide | Modulo => match (lhs, rhs) {
+            (Null, Null) => Some(Int64),
+            (Null, other) | (other, Null) if other.is_numeric() => Some(other.clone()),
+            _ => numeric_type(lhs, rhs),
+        },
+        // The other operand may be any value that has a text form.
+        StringConcat => match (lhs, rhs) {
+            _ if !has_text(lhs) || !has_text(rhs) => None,
+            (LargeUtf8, _) | (_, LargeUtf8) => Some(LargeUtf8),
+            (Utf8 | Null, _) | (_, Utf8 | Null) => Some(Utf8),
+            _ => None,
+        },
+    };
+    match common {
+        Some(common) => Ok(common),
+        None => plan_err!("cannot apply '{}' to {} and {}", op, lhs, rhs),
+    }
+}
+
+/// Returns the types `lhs` and `rhs` must be cast to before `lhs op rhs` is
+/// evaluated. Unlike [`binary_operand_type`] the two may differ, e.g. for
+/// `timestamp + interval`.
+pub fn binary_operand_types(
+    lhs: &DataType,
+    op: &Operator,
+    rhs: &DataType,
+) -> Result<(DataType, DataType)> {
+    if let Some(types) = temporal_arithmetic_types(lhs, op, rhs) {
+        return Ok(types);
+    }
+    if let Some(types) = decimal_arithmetic_types(lhs, op, rhs) {
+        return Ok(types);
+    }
+    let operand_type = binary_operand_type(lhs, op, rhs)?;
+    Ok((operand_type.clone(), operand_type))
+}
+
+/// Dates and timestamps can be shifted by an interval, and intervals can be
+/// added to each other. Strings on the interval side are parsed as
+/// intervals, e.g. `ts + '1 day'`.
+fn temporal_arithmetic_types(
+    lhs: &DataType,
+    op: &Operator,
+    rhs: &DataType,
+) -> Option<(DataType, DataType)> {
+    use DataType::*;
+
+    let interval = Interval(IntervalUnit::MonthDayNano);
+    let is_datetime = |t: &DataType| matches!(t, Date32 | Timestamp(..));
+    let is_interval = |t: &DataType| matches!(t, Interval(_) | Utf8 | Null);
+    match op {
+        Operator::Plus | Operator::Minus if is_datetime(lhs) && is_interval(rhs) => {
+            Some((lhs.clone(), interval))
+        }
+        Operator::Plus if is_interval(lhs) && is_datetime(rhs) => Some((interval, rhs.clone())),
+        Operator::Plus | Operator::Minus
+            if matches!((lhs, rhs), (Interval(_), _) | (_, Interval(_)))
+                && is_interval(lhs)
+                && is_interval(rhs) =>
+        {
+            Some((interval.clone(), interval))
+        }
+        _ => None,
+    }
+}
+
+/// Decimals keep their own precision and scale in arithmetic, which the
+/// result type is derived from. Integers are read as decimals with a scale
+/// of 0.
+fn decimal_arithmetic_types(
+    lhs: &DataType,
+    op: &Operator,
+    rhs: &DataType,
+) -> Option<(DataType, DataType)> {
+    use DataType::*;
+
+    if !op.is_arithmetic() || !matches!((lhs, rhs), (Decimal128(..), _) | (_, Decimal128(..))) {
+        return None;
+    }
+    // NULL takes the type of the other side.
+    let lhs_type = if lhs == &Null { rhs } else { lhs };
+    let rhs_type = if rhs == &Null { lhs } else { rhs };
+    Some((decimal_type(lhs_type)?, decimal_type(rhs_type)?))
+}
+
+/// Returns the decimal type that holds every value of `data_type` exactly,
+/// or `None` unless it is a decimal or an integer.
+pub fn decimal_type(data_type: &DataType) -> Option<DataType> {
+    use DataType::*;
+
+    let precision = match data_type {
+        Decimal128(..) => return Some(data_type.clone()),
+        Int8 | UInt8 => 3,
+        Int16 | UInt16 => 5,
+        Int32 | UInt32 => 10,
+        Int64 => 19,
+        UInt64 => 20,
+        _ => return None,
+    };
+    Some(Decimal128(precision, 0))
+}
+
+/// Returns the type of `Decimal128(p1, s1) op Decimal128(p2, s2)`. These
+/// are the Hive rules the Arrow kernels follow, with precision capped at
+/// 38 digits:
+///
+/// | op     | scale              | precision                         |
+/// |--------|--------------------|-----------------------------------|
+/// | `+ -`  | `max(s1, s2)`      | `max(p1 - s1, p2 - s2) + s + 1`   |
+/// | `*`    | `s1 + s2`          | `p1 + p2 + 1`                     |
+/// | `/`    | `s1 + 4`           | `p1 - s1 + s2 + s`                |
+/// | `%`    | `max(s1, s2)`      | `min(p1 - s1, p2 - s2) + s`       |
+fn decimal_result_type((p1, s1): (u8, i8), op: &Operator, (p2, s2): (u8, i8)) -> Result<DataType> {
+    let (p1, s1, p2, s2) = (p1 as i16, s1 as i16, p2 as i16, s2 as i16);
+    let (precision, scale) = match op {
+        Operator::Plus | Operator::Minus => {
+            let scale = s1.max(s2);
+            ((p1 - s1).max(p2 - s2) + scale + 1, scale)
+        }
+        Operator::Multiply => (p1 + p2 + 1, s1 + s2),
+        Operator::Divide => {
+            let scale = (s1 + 4).min(DECIMAL128_MAX_SCALE as i16);
+            (p1 - s1 + s2 + scale, scale)
+        }
+        Operator::Modulo => {
+            let scale = s1.max(s2);
+            ((p1 - s1).min(p2 - s2) + scale, scale)
+        }
+        other => return internal_err!("'{}' is not an arithmetic operator", other),
+    };
+    if scale > DECIMAL128_MAX_SCALE as i16 {
+        return plan_err!(
+            "the scale of Decimal128({}, {}) {} Decimal128({}, {}) exceeds {}",
+            p1,
+            s1,
+            op,
+            p2,
+            s2,
+            DECIMAL128_MAX_SCALE
+        );
+    }
+    let precision = precision.min(DECIMAL128_MAX_PRECISION as i16);
+    Ok(DataType::Decimal128(precision as u8, scale as i8))
+}
+
+/// Whether values of type `data_type` can be cast to a string.
+pub fn has_text(data_type: &DataType) -> bool {
+    data_type.is_primitive()
+        || matches!(
+            data_type,
+            DataType::Utf8 | DataType::LargeUtf8 | DataType::Boolean | DataType::Null
+        )
+}
+
+/// Returns the type produced by `lhs op rhs`.
+pub fn binary_result_type(lhs: &DataType, op: &Operator, rhs: &DataType) -> Result<DataType> {
+    let (lhs_type, rhs_type) = binary_operand_types(lhs, op, rhs)?;
+    Ok(if op.is_arithmetic() || op == &Operator::StringConcat {
+        match (lhs_type, rhs_type) {
+            (DataType::Decimal128(p1, s1), DataType::Decimal128(p2, s2)) => {
+                decimal_result_type((p1, s1), op, (p2, s2))?
+            }
+            // Shifting a date or timestamp by an interval keeps its type.
+            (DataType::Interval(_), rhs_type) => rhs_type,
+            (lhs_type, _) => lhs_type,
+        }
+    } else {
+        DataType::Boolean
+    })
+}
+
+/// Returns the string type both `lhs` and `rhs` must be cast to, e.g. for
+/// `lhs LIKE rhs`.
+pub fn string_type(lhs: &DataType, rhs: &DataType) -> Option<DataType> {
+    use DataType::*;
+
+    match (lhs, rhs) {
+        (Utf8 | Null, Utf8 | Null) => Some(Utf8),
+        (LargeUtf8, Utf8 | LargeUtf8 | Null) | (Utf8 | Null, LargeUtf8) => Some(LargeUtf8),
+        _ => None,
+    }
+}
+
+/// Returns the type produced by applying `op` to a value of type `operand`.
+pub fn unary_result_type(op: UnaryOperator, operand: &DataType) -> Result<DataType> {
+    use DataType::*;
+    use UnaryOperator::*;
+
+    let result = match op {
+        Not => matches!(operand, Boolean | Null).then_some(Boolean),
+        Negative => (operand.is_signed_integer()
+            || operand.is_floating()
+            || matches!(operand, Decimal128(..) | Null))
+        .then(|| operand.clone()),
+        IsNull | IsNotNull => Some(Boolean),
+        IsTrue | IsNotTrue | IsFalse | IsNotFalse | IsUnknown | IsNotUnknown => {
+            matches!(operand, Boolean | Null).then_some(Boolean)
+        }
+    };
+    match result {
+        Some(result) => Ok(result),
+        None => plan_err!("cannot apply '{}' to {}", op, operand),
+    }
+}
+
+/// Returns the type all of `types` must be cast to before they are compared
+/// with each other, e.g. the value and the items of an `IN` list.
+pub fn comparison_operand_type(types: &[DataType]) -> Result<DataType> {
+    let Some((first, rest)) = types.split_first() else {
+        return Ok(DataType::Null);
+    };
+    rest.iter().try_fold(first.clone(), |common, t| {
+        binary_operand_type(&common, &Operator::Eq, t)
+    })
+}
+
+fn comparison_type(lhs: &DataType, rhs: &DataType) -> Option<DataType> {
+    use DataType::*;
+
+    match (lhs, rhs) {
+        _ if lhs == rhs => Some(lhs.clone()),
+        (Null, Null) => Some(Boolean),
+        (Null, other) | (other, Null) => Some(other.clone()),
+        // Strings compared with numbers or dates are parsed as such. Type
+        // coercion only accepts string literals on the number side.
+        (Utf8, other) | (other, Utf8) if other.is_numeric() || other.is_temporal() => {
+            Some(other.clone())
+        }
+        _ => temporal_type(lhs, rhs).or_else(|| numeric_type(lhs, rhs)),
+    }
+}
+
+/// Returns the type that can represent both `lhs` and `rhs` when both are
+/// dates, times or timestamps: a date widens to a timestamp without a time
+/// zone and timestamps use the finer of the two units.
+pub fn temporal_type(lhs: &DataType, rhs: &DataType) -> Option<DataType> {
+    use DataType::*;
+
+    match (lhs, rhs) {
+        _ if lhs == rhs => Some(lhs.clone()),
+        (Date32, Timestamp(_, None)) => Some(rhs.clone()),
+        (Timestamp(_, None), Date32) => Some(lhs.clone()),
+        (Timestamp(l_unit, l_tz), Timestamp(r_unit, r_tz)) => {
+            let unit = if time_unit_rank(l_unit) >= time_unit_rank(r_unit) {
+                *l_unit
+            } else {
+                *r_unit
+            };
+            Some(Timestamp(unit, l_tz.clone().or_else(|| r_tz.clone())))
+        }
+        (Time32(_) | Time64(_), Time32(_) | Time64(_)) => Some(Time64(TimeUnit::Nanosecond)),
+        _ => None,
+    }
+}
+
+fn time_unit_rank(unit: &TimeUnit) -> u8 {
+    match unit {
+        TimeUnit::Second => 0,
+        TimeUnit::Millisecond => 1,
+        TimeUnit::Microsecond => 2,
+        TimeUnit::Nanosecond => 3,
+    }
+}
+
+/// Returns the type every one of `types` can be cast to so that the values
+/// can be combined into one column, e.g. the branches of a `CASE`. NULL
+/// adapts to any type.
+pub fn common_type(types: &[DataType]) -> Option<DataType> {
+    types
+        .iter()
+        .try_fold(DataType::Null, |common, t| match (&common, t) {
+            _ if &common == t => Some(common),
+            (DataType::Null, other) | (other, DataType::Null) => Some(other.clone()),
+            _ => temporal_type(&common, t).or_else(|| numeric_type(&common, t)),
+        })
+}
+
+/// Returns the narrowest numeric type that can represent values of both
+/// `lhs` and `rhs`.
+pub fn numeric_type(lhs: &DataType, rhs: &DataType) -> Option<DataType> {
+    use DataType::*;
+
+    if !lhs.is_numeric() || !rhs.is_numeric() {
+        return None;
+    }
+    if lhs == rhs {
+        return Some(lhs.clone());
+    }
+    if matches!(lhs, Decimal128(..)) || matches!(rhs, Decimal128(..)) {
+        if lhs.is_floating() || rhs.is_floating() {
+            return Some(Float64);
+        }
+        let (Decimal128(p1, s1), Decimal128(p2, s2)) = (decimal_type(lhs)?, decimal_type(rhs)?)
+        else {
+            return None;
+        };
+        // Enough digits left of the point for both, and the finer scale.
+        let scale = s1.max(s2);
+        let integral = (p1 as i16 - s1 as i16).max(p2 as i16 - s2 as i16);
+        let precision = (integral + scale as i16).min(DECIMAL128_MAX_PRECISION as i16);
+        return Some(Decimal128(precision as u8, scale));
+    }
+    if lhs.is_floating() || rhs.is_floating() {
+        return Some(match (lhs, rhs) {
+            (Float32, Float32) => Float32,
+            _ => Float64,
+        });
+    }
+
+    let width = |t: &DataType| t.primitive_width().unwrap_or(8);
+    let signed = |width: usize| match width {
+        1 => Int8,
+        2 => Int16,
+        4 => Int32,
+        _ => Int64,
+    };
+    let unsigned = |width: usize| match width {
+        1 => UInt8,
+        2 => UInt16,
+        4 => UInt32,
+        _ => UInt64,
+    };
+    let is_signed = |t: &DataType| t.is_signed_integer();
+    Some(match (is_signed(lhs), is_signed(rhs)) {
+        (true, true) => signed(width(lhs).max(width(rhs))),
+        (false, false) => unsigned(width(lhs).max(width(rhs))),
+        // A signed type twice as wide as the unsigned one holds both.
+        (true, false) => signed(width(lhs).max(width(rhs) * 2)),
+        (false, true) => signed(width(rhs).max(width(lhs) * 2)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::DBError;
+
+    #[test]
+    fn test_binary_types() -> Result<()> {
+        use DataType::*;
+
+        assert_eq!(binary_result_type(&Int32, &Operator::Eq, &Int64)?, Boolean);
+        assert_eq!(binary_operand_type(&Int32, &Operator::Eq, &Int64)?, Int64);
+        assert_eq!(binary_result_type(&Int32, &Operator::Plus, &Int64)?, Int64);
+        assert_eq!(binary_result_type(&UInt8, &Operator::Plus, &Int8)?, Int16);
+        assert_eq!(
+            binary_result_type(&UInt64, &Operator::Plus, &UInt8)?,
+            UInt64
+        );
+        assert_eq!(
+            binary_result_type(&Int64, &Operator::Divide, &Float32)?,
+            Float64
+        );
+        assert_eq!(binary_operand_type(&Utf8, &Operator::Lt, &Int64)?, Int64);
+        assert_eq!(
+            binary_operand_type(&Null, &Operator::And, &Boolean)?,
+            Boolean
+        );
+
+        let err = binary_result_type(&Utf8, &Operator::Plus, &Int64).unwrap_err();
+        assert!(matches!(err, DBError::Plan(_)));
+        assert_eq!(
+            err.to_string(),
+            "plan error: cannot apply '+' to Utf8 and Int64"
+        );
+        assert!(binary_result_type(&Int64, &Operator::And, &Boolean).is_err());
+        assert!(binary_result_type(&Boolean, &Operator::Lt, &Int64).is_err());
+        assert_eq!(
+            binary_result_type(&Int32, &Operator::StringConcat, &LargeUtf8)?,
+            LargeUtf8
+        );
+        assert!(binary_result_type(&Int32, &Operator::StringConcat, &Int32).is_err());
+
+        assert_eq!(comparison_operand_type(&[Int32, Null, Int64, Int8])?, Int64);
+        assert!(comparison_operand_type(&[Int32, Boolean]).is_err());
+        assert_eq!(
+            unary_result_type(UnaryOperator::Negative, &Float32)?,
+            Float32
+        );
+        assert!(unary_result_type(UnaryOperator::Negative, &UInt8).is_err());
+        assert!(unary_result_type(UnaryOperator::Not, &Int32).is_err());
+        assert_eq!(string_type(&LargeUtf8, &Null), Some(LargeUtf8));
+        assert_eq!(string_type(&Utf8, &Int32), None);
+
+        assert_eq!(common_type(&[Null, Int32, Int64]), Some(Int64));
+        assert_eq!(common_type(&[Utf8, Null, Utf8]), Some(Utf8));
+        assert_eq!(common_type(&[Null]), Some(Null));
+        assert_eq!(common_type(&[Utf8, Int32]), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_temporal_types() -> Result<()> {
+        use DataType::*;
+
+        let interval = Interval(IntervalUnit::MonthDayNano);
+        let ts = |unit, tz: Option<&str>| Timestamp(unit, tz.map(Into::into));
+        let micros = ts(TimeUnit::Microsecond, None);
+
+        assert_eq!(
+            binary_operand_types(&micros, &Operator::Plus, &Utf8)?,
+            (micros.clone(), interval.clone())
+        );
+        assert_eq!(
+            binary_result_type(&interval, &Operator::Plus, &Date32)?,
+            Date32
+        );
+        assert_eq!(
+            binary_result_type(&interval, &Operator::Minus, &interval)?,
+            interval
+        );
+        assert!(binary_result_type(&interval, &Operator::Minus, &Date32).is_err());
+        assert!(binary_result_type(&micros, &Operator::Multiply, &interval).is_err());
+
+        assert_eq!(binary_operand_type(&Utf8, &Operator::Lt, &Date32)?, Date32);
+        assert_eq!(
+            binary_operand_type(&Date32, &Operator::Eq, &micros)?,
+            micros
+        );
+        assert_eq!(
+            temporal_type(
+                &ts(TimeUnit::Second, Some("+00:00")),
+                &ts(TimeUnit::Millisecond, None)
+            ),
+            Some(ts(TimeUnit::Millisecond, Some("+00:00")))
+        );
+        assert_eq!(common_type(&[Null, Date32, micros.clone()]), Some(micros));
+        assert!(binary_operand_type(&Date32, &Operator::Eq, &Int32).is_err());
+        assert!(
+            binary_operand_type(
+                &Date32,
+                &Operator::Eq,
+                &ts(TimeUnit::Second, Some("+00:00"))
+            )
+            .is_err()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_decimal_types() -> Result<()> {
+        use DataType::*;
+
+        let d = Decimal128;
+        assert_eq!(
+            binary_operand_types(&d(5, 2), &Operator::Plus, &Int32)?,
+            (d(5, 2), d(10, 0))
+        );
+        assert_eq!(
+            binary_result_type(&d(5, 2), &Operator::Plus, &Int32)?,
+            d(13, 2)
+        );
+        assert_eq!(
+            binary_result_type(&d(5, 2), &Operator::Minus, &d(7, 4))?,
+            d(8, 4)
+        );
+        assert_eq!(
+            binary_result_type(&d(5, 2), &Operator::Multiply, &d(3, 1))?,
+            d(9, 3)
+        );
+        assert_eq!(
+            binary_result_type(&d(5, 2), &Operator::Divide, &d(3, 1))?,
+            d(10, 6)
+        );
+        assert_eq!(
+            binary_result_type(&d(5, 2), &Operator::Modulo, &d(3, 1))?,
+            d(4, 2)
+        );
+        assert_eq!(
+            binary_result_type(&d(38, 10), &Operator::Plus, &d(38, 0))?,
+            d(38, 10)
+        );
+        assert_eq!(
+            binary_result_type(&Null, &Operator::Plus, &d(5, 2))?,
+            d(6, 2)
+        );
+        assert!(matches!(
+            binary_result_type(&d(38, 20), &Operator::Multiply, &d(38, 20)),
+            Err(DBError::Plan(_))
+        ));
+
+        assert_eq!(
+            binary_result_type(&d(5, 2), &Operator::Plus, &Float32)?,
+            Float64
+        );
+        assert_eq!(
+            binary_operand_type(&d(5, 2), &Operator::Lt, &d(7, 4))?,
+            d(7, 4)
+        );
+        assert_eq!(
+            binary_operand_type(&Int64, &Operator::Eq, &d(5, 2))?,
+            d(21, 2)
+        );
+        assert_eq!(
+            binary_operand_type(&Utf8, &Operator::Eq, &d(5, 2))?,
+            d(5, 2)
+        );
+        assert_eq!(common_type(&[d(5, 2), Null, d(4, 3)]), Some(d(6, 3)));
+        assert_eq!(
+            unary_result_type(UnaryOperator::Negative, &d(5, 2))?,
+            d(5, 2)
+        );
+        Ok(())
+    }
+}
@@ -1,2 +1,3 @@
+pub mod coercion;
 pub mod operator;
 pub mod values;
@@ -12,8 +12,60 @@ pub enum Operator {
     Minus,
     Multiply,
     Divide,
+    Modulo,
     And,
     Or,
+    /// Like `!=`, but NULL is distinct from every value except NULL, so the
+    /// result is never NULL.
+    IsDistinctFrom,
+    /// Like `=`, but NULL equals NULL, so the result is never NULL.
+    IsNotDistinctFrom,
+    /// `||`, joins two strings.
+    StringConcat,
+}
+
+impl Operator {
+    /// How tightly the operator binds, using the scale of the SQL parser so
+    /// printed expressions parse back to the same tree.
+    pub fn precedence(&self) -> u8 {
+        use Operator::*;
+
+        match self {
+            Or => 5,
+            And => 10,
+            StringConcat => 16,
+            IsDistinctFrom | IsNotDistinctFrom => 17,
+            Eq | NotEq | Lt | LtEq | Gt | GtEq => 20,
+            Plus | Minus => 30,
+            Multiply | Divide | Modulo => 40,
+        }
+    }
+
+    /// Whether the operator compares its operands, producing a boolean.
+    pub fn is_comparison(&self) -> bool {
+        matches!(
+            self,
+            Operator::Eq
+                | Operator::NotEq
+                | Operator::Lt
+                | Operator::LtEq
+                | Operator::Gt
+                | Operator::GtEq
+                | Operator::IsDistinctFrom
+                | Operator::IsNotDistinctFrom
+        )
+    }
+
+    pub fn is_arithmetic(&self) -> bool {
+        matches!(
+            self,
+            Operator::Plus
+                | Operator::Minus
+                | Operator::Multiply
+                | Operator::Divide
+                | Operator::Modulo
+        )
+    }
 }
 
 impl Display for Operator {
@@ -31,8 +83,64 @@ impl Display for Operator {
             Minus => write!(f, "-"),
             Multiply => write!(f, "*"),
             Divide => write!(f, "/"),
+            Modulo => write!(f, "%"),
             And => write!(f, "AND"),
             Or => write!(f, "OR"),
+            IsDistinctFrom => write!(f, "IS DISTINCT FROM"),
+            IsNotDistinctFrom => write!(f, "IS NOT DISTINCT FROM"),
+            StringConcat => write!(f, "||"),
+        }
+    }
+}
+
+/// An operator with a single operand.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UnaryOperator {
+    Not,
+    Negative,
+    IsNull,
+    IsNotNull,
+    IsTrue,
+    IsNotTrue,
+    IsFalse,
+    IsNotFalse,
+    IsUnknown,
+    IsNotUnknown,
+}
+
+impl UnaryOperator {
+    /// How tightly the operator binds, on the scale of
+    /// [`Operator::precedence`].
+    pub fn precedence(&self) -> u8 {
+        match self {
+            UnaryOperator::Not => 15,
+            UnaryOperator::Negative => 50,
+            _ => 17,
+        }
+    }
+
+    /// Whether the operator is written before its operand, as in `NOT x`,
+    /// rather than after it, as in `x IS NULL`.
+    pub fn is_prefix(&self) -> bool {
+        matches!(self, UnaryOperator::Not | UnaryOperator::Negative)
+    }
+}
+
+impl Display for UnaryOperator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use UnaryOperator::*;
+
+        match self {
+            Not => write!(f, "NOT"),
+            Negative => write!(f, "-"),
+            IsNull => write!(f, "IS NULL"),
+            IsNotNull => write!(f, "IS NOT NULL"),
+            IsTrue => write!(f, "IS TRUE"),
+            IsNotTrue => write!(f, "IS NOT TRUE"),
+            IsFalse => write!(f, "IS FALSE"),
+            IsNotFalse => write!(f, "IS NOT FALSE"),
+            IsUnknown => write!(f, "IS UNKNOWN"),
+            IsNotUnknown => write!(f, "IS NOT UNKNOWN"),
         }
     }
 }
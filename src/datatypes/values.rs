@@ -1,13 +1,24 @@
-use crate::error::Result;
-use arrow::array::{
-    Array, ArrayData, ArrayRef, BooleanArray, Float32Array, Float64Array, Int8Array, Int16Array,
-    Int32Array, Int64Array, Scalar, StringArray, UInt8Array, UInt16Array, UInt32Array, UInt64Array,
-    make_array,
+use crate::error::{Result, internal_err, not_impl_err};
+use arrow::{
+    array::{
+        Array, ArrayData, ArrayRef, AsArray, BooleanArray, Date32Array, Decimal128Array,
+        Float32Array, Float64Array, Int8Array, Int16Array, Int32Array, Int64Array,
+        IntervalMonthDayNanoArray, Scalar, StringArray, Time64MicrosecondArray,
+        Time64NanosecondArray, TimestampMicrosecondArray, TimestampMillisecondArray,
+        TimestampNanosecondArray, TimestampSecondArray, UInt8Array, UInt16Array, UInt32Array,
+        UInt64Array, make_array,
+    },
+    datatypes::{
+        Decimal128Type, IntervalMonthDayNano, IntervalUnit, Time64MicrosecondType,
+        Time64NanosecondType, TimeUnit, TimestampMicrosecondType, TimestampMillisecondType,
+        TimestampNanosecondType, TimestampSecondType,
+    },
+    util::display::array_value_to_string,
 };
 use arrow_schema::DataType;
 use std::{fmt::Display, iter, sync::Arc};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum ScalarValue {
     Null,
     Boolean(Option<bool>),
@@ -22,6 +33,17 @@ pub enum ScalarValue {
     Float32(Option<f32>),
     Float64(Option<f64>),
     String(Option<String>),
+    /// Days since the UNIX epoch.
+    Date32(Option<i32>),
+    /// Time since midnight in microseconds or nanoseconds.
+    Time64(Option<i64>, TimeUnit),
+    /// Time since the UNIX epoch in the given unit. With a time zone the
+    /// epoch is in UTC, without one the value is a local date and time.
+    Timestamp(Option<i64>, TimeUnit, Option<Arc<str>>),
+    Interval(Option<IntervalMonthDayNano>),
+    /// A fixed point number `value / 10^scale` with at most `precision`
+    /// digits.
+    Decimal128(Option<i128>, u8, i8),
 }
 
 macro_rules! format_option {
@@ -49,10 +71,49 @@ impl Display for ScalarValue {
             ScalarValue::Float32(v) => format_option!(f, v),
             ScalarValue::Float64(v) => format_option!(f, v),
             ScalarValue::String(v) => format_option!(f, v),
+            // Dates, times and decimals are formatted the way Arrow prints
+            // them.
+            ScalarValue::Date32(_)
+            | ScalarValue::Time64(..)
+            | ScalarValue::Timestamp(..)
+            | ScalarValue::Interval(_)
+            | ScalarValue::Decimal128(..) => {
+                if self.is_null() {
+                    return write!(f, "NULL");
+                }
+                match array_value_to_string(&self.to_array(1), 0) {
+                    Ok(s) => write!(f, "{}", s),
+                    Err(_) => Err(std::fmt::Error),
+                }
+            }
         }
     }
 }
 
+/// Reads the value at `$index` of a primitive array of type `$type`, NULL
+/// included.
+macro_rules! primitive_value {
+    ($array:expr, $index:expr, $type:ty) => {{
+        let array = $array.as_primitive::<$type>();
+        array.is_valid($index).then(|| array.value($index))
+    }};
+}
+
+/// Reads the value at `$index` of an array of type `$array_type`, NULL
+/// included.
+macro_rules! typed_value {
+    ($array:expr, $index:expr, $array_type:ident, $scalar:ident) => {{
+        let Some(array) = $array.as_any().downcast_ref::<$array_type>() else {
+            return internal_err!(
+                "expected {}, got {}",
+                stringify!($array_type),
+                $array.data_type()
+            );
+        };
+        ScalarValue::$scalar(array.is_valid($index).then(|| array.value($index).into()))
+    }};
+}
+
 /// Macro to build an array from an optional scalar value.
 macro_rules! build_array_from_option {
     ($data_type:ident, $array_type:ident, $expr:expr, $size:expr) => {
@@ -79,11 +140,45 @@ impl ScalarValue {
             ScalarValue::Float32(v) => build_array_from_option!(Float32, Float32Array, v, num_rows),
             ScalarValue::Float64(v) => build_array_from_option!(Float64, Float64Array, v, num_rows),
             ScalarValue::String(v) => match v {
-                Some(v) => Arc::new(StringArray::from_iter_values(
-                    iter::repeat(v).take(num_rows),
-                )),
+                Some(v) => Arc::new(StringArray::from_iter_values(iter::repeat_n(v, num_rows))),
                 None => make_array(ArrayData::new_null(&DataType::Utf8, num_rows)),
             },
+            ScalarValue::Date32(v) => build_array_from_option!(Date32, Date32Array, v, num_rows),
+            ScalarValue::Time64(Some(v), TimeUnit::Microsecond) => {
+                Arc::new(Time64MicrosecondArray::from_value(*v, num_rows))
+            }
+            ScalarValue::Time64(Some(v), _) => {
+                Arc::new(Time64NanosecondArray::from_value(*v, num_rows))
+            }
+            ScalarValue::Timestamp(Some(v), unit, tz) => {
+                let tz = tz.clone();
+                match unit {
+                    TimeUnit::Second => Arc::new(
+                        TimestampSecondArray::from_value(*v, num_rows).with_timezone_opt(tz),
+                    ),
+                    TimeUnit::Millisecond => Arc::new(
+                        TimestampMillisecondArray::from_value(*v, num_rows).with_timezone_opt(tz),
+                    ),
+                    TimeUnit::Microsecond => Arc::new(
+                        TimestampMicrosecondArray::from_value(*v, num_rows).with_timezone_opt(tz),
+                    ),
+                    TimeUnit::Nanosecond => Arc::new(
+                        TimestampNanosecondArray::from_value(*v, num_rows).with_timezone_opt(tz),
+                    ),
+                }
+            }
+            ScalarValue::Interval(Some(v)) => {
+                Arc::new(IntervalMonthDayNanoArray::from_value(*v, num_rows))
+            }
+            ScalarValue::Decimal128(Some(v), _, _) => {
+                Arc::new(Decimal128Array::from_value(*v, num_rows).with_data_type(self.data_type()))
+            }
+            ScalarValue::Time64(None, _)
+            | ScalarValue::Timestamp(None, ..)
+            | ScalarValue::Interval(None)
+            | ScalarValue::Decimal128(None, ..) => {
+                make_array(ArrayData::new_null(&self.data_type(), num_rows))
+            }
         }
     }
 
@@ -102,6 +197,63 @@ impl ScalarValue {
             ScalarValue::String(_) => DataType::Utf8,
             ScalarValue::Float32(_) => DataType::Float32,
             ScalarValue::Float64(_) => DataType::Float64,
+            ScalarValue::Date32(_) => DataType::Date32,
+            ScalarValue::Time64(_, unit) => DataType::Time64(*unit),
+            ScalarValue::Timestamp(_, unit, tz) => DataType::Timestamp(*unit, tz.clone()),
+            ScalarValue::Interval(_) => DataType::Interval(IntervalUnit::MonthDayNano),
+            ScalarValue::Decimal128(_, precision, scale) => {
+                DataType::Decimal128(*precision, *scale)
+            }
+        }
+    }
+
+    /// Creates a NULL value of the given [`DataType`].
+    pub fn try_new_null(data_type: &DataType) -> Result<Self> {
+        Ok(match data_type {
+            DataType::Null => ScalarValue::Null,
+            DataType::Boolean => ScalarValue::Boolean(None),
+            DataType::Int8 => ScalarValue::Int8(None),
+            DataType::Int16 => ScalarValue::Int16(None),
+            DataType::Int32 => ScalarValue::Int32(None),
+            DataType::Int64 => ScalarValue::Int64(None),
+            DataType::UInt8 => ScalarValue::Uint8(None),
+            DataType::UInt16 => ScalarValue::Uint16(None),
+            DataType::UInt32 => ScalarValue::Uint32(None),
+            DataType::UInt64 => ScalarValue::Uint64(None),
+            DataType::Float32 => ScalarValue::Float32(None),
+            DataType::Float64 => ScalarValue::Float64(None),
+            DataType::Utf8 => ScalarValue::String(None),
+            DataType::Date32 => ScalarValue::Date32(None),
+            DataType::Time64(unit) => ScalarValue::Time64(None, *unit),
+            DataType::Timestamp(unit, tz) => ScalarValue::Timestamp(None, *unit, tz.clone()),
+            DataType::Interval(IntervalUnit::MonthDayNano) => ScalarValue::Interval(None),
+            DataType::Decimal128(precision, scale) => {
+                ScalarValue::Decimal128(None, *precision, *scale)
+            }
+            other => return not_impl_err!("unsupported scalar type {}", other),
+        })
+    }
+
+    pub fn is_null(&self) -> bool {
+        match self {
+            ScalarValue::Null => true,
+            ScalarValue::Boolean(v) => v.is_none(),
+            ScalarValue::Int8(v) => v.is_none(),
+            ScalarValue::Int16(v) => v.is_none(),
+            ScalarValue::Int32(v) => v.is_none(),
+            ScalarValue::Int64(v) => v.is_none(),
+            ScalarValue::Uint8(v) => v.is_none(),
+            ScalarValue::Uint16(v) => v.is_none(),
+            ScalarValue::Uint32(v) => v.is_none(),
+            ScalarValue::Uint64(v) => v.is_none(),
+            ScalarValue::Float32(v) => v.is_none(),
+            ScalarValue::Float64(v) => v.is_none(),
+            ScalarValue::String(v) => v.is_none(),
+            ScalarValue::Date32(v) => v.is_none(),
+            ScalarValue::Time64(v, _) => v.is_none(),
+            ScalarValue::Timestamp(v, ..) => v.is_none(),
+            ScalarValue::Interval(v) => v.is_none(),
+            ScalarValue::Decimal128(v, ..) => v.is_none(),
         }
     }
 
@@ -109,22 +261,110 @@ impl ScalarValue {
         Ok(Scalar::new(self.to_array(1)))
     }
 
-    // pub fn try_from_array(array: &dyn Array, index: usize) -> Result<Self> {
-    //     Ok(match array.data_type() {
-    //         DataType::Null => ScalarValue::Null,
-    //         DataType::Boolean => typed_cast!(array, index, BooleanArray, Boolean)?,
-    //         DataType::Int8 => typed_cast!(array, index, Int8Array, Int8)?,
-    //         DataType::Int16 => typed_cast!(array, index, Int16Array, Int16)?,
-    //         DataType::Int32 => typed_cast!(array, index, Int32Array, Int32)?,
-    //         DataType::Int64 => typed_cast!(array, index, Int64Array, Int64)?,
-    //         DataType::UInt8 => typed_cast!(array, index, UInt8Array, UInt8)?,
-    //         DataType::UInt16 => typed_cast!(array, index, UInt16Array, UInt16)?,
-    //         DataType::UInt32 => typed_cast!(array, index, UInt32Array, UInt32)?,
-    //         DataType::UInt64 => typed_cast!(array, index, UInt64Array, UInt64)?,
-    //         DataType::Utf8 => typed_cast!(array, index, StringArray, Utf8)?,
-    //         DataType::Float32 => typed_cast!(array, index, Float32Array, Float32)?,
-    //         DataType::Float64 => typed_cast!(array, index, Float64Array, Float64)?,
-    //         other => unimplemented!(),
-    //     })
-    // }
+    /// Returns the value at `index` of `array`.
+    pub fn try_from_array(array: &dyn Array, index: usize) -> Result<Self> {
+        if index >= array.len() {
+            return internal_err!(
+                "index {} out of bounds for an array of length {}",
+                index,
+                array.len()
+            );
+        }
+        Ok(match array.data_type() {
+            DataType::Null => ScalarValue::Null,
+            DataType::Boolean => typed_value!(array, index, BooleanArray, Boolean),
+            DataType::Int8 => typed_value!(array, index, Int8Array, Int8),
+            DataType::Int16 => typed_value!(array, index, Int16Array, Int16),
+            DataType::Int32 => typed_value!(array, index, Int32Array, Int32),
+            DataType::Int64 => typed_value!(array, index, Int64Array, Int64),
+            DataType::UInt8 => typed_value!(array, index, UInt8Array, Uint8),
+            DataType::UInt16 => typed_value!(array, index, UInt16Array, Uint16),
+            DataType::UInt32 => typed_value!(array, index, UInt32Array, Uint32),
+            DataType::UInt64 => typed_value!(array, index, UInt64Array, Uint64),
+            DataType::Float32 => typed_value!(array, index, Float32Array, Float32),
+            DataType::Float64 => typed_value!(array, index, Float64Array, Float64),
+            DataType::Utf8 => typed_value!(array, index, StringArray, String),
+            DataType::Date32 => typed_value!(array, index, Date32Array, Date32),
+            DataType::Time64(TimeUnit::Microsecond) => ScalarValue::Time64(
+                primitive_value!(array, index, Time64MicrosecondType),
+                TimeUnit::Microsecond,
+            ),
+            DataType::Time64(TimeUnit::Nanosecond) => ScalarValue::Time64(
+                primitive_value!(array, index, Time64NanosecondType),
+                TimeUnit::Nanosecond,
+            ),
+            DataType::Timestamp(unit, tz) => {
+                let value = match unit {
+                    TimeUnit::Second => primitive_value!(array, index, TimestampSecondType),
+                    TimeUnit::Millisecond => {
+                        primitive_value!(array, index, TimestampMillisecondType)
+                    }
+                    TimeUnit::Microsecond => {
+                        primitive_value!(array, index, TimestampMicrosecondType)
+                    }
+                    TimeUnit::Nanosecond => primitive_value!(array, index, TimestampNanosecondType),
+                };
+                ScalarValue::Timestamp(value, *unit, tz.clone())
+            }
+            DataType::Interval(IntervalUnit::MonthDayNano) => {
+                typed_value!(array, index, IntervalMonthDayNanoArray, Interval)
+            }
+            DataType::Decimal128(precision, scale) => ScalarValue::Decimal128(
+                primitive_value!(array, index, Decimal128Type),
+                *precision,
+                *scale,
+            ),
+            other => return not_impl_err!("unsupported scalar type {}", other),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_array_round_trip() -> Result<()> {
+        let values = vec![
+            ScalarValue::Null,
+            ScalarValue::Boolean(Some(true)),
+            ScalarValue::Int8(Some(-8)),
+            ScalarValue::Int16(None),
+            ScalarValue::Int32(Some(32)),
+            ScalarValue::Int64(Some(i64::MIN)),
+            ScalarValue::Uint8(Some(8)),
+            ScalarValue::Uint16(Some(16)),
+            ScalarValue::Uint32(None),
+            ScalarValue::Uint64(Some(u64::MAX)),
+            ScalarValue::Float32(Some(0.5)),
+            ScalarValue::Float64(None),
+            ScalarValue::String(Some("abc".to_string())),
+            ScalarValue::String(None),
+            ScalarValue::Date32(Some(19_723)),
+            ScalarValue::Time64(Some(3_600_000_000), TimeUnit::Microsecond),
+            ScalarValue::Timestamp(Some(1_700_000_000), TimeUnit::Second, None),
+            ScalarValue::Timestamp(None, TimeUnit::Nanosecond, Some("+01:00".into())),
+            ScalarValue::Interval(Some(IntervalMonthDayNano::new(1, 2, 3))),
+            ScalarValue::Interval(None),
+            ScalarValue::Decimal128(Some(-12_345), 7, 2),
+            ScalarValue::Decimal128(None, 38, 10),
+        ];
+        for value in values {
+            let array = value.to_array(3);
+            assert_eq!(ScalarValue::try_from_array(&array, 2)?, value);
+        }
+        assert!(ScalarValue::try_from_array(&Int32Array::from(vec![1]), 1).is_err());
+
+        assert_eq!(ScalarValue::Date32(Some(19_723)).to_string(), "2024-01-01");
+        assert_eq!(
+            ScalarValue::Timestamp(Some(1_704_070_800_500), TimeUnit::Millisecond, None)
+                .to_string(),
+            "2024-01-01T01:00:00.500"
+        );
+        assert_eq!(
+            ScalarValue::Decimal128(Some(-12_345), 7, 2).to_string(),
+            "-123.45"
+        );
+        Ok(())
+    }
 }
@@ -1,10 +1,9 @@
 use std::io;
 
+use arrow_schema::Schema;
 use thiserror::Error;
 
-// pub type Result<T> = std::result::Result<T, DBError>;
-
-pub type Result<T> = anyhow::Result<T>;
+pub type Result<T> = std::result::Result<T, DBError>;
 
 #[derive(Error, Debug)]
 pub enum DBError {
@@ -13,4 +12,93 @@ pub enum DBError {
 
     #[error("io error: {0}")]
     IoError(#[from] io::Error),
+
+    /// A column that does not exist was referenced. `candidates` lists the
+    /// columns that are available at that point of the plan.
+    #[error("column '{name}' not found, available columns: {}", candidates.join(", "))]
+    ColumnNotFound {
+        name: String,
+        candidates: Vec<String>,
+    },
+
+    /// An unqualified column matches columns of several relations.
+    #[error("column reference '{name}' is ambiguous, candidates: {}", candidates.join(", "))]
+    AmbiguousColumn {
+        name: String,
+        candidates: Vec<String>,
+    },
+
+    /// The query is invalid, e.g. it refers to an unknown table or combines
+    /// incompatible types.
+    #[error("plan error: {0}")]
+    Plan(String),
+
+    /// The query is valid but uses a feature that is not supported yet.
+    #[error("not implemented: {0}")]
+    NotImplemented(String),
+
+    /// An error raised while the query is running.
+    #[error("execution error: {0}")]
+    Execution(String),
+
+    #[error("schema error: {0}")]
+    Schema(String),
+
+    #[error("parse error: {0}")]
+    Parse(String),
+
+    /// A bug in the engine rather than in the query.
+    #[error("internal error: {0}")]
+    Internal(String),
+}
+
+impl DBError {
+    pub fn column_not_found(name: impl Into<String>, schema: &Schema) -> Self {
+        DBError::ColumnNotFound {
+            name: name.into(),
+            candidates: schema.fields().iter().map(|f| f.name().clone()).collect(),
+        }
+    }
+}
+
+impl From<sqlparser::parser::ParserError> for DBError {
+    fn from(err: sqlparser::parser::ParserError) -> Self {
+        DBError::Parse(err.to_string())
+    }
+}
+
+macro_rules! plan_err {
+    ($($arg:tt)*) => {
+        Err($crate::error::DBError::Plan(format!($($arg)*)))
+    };
+}
+
+macro_rules! not_impl_err {
+    ($($arg:tt)*) => {
+        Err($crate::error::DBError::NotImplemented(format!($($arg)*)))
+    };
+}
+
+macro_rules! exec_err {
+    ($($arg:tt)*) => {
+        Err($crate::error::DBError::Execution(format!($($arg)*)))
+    };
+}
+
+macro_rules! schema_err {
+    ($($arg:tt)*) => {
+        Err($crate::error::DBError::Schema(format!($($arg)*)))
+    };
 }
+
+macro_rules! internal_err {
+    ($($arg:tt)*) => {
+        Err($crate::error::DBError::Internal(format!($($arg)*)))
+    };
+}
+
+pub(crate) use exec_err;
+pub(crate) use internal_err;
+pub(crate) use not_impl_err;
+pub(crate) use plan_err;
+pub(crate) use schema_err;
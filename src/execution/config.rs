@@ -0,0 +1,35 @@
+use derive_builder::Builder;
+
+/// How `+`, `-` and `*` behave when an integer result does not fit its type.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum OverflowMode {
+    /// Fail the query with an execution error, as the SQL standard requires.
+    #[default]
+    Checked,
+    /// Wrap around in two's complement, e.g. `i32::MAX + 1 = i32::MIN`.
+    Wrapping,
+}
+
+/// Settings shared by every query of a
+/// [`SessionContext`](super::SessionContext).
+///
+/// Integer division and modulo are always checked: dividing by zero fails the
+/// query with an execution error instead of producing NULL, so a division is
+/// only as nullable as its operands. Floating point division follows IEEE 754
+/// and yields infinity or NaN.
+#[derive(Debug, Default, Builder, Clone)]
+#[builder(default)]
+pub struct SessionConfig {
+    /// How integer arithmetic handles overflow.
+    overflow_mode: OverflowMode,
+}
+
+impl SessionConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn overflow_mode(&self) -> OverflowMode {
+        self.overflow_mode
+    }
+}
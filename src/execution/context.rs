@@ -1,52 +1,208 @@
-use std::sync::Arc;
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+};
 
 use arrow::array::RecordBatch;
 
 use crate::{
-    datasource::{CsvDataSource, CsvReadOptions},
+    catalog::{CatalogProvider, SchemaProvider, TableReference},
+    datasource::{CsvDataSource, CsvReadOptions, DataSource, MemDataSource},
     logical::plan::{LogicalPlan, Scan},
+    sql::{ContextProvider, SqlPlanner},
 };
 
-use super::DataFrame;
+use super::{DataFrame, SessionConfig};
+
+/// Catalog and schema used to resolve table names that are not fully qualified.
+pub const DEFAULT_CATALOG: &str = "default";
+pub const DEFAULT_SCHEMA: &str = "public";
+
+#[derive(Debug)]
+pub struct SessionContext {
+    catalogs: RwLock<HashMap<String, Arc<CatalogProvider>>>,
+    config: SessionConfig,
+}
+use crate::error::{DBError, Result, plan_err};
 
-#[derive(Debug, Default)]
-pub struct SessionContext;
-use crate::error::Result;
+impl Default for SessionContext {
+    fn default() -> Self {
+        let catalog = CatalogProvider::new();
+        catalog.register_schema(DEFAULT_SCHEMA, Arc::new(SchemaProvider::new()));
+        let catalogs = HashMap::from([(DEFAULT_CATALOG.to_string(), Arc::new(catalog))]);
+        Self {
+            catalogs: RwLock::new(catalogs),
+            config: SessionConfig::default(),
+        }
+    }
+}
 
 impl SessionContext {
     pub fn new() -> Self {
         Self::default()
     }
 
+    pub fn new_with_config(config: SessionConfig) -> Self {
+        Self {
+            config,
+            ..Self::default()
+        }
+    }
+
+    pub fn config(&self) -> &SessionConfig {
+        &self.config
+    }
+
+    /// Registers `catalog` under `name`, returning the catalog it replaced.
+    pub fn register_catalog(
+        &self,
+        name: impl Into<String>,
+        catalog: Arc<CatalogProvider>,
+    ) -> Option<Arc<CatalogProvider>> {
+        self.catalogs.write().unwrap().insert(name.into(), catalog)
+    }
+
+    pub fn catalog(&self, name: &str) -> Option<Arc<CatalogProvider>> {
+        self.catalogs.read().unwrap().get(name).cloned()
+    }
+
+    /// Registers `source` under `name` so SQL queries and [`Self::table`] can
+    /// refer to it, returning the table previously registered under that name.
+    /// `name` may be qualified as `schema.table` or `catalog.schema.table`.
+    pub fn register_table(
+        &self,
+        name: &str,
+        source: Arc<dyn DataSource>,
+    ) -> Result<Option<Arc<dyn DataSource>>> {
+        let table = TableReference::parse(name)?;
+        let schema = self.schema_for(&table)?;
+        Ok(schema.register_table(table.table(), source))
+    }
+
+    /// Registers the CSV file at `path` as a table.
+    pub fn register_csv(
+        &self,
+        name: &str,
+        path: impl Into<String>,
+        options: CsvReadOptions,
+    ) -> Result<()> {
+        let source = CsvDataSource::try_new(path, options)?;
+        self.register_table(name, Arc::new(source))?;
+        Ok(())
+    }
+
+    /// Registers in-memory batches as a table. All batches must share the
+    /// schema of the first one.
+    pub fn register_batches(&self, name: &str, batches: Vec<RecordBatch>) -> Result<()> {
+        let Some(first) = batches.first() else {
+            return plan_err!("cannot register table '{}' without any batches", name);
+        };
+        let source = MemDataSource::try_new(first.schema(), batches)?;
+        self.register_table(name, Arc::new(source))?;
+        Ok(())
+    }
+
+    /// Removes a table, returning it if it was registered.
+    pub fn deregister_table(&self, name: &str) -> Result<Option<Arc<dyn DataSource>>> {
+        let table = TableReference::parse(name)?;
+        let schema = self.schema_for(&table)?;
+        Ok(schema.deregister_table(table.table()))
+    }
+
+    /// Returns the names of the tables in the default schema.
+    pub fn table_names(&self) -> Vec<String> {
+        self.catalog(DEFAULT_CATALOG)
+            .and_then(|catalog| catalog.schema(DEFAULT_SCHEMA))
+            .map(|schema| schema.table_names())
+            .unwrap_or_default()
+    }
+
+    /// Creates a [`DataFrame`] that scans a registered table.
+    pub fn table(&self, name: &str) -> Result<DataFrame> {
+        let table = TableReference::parse(name)?;
+        let source = self
+            .get_table(&table)
+            .ok_or_else(|| DBError::Plan(format!("table '{}' not found", table)))?;
+        let scan = Scan::new(table.to_string(), source, None).with_qualifier(table.table());
+        let plan = LogicalPlan::Scan(scan);
+        Ok(DataFrame::new(plan, self.config.clone()))
+    }
+
+    /// Plans a SQL query against the registered tables.
+    pub fn sql(&self, sql: &str) -> Result<DataFrame> {
+        let plan = SqlPlanner::new(self).sql_to_plan(sql)?;
+        Ok(DataFrame::new(plan, self.config.clone()))
+    }
+
     pub fn csv(&self, path: impl Into<String>, options: CsvReadOptions) -> Result<DataFrame> {
         let path = path.into();
         let source = CsvDataSource::try_new(&path, options)?;
         let plan = LogicalPlan::Scan(Scan::new(&path, Arc::new(source), None));
-        Ok(DataFrame::new(plan))
+        Ok(DataFrame::new(plan, self.config.clone()))
+    }
+
+    fn schema_for(&self, table: &TableReference) -> Result<Arc<SchemaProvider>> {
+        let (catalog_name, schema_name, _) = table.resolve(DEFAULT_CATALOG, DEFAULT_SCHEMA);
+        let catalog = self
+            .catalog(catalog_name)
+            .ok_or_else(|| DBError::Plan(format!("catalog '{}' not found", catalog_name)))?;
+        catalog.schema(schema_name).ok_or_else(|| {
+            DBError::Plan(format!(
+                "schema '{}.{}' not found",
+                catalog_name, schema_name
+            ))
+        })
+    }
+}
+
+impl ContextProvider for SessionContext {
+    fn get_table(&self, table: &TableReference) -> Option<Arc<dyn DataSource>> {
+        let (catalog, schema, name) = table.resolve(DEFAULT_CATALOG, DEFAULT_SCHEMA);
+        self.catalog(catalog)?.schema(schema)?.table(name)
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use arrow::util::pretty;
+    use std::sync::Arc;
+
+    use arrow::{
+        array::{AsArray, record_batch},
+        datatypes::{Float64Type, Int32Type, Int64Type},
+        util::pretty,
+    };
+    use arrow_schema::{DataType, TimeUnit};
+    use chrono::NaiveDate;
 
     use crate::{
+        catalog::{CatalogProvider, SchemaProvider},
         datasource::CsvReadOptionsBuilder,
-        execution::context::SessionContext,
-        logical::expression::expr_fn::{col, lit},
+        error::DBError,
+        execution::{OverflowMode, SessionConfigBuilder, context::SessionContext},
+        logical::{
+            expression::expr_fn::{
+                avg, coalesce, col, concat, count, count_distinct, date_part, date_trunc, floor,
+                interval, lit, lower, max, min, nullif, parse_decimal_lit, random, rank,
+                regexp_extract, replace, reverse, starts_with, sum, when,
+            },
+            plan::JoinType,
+        },
     };
     #[test]
     fn test_session_context() -> anyhow::Result<()> {
         let ctx = SessionContext::new();
-        let opts = CsvReadOptionsBuilder::default().build()?;
+        let opts = CsvReadOptionsBuilder::default()
+            .has_header(true)
+            .delimiter(b',')
+            .build()?;
         let df = ctx
             .csv("testdata/csv/simple.csv", opts)?
             .filter(col("c1").eq(lit(1)))
-            .project(vec![col("c1"), col("c2")]);
+            .project(vec![col("c1"), col("c2")])?;
 
         assert_eq!(
             df.plan().to_string(),
-            vec![
+            [
                 "Projection: c1, c2\n",
                 "\tFilter: c1 = 1\n",
                 "\t\tScan: testdata/csv/simple.csv; projection=None\n"
@@ -68,10 +224,10 @@ mod tests {
         let df = ctx
             .csv("testdata/csv/simple.csv", opts)?
             // .filter(col("c1").eq(lit(1)))
-            .project(vec![col("c3"), col("c2")]);
+            .project(vec![col("c3"), col("c2")])?;
 
         let ret = df.collect()?;
-        let _ = pretty::print_batches(&[ret]);
+        let _ = pretty::print_batches(&ret);
         Ok(())
     }
 
@@ -87,11 +243,11 @@ mod tests {
             .csv("testdata/csv/simple.csv", opts)?
             // .filter(col("c1").eq(lit(1)))
             // .project(vec![col("c3"), lit(1)]);
-            .project(vec![col("c1"), col("c3"), col("c3").add(lit(1 as i64))]);
+            .project(vec![col("c1"), col("c3"), col("c3").plus(lit(1_i64))])?;
 
         let ret = df.collect()?;
         // let _ = pretty::print_batches(&[ret]);
-        let results = pretty::pretty_format_batches(&[ret]).unwrap().to_string();
+        let results = pretty::pretty_format_batches(&ret).unwrap().to_string();
         let results = results.trim().lines().collect::<Vec<_>>();
         let expected = vec![
             "+----+----+--------+",
@@ -108,4 +264,1435 @@ mod tests {
         assert_eq!(results, expected);
         Ok(())
     }
+
+    #[test]
+    fn test_session_execute_filter() -> anyhow::Result<()> {
+        let ctx = SessionContext::new();
+        let opts = CsvReadOptionsBuilder::default()
+            .has_header(true)
+            .delimiter(b',')
+            .quote(b'"')
+            .build()?;
+        let df = ctx
+            .csv("testdata/csv/simple.csv", opts)?
+            .filter(
+                col("c2")
+                    .gt(lit(1_i64))
+                    .and(col("c3").lt_eq(lit(5_i64)).or(col("c1").eq(lit("f")))),
+            )
+            .project(vec![col("c1"), col("c2")])?;
+
+        let ret = df.collect()?;
+        let results = pretty::pretty_format_batches(&ret).unwrap().to_string();
+        let results = results.trim().lines().collect::<Vec<_>>();
+        let expected = vec![
+            "+----+----+",
+            "| c1 | c2 |",
+            "+----+----+",
+            "| b  | 2  |",
+            "| c  | 3  |",
+            "| d  | 4  |",
+            "| f  | 6  |",
+            "+----+----+",
+        ];
+        assert_eq!(results, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn test_session_aggregate() -> anyhow::Result<()> {
+        let ctx = SessionContext::new();
+        let opts = CsvReadOptionsBuilder::default()
+            .has_header(true)
+            .delimiter(b',')
+            .quote(b'"')
+            .build()?;
+        let df = ctx
+            .csv("testdata/csv/simple_aggregate.csv", opts)?
+            .aggregate(vec![col("c1")], vec![
+                count(col("c2")),
+                sum(col("c2")),
+                min(col("c3")),
+                max(col("c3")),
+                avg(col("c2")),
+            ])?;
+        assert_eq!(
+            df.plan().to_string(),
+            "Aggregate: groupBy=[c1], aggr=[COUNT(c2), SUM(c2), MIN(c3), MAX(c3), AVG(c2)]\n"
+                .to_string()
+                + "\tScan: testdata/csv/simple_aggregate.csv; projection=None\n"
+        );
+
+        let ret = df.collect()?;
+        let results = pretty::pretty_format_batches(&ret).unwrap().to_string();
+        let results = results.trim().lines().collect::<Vec<_>>();
+        let expected = vec![
+            "+----+-----------+---------+---------+---------+---------+",
+            "| c1 | COUNT(c2) | SUM(c2) | MIN(c3) | MAX(c3) | AVG(c2) |",
+            "+----+-----------+---------+---------+---------+---------+",
+            "| a  | 2         | 3       | 2       | 3       | 1.5     |",
+            "| c  | 2         | 8       | 4       | 6       | 4.0     |",
+            "| d  | 1         | 4       | 5       | 5       | 4.0     |",
+            "| f  | 1         | 6       | 7       | 7       | 6.0     |",
+            "| b  | 1         | 7       | 8       | 8       | 7.0     |",
+            "+----+-----------+---------+---------+---------+---------+",
+        ];
+        assert_eq!(results, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn test_session_aggregate_without_group_by() -> anyhow::Result<()> {
+        let ctx = SessionContext::new();
+        let opts = CsvReadOptionsBuilder::default()
+            .has_header(true)
+            .delimiter(b',')
+            .quote(b'"')
+            .build()?;
+        let df = ctx
+            .csv("testdata/csv/distinct.csv", opts.clone())?
+            .aggregate(vec![], vec![
+                count(col("c1")),
+                count_distinct(col("c1")),
+                max(col("c1")),
+            ])?;
+
+        let ret = df.collect()?;
+        let results = pretty::pretty_format_batches(&ret).unwrap().to_string();
+        let results = results.trim().lines().collect::<Vec<_>>();
+        let expected = vec![
+            "+-----------+--------------------+---------+",
+            "| COUNT(c1) | COUNT(DISTINCT c1) | MAX(c1) |",
+            "+-----------+--------------------+---------+",
+            "| 7         | 5                  | f       |",
+            "+-----------+--------------------+---------+",
+        ];
+        assert_eq!(results, expected);
+
+        // An aggregate without GROUP BY still produces one row for empty input.
+        let df = ctx
+            .csv("testdata/csv/distinct.csv", opts)?
+            .filter(col("c2").gt(lit(100_i64)))
+            .aggregate(vec![], vec![count(col("c1")), sum(col("c2"))])?;
+        let ret = df.collect()?;
+        let results = pretty::pretty_format_batches(&ret).unwrap().to_string();
+        let results = results.trim().lines().collect::<Vec<_>>();
+        let expected = vec![
+            "+-----------+---------+",
+            "| COUNT(c1) | SUM(c2) |",
+            "+-----------+---------+",
+            "| 0         |         |",
+            "+-----------+---------+",
+        ];
+        assert_eq!(results, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn test_session_sql() -> anyhow::Result<()> {
+        let ctx = SessionContext::new();
+        let opts = CsvReadOptionsBuilder::default()
+            .has_header(true)
+            .delimiter(b',')
+            .quote(b'"')
+            .build()?;
+        ctx.register_csv("t", "testdata/csv/simple_aggregate.csv", opts)?;
+
+        let ret = ctx
+            .sql("SELECT c1, c3 + 1 FROM t WHERE c2 > 5")?
+            .collect()?;
+        let results = pretty::pretty_format_batches(&ret).unwrap().to_string();
+        let results = results.trim().lines().collect::<Vec<_>>();
+        let expected = vec![
+            "+----+--------+",
+            "| c1 | c3 + 1 |",
+            "+----+--------+",
+            "| f  | 8      |",
+            "| b  | 9      |",
+            "+----+--------+",
+        ];
+        assert_eq!(results, expected);
+
+        let ret = ctx
+            .sql("SELECT c1, COUNT(*), SUM(c2) FROM t GROUP BY c1 HAVING SUM(c2) > 3")?
+            .collect()?;
+        let results = pretty::pretty_format_batches(&ret).unwrap().to_string();
+        let results = results.trim().lines().collect::<Vec<_>>();
+        let expected = vec![
+            "+----+----------+---------+",
+            "| c1 | COUNT(1) | SUM(c2) |",
+            "+----+----------+---------+",
+            "| c  | 2        | 8       |",
+            "| d  | 1        | 4       |",
+            "| f  | 1        | 6       |",
+            "| b  | 1        | 7       |",
+            "+----+----------+---------+",
+        ];
+        assert_eq!(results, expected);
+
+        assert!(ctx.sql("SELECT c1 FROM t JOIN u ON c1 = c1").is_err());
+
+        // A string column is not compared with a number, it is rejected
+        // before any row is read.
+        let err = ctx
+            .sql("SELECT c1 FROM t WHERE c1 = 1")?
+            .collect()
+            .unwrap_err();
+        assert!(matches!(err, DBError::Plan(_)), "{}", err);
+
+        let ret = ctx.sql("SELECT COUNT(*) FROM t HAVING 1 = 0")?.collect()?;
+        assert_eq!(ret.iter().map(|b| b.num_rows()).sum::<usize>(), 0);
+
+        // A number refers to a column of the select list by position.
+        let ret = ctx
+            .sql("SELECT c1, c2 FROM t ORDER BY 2 DESC LIMIT 1")?
+            .collect()?;
+        let results = pretty::pretty_format_batches(&ret).unwrap().to_string();
+        assert_eq!(results.trim().lines().collect::<Vec<_>>(), vec![
+            "+----+----+",
+            "| c1 | c2 |",
+            "+----+----+",
+            "| b  | 7  |",
+            "+----+----+",
+        ]);
+        let err = ctx.sql("SELECT c1, c2 FROM t ORDER BY 3").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "plan error: ORDER BY position 3 is not in the select list"
+        );
+
+        // NaNs computed from negative square roots sort last.
+        let ret = ctx
+            .sql("SELECT SQRT(c2 - 3) AS s FROM t ORDER BY s")?
+            .collect()?;
+        let s = ret[0].column(0).as_primitive::<Float64Type>();
+        assert_eq!(&s.values()[..5], &[
+            0.0,
+            1.0,
+            2.0_f64.sqrt(),
+            3.0_f64.sqrt(),
+            2.0
+        ]);
+        assert!(s.value(5).is_nan() && s.value(6).is_nan());
+
+        // Aggregates that only differ in nesting are kept apart.
+        ctx.register_batches("m", vec![record_batch!(
+            ("c2", Int32, [1, 2, 3]),
+            ("c3", Int32, [10, 20, 30])
+        )?])?;
+        for sql in [
+            "SELECT SUM((c2 + c3) * 2) AS a, SUM(c2 + (c3 * 2)) AS b FROM m",
+            "SELECT SUM((c2 + c3) * 2), SUM(c2 + (c3 * 2)) FROM m",
+        ] {
+            let ret = ctx.sql(sql)?.collect()?;
+            assert_eq!(ret[0].column(0).as_primitive::<Int64Type>().value(0), 132);
+            assert_eq!(ret[0].column(1).as_primitive::<Int64Type>().value(0), 126);
+        }
+        let schema = ctx
+            .sql("SELECT SUM((c2 + c3) * 2), SUM(c2 + (c3 * 2)) FROM m")?
+            .collect()?[0]
+            .schema();
+        assert_eq!(schema.field(0).name(), "SUM((c2 + c3) * 2)");
+        assert_eq!(schema.field(1).name(), "SUM(c2 + c3 * 2)");
+
+        // Aggregates whose arguments differ only in the type of a literal,
+        // or a string literal and a column, are not merged either.
+        for (sql, expected) in [
+            ("SELECT SUM(c2 / 2) AS a, SUM(c2 / 2.0) AS b FROM t", vec![
+                "+----+------+",
+                "| a  | b    |",
+                "+----+------+",
+                "| 12 | 14.0 |",
+                "+----+------+",
+            ]),
+            ("SELECT SUM(c2 / 2.0) AS b, SUM(c2 / 2) AS a FROM t", vec![
+                "+------+----+",
+                "| b    | a  |",
+                "+------+----+",
+                "| 14.0 | 12 |",
+                "+------+----+",
+            ]),
+            (
+                "SELECT COUNT(CASE WHEN c1 = 'c1' THEN 1 END) AS a, \
+                 COUNT(CASE WHEN c1 = c1 THEN 1 END) AS b FROM t",
+                vec![
+                    "+---+---+",
+                    "| a | b |",
+                    "+---+---+",
+                    "| 0 | 7 |",
+                    "+---+---+",
+                ],
+            ),
+        ] {
+            let ret = ctx.sql(sql)?.collect()?;
+            let results = pretty::pretty_format_batches(&ret).unwrap().to_string();
+            assert_eq!(
+                results.trim().lines().collect::<Vec<_>>(),
+                expected,
+                "{}",
+                sql
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_session_sort() -> anyhow::Result<()> {
+        let ctx = SessionContext::new();
+        ctx.register_batches("t", vec![
+            record_batch!(
+                ("a", Int32, [Some(1), None, Some(2)]),
+                ("b", Float64, [Some(0.5), Some(1.5), None])
+            )?,
+            record_batch!(
+                ("a", Int32, [Some(2), Some(1)]),
+                ("b", Float64, [Some(f64::NAN), Some(-0.5)])
+            )?,
+        ])?;
+
+        let ret = ctx
+            .table("t")?
+            .sort(vec![col("a").sort(false, true), col("b").sort(true, false)])
+            .collect()?;
+        let results = pretty::pretty_format_batches(&ret).unwrap().to_string();
+        let results = results.trim().lines().collect::<Vec<_>>();
+        let expected = vec![
+            "+---+------+",
+            "| a | b    |",
+            "+---+------+",
+            "|   | 1.5  |",
+            "| 2 | NaN  |",
+            "| 2 |      |",
+            "| 1 | -0.5 |",
+            "| 1 | 0.5  |",
+            "+---+------+",
+        ];
+        assert_eq!(results, expected);
+
+        let ret = ctx
+            .sql("SELECT a FROM t WHERE a > 0 ORDER BY b DESC NULLS LAST")?
+            .collect()?;
+        let results = pretty::pretty_format_batches(&ret).unwrap().to_string();
+        let results = results.trim().lines().collect::<Vec<_>>();
+        let expected = vec![
+            "+---+", "| a |", "+---+", "| 2 |", "| 1 |", "| 1 |", "| 2 |", "+---+",
+        ];
+        assert_eq!(results, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn test_session_join() -> anyhow::Result<()> {
+        let ctx = SessionContext::new();
+        let opts = CsvReadOptionsBuilder::default()
+            .has_header(true)
+            .delimiter(b',')
+            .quote(b'"')
+            .build()?;
+        ctx.register_csv("l", "testdata/csv/join_left.csv", opts.clone())?;
+        ctx.register_csv("l2", "testdata/csv/join_left_2.csv", opts.clone())?;
+        ctx.register_csv("r", "testdata/csv/join_right.csv", opts)?;
+
+        let join = |left: &str, join_type, filter| -> anyhow::Result<Vec<String>> {
+            let ret = ctx
+                .table(left)?
+                .join(ctx.table("r")?, join_type, &["l1"], &["r1"], filter)?
+                .collect()?;
+            let results = pretty::pretty_format_batches(&ret)?.to_string();
+            Ok(results.trim().lines().map(String::from).collect())
+        };
+
+        assert_eq!(join("l2", JoinType::Inner, None)?, vec![
+            "+----+----+----+----+-----+------+",
+            "| l1 | l2 | l3 | r1 | r2  | r3   |",
+            "+----+----+----+----+-----+------+",
+            "| a  | 1  | 10 | a  | 100 | 1000 |",
+            "| a  | 1  | 10 | a  | 100 | 1000 |",
+            "| b  | 2  | 20 | b  | 200 | 2000 |",
+            "| c  | 3  | 30 | c  | 300 | 3000 |",
+            "+----+----+----+----+-----+------+",
+        ]);
+        assert_eq!(
+            join("l", JoinType::Left, Some(col("l2").gt(lit(1))))?,
+            vec![
+                "+----+----+----+----+-----+------+",
+                "| l1 | l2 | l3 | r1 | r2  | r3   |",
+                "+----+----+----+----+-----+------+",
+                "| b  | 2  | 20 | b  | 200 | 2000 |",
+                "| c  | 3  | 30 | c  | 300 | 3000 |",
+                "| a  | 1  | 10 |    |     |      |",
+                "| d  | 4  | 40 |    |     |      |",
+                "| e  | 5  | 50 |    |     |      |",
+                "| f  | 6  | 60 |    |     |      |",
+                "+----+----+----+----+-----+------+",
+            ]
+        );
+        assert_eq!(
+            join("l", JoinType::Full, Some(col("r2").lt(lit(300))))?,
+            vec![
+                "+----+----+----+----+-----+------+",
+                "| l1 | l2 | l3 | r1 | r2  | r3   |",
+                "+----+----+----+----+-----+------+",
+                "| a  | 1  | 10 | a  | 100 | 1000 |",
+                "| b  | 2  | 20 | b  | 200 | 2000 |",
+                "|    |    |    | c  | 300 | 3000 |",
+                "| c  | 3  | 30 |    |     |      |",
+                "| d  | 4  | 40 |    |     |      |",
+                "| e  | 5  | 50 |    |     |      |",
+                "| f  | 6  | 60 |    |     |      |",
+                "+----+----+----+----+-----+------+",
+            ]
+        );
+        assert_eq!(join("l2", JoinType::LeftSemi, None)?, vec![
+            "+----+----+----+",
+            "| l1 | l2 | l3 |",
+            "+----+----+----+",
+            "| a  | 1  | 10 |",
+            "| a  | 1  | 10 |",
+            "| b  | 2  | 20 |",
+            "| c  | 3  | 30 |",
+            "+----+----+----+",
+        ]);
+        assert_eq!(join("l", JoinType::LeftAnti, None)?, vec![
+            "+----+----+----+",
+            "| l1 | l2 | l3 |",
+            "+----+----+----+",
+            "| d  | 4  | 40 |",
+            "| e  | 5  | 50 |",
+            "| f  | 6  | 60 |",
+            "+----+----+----+",
+        ]);
+        assert_eq!(join("l2", JoinType::RightSemi, None)?.len(), 7);
+        assert_eq!(
+            join("l", JoinType::RightAnti, Some(col("l2").gt(lit(1))))?,
+            vec![
+                "+----+-----+------+",
+                "| r1 | r2  | r3   |",
+                "+----+-----+------+",
+                "| a  | 100 | 1000 |",
+                "+----+-----+------+",
+            ]
+        );
+
+        let ret = ctx
+            .sql(
+                "SELECT l.l1, x.r2 FROM l LEFT JOIN r AS x ON l.l1 = x.r1 AND x.r2 > 100 \
+                 ORDER BY l.l1 LIMIT 3",
+            )?
+            .collect()?;
+        let results = pretty::pretty_format_batches(&ret)?.to_string();
+        assert_eq!(results.trim().lines().collect::<Vec<_>>(), vec![
+            "+----+-----+",
+            "| l1 | r2  |",
+            "+----+-----+",
+            "| a  |     |",
+            "| b  | 200 |",
+            "| c  | 300 |",
+            "+----+-----+",
+        ]);
+        let ret = ctx
+            .sql("SELECT s.l1 FROM (SELECT l1, l2 FROM l) AS s WHERE s.l2 > 4")?
+            .collect()?;
+        assert_eq!(ret.iter().map(|b| b.num_rows()).sum::<usize>(), 2);
+
+        // Conjuncts on the preserved side of an outer join run before it.
+        let df = ctx
+            .table("l")?
+            .join(ctx.table("r")?, JoinType::Left, &["l1"], &["r1"], None)?
+            .filter(col("l2").gt(lit(2)).and(col("r2").gt(lit(100))));
+        let ret = df.collect()?;
+        assert_eq!(ret.iter().map(|b| b.num_rows()).sum::<usize>(), 1);
+
+        // Both sides have l1, l2 and l3; qualifiers tell them apart.
+        let ret = ctx
+            .table("l")?
+            .join(
+                ctx.table("l2")?,
+                JoinType::Inner,
+                &["l.l1"],
+                &["l2.l1"],
+                Some(col("l2.l3").gt(lit(10))),
+            )?
+            .collect()?;
+        assert_eq!(ret.iter().map(|b| b.num_rows()).sum::<usize>(), 5);
+        let err = ctx
+            .table("l")?
+            .join(
+                ctx.table("l2")?,
+                JoinType::Inner,
+                &["l1"],
+                &["l1"],
+                Some(col("l3").gt(lit(10))),
+            )
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "column reference 'l3' is ambiguous, candidates: l.l3, l2.l3"
+        );
+
+        // A self join needs an alias for one of the sides.
+        let err = ctx
+            .table("l")?
+            .join(ctx.table("l")?, JoinType::Inner, &["l1"], &["l1"], None)
+            .unwrap_err();
+        assert!(matches!(err, DBError::Schema(_)), "{err}");
+        let ret = ctx
+            .table("l")?
+            .join(
+                ctx.table("l")?.alias("x")?,
+                JoinType::Inner,
+                &["l.l1"],
+                &["x.l1"],
+                None,
+            )?
+            .collect()?;
+        assert_eq!(ret.iter().map(|b| b.num_rows()).sum::<usize>(), 6);
+        Ok(())
+    }
+
+    #[test]
+    fn test_session_limit() -> anyhow::Result<()> {
+        let ctx = SessionContext::new();
+        let opts = CsvReadOptionsBuilder::default()
+            .has_header(true)
+            .delimiter(b',')
+            .quote(b'"')
+            .build()?;
+        ctx.register_csv("t", "testdata/csv/simple.csv", opts)?;
+
+        let ret = ctx
+            .sql("SELECT c1, c2 FROM t LIMIT 2 OFFSET 3")?
+            .collect()?;
+        let results = pretty::pretty_format_batches(&ret).unwrap().to_string();
+        let results = results.trim().lines().collect::<Vec<_>>();
+        let expected = vec![
+            "+----+----+",
+            "| c1 | c2 |",
+            "+----+----+",
+            "| d  | 4  |",
+            "| e  | 5  |",
+            "+----+----+",
+        ];
+        assert_eq!(results, expected);
+
+        let ret = ctx
+            .table("t")?
+            .sort(vec![col("c2").sort(false, true)])
+            .limit(0, Some(2))
+            .project(vec![col("c1")])?
+            .collect()?;
+        let results = pretty::pretty_format_batches(&ret).unwrap().to_string();
+        let results = results.trim().lines().collect::<Vec<_>>();
+        assert_eq!(results, vec![
+            "+----+", "| c1 |", "+----+", "| f  |", "| e  |", "+----+"
+        ]);
+
+        let ret = ctx.table("t")?.limit(10, None).collect()?;
+        assert_eq!(ret.iter().map(|b| b.num_rows()).sum::<usize>(), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_session_alias() -> anyhow::Result<()> {
+        let ctx = SessionContext::new();
+        ctx.register_batches("t", vec![record_batch!(
+            ("a", Int32, [Some(1), None, Some(3)]),
+            ("b", Int32, [Some(30), Some(20), Some(10)])
+        )?])?;
+
+        let format = |ret: &[arrow::array::RecordBatch]| -> anyhow::Result<Vec<String>> {
+            let results = pretty::pretty_format_batches(ret)?.to_string();
+            Ok(results.trim().lines().map(String::from).collect())
+        };
+
+        let df = ctx.sql("SELECT a, b + 1 AS total FROM t ORDER BY total")?;
+        let schema = df.plan().schema();
+        assert_eq!(schema.qualified_names(), vec!["t.a", "total"]);
+        assert_eq!(format(&df.collect()?)?, vec![
+            "+---+-------+",
+            "| a | total |",
+            "+---+-------+",
+            "| 3 | 11    |",
+            "|   | 21    |",
+            "| 1 | 31    |",
+            "+---+-------+",
+        ]);
+
+        let df = ctx.sql("SELECT 1 AS one, a + 1 FROM t")?;
+        let schema = df.plan().schema();
+        assert_eq!(schema.qualified_names(), vec!["one", "a + 1"]);
+        assert!(!schema.field(0).is_nullable());
+        assert!(schema.field(1).is_nullable());
+
+        // Sorting by a column that is not selected goes below the projection,
+        // where the alias stands for the expression it names.
+        let ret = ctx
+            .sql("SELECT a AS x FROM t ORDER BY x DESC NULLS LAST, b")?
+            .collect()?;
+        assert_eq!(format(&ret)?, vec![
+            "+---+", "| x |", "+---+", "| 3 |", "| 1 |", "|   |", "+---+",
+        ]);
+
+        let ret = ctx
+            .sql("SELECT s.total FROM (SELECT b + b AS total FROM t) AS s WHERE total > 30")?
+            .collect()?;
+        assert_eq!(format(&ret)?, vec![
+            "+-------+",
+            "| total |",
+            "+-------+",
+            "| 60    |",
+            "| 40    |",
+            "+-------+",
+        ]);
+
+        let ret = ctx
+            .table("t")?
+            .project(vec![col("a").alias("a1"), col("a").alias("a2")])?
+            .collect()?;
+        assert_eq!(ret[0].schema().field(1).name(), "a2");
+
+        // Two expressions producing the same column need an alias.
+        for sql in [
+            "SELECT a, a FROM t",
+            "SELECT b + 1, b + 1 FROM t",
+            "SELECT a, b AS a FROM t",
+            "SELECT t.a, b AS a FROM t",
+        ] {
+            assert!(matches!(ctx.sql(sql), Err(DBError::Plan(_))), "{}", sql);
+        }
+        assert!(ctx.sql("SELECT b + 1, b + 1 AS c FROM t").is_ok());
+
+        // Literals are named apart from columns and from literals of other
+        // types.
+        ctx.register_batches("u", vec![record_batch!(
+            ("c1", Utf8, ["c2", "x"]),
+            ("c2", Utf8, ["c2", "y"]),
+            ("c3", Int32, [3, 4])
+        )?])?;
+        let ret = ctx
+            .sql("SELECT c3 / 2, c3 / 2.0, c1 = 'c2', c1 = c2 FROM u")?
+            .collect()?;
+        assert_eq!(format(&ret)?, vec![
+            "+--------+----------+-----------+---------+",
+            "| c3 / 2 | c3 / 2.0 | c1 = 'c2' | c1 = c2 |",
+            "+--------+----------+-----------+---------+",
+            "| 1      | 1.5      | true      | true    |",
+            "| 2      | 2.0      | false     | false   |",
+            "+--------+----------+-----------+---------+",
+        ]);
+        let err = ctx
+            .table("t")?
+            .project(vec![col("a").plus(lit(1)).alias("x"), col("b").alias("x")])
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "plan error: projection contains duplicate column 'b AS x', use an alias to rename it"
+        );
+        let err = ctx
+            .table("t")?
+            .aggregate(vec![], vec![max(col("a")), max(col("a"))])
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "plan error: aggregate contains duplicate column 'MAX(a)'"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_session_arithmetic() -> anyhow::Result<()> {
+        let batch = record_batch!(("a", Int32, [7, i32::MAX]), ("b", Int32, [2, 0]))?;
+        let ctx = SessionContext::new();
+        ctx.register_batches("t", vec![batch.clone()])?;
+
+        let ret = ctx
+            .sql("SELECT a * 2 - 1, a / b, a % b, a / 2.0 AS half FROM t WHERE b <> 0")?
+            .collect()?;
+        let results = pretty::pretty_format_batches(&ret)?.to_string();
+        assert_eq!(results.trim().lines().collect::<Vec<_>>(), vec![
+            "+-----------+-------+-------+------+",
+            "| a * 2 - 1 | a / b | a % b | half |",
+            "+-----------+-------+-------+------+",
+            "| 13        | 3     | 1     | 3.5  |",
+            "+-----------+-------+-------+------+",
+        ]);
+
+        let df = ctx
+            .table("t")?
+            .project(vec![col("a").multiply(lit(2)).alias("x")])?;
+        let err = df.collect().unwrap_err();
+        assert!(matches!(err, DBError::Execution(_)), "{}", err);
+        let err = ctx.sql("SELECT a % b FROM t")?.collect().unwrap_err();
+        assert_eq!(err.to_string(), "execution error: division by zero");
+
+        let config = SessionConfigBuilder::default()
+            .overflow_mode(OverflowMode::Wrapping)
+            .build()?;
+        let ctx = SessionContext::new_with_config(config);
+        ctx.register_batches("t", vec![batch])?;
+        let ret = ctx.sql("SELECT a + a FROM t")?.collect()?;
+        assert_eq!(ret[0].column(0).as_primitive::<Int32Type>().values(), &[
+            14, -2
+        ]);
+        assert!(ctx.sql("SELECT a / b FROM t")?.collect().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_session_cast() -> anyhow::Result<()> {
+        let ctx = SessionContext::new();
+        ctx.register_batches("t", vec![record_batch!(
+            ("s", Utf8, [Some("10"), Some("x"), None, Some("3")]),
+            ("b", Boolean, [Some(true), Some(false), None, Some(true)])
+        )?])?;
+
+        let ret = ctx
+            .sql("SELECT TRY_CAST(s AS INT) AS n, CAST(b AS BIGINT) FROM t")?
+            .collect()?;
+        assert_eq!(ret[0].schema().field(0).data_type(), &DataType::Int32);
+        assert!(ret[0].schema().field(0).is_nullable());
+        let results = pretty::pretty_format_batches(&ret)?.to_string();
+        assert_eq!(results.trim().lines().collect::<Vec<_>>(), vec![
+            "+----+------------------+",
+            "| n  | CAST(b AS Int64) |",
+            "+----+------------------+",
+            "| 10 | 1                |",
+            "|    | 0                |",
+            "|    |                  |",
+            "| 3  | 1                |",
+            "+----+------------------+",
+        ]);
+
+        // An explicit cast is part of the name, so it does not clash with
+        // the column it converts.
+        let ret = ctx
+            .sql("SELECT b, CAST(b AS VARCHAR) FROM t ORDER BY b")?
+            .collect()?;
+        let schema = ret[0].schema();
+        assert_eq!(schema.field(0).data_type(), &DataType::Boolean);
+        assert_eq!(schema.field(1).name(), "CAST(b AS Utf8)");
+
+        let ret = ctx
+            .table("t")?
+            .filter(col("s").try_cast_to(DataType::Int64).gt(lit(5_i64)))
+            .project(vec![col("s")])?
+            .collect()?;
+        assert_eq!(ret[0].num_rows(), 1);
+
+        // 'x' is not a number.
+        let err = ctx
+            .sql("SELECT s FROM t WHERE s::INT > 5")?
+            .collect()
+            .unwrap_err();
+        assert!(matches!(err, DBError::ArrowError(_)), "{}", err);
+
+        let err = ctx
+            .table("t")?
+            .project(vec![col("b").cast_to(DataType::Date32)])?
+            .collect()
+            .unwrap_err();
+        assert_eq!(err.to_string(), "plan error: cannot cast Boolean to Date32");
+        Ok(())
+    }
+
+    #[test]
+    fn test_session_conditional() -> anyhow::Result<()> {
+        let ctx = SessionContext::new();
+        ctx.register_batches("t", vec![record_batch!(
+            ("x", Int32, [Some(4), Some(0), None, Some(-2)]),
+            ("s", Utf8, [Some("a"), None, None, Some("")])
+        )?])?;
+
+        // THEN is only evaluated where x <> 0, so nothing is divided by zero.
+        let ret = ctx
+            .sql(
+                "SELECT CASE WHEN x <> 0 THEN 8 / x END AS q, \
+                 CASE x WHEN 4 THEN 'four' WHEN 0 THEN 'zero' ELSE 'other' END AS name, \
+                 COALESCE(s, 'none') AS c, NULLIF(s, '') AS n, IF(x > 0, x, 0.5) AS i \
+                 FROM t",
+            )?
+            .collect()?;
+        let schema = ret[0].schema();
+        assert_eq!(schema.field(0).data_type(), &DataType::Int64);
+        assert!(!schema.field(1).is_nullable());
+        assert_eq!(schema.field(4).data_type(), &DataType::Float64);
+        let results = pretty::pretty_format_batches(&ret)?.to_string();
+        assert_eq!(results.trim().lines().collect::<Vec<_>>(), vec![
+            "+----+-------+------+---+-----+",
+            "| q  | name  | c    | n | i   |",
+            "+----+-------+------+---+-----+",
+            "| 2  | four  | a    | a | 4.0 |",
+            "|    | zero  | none |   | 0.5 |",
+            "|    | other | none |   | 0.5 |",
+            "| -4 | other |      |   | 0.5 |",
+            "+----+-------+------+---+-----+",
+        ]);
+
+        let ret = ctx
+            .table("t")?
+            .project(vec![
+                when(col("x").gt(lit(0)), lit("pos"))
+                    .when(col("x").lt(lit(0)), lit("neg"))
+                    .end()
+                    .alias("sign"),
+                coalesce(vec![nullif(col("x"), lit(0)), lit(100_i64)]).alias("c"),
+            ])?
+            .collect()?;
+        let results = pretty::pretty_format_batches(&ret)?.to_string();
+        assert_eq!(results.trim().lines().collect::<Vec<_>>(), vec![
+            "+------+-----+",
+            "| sign | c   |",
+            "+------+-----+",
+            "| pos  | 4   |",
+            "|      | 100 |",
+            "|      | 100 |",
+            "| neg  | -2  |",
+            "+------+-----+",
+        ]);
+
+        let err = ctx
+            .sql("SELECT CASE WHEN x > 0 THEN s ELSE x END FROM t")
+            .unwrap_err();
+        assert!(matches!(err, DBError::Plan(_)), "{}", err);
+        let err = ctx
+            .sql("SELECT CASE WHEN x THEN 1 END FROM t")?
+            .collect()
+            .unwrap_err();
+        assert!(matches!(err, DBError::Plan(_)), "{}", err);
+        Ok(())
+    }
+
+    #[test]
+    fn test_session_predicates() -> anyhow::Result<()> {
+        let ctx = SessionContext::new();
+        ctx.register_batches("t", vec![record_batch!(
+            ("x", Int32, [Some(1), Some(5), None, Some(-3)]),
+            ("y", Int32, [Some(1), None, None, Some(0)])
+        )?])?;
+
+        let ret = ctx
+            .sql(
+                "SELECT -x AS n, x IS NULL AS a, x IS DISTINCT FROM y AS b, \
+                 x IN (1, 2, 3, 4, 5) AS c, x NOT IN (1, NULL) AS d, x IN (y, 5) AS e, \
+                 x BETWEEN 0 AND 4 AS f, NOT x > 0 AS g, x > 0 IS NOT TRUE AS h \
+                 FROM t",
+            )?
+            .collect()?;
+        let schema = ret[0].schema();
+        assert!(!schema.field(1).is_nullable());
+        assert!(!schema.field(2).is_nullable());
+        let results = pretty::pretty_format_batches(&ret)?.to_string();
+        assert_eq!(results.trim().lines().collect::<Vec<_>>(), vec![
+            "+----+-------+-------+-------+-------+-------+-------+-------+-------+",
+            "| n  | a     | b     | c     | d     | e     | f     | g     | h     |",
+            "+----+-------+-------+-------+-------+-------+-------+-------+-------+",
+            "| -1 | false | false | true  | false | true  | true  | false | false |",
+            "| -5 | false | true  | true  |       | true  | false | false | false |",
+            "|    | true  | false |       |       |       |       |       | true  |",
+            "| 3  | false | true  | false |       | false | false | true  | true  |",
+            "+----+-------+-------+-------+-------+-------+-------+-------+-------+",
+        ]);
+
+        let ret = ctx
+            .table("t")?
+            .filter(
+                col("x")
+                    .is_not_null()
+                    .and(col("x").not_between(lit(0), lit(2))),
+            )
+            .project(vec![col("x")])?
+            .collect()?;
+        let values = ret[0].column(0).as_primitive::<Int32Type>();
+        assert_eq!(values.values(), &[5, -3]);
+
+        for sql in [
+            "SELECT NOT x FROM t",
+            "SELECT x IN (1, 'a', true) FROM t",
+            "SELECT -CAST(x AS INT UNSIGNED) FROM t",
+        ] {
+            let err = ctx.sql(sql).unwrap_err();
+            assert!(matches!(err, DBError::Plan(_)), "{}: {}", sql, err);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_session_string_matching() -> anyhow::Result<()> {
+        let ctx = SessionContext::new();
+        ctx.register_batches("logs", vec![record_batch!(
+            ("level", Utf8, [
+                Some("ERROR"),
+                Some("warn"),
+                Some("INFO"),
+                None
+            ]),
+            ("msg", Utf8, [
+                Some("disk 95% full"),
+                Some("slow query: 1200ms"),
+                Some("GET /index 200"),
+                Some("POST /login 500")
+            ])
+        )?])?;
+
+        let ret = ctx
+            .sql(
+                "SELECT level FROM logs \
+                 WHERE msg LIKE '%!%%' ESCAPE '!' OR level ILIKE 'W%' \
+                 OR regexp_like(msg, '^post', 'i')",
+            )?
+            .collect()?;
+        let results = pretty::pretty_format_batches(&ret)?.to_string();
+        assert_eq!(results.trim().lines().collect::<Vec<_>>(), vec![
+            "+-------+",
+            "| level |",
+            "+-------+",
+            "| ERROR |",
+            "| warn  |",
+            "|       |",
+            "+-------+",
+        ]);
+
+        let ret = ctx
+            .sql(
+                "SELECT level SIMILAR TO '(ERROR|WARN)' AS bad, msg NOT LIKE '%/%' AS no_path, \
+                 regexp_replace(msg, '[0-9]+', 'N', 'g') AS masked, \
+                 regexp_extract(msg, '(GET|POST) (\\S+)', 2) AS path \
+                 FROM logs",
+            )?
+            .collect()?;
+        let results = pretty::pretty_format_batches(&ret)?.to_string();
+        assert_eq!(results.trim().lines().collect::<Vec<_>>(), vec![
+            "+-------+---------+-----------------+--------+",
+            "| bad   | no_path | masked          | path   |",
+            "+-------+---------+-----------------+--------+",
+            "| true  | true    | disk N% full    |        |",
+            "| false | true    | slow query: Nms |        |",
+            "| false | false   | GET /index N    | /index |",
+            "|       | false   | POST /login N   | /login |",
+            "+-------+---------+-----------------+--------+",
+        ]);
+
+        let ret = ctx
+            .table("logs")?
+            .filter(col("msg").similar_to(lit("%[0-9]+ms")))
+            .project(vec![
+                regexp_extract(col("msg"), lit("([0-9]+)ms"), lit(1)).alias("ms"),
+            ])?
+            .collect()?;
+        assert_eq!(
+            pretty::pretty_format_batches(&ret)?
+                .to_string()
+                .lines()
+                .nth(3),
+            Some("| 1200 |")
+        );
+
+        for sql in [
+            "SELECT * FROM logs WHERE level LIKE 1",
+            "SELECT regexp_like(msg) FROM logs",
+            "SELECT regexp_extract(msg, 'a', 'b') FROM logs",
+        ] {
+            let err = ctx.sql(sql).and_then(|df| df.collect()).unwrap_err();
+            assert!(matches!(err, DBError::Plan(_)), "{}: {}", sql, err);
+        }
+        let err = ctx
+            .sql("SELECT regexp_like(msg, '(') FROM logs")?
+            .collect()
+            .unwrap_err();
+        assert!(matches!(err, DBError::Execution(_)), "{}", err);
+        Ok(())
+    }
+
+    #[test]
+    fn test_session_string_functions() -> anyhow::Result<()> {
+        let ctx = SessionContext::new();
+        ctx.register_batches("people", vec![record_batch!(
+            ("name", Utf8, [
+                Some(" ada lovelace "),
+                Some("Alan Turing"),
+                None
+            ]),
+            ("id", Int32, [Some(7), Some(42), Some(3)])
+        )?])?;
+
+        let ret = ctx
+            .sql(
+                "SELECT upper(trim(name)) AS upper, length(name) AS len,                  SUBSTRING(trim(name) FROM 1 FOR 3) || '-' || lpad(CAST(id AS VARCHAR), 3, '0') AS code,                  split_part(trim(name), ' ', -1) AS last, strpos(name, 'a') AS pos                  FROM people",
+            )?
+            .collect()?;
+        let results = pretty::pretty_format_batches(&ret)?.to_string();
+        assert_eq!(results.trim().lines().collect::<Vec<_>>(), vec![
+            "+--------------+-----+---------+----------+-----+",
+            "| upper        | len | code    | last     | pos |",
+            "+--------------+-----+---------+----------+-----+",
+            "| ADA LOVELACE | 14  | ada-007 | lovelace | 2   |",
+            "| ALAN TURING  | 11  | Ala-042 | Turing   | 3   |",
+            "|              |     |         |          |     |",
+            "+--------------+-----+---------+----------+-----+",
+        ]);
+
+        let ret = ctx
+            .table("people")?
+            .filter(starts_with(lower(col("name")), lit("alan")))
+            .project(vec![
+                concat(vec![reverse(col("name")), lit("#"), col("id")]).alias("c"),
+                replace(col("name"), lit("n"), lit("N")).alias("r"),
+            ])?
+            .collect()?;
+        let results = pretty::pretty_format_batches(&ret)?.to_string();
+        assert_eq!(
+            results.trim().lines().nth(3),
+            Some("| gniruT nalA#42 | AlaN TuriNg |")
+        );
+
+        for sql in [
+            "SELECT upper(id) FROM people",
+            "SELECT lpad(name) FROM people",
+            "SELECT substr(name, 'a') FROM people",
+            "SELECT id || id FROM people",
+        ] {
+            let err = ctx.sql(sql).and_then(|df| df.collect()).unwrap_err();
+            assert!(matches!(err, DBError::Plan(_)), "{}: {}", sql, err);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_session_math_functions() -> anyhow::Result<()> {
+        let ctx = SessionContext::new();
+        ctx.register_batches("m", vec![record_batch!(
+            ("i", Int32, [Some(-17), Some(250), None]),
+            ("f", Float64, [Some(2.25), Some(-0.5), Some(9.0)])
+        )?])?;
+
+        let ret = ctx
+            .sql(
+                "SELECT abs(i) AS a, round(i, -1) AS r, trunc(f, 1) AS t, round(f) AS rf, \
+                 CEIL(f) AS c, sign(i) AS s, sqrt(f) AS q, power(2, i % 5) AS p, \
+                 greatest(i, f) AS g, least(i, 0) AS l \
+                 FROM m",
+            )?
+            .collect()?;
+        let schema = ret[0].schema();
+        assert_eq!(schema.field(0).data_type(), &DataType::Int32);
+        assert_eq!(schema.field(1).data_type(), &DataType::Int32);
+        assert_eq!(schema.field(8).data_type(), &DataType::Float64);
+        let results = pretty::pretty_format_batches(&ret)?.to_string();
+        assert_eq!(results.trim().lines().collect::<Vec<_>>(), vec![
+            "+-----+-----+------+------+------+----+-----+------+-------+-----+",
+            "| a   | r   | t    | rf   | c    | s  | q   | p    | g     | l   |",
+            "+-----+-----+------+------+------+----+-----+------+-------+-----+",
+            "| 17  | -20 | 2.2  | 2.0  | 3.0  | -1 | 1.5 | 0.25 | 2.25  | -17 |",
+            "| 250 | 250 | -0.5 | -1.0 | -0.0 | 1  | NaN | 1.0  | 250.0 | 0   |",
+            "|     |     | 9.0  | 9.0  | 9.0  |    | 3.0 |      | 9.0   | 0   |",
+            "+-----+-----+------+------+------+----+-----+------+-------+-----+",
+        ]);
+
+        let ret = ctx
+            .table("m")?
+            .project(vec![
+                random().alias("r1"),
+                random().alias("r2"),
+                floor(col("f").multiply(lit(10.0))).alias("f"),
+            ])?
+            .collect()?;
+        let r1 = ret[0]
+            .column(0)
+            .as_primitive::<arrow::datatypes::Float64Type>();
+        let r2 = ret[0]
+            .column(1)
+            .as_primitive::<arrow::datatypes::Float64Type>();
+        assert_ne!(r1, r2);
+        assert!(r1.values().iter().all(|v| (0.0..1.0).contains(v)));
+
+        for sql in ["SELECT abs('a') FROM m", "SELECT round(f, 1.5) FROM m"] {
+            let err = ctx.sql(sql).and_then(|df| df.collect()).unwrap_err();
+            assert!(matches!(err, DBError::Plan(_)), "{}: {}", sql, err);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_session_temporal() -> anyhow::Result<()> {
+        let ctx = SessionContext::new();
+        let opts = CsvReadOptionsBuilder::default()
+            .has_header(true)
+            .delimiter(b',')
+            .quote(b'"')
+            .build()?;
+        ctx.register_csv("events", "testdata/csv/events.csv", opts)?;
+
+        let ret = ctx
+            .sql(
+                "SELECT id, day, ts + INTERVAL '1 month' AS next, date_trunc('month', ts) AS m, \
+                 EXTRACT(DOW FROM day) AS dow, date_part('second', ts) AS s \
+                 FROM events WHERE ts >= '2024-01-31' ORDER BY id",
+            )?
+            .collect()?;
+        let schema = ret[0].schema();
+        assert_eq!(schema.field(1).data_type(), &DataType::Date32);
+        assert_eq!(
+            schema.field(2).data_type(),
+            &DataType::Timestamp(TimeUnit::Millisecond, None)
+        );
+        let results = pretty::pretty_format_batches(&ret)?.to_string();
+        assert_eq!(results.trim().lines().collect::<Vec<_>>(), vec![
+            "+----+------------+-------------------------+---------------------+-----+------+",
+            "| id | day        | next                    | m                   | dow | s    |",
+            "+----+------------+-------------------------+---------------------+-----+------+",
+            "| 2  | 2024-01-31 | 2024-02-29T23:15:45.500 | 2024-01-01T00:00:00 | 3.0 | 45.5 |",
+            "| 3  | 2024-02-29 | 2024-03-29T12:00:00     | 2024-02-01T00:00:00 | 4.0 | 0.0  |",
+            "+----+------------+-------------------------+---------------------+-----+------+",
+        ]);
+
+        let ret = ctx
+            .sql(
+                "SELECT count(*) AS n FROM events \
+                 WHERE day BETWEEN DATE '2024-01-01' AND '2024-01-31' \
+                 AND day <= CURRENT_DATE AND ts < now()",
+            )?
+            .collect()?;
+        let results = pretty::pretty_format_batches(&ret)?.to_string();
+        assert_eq!(results.trim().lines().collect::<Vec<_>>(), vec![
+            "+---+", "| n |", "+---+", "| 2 |", "+---+",
+        ]);
+
+        let ret = ctx
+            .sql(
+                "SELECT kind = 'login' AS login, MIN(day) AS first, MAX(ts) AS last, \
+                 MAX(CAST(ts AS TIME)) AS latest FROM events GROUP BY kind = 'login' \
+                 ORDER BY login",
+            )?
+            .collect()?;
+        let results = pretty::pretty_format_batches(&ret)?.to_string();
+        assert_eq!(results.trim().lines().collect::<Vec<_>>(), vec![
+            "+-------+------------+---------------------+--------------+",
+            "| login | first      | last                | latest       |",
+            "+-------+------------+---------------------+--------------+",
+            "| false | 2024-01-31 | 2024-02-29T12:00:00 | 23:15:45.500 |",
+            "| true  | 2024-01-15 | 2024-01-15T08:30:00 | 08:30:00     |",
+            "+-------+------------+---------------------+--------------+",
+        ]);
+
+        let noon = NaiveDate::from_ymd_opt(2024, 1, 31)
+            .and_then(|d| d.and_hms_opt(12, 0, 0))
+            .expect("valid timestamp");
+        let ret = ctx
+            .table("events")?
+            .filter(col("ts").minus(interval(0, 1, 0)).lt(lit(noon)))
+            .project(vec![
+                col("id"),
+                date_trunc(lit("week"), col("day")).alias("w"),
+                date_part(lit("doy"), col("ts")).alias("doy"),
+            ])?
+            .collect()?;
+        let results = pretty::pretty_format_batches(&ret)?.to_string();
+        assert_eq!(results.trim().lines().collect::<Vec<_>>(), vec![
+            "+----+---------------------+------+",
+            "| id | w                   | doy  |",
+            "+----+---------------------+------+",
+            "| 1  | 2024-01-15T00:00:00 | 15.0 |",
+            "| 2  | 2024-01-29T00:00:00 | 31.0 |",
+            "+----+---------------------+------+",
+        ]);
+
+        for (sql, plan_error) in [
+            ("SELECT ts * 2 FROM events", true),
+            ("SELECT day + 1 FROM events", true),
+            ("SELECT date_trunc('fortnight', ts) FROM events", false),
+            ("SELECT to_timestamp(kind, '%Y') FROM events", false),
+        ] {
+            let err = ctx.sql(sql).and_then(|df| df.collect()).unwrap_err();
+            let expected = if plan_error {
+                matches!(err, DBError::Plan(_))
+            } else {
+                matches!(err, DBError::Execution(_))
+            };
+            assert!(expected, "{}: {}", sql, err);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_session_decimal() -> anyhow::Result<()> {
+        let ctx = SessionContext::new();
+        let opts = CsvReadOptionsBuilder::default()
+            .has_header(true)
+            .delimiter(b',')
+            .quote(b'"')
+            .column_types(vec![
+                ("amount".to_string(), DataType::Decimal128(10, 2)),
+                ("fee".to_string(), DataType::Decimal128(4, 2)),
+            ])
+            .build()?;
+        ctx.register_csv("payments", "testdata/csv/payments.csv", opts)?;
+
+        let ret = ctx
+            .sql(
+                "SELECT customer, SUM(amount) AS total, AVG(amount) AS average, MAX(fee) AS fee \
+                 FROM payments GROUP BY customer ORDER BY customer",
+            )?
+            .collect()?;
+        let schema = ret[0].schema();
+        assert_eq!(schema.field(1).data_type(), &DataType::Decimal128(20, 2));
+        assert_eq!(schema.field(2).data_type(), &DataType::Decimal128(14, 6));
+        let results = pretty::pretty_format_batches(&ret)?.to_string();
+        assert_eq!(results.trim().lines().collect::<Vec<_>>(), vec![
+            "+----------+---------+-------------+------+",
+            "| customer | total   | average     | fee  |",
+            "+----------+---------+-------------+------+",
+            "| alice    | 20.19   | 10.095000   | 0.30 |",
+            "| bob      | 0.10    | 0.100000    | 0.01 |",
+            "| carol    | 1234.56 | 1234.560000 |      |",
+            "+----------+---------+-------------+------+",
+        ]);
+
+        let ret = ctx
+            .sql(
+                "SELECT id, amount + fee AS gross, amount * 2 AS twice, amount / 3 AS third, \
+                 -amount AS negated, CAST(amount AS DECIMAL(6, 1)) AS rounded \
+                 FROM payments WHERE amount > '0.15' ORDER BY id",
+            )?
+            .collect()?;
+        let schema = ret[0].schema();
+        assert_eq!(schema.field(1).data_type(), &DataType::Decimal128(11, 2));
+        assert_eq!(schema.field(2).data_type(), &DataType::Decimal128(30, 2));
+        assert_eq!(schema.field(3).data_type(), &DataType::Decimal128(14, 6));
+        let results = pretty::pretty_format_batches(&ret)?.to_string();
+        assert_eq!(results.trim().lines().collect::<Vec<_>>(), vec![
+            "+----+-------+---------+------------+----------+---------+",
+            "| id | gross | twice   | third      | negated  | rounded |",
+            "+----+-------+---------+------------+----------+---------+",
+            "| 1  | 20.29 | 39.98   | 6.663333   | -19.99   | 20.0    |",
+            "| 3  | 0.21  | 0.40    | 0.066666   | -0.20    | 0.2     |",
+            "| 4  |       | 2469.12 | 411.520000 | -1234.56 | 1234.6  |",
+            "+----+-------+---------+------------+----------+---------+",
+        ]);
+
+        let ret = ctx
+            .table("payments")?
+            .filter(col("amount").lt(parse_decimal_lit("10", 4, 2)?))
+            .aggregate(vec![], vec![sum(col("amount"))])?
+            .collect()?;
+        let results = pretty::pretty_format_batches(&ret)?.to_string();
+        assert_eq!(results.trim().lines().collect::<Vec<_>>(), vec![
+            "+-------------+",
+            "| SUM(amount) |",
+            "+-------------+",
+            "| 0.30        |",
+            "+-------------+",
+        ]);
+
+        let ret = ctx
+            .sql("SELECT TRY_CAST(amount AS DECIMAL(5, 2)) AS a FROM payments WHERE id = 4")?
+            .collect()?;
+        assert!(ret[0].column(0).is_null(0));
+        assert!(matches!(
+            parse_decimal_lit("123.4", 4, 2),
+            Err(DBError::Parse(_))
+        ));
+        assert_eq!(parse_decimal_lit("12.345", 4, 2)?.to_string(), "12.34");
+        for sql in [
+            "SELECT CAST(amount AS DECIMAL(5, 2)) FROM payments",
+            "SELECT amount / 0 FROM payments",
+        ] {
+            assert!(ctx.sql(sql).and_then(|df| df.collect()).is_err(), "{}", sql);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_session_window() -> anyhow::Result<()> {
+        let ctx = SessionContext::new();
+        ctx.register_batches("sales", vec![
+            record_batch!(
+                ("region", Utf8, ["east", "east", "west"]),
+                ("month", Int32, [1, 2, 1]),
+                ("amount", Int32, [Some(10), Some(20), Some(5)])
+            )?,
+            record_batch!(
+                ("region", Utf8, ["west", "east", "west"]),
+                ("month", Int32, [2, 3, 3]),
+                ("amount", Int32, [Some(15), Some(30), None])
+            )?,
+        ])?;
+
+        let ret = ctx
+            .sql(
+                "SELECT region, month, \
+                 ROW_NUMBER() OVER (PARTITION BY region ORDER BY month) AS rn, \
+                 SUM(amount) OVER (PARTITION BY region ORDER BY month) AS running, \
+                 LAG(amount, 1, 0) OVER (PARTITION BY region ORDER BY month) AS prev, \
+                 AVG(amount) OVER (ORDER BY month, region ROWS BETWEEN CURRENT ROW AND 1 FOLLOWING) \
+                 AS ahead FROM sales ORDER BY region, month",
+            )?
+            .collect()?;
+        let results = pretty::pretty_format_batches(&ret)?.to_string();
+        assert_eq!(results.trim().lines().collect::<Vec<_>>(), vec![
+            "+--------+-------+----+---------+------+-------+",
+            "| region | month | rn | running | prev | ahead |",
+            "+--------+-------+----+---------+------+-------+",
+            "| east   | 1     | 1  | 10      | 0    | 7.5   |",
+            "| east   | 2     | 2  | 30      | 10   | 17.5  |",
+            "| east   | 3     | 3  | 60      | 20   | 30.0  |",
+            "| west   | 1     | 1  | 5       | 0    | 12.5  |",
+            "| west   | 2     | 2  | 20      | 5    | 22.5  |",
+            "| west   | 3     | 3  | 20      | 15   |       |",
+            "+--------+-------+----+---------+------+-------+",
+        ]);
+
+        // The filter only reads the partition column, so it runs before the
+        // window without changing the ranks.
+        let df = ctx
+            .table("sales")?
+            .window(vec![
+                rank()
+                    .partition_by(vec![col("region")])
+                    .order_by(vec![col("amount").sort(false, false)])
+                    .build()
+                    .alias("r"),
+            ])?
+            .filter(col("region").eq(lit("east")))
+            .project(vec![col("month"), col("r")])?
+            .sort(vec![col("month").sort(true, false)]);
+        let ret = df.collect()?;
+        assert_eq!(ret[0].schema().field(1).data_type(), &DataType::Int64);
+        let results = pretty::pretty_format_batches(&ret)?.to_string();
+        assert_eq!(results.trim().lines().collect::<Vec<_>>(), vec![
+            "+-------+---+",
+            "| month | r |",
+            "+-------+---+",
+            "| 1     | 3 |",
+            "| 2     | 2 |",
+            "| 3     | 1 |",
+            "+-------+---+",
+        ]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_session_type_coercion() -> anyhow::Result<()> {
+        let ctx = SessionContext::new();
+        let opts = CsvReadOptionsBuilder::default()
+            .has_header(true)
+            .delimiter(b',')
+            .quote(b'"')
+            .build()?;
+        ctx.register_csv("t", "testdata/csv/simple.csv", opts)?;
+        ctx.register_batches("u", vec![record_batch!(("a", Int32, [1, 2, 3]))?])?;
+
+        let ret = ctx
+            .sql("SELECT c1, c2 + 0.5, c2 > 3 FROM t WHERE c3 < '4' OR c2 = 6")?
+            .collect()?;
+        assert_eq!(ret[0].schema().field(1).data_type(), &DataType::Float64);
+        assert_eq!(ret[0].schema().field(2).data_type(), &DataType::Boolean);
+        let results = pretty::pretty_format_batches(&ret).unwrap().to_string();
+        let results = results.trim().lines().collect::<Vec<_>>();
+        let expected = vec![
+            "+----+----------+--------+",
+            "| c1 | c2 + 0.5 | c2 > 3 |",
+            "+----+----------+--------+",
+            "| a  | 1.5      | false  |",
+            "| b  | 2.5      | false  |",
+            "| f  | 6.5      | true   |",
+            "+----+----------+--------+",
+        ];
+        assert_eq!(results, expected);
+
+        // Int32 column combined with an Int64 literal.
+        let ret = ctx.sql("SELECT a + 1 FROM u WHERE a >= 2")?.collect()?;
+        assert_eq!(ret[0].schema().field(0).data_type(), &DataType::Int64);
+
+        // The projection computes its output types, so the error is raised
+        // while planning.
+        let Err(err) = ctx.sql("SELECT c1 + 1 FROM t") else {
+            panic!("expected a type error");
+        };
+        assert_eq!(
+            err.to_string(),
+            "plan error: cannot apply '+' to Utf8 and Int64"
+        );
+        assert!(ctx.sql("SELECT c1 FROM t WHERE c2")?.collect().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_session_catalog() -> anyhow::Result<()> {
+        let ctx = SessionContext::new();
+        let opts = CsvReadOptionsBuilder::default()
+            .has_header(true)
+            .delimiter(b',')
+            .quote(b'"')
+            .build()?;
+        ctx.register_csv("orders", "testdata/csv/simple.csv", opts)?;
+        ctx.register_batches("t", vec![
+            record_batch!(("a", Int32, [1, 2]))?,
+            record_batch!(("a", Int32, [3]))?,
+        ])?;
+        assert_eq!(ctx.table_names(), vec!["orders", "t"]);
+
+        let ret = ctx.table("t")?.project(vec![col("a")])?.collect()?;
+        assert_eq!(ret.iter().map(|b| b.num_rows()).sum::<usize>(), 3);
+
+        let df = ctx.table("public.orders")?.project(vec![col("c1")])?;
+        assert_eq!(
+            df.plan().to_string(),
+            "Projection: c1\n\tScan: public.orders AS orders; projection=None\n"
+        );
+        assert_eq!(df.collect()?[0].num_rows(), 6);
+
+        let schema = Arc::new(SchemaProvider::new());
+        let catalog = Arc::new(CatalogProvider::new());
+        catalog.register_schema("sales", schema.clone());
+        ctx.register_catalog("db", catalog);
+        ctx.register_batches("db.sales.items", vec![record_batch!(("b", Utf8, ["x"]))?])?;
+        assert_eq!(schema.table_names(), vec!["items"]);
+        let ret = ctx.sql("SELECT b FROM db.sales.items")?.collect()?;
+        assert_eq!(ret[0].num_rows(), 1);
+
+        assert!(ctx.deregister_table("t")?.is_some());
+        assert!(ctx.deregister_table("t")?.is_none());
+        assert_eq!(ctx.table_names(), vec!["orders"]);
+        assert_eq!(
+            ctx.table("t").unwrap_err().to_string(),
+            "plan error: table 't' not found"
+        );
+        assert_eq!(
+            ctx.register_batches("missing.t", vec![record_batch!(("a", Int32, [1]))?])
+                .unwrap_err()
+                .to_string(),
+            "plan error: schema 'default.missing' not found"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_session_errors() -> anyhow::Result<()> {
+        let ctx = SessionContext::new();
+        ctx.register_batches("t", vec![record_batch!(("a", Int32, [1, 2]))?])?;
+
+        let df = ctx.table("t")?.filter(col("b").gt(lit(1)));
+        let Err(err) = df.collect() else {
+            panic!("expected a missing column error");
+        };
+        assert!(
+            matches!(&err, DBError::ColumnNotFound { name, candidates } if name == "b" && candidates == &["t.a"])
+        );
+        assert_eq!(
+            err.to_string(),
+            "column 'b' not found, available columns: t.a"
+        );
+
+        let ret = ctx
+            .table("t")?
+            .project(vec![col("a").gt(lit(1)).plus(lit(1))])
+            .and_then(|df| df.collect());
+        assert!(matches!(ret, Err(DBError::Plan(_))));
+        assert!(matches!(ctx.sql("SELECT 1 +"), Err(DBError::Parse(_))));
+        assert!(matches!(
+            ctx.sql("SELECT DISTINCT a FROM t"),
+            Err(DBError::NotImplemented(_))
+        ));
+        Ok(())
+    }
 }
@@ -1,40 +1,147 @@
 use std::sync::Arc;
 
-use crate::error::Result;
+use crate::error::{Result, plan_err};
 use crate::logical::{
-    expression::expr::LogicalExpr,
-    plan::{Filter, LogicalPlan, Projection},
+    expression::{expr::LogicalExpr, expr_fn::col, sort::SortExpr},
+    plan::{
+        Aggregate, Filter, Join, JoinType, Limit, LogicalPlan, Projection, Sort, SubqueryAlias,
+        Window,
+    },
 };
+use crate::optimizer::Optimizer;
+use crate::physical::plan::RecordBatchStream;
 use arrow::array::RecordBatch;
 
-use super::planner::Planner;
+use super::{SessionConfig, planner::Planner};
 
+#[derive(Debug)]
 pub struct DataFrame {
     plan: LogicalPlan,
+    config: SessionConfig,
 }
 
 impl DataFrame {
-    pub fn new(plan: LogicalPlan) -> Self {
-        Self { plan }
+    pub fn new(plan: LogicalPlan, config: SessionConfig) -> Self {
+        Self { plan, config }
     }
 
-    pub fn project(self, columns: Vec<LogicalExpr>) -> Self {
-        let plan = LogicalPlan::Projection(Projection::new(Arc::new(self.plan), columns));
-        Self { plan }
+    pub fn project(self, columns: Vec<LogicalExpr>) -> Result<Self> {
+        let plan = LogicalPlan::Projection(Projection::try_new(Arc::new(self.plan), columns)?);
+        Ok(Self {
+            plan,
+            config: self.config,
+        })
     }
 
     pub fn filter(self, expr: LogicalExpr) -> Self {
         let plan = LogicalPlan::Filter(Filter::new(Arc::new(self.plan), expr));
-        Self { plan }
+        Self {
+            plan,
+            config: self.config,
+        }
+    }
+
+    /// Groups the rows by `group_by` and computes `aggr_exprs` for every group.
+    pub fn aggregate(
+        self,
+        group_by: Vec<LogicalExpr>,
+        aggr_exprs: Vec<LogicalExpr>,
+    ) -> Result<Self> {
+        let aggregate = Aggregate::try_new(Arc::new(self.plan), group_by, aggr_exprs)?;
+        Ok(Self {
+            plan: LogicalPlan::Aggregate(aggregate),
+            config: self.config,
+        })
+    }
+
+    /// Appends a column for every window function in `window_exprs`, e.g.
+    /// `row_number().order_by(vec![col("c2").sort(true, false)]).build()`.
+    /// The rows and columns of the input are kept.
+    pub fn window(self, window_exprs: Vec<LogicalExpr>) -> Result<Self> {
+        let window = Window::try_new(Arc::new(self.plan), window_exprs)?;
+        Ok(Self {
+            plan: LogicalPlan::Window(window),
+            config: self.config,
+        })
+    }
+
+    /// Sorts the rows by `exprs`, the first key being the most significant.
+    pub fn sort(self, exprs: Vec<SortExpr>) -> Self {
+        let plan = LogicalPlan::Sort(Sort::new(Arc::new(self.plan), exprs));
+        Self {
+            plan,
+            config: self.config,
+        }
+    }
+
+    /// Skips the first `skip` rows and returns at most `fetch` of the rest.
+    pub fn limit(self, skip: usize, fetch: Option<usize>) -> Self {
+        let plan = LogicalPlan::Limit(Limit::new(Arc::new(self.plan), skip, fetch));
+        Self {
+            plan,
+            config: self.config,
+        }
+    }
+
+    /// Joins with `right` on `left_keys[i] = right_keys[i]` for every `i`.
+    /// `filter` may reference columns of both sides and is applied to every
+    /// pair of rows with equal keys.
+    pub fn join(
+        self,
+        right: DataFrame,
+        join_type: JoinType,
+        left_keys: &[&str],
+        right_keys: &[&str],
+        filter: Option<LogicalExpr>,
+    ) -> Result<Self> {
+        if left_keys.len() != right_keys.len() {
+            return plan_err!(
+                "join needs the same number of keys on both sides, got {} and {}",
+                left_keys.len(),
+                right_keys.len()
+            );
+        }
+        let on = left_keys
+            .iter()
+            .zip(right_keys.iter())
+            .map(|(l, r)| (col(*l), col(*r)))
+            .collect();
+        let join = Join::try_new(
+            Arc::new(self.plan),
+            Arc::new(right.plan),
+            on,
+            filter,
+            join_type,
+        )?;
+        Ok(Self {
+            plan: LogicalPlan::Join(join),
+            config: self.config,
+        })
+    }
+
+    /// Names the rows of this frame `alias`; its columns can then be
+    /// referred to as `alias.column`.
+    pub fn alias(self, alias: impl Into<String>) -> Result<Self> {
+        let alias = SubqueryAlias::try_new(Arc::new(self.plan), alias)?;
+        Ok(Self {
+            plan: LogicalPlan::SubqueryAlias(alias),
+            config: self.config,
+        })
     }
 
     pub fn plan(&self) -> &LogicalPlan {
         &self.plan
     }
 
-    pub fn collect(&self) -> Result<RecordBatch> {
-        let optimized = &self.plan;
-        let physical_plan = Planner::create_physical_plan(optimized)?;
+    /// Executes the plan and buffers every resulting batch in memory.
+    pub fn collect(&self) -> Result<Vec<RecordBatch>> {
+        self.execute_stream()?.collect()
+    }
+
+    /// Executes the plan and returns a stream that yields batches on demand.
+    pub fn execute_stream(&self) -> Result<RecordBatchStream> {
+        let optimized = Optimizer::new().optimize(&self.plan)?;
+        let physical_plan = Planner::new(&self.config).create_physical_plan(&optimized)?;
         physical_plan.execute()
     }
 }
@@ -1,5 +1,9 @@
-mod context;
+mod config;
+pub mod context;
 mod data_frame;
 mod planner;
 
+pub use config::*;
+pub use context::SessionContext;
 pub use data_frame::DataFrame;
+pub(crate) use planner::Planner;
@@ -1,73 +1,377 @@
 use std::sync::Arc;
 
-use arrow_schema::Schema;
-
-use crate::error::Result;
+use crate::datasource::project_schema;
+use crate::datatypes::operator::{Operator, UnaryOperator};
+use crate::error::{Result, internal_err, plan_err};
+use crate::execution::{OverflowMode, SessionConfig};
 use crate::logical::expression::expr::LogicalExpr;
+use crate::logical::expression::expr_fn::binary_expr;
+use crate::logical::expression::function::ScalarFunction;
+use crate::logical::schema::QualifiedSchema;
 use crate::physical::expr::PhysicalExpression;
+use crate::physical::expr::aggregate::AggregateExpr;
 use crate::physical::expr::binary::BinaryExpr;
+use crate::physical::expr::cast::{CastExpr, TryCastExpr};
 use crate::physical::expr::column::ColumnExpr;
+use crate::physical::expr::conditional::{CaseExpr, CoalesceExpr};
+use crate::physical::expr::function::ScalarFunctionExpr;
+use crate::physical::expr::in_list::InListExpr;
+use crate::physical::expr::like::LikeExpr;
 use crate::physical::expr::literal::LiteralExpr;
-use crate::physical::plan::{FilterExec, ProjectionExec, ScanExec};
+use crate::physical::expr::regexp::{PatternSyntax, RegexpExpr};
+use crate::physical::expr::unary::UnaryExpr;
+use crate::physical::expr::window::WindowExpr;
+use crate::physical::plan::{
+    FilterExec, HashAggregateExec, HashJoinExec, LimitExec, PhysicalSortExpr, ProjectionExec,
+    ScanExec, SortExec, WindowExec,
+};
 use crate::{logical::plan::LogicalPlan, physical::plan::PhysicalPlan};
 
-pub struct Planner;
+/// Turns an optimized [`LogicalPlan`] into an executable [`PhysicalPlan`].
+pub struct Planner<'a> {
+    config: &'a SessionConfig,
+}
+
+impl<'a> Planner<'a> {
+    pub fn new(config: &'a SessionConfig) -> Self {
+        Self { config }
+    }
 
-impl Planner {
-    pub fn create_physical_plan(plan: &LogicalPlan) -> Result<Arc<PhysicalPlan>> {
+    pub fn create_physical_plan(&self, plan: &LogicalPlan) -> Result<Arc<PhysicalPlan>> {
         let phy_plan = match plan {
-            LogicalPlan::Scan(scan) => PhysicalPlan::Scan(ScanExec::new(
-                scan.path.clone(),
-                scan.source.clone(),
-                scan.projection.clone(),
-            )),
+            LogicalPlan::Scan(scan) => {
+                let read_columns = scan.read_columns();
+                let read_schema = project_schema(&scan.source.schema(), read_columns.as_ref());
+                let read_schema =
+                    QualifiedSchema::from_schema(scan.qualifier.as_deref(), read_schema);
+                let filters = scan
+                    .filters
+                    .iter()
+                    .map(|e| self.create_physical_expr(&read_schema, e))
+                    .collect::<Result<_>>()?;
+                PhysicalPlan::Scan(ScanExec::new(
+                    scan.source.clone(),
+                    scan.projection.clone(),
+                    read_columns,
+                    filters,
+                    scan.fetch,
+                ))
+            }
             LogicalPlan::Projection(projection) => {
-                let input = Self::create_physical_plan(&projection.input)?;
-
-                let mut fields = Vec::with_capacity(projection.exprs.len());
-                let mut project_expr = Vec::with_capacity(projection.exprs.len());
-                for curr_expr in projection.exprs.iter() {
-                    let field = curr_expr.to_field(plan)?;
-                    let expr = Self::create_physical_expr(&projection.input.schema(), curr_expr);
-                    fields.push(field);
-                    project_expr.push(expr);
-                }
-                let schema = Arc::new(Schema::new(fields));
-
-                // TODO: 计算新的 schema
-                PhysicalPlan::Projection(ProjectionExec::new(input.clone(), schema, project_expr))
+                let input = self.create_physical_plan(&projection.input)?;
+                let input_schema = projection.input.schema();
+                let project_expr = projection
+                    .exprs
+                    .iter()
+                    .map(|e| self.create_physical_expr(&input_schema, e))
+                    .collect::<Result<_>>()?;
+                let schema = projection.schema.as_arrow().clone();
+                PhysicalPlan::Projection(ProjectionExec::new(input, schema, project_expr))
             }
             LogicalPlan::Filter(filter) => {
-                let input = Self::create_physical_plan(&filter.input)?;
-                let predicate =
-                    Self::create_physical_expr(&filter.input.schema(), &filter.predicate);
+                let input = self.create_physical_plan(&filter.input)?;
+                let input_schema = filter.input.schema();
+                let predicate = self.create_physical_expr(&input_schema, &filter.predicate)?;
                 PhysicalPlan::Filter(FilterExec::new(input, predicate))
             }
-            _ => unimplemented!(),
+            LogicalPlan::Aggregate(aggregate) => {
+                let input = self.create_physical_plan(&aggregate.input)?;
+                let input_schema = aggregate.input.schema();
+                let group_exprs = aggregate
+                    .group_exprs
+                    .iter()
+                    .map(|e| self.create_physical_expr(&input_schema, e))
+                    .collect::<Result<_>>()?;
+                let aggr_exprs = aggregate
+                    .aggr_exprs
+                    .iter()
+                    .map(|e| self.create_aggregate_expr(&input_schema, e))
+                    .collect::<Result<Vec<_>>>()?;
+                PhysicalPlan::HashAggregate(HashAggregateExec::new(
+                    input,
+                    group_exprs,
+                    aggr_exprs,
+                    aggregate.schema.as_arrow().clone(),
+                ))
+            }
+            LogicalPlan::Sort(sort) => {
+                let input = self.create_physical_plan(&sort.input)?;
+                let input_schema = sort.input.schema();
+                let exprs = sort
+                    .exprs
+                    .iter()
+                    .map(|e| {
+                        Ok(PhysicalSortExpr::new(
+                            self.create_physical_expr(&input_schema, &e.expr)?,
+                            e.asc,
+                            e.nulls_first,
+                        ))
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                PhysicalPlan::Sort(SortExec::new(input, exprs))
+            }
+            LogicalPlan::Limit(limit) => {
+                let input = self.create_physical_plan(&limit.input)?;
+                PhysicalPlan::Limit(LimitExec::new(input, limit.skip, limit.fetch))
+            }
+            LogicalPlan::Join(join) => {
+                let left = self.create_physical_plan(&join.left)?;
+                let right = self.create_physical_plan(&join.right)?;
+                let left_schema = join.left.schema();
+                let right_schema = join.right.schema();
+                let on = join
+                    .on
+                    .iter()
+                    .map(|(l, r)| {
+                        Ok((
+                            self.create_physical_expr(&left_schema, l)?,
+                            self.create_physical_expr(&right_schema, r)?,
+                        ))
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                // The filter sees the columns of both sides.
+                let filter_schema = left_schema.join(&right_schema)?;
+                let filter = join
+                    .filter
+                    .as_ref()
+                    .map(|e| self.create_physical_expr(&filter_schema, e))
+                    .transpose()?;
+                PhysicalPlan::HashJoin(HashJoinExec::new(
+                    left,
+                    right,
+                    on,
+                    filter,
+                    join.join_type,
+                    join.schema.as_arrow().clone(),
+                ))
+            }
+            LogicalPlan::Window(window) => {
+                let input = self.create_physical_plan(&window.input)?;
+                let input_schema = window.input.schema();
+                let window_exprs = window
+                    .window_exprs
+                    .iter()
+                    .map(|e| self.create_window_expr(&input_schema, e))
+                    .collect::<Result<Vec<_>>>()?;
+                PhysicalPlan::Window(WindowExec::new(
+                    input,
+                    window_exprs,
+                    window.schema.as_arrow().clone(),
+                ))
+            }
+            // Aliases only rename relations, which the physical plan does not
+            // know about.
+            LogicalPlan::SubqueryAlias(alias) => return self.create_physical_plan(&alias.input),
         };
         Ok(Arc::new(phy_plan))
     }
 
-    pub fn create_physical_expr(schema: &Schema, expr: &LogicalExpr) -> PhysicalExpression {
-        match expr {
+    pub fn create_physical_expr(
+        &self,
+        schema: &QualifiedSchema,
+        expr: &LogicalExpr,
+    ) -> Result<PhysicalExpression> {
+        Ok(match expr {
             LogicalExpr::Column(v) => {
-                let (index, _) = schema.column_with_name(&v.name).unwrap();
+                let index = schema.index_of_column(v)?;
                 PhysicalExpression::Column(ColumnExpr::new(v.name.clone(), index))
             }
             LogicalExpr::Literal(v) => PhysicalExpression::Literal(LiteralExpr::new(v.clone())),
             LogicalExpr::Binary(v) => {
-                let left = Self::create_physical_expr(schema, &v.lhs);
-                let right = Self::create_physical_expr(schema, &v.rhs);
-                PhysicalExpression::Binary(BinaryExpr::new(
-                    Arc::new(left),
-                    v.op.clone(),
-                    Arc::new(right),
+                let left = self.create_physical_expr(schema, &v.lhs)?;
+                let right = self.create_physical_expr(schema, &v.rhs)?;
+                let fail_on_overflow = self.config.overflow_mode() == OverflowMode::Checked;
+                PhysicalExpression::Binary(
+                    BinaryExpr::new(Arc::new(left), v.op.clone(), Arc::new(right))
+                        .with_fail_on_overflow(fail_on_overflow),
+                )
+            }
+            LogicalExpr::Unary(v) => {
+                let fail_on_overflow = self.config.overflow_mode() == OverflowMode::Checked;
+                PhysicalExpression::Unary(
+                    UnaryExpr::new(v.op, Arc::new(self.create_physical_expr(schema, &v.expr)?))
+                        .with_fail_on_overflow(fail_on_overflow),
+                )
+            }
+            LogicalExpr::InList(v) => {
+                let value = Arc::new(self.create_physical_expr(schema, &v.expr)?);
+                let literals = v
+                    .list
+                    .iter()
+                    .map(|e| match e.as_ref() {
+                        LogicalExpr::Literal(value) => Some(value.clone()),
+                        _ => None,
+                    })
+                    .collect::<Option<Vec<_>>>();
+                match literals {
+                    Some(literals) => PhysicalExpression::InList(InListExpr::try_new_with_values(
+                        value,
+                        &literals,
+                        v.expr.data_type(schema)?,
+                        v.negated,
+                    )?),
+                    None => PhysicalExpression::InList(InListExpr::new(
+                        value,
+                        v.list
+                            .iter()
+                            .map(|e| Ok(Arc::new(self.create_physical_expr(schema, e)?)))
+                            .collect::<Result<_>>()?,
+                        v.negated,
+                    )),
+                }
+            }
+            // Evaluated as `expr >= low AND expr <= high`, or
+            // `expr < low OR expr > high` when negated.
+            LogicalExpr::Between(v) => {
+                let (low_op, high_op, op) = if v.negated {
+                    (Operator::Lt, Operator::Gt, Operator::Or)
+                } else {
+                    (Operator::GtEq, Operator::LtEq, Operator::And)
+                };
+                let expr = binary_expr(
+                    binary_expr(v.expr.as_ref().clone(), low_op, v.low.as_ref().clone()),
+                    op,
+                    binary_expr(v.expr.as_ref().clone(), high_op, v.high.as_ref().clone()),
+                );
+                return self.create_physical_expr(schema, &expr);
+            }
+            LogicalExpr::Like(v) => PhysicalExpression::Like(LikeExpr::new(
+                v.negated,
+                v.case_insensitive,
+                Arc::new(self.create_physical_expr(schema, &v.expr)?),
+                Arc::new(self.create_physical_expr(schema, &v.pattern)?),
+                v.escape_char,
+            )),
+            LogicalExpr::SimilarTo(v) => {
+                let matches = PhysicalExpression::Regexp(RegexpExpr::try_new(
+                    ScalarFunction::RegexpLike,
+                    vec![
+                        Arc::new(self.create_physical_expr(schema, &v.expr)?),
+                        Arc::new(self.create_physical_expr(schema, &v.pattern)?),
+                    ],
+                    PatternSyntax::SimilarTo {
+                        escape_char: v.escape_char,
+                    },
+                )?);
+                if v.negated {
+                    PhysicalExpression::Unary(UnaryExpr::new(UnaryOperator::Not, Arc::new(matches)))
+                } else {
+                    matches
+                }
+            }
+            LogicalExpr::ScalarFunction(v) => {
+                let args = v
+                    .args
+                    .iter()
+                    .map(|e| Ok(Arc::new(self.create_physical_expr(schema, e)?)))
+                    .collect::<Result<_>>()?;
+                if v.fun.is_regexp() {
+                    PhysicalExpression::Regexp(RegexpExpr::try_new(
+                        v.fun,
+                        args,
+                        PatternSyntax::Regex,
+                    )?)
+                } else {
+                    PhysicalExpression::ScalarFunction(ScalarFunctionExpr::new(v.fun, args))
+                }
+            }
+            LogicalExpr::Cast(v) => PhysicalExpression::Cast(CastExpr::new(
+                Arc::new(self.create_physical_expr(schema, &v.expr)?),
+                v.data_type.clone(),
+            )),
+            LogicalExpr::TryCast(v) => PhysicalExpression::TryCast(TryCastExpr::new(
+                Arc::new(self.create_physical_expr(schema, &v.expr)?),
+                v.data_type.clone(),
+            )),
+            LogicalExpr::Alias(v) => return self.create_physical_expr(schema, &v.expr),
+            LogicalExpr::Case(v) => {
+                let create = |e: &LogicalExpr| -> Result<Arc<PhysicalExpression>> {
+                    Ok(Arc::new(self.create_physical_expr(schema, e)?))
+                };
+                let when_then = v
+                    .when_then
+                    .iter()
+                    .map(|(when, then)| Ok((create(when)?, create(then)?)))
+                    .collect::<Result<_>>()?;
+                PhysicalExpression::Case(CaseExpr::new(
+                    v.expr.as_deref().map(create).transpose()?,
+                    when_then,
+                    v.else_expr.as_deref().map(create).transpose()?,
+                    expr.data_type(schema)?,
                 ))
             }
-            _ => {
-                println!("expr: {:?}", expr);
-                todo!()
+            LogicalExpr::Coalesce(v) => PhysicalExpression::Coalesce(CoalesceExpr::new(
+                v.args
+                    .iter()
+                    .map(|e| Ok(Arc::new(self.create_physical_expr(schema, e)?)))
+                    .collect::<Result<_>>()?,
+            )),
+            LogicalExpr::Aggregate(_) => {
+                return plan_err!("aggregate expression {} is not allowed here", expr);
+            }
+            LogicalExpr::WindowFunction(_) => {
+                return plan_err!("window expression {} is not allowed here", expr);
             }
+        })
+    }
+
+    pub fn create_aggregate_expr(
+        &self,
+        schema: &QualifiedSchema,
+        expr: &LogicalExpr,
+    ) -> Result<AggregateExpr> {
+        match expr {
+            LogicalExpr::Aggregate(v) => Ok(AggregateExpr::new(
+                v.fun.clone(),
+                Arc::new(self.create_physical_expr(schema, &v.expr)?),
+                v.distinct,
+                v.expr.data_type(schema)?,
+            )),
+            _ => internal_err!("expected an aggregate expression, got {}", expr),
         }
     }
+
+    pub fn create_window_expr(
+        &self,
+        schema: &QualifiedSchema,
+        expr: &LogicalExpr,
+    ) -> Result<WindowExpr> {
+        let LogicalExpr::WindowFunction(v) = expr.unalias() else {
+            return internal_err!("expected a window expression, got {}", expr);
+        };
+        let args = v
+            .args
+            .iter()
+            .map(|e| Ok(Arc::new(self.create_physical_expr(schema, e)?)))
+            .collect::<Result<_>>()?;
+        let arg_types = v
+            .args
+            .iter()
+            .map(|e| e.data_type(schema))
+            .collect::<Result<_>>()?;
+        let partition_by = v
+            .partition_by
+            .iter()
+            .map(|e| self.create_physical_expr(schema, e))
+            .collect::<Result<_>>()?;
+        let order_by = v
+            .order_by
+            .iter()
+            .map(|e| {
+                Ok(PhysicalSortExpr::new(
+                    self.create_physical_expr(schema, &e.expr)?,
+                    e.asc,
+                    e.nulls_first,
+                ))
+            })
+            .collect::<Result<_>>()?;
+        WindowExpr::try_new(
+            v.fun.clone(),
+            args,
+            arg_types,
+            partition_by,
+            order_by,
+            v.frame,
+        )
+    }
 }
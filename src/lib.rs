@@ -1,9 +1,14 @@
-mod datasource;
+pub mod catalog;
+pub mod datasource;
 mod datatypes;
-mod error;
-mod execution;
-mod logical;
+pub mod error;
+pub mod execution;
+pub mod logical;
+mod optimizer;
 mod physical;
+mod sql;
+
+pub use physical::plan::RecordBatchStream;
 
 // use arrow::datatypes::DataType;
 pub fn add(left: u64, right: u64) -> u64 {
@@ -1,2 +1,93 @@
+use std::{fmt::Display, sync::Arc};
+
+use arrow_schema::{DECIMAL128_MAX_PRECISION, DECIMAL128_MAX_SCALE, DataType};
+
+use super::expr::LogicalExpr;
+use crate::error::{Result, plan_err};
+use crate::logical::schema::QualifiedSchema;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum AggregateFunction {
+    Count,
+    Sum,
+    Min,
+    Max,
+    Avg,
+}
+
+impl AggregateFunction {
+    /// Returns the result type of the function when applied to `input_type`.
+    /// Decimal sums get 10 more digits of precision, and decimal averages 4
+    /// more digits of precision and scale.
+    pub fn return_type(&self, input_type: &DataType) -> Result<DataType> {
+        use DataType::*;
+
+        match self {
+            AggregateFunction::Count => Ok(Int64),
+            AggregateFunction::Min | AggregateFunction::Max => Ok(input_type.clone()),
+            AggregateFunction::Sum => match input_type {
+                Int8 | Int16 | Int32 | Int64 => Ok(Int64),
+                UInt8 | UInt16 | UInt32 | UInt64 => Ok(UInt64),
+                Float32 | Float64 => Ok(Float64),
+                Decimal128(precision, scale) => Ok(Decimal128(
+                    (*precision + 10).min(DECIMAL128_MAX_PRECISION),
+                    *scale,
+                )),
+                other => plan_err!("SUM does not support type {}", other),
+            },
+            AggregateFunction::Avg => match input_type {
+                Int8 | Int16 | Int32 | Int64 | UInt8 | UInt16 | UInt32 | UInt64 | Float32
+                | Float64 => Ok(Float64),
+                Decimal128(precision, scale) => Ok(Decimal128(
+                    (*precision + 4).min(DECIMAL128_MAX_PRECISION),
+                    (*scale + 4).min(DECIMAL128_MAX_SCALE),
+                )),
+                other => plan_err!("AVG does not support type {}", other),
+            },
+        }
+    }
+}
+
+impl Display for AggregateFunction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AggregateFunction::Count => write!(f, "COUNT"),
+            AggregateFunction::Sum => write!(f, "SUM"),
+            AggregateFunction::Min => write!(f, "MIN"),
+            AggregateFunction::Max => write!(f, "MAX"),
+            AggregateFunction::Avg => write!(f, "AVG"),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
-pub struct AggregateExpr {}
+pub struct AggregateExpr {
+    pub fun: AggregateFunction,
+    pub expr: Arc<LogicalExpr>,
+    /// Whether only distinct input values are aggregated.
+    pub distinct: bool,
+}
+
+impl AggregateExpr {
+    pub fn new(fun: AggregateFunction, expr: Arc<LogicalExpr>, distinct: bool) -> Self {
+        Self {
+            fun,
+            expr,
+            distinct,
+        }
+    }
+
+    pub fn data_type(&self, schema: &QualifiedSchema) -> Result<DataType> {
+        self.fun.return_type(&self.expr.data_type(schema)?)
+    }
+}
+
+impl Display for AggregateExpr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.distinct {
+            write!(f, "{}(DISTINCT {})", self.fun, self.expr)
+        } else {
+            write!(f, "{}({})", self.fun, self.expr)
+        }
+    }
+}
@@ -0,0 +1,25 @@
+use std::{fmt::Display, sync::Arc};
+
+use super::expr::LogicalExpr;
+
+/// Names the value of `expr`, e.g. `c3 + 1 AS total`.
+#[derive(Debug, Clone)]
+pub struct Alias {
+    pub expr: Arc<LogicalExpr>,
+    pub name: String,
+}
+
+impl Alias {
+    pub fn new(expr: Arc<LogicalExpr>, name: impl Into<String>) -> Self {
+        Self {
+            expr,
+            name: name.into(),
+        }
+    }
+}
+
+impl Display for Alias {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} AS {}", self.expr, self.name)
+    }
+}
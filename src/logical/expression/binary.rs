@@ -2,7 +2,7 @@ use std::{fmt::Display, sync::Arc};
 
 use crate::datatypes::operator::Operator;
 
-use super::expr::LogicalExpr;
+use super::expr::{LogicalExpr, format_operand};
 
 #[derive(Debug, Clone)]
 pub struct BinaryExpr {
@@ -15,10 +15,23 @@ impl BinaryExpr {
     pub fn new(lhs: Arc<LogicalExpr>, op: Operator, rhs: Arc<LogicalExpr>) -> Self {
         Self { lhs, op, rhs }
     }
+
+    /// Formats the expression, rendering the operands with `f`. Operators
+    /// are left-associative, so `(a - b) - c` prints as `a - b - c` and
+    /// `a - (b - c)` keeps its parentheses.
+    pub(crate) fn format(&self, f: impl Fn(&LogicalExpr) -> String) -> String {
+        let precedence = self.op.precedence();
+        format!(
+            "{} {} {}",
+            format_operand(&self.lhs, precedence, &f),
+            self.op,
+            format_operand(&self.rhs, precedence + 1, &f)
+        )
+    }
 }
 
 impl Display for BinaryExpr {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{} {} {}", self.lhs, self.op, self.rhs)
+        write!(f, "{}", self.format(|e| e.to_string()))
     }
 }
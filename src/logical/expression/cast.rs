@@ -0,0 +1,59 @@
+use std::{fmt::Display, sync::Arc};
+
+use arrow_schema::DataType;
+
+use super::expr::LogicalExpr;
+
+/// Converts the value of `expr` to `data_type`.
+#[derive(Debug, Clone)]
+pub struct Cast {
+    pub expr: Arc<LogicalExpr>,
+    pub data_type: DataType,
+    /// Whether the cast was inserted by type coercion rather than written in
+    /// the query. Implicit casts are left out of column names.
+    pub implicit: bool,
+}
+
+impl Cast {
+    pub fn new(expr: Arc<LogicalExpr>, data_type: DataType) -> Self {
+        Self {
+            expr,
+            data_type,
+            implicit: false,
+        }
+    }
+
+    pub fn new_implicit(expr: Arc<LogicalExpr>, data_type: DataType) -> Self {
+        Self {
+            expr,
+            data_type,
+            implicit: true,
+        }
+    }
+}
+
+impl Display for Cast {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "CAST({} AS {})", self.expr, self.data_type)
+    }
+}
+
+/// Like [`Cast`], but values that cannot be converted become NULL instead of
+/// failing the query.
+#[derive(Debug, Clone)]
+pub struct TryCast {
+    pub expr: Arc<LogicalExpr>,
+    pub data_type: DataType,
+}
+
+impl TryCast {
+    pub fn new(expr: Arc<LogicalExpr>, data_type: DataType) -> Self {
+        Self { expr, data_type }
+    }
+}
+
+impl Display for TryCast {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "TRY_CAST({} AS {})", self.expr, self.data_type)
+    }
+}
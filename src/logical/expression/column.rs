@@ -1,30 +1,55 @@
 use std::fmt::Display;
 
-use crate::{error::Result, logical::plan::LogicalPlan};
-use arrow_schema::{Field, Schema};
-#[derive(Debug, Clone)]
+use crate::{error::Result, logical::schema::QualifiedSchema};
+use arrow_schema::Field;
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Column {
+    /// The relation the column belongs to, e.g. `t` in `t.c1`. Unqualified
+    /// columns match a field of any relation.
+    pub relation: Option<String>,
     pub name: String,
 }
 
 impl Column {
     pub fn new(name: impl Into<String>) -> Self {
-        Self { name: name.into() }
+        Self {
+            relation: None,
+            name: name.into(),
+        }
     }
 
-    /// Resolves this column to its [`Field`] definition from a schema.
-    pub fn to_field(&self, schema: &Schema) -> Result<Field> {
-        let (_, field) = schema.column_with_name(&self.name).unwrap();
-        Ok(field.clone())
+    pub fn new_qualified(relation: Option<impl Into<String>>, name: impl Into<String>) -> Self {
+        Self {
+            relation: relation.map(Into::into),
+            name: name.into(),
+        }
+    }
+
+    /// Parses `t.c1` into a column qualified by `t`, anything else into an
+    /// unqualified column.
+    pub fn from_qualified_name(name: impl Into<String>) -> Self {
+        let name = name.into();
+        let is_ident =
+            |s: &str| !s.is_empty() && s.chars().all(|c| c.is_alphanumeric() || c == '_');
+        match name.split_once('.') {
+            Some((relation, column)) if is_ident(relation) && is_ident(column) => {
+                Self::new_qualified(Some(relation), column)
+            }
+            _ => Self::new(name),
+        }
     }
 
-    pub fn to_field_from_plan(&self, plan: &LogicalPlan) -> Result<Field> {
-        self.to_field(&plan.schema())
+    /// Resolves this column to its [`Field`] definition from a schema.
+    pub fn to_field(&self, schema: &QualifiedSchema) -> Result<Field> {
+        schema.field_from_column(self).cloned()
     }
 }
 
 impl Display for Column {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.name)
+        match &self.relation {
+            Some(relation) => write!(f, "{}.{}", relation, self.name),
+            None => write!(f, "{}", self.name),
+        }
     }
 }
@@ -0,0 +1,73 @@
+use std::{fmt::Display, sync::Arc};
+
+use super::expr::LogicalExpr;
+
+/// `CASE [expr] WHEN when THEN then ... [ELSE else_expr] END`.
+///
+/// Without `expr` every `when` is a boolean condition; with it the first
+/// `when` equal to `expr` selects its `then`. Rows no branch selects get
+/// `else_expr`, or NULL when there is none.
+#[derive(Debug, Clone)]
+pub struct Case {
+    pub expr: Option<Arc<LogicalExpr>>,
+    pub when_then: Vec<(Arc<LogicalExpr>, Arc<LogicalExpr>)>,
+    pub else_expr: Option<Arc<LogicalExpr>>,
+}
+
+impl Case {
+    pub fn new(
+        expr: Option<Arc<LogicalExpr>>,
+        when_then: Vec<(Arc<LogicalExpr>, Arc<LogicalExpr>)>,
+        else_expr: Option<Arc<LogicalExpr>>,
+    ) -> Self {
+        Self {
+            expr,
+            when_then,
+            else_expr,
+        }
+    }
+
+    /// Formats the expression, rendering every sub-expression with `f`.
+    pub(crate) fn format(&self, f: impl Fn(&LogicalExpr) -> String) -> String {
+        let mut s = "CASE".to_string();
+        if let Some(expr) = &self.expr {
+            s += &format!(" {}", f(expr));
+        }
+        for (when, then) in self.when_then.iter() {
+            s += &format!(" WHEN {} THEN {}", f(when), f(then));
+        }
+        if let Some(else_expr) = &self.else_expr {
+            s += &format!(" ELSE {}", f(else_expr));
+        }
+        s + " END"
+    }
+}
+
+impl Display for Case {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.format(|e| e.to_string()))
+    }
+}
+
+/// Returns the first of `args` that is not NULL.
+#[derive(Debug, Clone)]
+pub struct Coalesce {
+    pub args: Vec<Arc<LogicalExpr>>,
+}
+
+impl Coalesce {
+    pub fn new(args: Vec<Arc<LogicalExpr>>) -> Self {
+        Self { args }
+    }
+
+    pub(crate) fn format(&self, f: impl Fn(&LogicalExpr) -> String) -> String {
+        let args = self.args.iter().map(|e| f(e)).collect::<Vec<_>>();
+        format!("COALESCE({})", args.join(", "))
+    }
+}
+
+impl Display for Coalesce {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.format(|e| e.to_string()))
+    }
+}
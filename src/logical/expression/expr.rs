@@ -1,69 +1,440 @@
-use std::fmt::Display;
+use std::{fmt::Display, sync::Arc};
 
-use anyhow::Ok;
-use arrow_schema::{DataType, Field, Schema};
+use arrow_schema::{DataType, Field};
 
-use super::{aggregate::AggregateExpr, binary::BinaryExpr, column::Column};
-use crate::datatypes::values::ScalarValue;
-use crate::error::Result;
-use crate::logical::plan::LogicalPlan;
+use super::{
+    aggregate::{AggregateExpr, AggregateFunction},
+    alias::Alias,
+    binary::BinaryExpr,
+    cast::{Cast, TryCast},
+    column::Column,
+    conditional::{Case, Coalesce},
+    function::ScalarFunctionExpr,
+    like::Like,
+    predicate::{Between, InList},
+    unary::UnaryExpr,
+    window::WindowExpr,
+};
+use crate::datatypes::{
+    coercion::{
+        binary_result_type, common_type, comparison_operand_type, string_type, unary_result_type,
+    },
+    operator::{Operator, UnaryOperator},
+    values::ScalarValue,
+};
+use crate::error::{Result, plan_err};
+use crate::logical::schema::QualifiedSchema;
+
+/// Precedence of `BETWEEN` and `IN`, on the scale of
+/// [`Operator::precedence`].
+pub(crate) const BETWEEN_PRECEDENCE: u8 = 20;
+/// Precedence of `LIKE` and `SIMILAR TO`.
+pub(crate) const LIKE_PRECEDENCE: u8 = 19;
+
+/// Renders `operand` with `f`, in parentheses if it binds more loosely than
+/// `precedence`. Differently nested expressions thus never print the same.
+pub(crate) fn format_operand(
+    operand: &LogicalExpr,
+    precedence: u8,
+    f: &impl Fn(&LogicalExpr) -> String,
+) -> String {
+    if operand.precedence() < precedence {
+        format!("({})", f(operand))
+    } else {
+        f(operand)
+    }
+}
 
 #[derive(Debug, Clone)]
 pub enum LogicalExpr {
     Column(Column),
     Literal(ScalarValue),
     Binary(BinaryExpr),
+    Unary(UnaryExpr),
+    InList(InList),
+    Between(Between),
+    Like(Like),
+    SimilarTo(Like),
+    ScalarFunction(ScalarFunctionExpr),
+    Cast(Cast),
+    TryCast(TryCast),
     Aggregate(AggregateExpr),
+    Alias(Alias),
+    Case(Case),
+    Coalesce(Coalesce),
+    WindowFunction(WindowExpr),
 }
 
 impl Display for LogicalExpr {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             LogicalExpr::Column(column) => write!(f, "{}", column),
+            // Strings are quoted and floats keep their fraction, so literals
+            // never print like columns or integers.
+            LogicalExpr::Literal(ScalarValue::String(Some(s))) => {
+                write!(f, "'{}'", s.replace('\'', "''"))
+            }
+            LogicalExpr::Literal(ScalarValue::Float32(Some(v))) => write!(f, "{:?}", v),
+            LogicalExpr::Literal(ScalarValue::Float64(Some(v))) => write!(f, "{:?}", v),
             LogicalExpr::Literal(literal) => write!(f, "{}", literal),
             LogicalExpr::Binary(binary) => write!(f, "{}", binary),
-            // LogicExpr::Aggregate(aggregate) => write!(f, "{}", aggregate),
-            _ => todo!(),
+            LogicalExpr::Unary(unary) => write!(f, "{}", unary),
+            LogicalExpr::InList(in_list) => write!(f, "{}", in_list),
+            LogicalExpr::Between(between) => write!(f, "{}", between),
+            LogicalExpr::Like(like) => {
+                write!(f, "{}", like.format(like.like_op(), |e| e.to_string()))
+            }
+            LogicalExpr::SimilarTo(like) => {
+                write!(f, "{}", like.format("SIMILAR TO", |e| e.to_string()))
+            }
+            LogicalExpr::ScalarFunction(fun) => write!(f, "{}", fun),
+            LogicalExpr::Cast(cast) => write!(f, "{}", cast),
+            LogicalExpr::TryCast(cast) => write!(f, "{}", cast),
+            LogicalExpr::Aggregate(aggregate) => write!(f, "{}", aggregate),
+            LogicalExpr::Alias(alias) => write!(f, "{}", alias),
+            LogicalExpr::Case(case) => write!(f, "{}", case),
+            LogicalExpr::Coalesce(coalesce) => write!(f, "{}", coalesce),
+            LogicalExpr::WindowFunction(window) => write!(f, "{}", window),
         }
     }
 }
 
 impl LogicalExpr {
-    pub fn to_field(&self, plan: &LogicalPlan) -> Result<Field> {
-        let data_type = self.data_type(&plan.schema())?;
-        Ok(Field::new(self.to_string(), data_type, true))
-        // match self {
-        //     LogicalExpr::Column(e) => e.to_field_from_plan(plan),
-        //     LogicalExpr::Literal(e) => {
-        //         let data_type = self.data_type(&plan.schema())?;
-        //         Ok(Field::new(self.to_string(), data_type, true))
-        //     }
-        //     LogicalExpr::Binary(e)
-        //     _ => unimplemented!(),
-        // }
+    /// Returns the field the expression produces when evaluated on `schema`,
+    /// along with its qualifier. A bare column keeps the qualifier of the
+    /// field it refers to; computed and aliased values are unqualified.
+    pub fn to_qualified_field(&self, schema: &QualifiedSchema) -> Result<(Option<String>, Field)> {
+        if let LogicalExpr::Column(column) = self {
+            let index = schema.index_of_column(column)?;
+            let qualifier = schema.qualifier(index).map(String::from);
+            return Ok((qualifier, schema.field(index).clone()));
+        }
+        let field = Field::new(self.name(), self.data_type(schema)?, self.nullable(schema)?);
+        Ok((None, field))
+    }
+
+    /// Returns the name of the column produced by the expression. It matches
+    /// the `Display` output except that implicit casts and column qualifiers
+    /// are left out, so type coercion does not rename columns. An aliased
+    /// expression is named by its alias.
+    pub fn name(&self) -> String {
+        match self {
+            LogicalExpr::Column(e) => e.name.clone(),
+            LogicalExpr::Alias(e) => e.name.clone(),
+            LogicalExpr::Binary(e) => e.format(|e| e.name()),
+            LogicalExpr::Unary(e) => e.format(|e| e.name()),
+            LogicalExpr::InList(e) => e.format(|e| e.name()),
+            LogicalExpr::Between(e) => e.format(|e| e.name()),
+            LogicalExpr::Like(e) => e.format(e.like_op(), |e| e.name()),
+            LogicalExpr::SimilarTo(e) => e.format("SIMILAR TO", |e| e.name()),
+            LogicalExpr::ScalarFunction(e) => e.format(|e| e.name()),
+            LogicalExpr::Cast(e) if e.implicit => e.expr.name(),
+            LogicalExpr::Cast(e) => format!("CAST({} AS {})", e.expr.name(), e.data_type),
+            LogicalExpr::TryCast(e) => format!("TRY_CAST({} AS {})", e.expr.name(), e.data_type),
+            LogicalExpr::Aggregate(e) if e.distinct => {
+                format!("{}(DISTINCT {})", e.fun, e.expr.name())
+            }
+            LogicalExpr::Aggregate(e) => format!("{}({})", e.fun, e.expr.name()),
+            LogicalExpr::Case(e) => e.format(|e| e.name()),
+            LogicalExpr::Coalesce(e) => e.format(|e| e.name()),
+            LogicalExpr::WindowFunction(e) => e.format(|e| e.name()),
+            _ => self.to_string(),
+        }
+    }
+
+    /// Returns how tightly the outermost operator of the expression binds.
+    /// Function calls and other bracketed forms never need parentheses.
+    /// Implicit casts take the precedence of their operand, which names
+    /// print without the cast.
+    fn precedence(&self) -> u8 {
+        match self {
+            LogicalExpr::Cast(e) if e.implicit => e.expr.precedence(),
+            LogicalExpr::Binary(e) => e.op.precedence(),
+            LogicalExpr::Unary(e) => e.op.precedence(),
+            LogicalExpr::InList(_) | LogicalExpr::Between(_) => BETWEEN_PRECEDENCE,
+            LogicalExpr::Like(_) | LogicalExpr::SimilarTo(_) => LIKE_PRECEDENCE,
+            _ => u8::MAX,
+        }
+    }
+
+    /// Returns the direct children of the expression.
+    pub fn children(&self) -> Vec<&LogicalExpr> {
+        match self {
+            LogicalExpr::Column(_) | LogicalExpr::Literal(_) => vec![],
+            LogicalExpr::Binary(e) => vec![&e.lhs, &e.rhs],
+            LogicalExpr::Unary(e) => vec![&e.expr],
+            LogicalExpr::InList(e) => {
+                let mut children = vec![e.expr.as_ref()];
+                children.extend(e.list.iter().map(|e| e.as_ref()));
+                children
+            }
+            LogicalExpr::Between(e) => vec![&e.expr, &e.low, &e.high],
+            LogicalExpr::Like(e) | LogicalExpr::SimilarTo(e) => vec![&e.expr, &e.pattern],
+            LogicalExpr::ScalarFunction(e) => e.args.iter().map(|e| e.as_ref()).collect(),
+            LogicalExpr::Cast(e) => vec![&e.expr],
+            LogicalExpr::TryCast(e) => vec![&e.expr],
+            LogicalExpr::Aggregate(e) => vec![&e.expr],
+            LogicalExpr::Alias(e) => vec![&e.expr],
+            LogicalExpr::Case(e) => {
+                let mut children = e.expr.iter().map(|e| e.as_ref()).collect::<Vec<_>>();
+                for (when, then) in e.when_then.iter() {
+                    children.push(when);
+                    children.push(then);
+                }
+                children.extend(e.else_expr.iter().map(|e| e.as_ref()));
+                children
+            }
+            LogicalExpr::Coalesce(e) => e.args.iter().map(|e| e.as_ref()).collect(),
+            LogicalExpr::WindowFunction(e) => e
+                .args
+                .iter()
+                .chain(e.partition_by.iter())
+                .map(|e| e.as_ref())
+                .chain(e.order_by.iter().map(|e| e.expr.as_ref()))
+                .collect(),
+        }
+    }
+
+    /// Returns the columns referenced by the expression.
+    pub fn columns(&self) -> Vec<&Column> {
+        match self {
+            LogicalExpr::Column(column) => vec![column],
+            _ => self
+                .children()
+                .into_iter()
+                .flat_map(|e| e.columns())
+                .collect(),
+        }
+    }
+
+    /// Returns whether the expression calls a function, such as `random()`,
+    /// whose value differs every time it is evaluated.
+    pub fn is_volatile(&self) -> bool {
+        match self {
+            LogicalExpr::ScalarFunction(e) if e.fun.is_volatile() => true,
+            _ => self.children().into_iter().any(|e| e.is_volatile()),
+        }
+    }
+
+    /// Rewrites the expression top-down. `f` is called on every node first and
+    /// the node is replaced by its result; children are only visited when `f`
+    /// returns `None`.
+    pub fn transform(&self, f: &impl Fn(&LogicalExpr) -> Option<LogicalExpr>) -> LogicalExpr {
+        match f(self) {
+            Some(expr) => expr,
+            None => self.map_children(|e| e.transform(f)),
+        }
+    }
+
+    /// Rewrites the expression bottom-up. Children are rewritten first, then
+    /// `f` is called on the rebuilt node, which is kept when `f` returns
+    /// `None`.
+    pub fn transform_up(&self, f: &impl Fn(&LogicalExpr) -> Option<LogicalExpr>) -> LogicalExpr {
+        let expr = self.map_children(|e| e.transform_up(f));
+        f(&expr).unwrap_or(expr)
+    }
+
+    /// Returns a copy of the expression whose direct children are replaced by
+    /// `f(child)`.
+    pub fn map_children(&self, f: impl Fn(&LogicalExpr) -> LogicalExpr) -> LogicalExpr {
+        match self {
+            LogicalExpr::Column(_) | LogicalExpr::Literal(_) => self.clone(),
+            LogicalExpr::Binary(e) => LogicalExpr::Binary(BinaryExpr::new(
+                Arc::new(f(&e.lhs)),
+                e.op.clone(),
+                Arc::new(f(&e.rhs)),
+            )),
+            LogicalExpr::Unary(e) => LogicalExpr::Unary(UnaryExpr::new(e.op, Arc::new(f(&e.expr)))),
+            LogicalExpr::InList(e) => LogicalExpr::InList(InList::new(
+                Arc::new(f(&e.expr)),
+                e.list.iter().map(|e| Arc::new(f(e))).collect(),
+                e.negated,
+            )),
+            LogicalExpr::Between(e) => LogicalExpr::Between(Between::new(
+                Arc::new(f(&e.expr)),
+                e.negated,
+                Arc::new(f(&e.low)),
+                Arc::new(f(&e.high)),
+            )),
+            LogicalExpr::Like(e) => LogicalExpr::Like(e.map_operands(f)),
+            LogicalExpr::SimilarTo(e) => LogicalExpr::SimilarTo(e.map_operands(f)),
+            LogicalExpr::ScalarFunction(e) => LogicalExpr::ScalarFunction(ScalarFunctionExpr::new(
+                e.fun,
+                e.args.iter().map(|e| Arc::new(f(e))).collect(),
+            )),
+            LogicalExpr::Cast(e) => LogicalExpr::Cast(Cast {
+                expr: Arc::new(f(&e.expr)),
+                ..e.clone()
+            }),
+            LogicalExpr::TryCast(e) => {
+                LogicalExpr::TryCast(TryCast::new(Arc::new(f(&e.expr)), e.data_type.clone()))
+            }
+            LogicalExpr::Aggregate(e) => LogicalExpr::Aggregate(AggregateExpr::new(
+                e.fun.clone(),
+                Arc::new(f(&e.expr)),
+                e.distinct,
+            )),
+            LogicalExpr::Alias(e) => {
+                LogicalExpr::Alias(Alias::new(Arc::new(f(&e.expr)), e.name.clone()))
+            }
+            LogicalExpr::Case(e) => LogicalExpr::Case(Case::new(
+                e.expr.as_ref().map(|e| Arc::new(f(e))),
+                e.when_then
+                    .iter()
+                    .map(|(when, then)| (Arc::new(f(when)), Arc::new(f(then))))
+                    .collect(),
+                e.else_expr.as_ref().map(|e| Arc::new(f(e))),
+            )),
+            LogicalExpr::Coalesce(e) => LogicalExpr::Coalesce(Coalesce::new(
+                e.args.iter().map(|e| Arc::new(f(e))).collect(),
+            )),
+            LogicalExpr::WindowFunction(e) => LogicalExpr::WindowFunction(WindowExpr::new(
+                e.fun.clone(),
+                e.args.iter().map(|e| Arc::new(f(e))).collect(),
+                e.partition_by.iter().map(|e| Arc::new(f(e))).collect(),
+                e.order_by.iter().map(|e| e.with_expr(f(&e.expr))).collect(),
+                e.frame,
+            )),
+        }
     }
 
     /// Returns the [`DataType`] of the expression.
-    pub fn data_type(&self, schema: &Schema) -> Result<DataType> {
+    pub fn data_type(&self, schema: &QualifiedSchema) -> Result<DataType> {
         match self {
             LogicalExpr::Column(e) => Ok(e.to_field(schema)?.data_type().clone()),
             LogicalExpr::Literal(e) => Ok(e.data_type()),
             LogicalExpr::Binary(e) => {
-                // TODO: 临时以左边为准
-                let lhs = e.lhs.data_type(schema)?;
-                Ok(lhs)
+                binary_result_type(&e.lhs.data_type(schema)?, &e.op, &e.rhs.data_type(schema)?)
             }
-            _ => unimplemented!(),
+            LogicalExpr::Unary(e) => unary_result_type(e.op, &e.expr.data_type(schema)?),
+            LogicalExpr::InList(_) | LogicalExpr::Between(_) => {
+                // Fails if the operands cannot be compared with each other.
+                let types = self
+                    .children()
+                    .into_iter()
+                    .map(|e| e.data_type(schema))
+                    .collect::<Result<Vec<_>>>()?;
+                comparison_operand_type(&types)?;
+                Ok(DataType::Boolean)
+            }
+            LogicalExpr::Like(e) | LogicalExpr::SimilarTo(e) => {
+                let expr_type = e.expr.data_type(schema)?;
+                let pattern_type = e.pattern.data_type(schema)?;
+                if string_type(&expr_type, &pattern_type).is_none() {
+                    return plan_err!(
+                        "cannot apply '{}' to {} and {}",
+                        self,
+                        expr_type,
+                        pattern_type
+                    );
+                }
+                Ok(DataType::Boolean)
+            }
+            LogicalExpr::ScalarFunction(e) => e.data_type(schema),
+            LogicalExpr::Cast(e) => Ok(e.data_type.clone()),
+            LogicalExpr::TryCast(e) => Ok(e.data_type.clone()),
+            LogicalExpr::Aggregate(e) => e.data_type(schema),
+            LogicalExpr::Alias(e) => e.expr.data_type(schema),
+            LogicalExpr::Case(e) => {
+                let values = e.when_then.iter().map(|(_, then)| then).chain(&e.else_expr);
+                common_value_type(self, values, schema)
+            }
+            LogicalExpr::Coalesce(e) => common_value_type(self, &e.args, schema),
+            LogicalExpr::WindowFunction(e) => e.data_type(schema),
         }
     }
+
+    /// Returns whether the expression may evaluate to NULL.
+    pub fn nullable(&self, schema: &QualifiedSchema) -> Result<bool> {
+        match self {
+            LogicalExpr::Column(e) => Ok(e.to_field(schema)?.is_nullable()),
+            LogicalExpr::Literal(e) => Ok(e.is_null()),
+            LogicalExpr::Binary(e) => match e.op {
+                Operator::IsDistinctFrom | Operator::IsNotDistinctFrom => Ok(false),
+                _ => Ok(e.lhs.nullable(schema)? || e.rhs.nullable(schema)?),
+            },
+            LogicalExpr::Unary(e) => match e.op {
+                UnaryOperator::Not | UnaryOperator::Negative => e.expr.nullable(schema),
+                _ => Ok(false),
+            },
+            LogicalExpr::InList(_)
+            | LogicalExpr::Between(_)
+            | LogicalExpr::Like(_)
+            | LogicalExpr::SimilarTo(_)
+            | LogicalExpr::ScalarFunction(_) => {
+                for child in self.children() {
+                    if child.nullable(schema)? {
+                        return Ok(true);
+                    }
+                }
+                Ok(false)
+            }
+            LogicalExpr::Cast(e) => e.expr.nullable(schema),
+            LogicalExpr::TryCast(_) => Ok(true),
+            // COUNT never produces NULL, every other aggregate does on empty input.
+            LogicalExpr::Aggregate(e) => Ok(e.fun != AggregateFunction::Count),
+            LogicalExpr::Alias(e) => e.expr.nullable(schema),
+            LogicalExpr::Case(e) => {
+                if e.else_expr.is_none() {
+                    return Ok(true);
+                }
+                let values = e.when_then.iter().map(|(_, then)| then).chain(&e.else_expr);
+                for value in values {
+                    if value.nullable(schema)? {
+                        return Ok(true);
+                    }
+                }
+                Ok(false)
+            }
+            LogicalExpr::Coalesce(e) => {
+                for arg in e.args.iter() {
+                    if !arg.nullable(schema)? {
+                        return Ok(false);
+                    }
+                }
+                Ok(true)
+            }
+            LogicalExpr::WindowFunction(e) => Ok(e.nullable()),
+        }
+    }
+
+    /// Strips any aliases around the expression.
+    pub fn unalias(&self) -> &LogicalExpr {
+        match self {
+            LogicalExpr::Alias(e) => e.expr.unalias(),
+            _ => self,
+        }
+    }
+}
+
+/// Returns the type the `values` of the conditional `expr` are combined into.
+fn common_value_type<'a>(
+    expr: &LogicalExpr,
+    values: impl IntoIterator<Item = &'a Arc<LogicalExpr>>,
+    schema: &QualifiedSchema,
+) -> Result<DataType> {
+    let types = values
+        .into_iter()
+        .map(|e| e.data_type(schema))
+        .collect::<Result<Vec<_>>>()?;
+    match common_type(&types) {
+        Some(data_type) => Ok(data_type),
+        None => plan_err!(
+            "{} has incompatible result types: {}",
+            expr,
+            types
+                .iter()
+                .map(|t| t.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    }
 }
 #[cfg(test)]
 mod tests {
-    use std::sync::Arc;
-
-    use crate::datatypes::operator::Operator;
-
     use super::*;
+    use crate::logical::expression::expr_fn::{col, lit};
+
     #[test]
     fn test_expr_display() {
         let expr = LogicalExpr::Column(Column::new("a".to_string()));
@@ -81,5 +452,31 @@ mod tests {
             Arc::new(LogicalExpr::Literal(ScalarValue::Int32(Some(1)))),
         ));
         assert_eq!(expr.to_string(), "a = 1");
+
+        let expr = col("a")
+            .is_null()
+            .or((-col("b")).not_between(lit(1), lit(2)));
+        assert_eq!(expr.to_string(), "a IS NULL OR -b NOT BETWEEN 1 AND 2");
+
+        let expr = (!col("t.a").in_list(vec![lit(1), lit(2)], true)).is_not_true();
+        assert_eq!(expr.to_string(), "(NOT t.a NOT IN (1, 2)) IS NOT TRUE");
+        assert_eq!(expr.name(), "(NOT a NOT IN (1, 2)) IS NOT TRUE");
+
+        // Operands that bind more loosely than their operator keep their
+        // parentheses.
+        let expr = col("a").plus(col("b")).multiply(lit(2));
+        assert_eq!(expr.to_string(), "(a + b) * 2");
+        let expr = col("a").plus(col("b").multiply(lit(2)));
+        assert_eq!(expr.to_string(), "a + b * 2");
+        let expr = -col("a").minus(col("b").minus(col("c")));
+        assert_eq!(expr.name(), "-(a - (b - c))");
+
+        // Only casts inserted by type coercion are left out of names.
+        let sum = col("a").plus(col("b"));
+        let expr = sum.clone().cast_to(DataType::Int64).multiply(lit(2));
+        assert_eq!(expr.name(), "CAST(a + b AS Int64) * 2");
+        let expr =
+            LogicalExpr::Cast(Cast::new_implicit(Arc::new(sum), DataType::Int64)).multiply(lit(2));
+        assert_eq!(expr.name(), "(a + b) * 2");
     }
 }
@@ -1,11 +1,37 @@
-use std::sync::Arc;
+use std::{
+    ops::{Neg, Not},
+    sync::Arc,
+};
 
-use super::{binary::BinaryExpr, column::Column, expr::LogicalExpr};
-use crate::datatypes::operator::Operator;
+use arrow::{
+    compute::kernels::cast_utils::parse_decimal,
+    datatypes::{Date32Type, Decimal128Type, IntervalMonthDayNano},
+};
+use arrow_schema::{DataType, TimeUnit};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Timelike, Utc};
+
+use super::{
+    aggregate::{AggregateExpr, AggregateFunction},
+    alias::Alias,
+    binary::BinaryExpr,
+    cast::{Cast, TryCast},
+    column::Column,
+    conditional::{Case, Coalesce},
+    expr::LogicalExpr,
+    function::{ScalarFunction, ScalarFunctionExpr},
+    like::Like,
+    predicate::{Between, InList},
+    sort::SortExpr,
+    unary::UnaryExpr,
+    window::{WindowExpr, WindowFrame, WindowFunction},
+};
+use crate::datatypes::operator::{Operator, UnaryOperator};
 use crate::datatypes::values::ScalarValue;
+use crate::error::{DBError, Result};
 
+/// References a column; `t.c1` refers to column `c1` of relation `t`.
 pub fn col(name: impl Into<String>) -> LogicalExpr {
-    LogicalExpr::Column(Column::new(name))
+    LogicalExpr::Column(Column::from_qualified_name(name))
 }
 
 pub fn lit<T: LiteralExt>(value: T) -> LogicalExpr {
@@ -33,6 +59,86 @@ make_lit!(u8, Uint8);
 make_lit!(u16, Uint16);
 make_lit!(u32, Uint32);
 make_lit!(u64, Uint64);
+make_lit!(f32, Float32);
+make_lit!(f64, Float64);
+make_lit!(bool, Boolean);
+
+impl LiteralExt for &str {
+    fn lit(&self) -> LogicalExpr {
+        LogicalExpr::Literal(ScalarValue::String(Some(self.to_string())))
+    }
+}
+
+impl LiteralExt for String {
+    fn lit(&self) -> LogicalExpr {
+        LogicalExpr::Literal(ScalarValue::String(Some(self.clone())))
+    }
+}
+
+impl LiteralExt for NaiveDate {
+    fn lit(&self) -> LogicalExpr {
+        LogicalExpr::Literal(ScalarValue::Date32(Some(Date32Type::from_naive_date(
+            *self,
+        ))))
+    }
+}
+
+/// Timestamps and times are stored in microseconds.
+impl LiteralExt for NaiveDateTime {
+    fn lit(&self) -> LogicalExpr {
+        let micros = self.and_utc().timestamp_micros();
+        LogicalExpr::Literal(ScalarValue::Timestamp(
+            Some(micros),
+            TimeUnit::Microsecond,
+            None,
+        ))
+    }
+}
+
+impl LiteralExt for DateTime<Utc> {
+    fn lit(&self) -> LogicalExpr {
+        LogicalExpr::Literal(ScalarValue::Timestamp(
+            Some(self.timestamp_micros()),
+            TimeUnit::Microsecond,
+            Some("+00:00".into()),
+        ))
+    }
+}
+
+impl LiteralExt for NaiveTime {
+    fn lit(&self) -> LogicalExpr {
+        let micros =
+            self.num_seconds_from_midnight() as i64 * 1_000_000 + self.nanosecond() as i64 / 1_000;
+        LogicalExpr::Literal(ScalarValue::Time64(Some(micros), TimeUnit::Microsecond))
+    }
+}
+
+impl LiteralExt for IntervalMonthDayNano {
+    fn lit(&self) -> LogicalExpr {
+        LogicalExpr::Literal(ScalarValue::Interval(Some(*self)))
+    }
+}
+
+/// The decimal `value / 10^scale` of type `Decimal128(precision, scale)`,
+/// e.g. `decimal(1999, 10, 2)` for 19.99.
+pub fn decimal(value: i128, precision: u8, scale: i8) -> LogicalExpr {
+    LogicalExpr::Literal(ScalarValue::Decimal128(Some(value), precision, scale))
+}
+
+/// Parses `value` as a decimal of type `Decimal128(precision, scale)`, e.g.
+/// `parse_decimal_lit("19.99", 10, 2)`. Extra fractional digits are
+/// truncated, while values with too many integral digits are an error.
+pub fn parse_decimal_lit(value: &str, precision: u8, scale: i8) -> Result<LogicalExpr> {
+    let parsed = parse_decimal::<Decimal128Type>(value, precision, scale)
+        .map_err(|_| DBError::Parse(format!("cannot parse '{}' as a decimal", value)))?;
+    Ok(decimal(parsed, precision, scale))
+}
+
+/// An interval of `months`, `days` and `nanoseconds`, e.g. for
+/// `col("ts").plus(interval(0, 1, 0))`.
+pub fn interval(months: i32, days: i32, nanoseconds: i64) -> LogicalExpr {
+    lit(IntervalMonthDayNano::new(months, days, nanoseconds))
+}
 
 pub fn binary_expr(lhs: LogicalExpr, op: Operator, rhs: LogicalExpr) -> LogicalExpr {
     LogicalExpr::Binary(BinaryExpr::new(Arc::new(lhs), op, Arc::new(rhs)))
@@ -56,5 +162,469 @@ make_expr_fn!(gt, Gt);
 make_expr_fn!(gt_eq, GtEq);
 make_expr_fn!(and, And);
 make_expr_fn!(or, Or);
-make_expr_fn!(add, Plus);
+make_expr_fn!(plus, Plus);
 make_expr_fn!(minus, Minus);
+make_expr_fn!(multiply, Multiply);
+make_expr_fn!(divide, Divide);
+make_expr_fn!(modulo, Modulo);
+make_expr_fn!(is_distinct_from, IsDistinctFrom);
+make_expr_fn!(is_not_distinct_from, IsNotDistinctFrom);
+make_expr_fn!(string_concat, StringConcat);
+
+pub fn unary_expr(op: UnaryOperator, expr: LogicalExpr) -> LogicalExpr {
+    LogicalExpr::Unary(UnaryExpr::new(op, Arc::new(expr)))
+}
+
+macro_rules! make_unary_expr_fn {
+    ($fn:ident, $op:ident) => {
+        impl LogicalExpr {
+            pub fn $fn(self) -> LogicalExpr {
+                unary_expr(UnaryOperator::$op, self)
+            }
+        }
+    };
+}
+
+/// `!expr` builds `NOT expr`.
+impl Not for LogicalExpr {
+    type Output = LogicalExpr;
+
+    fn not(self) -> LogicalExpr {
+        unary_expr(UnaryOperator::Not, self)
+    }
+}
+
+/// `-expr` negates a number.
+impl Neg for LogicalExpr {
+    type Output = LogicalExpr;
+
+    fn neg(self) -> LogicalExpr {
+        unary_expr(UnaryOperator::Negative, self)
+    }
+}
+
+make_unary_expr_fn!(is_null, IsNull);
+make_unary_expr_fn!(is_not_null, IsNotNull);
+make_unary_expr_fn!(is_true, IsTrue);
+make_unary_expr_fn!(is_not_true, IsNotTrue);
+make_unary_expr_fn!(is_false, IsFalse);
+make_unary_expr_fn!(is_not_false, IsNotFalse);
+make_unary_expr_fn!(is_unknown, IsUnknown);
+make_unary_expr_fn!(is_not_unknown, IsNotUnknown);
+
+impl LogicalExpr {
+    /// Converts the value to `data_type`, failing the query if a value cannot
+    /// be converted, e.g. `col("c1").cast_to(DataType::Int64)`.
+    pub fn cast_to(self, data_type: DataType) -> LogicalExpr {
+        LogicalExpr::Cast(Cast::new(Arc::new(self), data_type))
+    }
+
+    /// Converts the value to `data_type`, producing NULL for values that
+    /// cannot be converted.
+    pub fn try_cast_to(self, data_type: DataType) -> LogicalExpr {
+        LogicalExpr::TryCast(TryCast::new(Arc::new(self), data_type))
+    }
+
+    /// `self LIKE pattern`, e.g. `col("msg").like(lit("error:%"))`.
+    pub fn like(self, pattern: LogicalExpr) -> LogicalExpr {
+        LogicalExpr::Like(Like::new(
+            false,
+            Arc::new(self),
+            Arc::new(pattern),
+            None,
+            false,
+        ))
+    }
+
+    pub fn not_like(self, pattern: LogicalExpr) -> LogicalExpr {
+        LogicalExpr::Like(Like::new(
+            true,
+            Arc::new(self),
+            Arc::new(pattern),
+            None,
+            false,
+        ))
+    }
+
+    /// Like [`LogicalExpr::like`], but ignoring case.
+    pub fn ilike(self, pattern: LogicalExpr) -> LogicalExpr {
+        LogicalExpr::Like(Like::new(
+            false,
+            Arc::new(self),
+            Arc::new(pattern),
+            None,
+            true,
+        ))
+    }
+
+    pub fn not_ilike(self, pattern: LogicalExpr) -> LogicalExpr {
+        LogicalExpr::Like(Like::new(
+            true,
+            Arc::new(self),
+            Arc::new(pattern),
+            None,
+            true,
+        ))
+    }
+
+    /// `self SIMILAR TO pattern`, e.g. `col("level").similar_to(lit("(WARN|ERROR)%"))`.
+    pub fn similar_to(self, pattern: LogicalExpr) -> LogicalExpr {
+        LogicalExpr::SimilarTo(Like::new(
+            false,
+            Arc::new(self),
+            Arc::new(pattern),
+            None,
+            false,
+        ))
+    }
+
+    pub fn not_similar_to(self, pattern: LogicalExpr) -> LogicalExpr {
+        LogicalExpr::SimilarTo(Like::new(
+            true,
+            Arc::new(self),
+            Arc::new(pattern),
+            None,
+            false,
+        ))
+    }
+
+    /// Tests whether the value is one of `list`, e.g.
+    /// `col("c1").in_list(vec![lit(1), lit(2)], false)` for `c1 IN (1, 2)`.
+    pub fn in_list(self, list: Vec<LogicalExpr>, negated: bool) -> LogicalExpr {
+        LogicalExpr::InList(InList::new(
+            Arc::new(self),
+            list.into_iter().map(Arc::new).collect(),
+            negated,
+        ))
+    }
+
+    /// `self BETWEEN low AND high`, both bounds included.
+    pub fn between(self, low: LogicalExpr, high: LogicalExpr) -> LogicalExpr {
+        LogicalExpr::Between(Between::new(
+            Arc::new(self),
+            false,
+            Arc::new(low),
+            Arc::new(high),
+        ))
+    }
+
+    /// `self NOT BETWEEN low AND high`.
+    pub fn not_between(self, low: LogicalExpr, high: LogicalExpr) -> LogicalExpr {
+        LogicalExpr::Between(Between::new(
+            Arc::new(self),
+            true,
+            Arc::new(low),
+            Arc::new(high),
+        ))
+    }
+
+    /// Names the expression, e.g. `col("c3").plus(lit(1)).alias("total")`.
+    pub fn alias(self, name: impl Into<String>) -> LogicalExpr {
+        LogicalExpr::Alias(Alias::new(Arc::new(self), name))
+    }
+
+    /// Creates a sort key, e.g. `col("c2").sort(false, true)` for
+    /// `c2 DESC NULLS FIRST`.
+    pub fn sort(self, asc: bool, nulls_first: bool) -> SortExpr {
+        SortExpr::new(Arc::new(self), asc, nulls_first)
+    }
+}
+
+/// Starts a searched `CASE`, e.g.
+/// `when(col("c1").gt(lit(0)), lit("pos")).otherwise(lit("neg"))`.
+pub fn when(condition: LogicalExpr, then: LogicalExpr) -> CaseBuilder {
+    CaseBuilder {
+        expr: None,
+        when_then: vec![(condition, then)],
+    }
+}
+
+/// Starts a simple `CASE` comparing `expr` to every `WHEN` value, e.g.
+/// `case(col("c1")).when(lit(1), lit("one")).end()`.
+pub fn case(expr: LogicalExpr) -> CaseBuilder {
+    CaseBuilder {
+        expr: Some(expr),
+        when_then: vec![],
+    }
+}
+
+/// Builds a [`Case`] expression, see [`when`] and [`case`].
+#[derive(Debug, Clone)]
+pub struct CaseBuilder {
+    expr: Option<LogicalExpr>,
+    when_then: Vec<(LogicalExpr, LogicalExpr)>,
+}
+
+impl CaseBuilder {
+    pub fn when(mut self, when: LogicalExpr, then: LogicalExpr) -> Self {
+        self.when_then.push((when, then));
+        self
+    }
+
+    /// Finishes the `CASE` with an `ELSE` branch.
+    pub fn otherwise(self, else_expr: LogicalExpr) -> LogicalExpr {
+        self.build(Some(else_expr))
+    }
+
+    /// Finishes the `CASE` without an `ELSE` branch, so rows no branch
+    /// selects are NULL.
+    pub fn end(self) -> LogicalExpr {
+        self.build(None)
+    }
+
+    fn build(self, else_expr: Option<LogicalExpr>) -> LogicalExpr {
+        LogicalExpr::Case(Case::new(
+            self.expr.map(Arc::new),
+            self.when_then
+                .into_iter()
+                .map(|(when, then)| (Arc::new(when), Arc::new(then)))
+                .collect(),
+            else_expr.map(Arc::new),
+        ))
+    }
+}
+
+/// Returns the first argument that is not NULL.
+pub fn coalesce(args: Vec<LogicalExpr>) -> LogicalExpr {
+    LogicalExpr::Coalesce(Coalesce::new(args.into_iter().map(Arc::new).collect()))
+}
+
+/// Returns NULL if `lhs` equals `rhs`, `lhs` otherwise.
+pub fn nullif(lhs: LogicalExpr, rhs: LogicalExpr) -> LogicalExpr {
+    when(lhs.clone().eq(rhs), LogicalExpr::Literal(ScalarValue::Null)).otherwise(lhs)
+}
+
+/// Returns `then` where `condition` is true, `otherwise` elsewhere.
+pub fn if_then_else(
+    condition: LogicalExpr,
+    then: LogicalExpr,
+    otherwise: LogicalExpr,
+) -> LogicalExpr {
+    when(condition, then).otherwise(otherwise)
+}
+
+/// Calls a scalar function, e.g.
+/// `scalar_function(ScalarFunction::RegexpLike, vec![col("c1"), lit("^a"), lit("i")])`.
+pub fn scalar_function(fun: ScalarFunction, args: Vec<LogicalExpr>) -> LogicalExpr {
+    LogicalExpr::ScalarFunction(ScalarFunctionExpr::new(
+        fun,
+        args.into_iter().map(Arc::new).collect(),
+    ))
+}
+
+macro_rules! make_scalar_fn {
+    ($fn:ident, $fun:ident $(, $arg:ident)*) => {
+        pub fn $fn($($arg: LogicalExpr),*) -> LogicalExpr {
+            scalar_function(ScalarFunction::$fun, vec![$($arg),*])
+        }
+    };
+}
+
+make_scalar_fn!(upper, Upper, expr);
+make_scalar_fn!(lower, Lower, expr);
+make_scalar_fn!(trim, Trim, expr);
+make_scalar_fn!(ltrim, Ltrim, expr);
+make_scalar_fn!(rtrim, Rtrim, expr);
+make_scalar_fn!(length, Length, expr);
+make_scalar_fn!(substr, Substr, expr, start);
+make_scalar_fn!(substring, Substr, expr, start, length);
+make_scalar_fn!(replace, Replace, expr, from, to);
+make_scalar_fn!(split_part, SplitPart, expr, delimiter, n);
+make_scalar_fn!(starts_with, StartsWith, expr, prefix);
+make_scalar_fn!(lpad, Lpad, expr, length, fill);
+make_scalar_fn!(rpad, Rpad, expr, length, fill);
+make_scalar_fn!(strpos, Strpos, expr, substring);
+make_scalar_fn!(reverse, Reverse, expr);
+
+make_scalar_fn!(abs, Abs, expr);
+make_scalar_fn!(ceil, Ceil, expr);
+make_scalar_fn!(floor, Floor, expr);
+make_scalar_fn!(round, Round, expr, digits);
+make_scalar_fn!(trunc, Trunc, expr, digits);
+make_scalar_fn!(sign, Sign, expr);
+make_scalar_fn!(sqrt, Sqrt, expr);
+make_scalar_fn!(power, Power, base, exponent);
+make_scalar_fn!(exp, Exp, expr);
+make_scalar_fn!(ln, Ln, expr);
+make_scalar_fn!(log10, Log10, expr);
+make_scalar_fn!(sin, Sin, expr);
+make_scalar_fn!(cos, Cos, expr);
+make_scalar_fn!(tan, Tan, expr);
+make_scalar_fn!(asin, Asin, expr);
+make_scalar_fn!(acos, Acos, expr);
+make_scalar_fn!(atan, Atan, expr);
+make_scalar_fn!(atan2, Atan2, y, x);
+make_scalar_fn!(pi, Pi);
+make_scalar_fn!(random, Random);
+
+make_scalar_fn!(date_trunc, DateTrunc, unit, expr);
+make_scalar_fn!(date_part, DatePart, field, expr);
+make_scalar_fn!(now, Now);
+make_scalar_fn!(current_date, CurrentDate);
+make_scalar_fn!(to_timestamp, ToTimestamp, expr);
+make_scalar_fn!(to_timestamp_with_format, ToTimestamp, expr, format);
+
+/// Joins the text of `args`, skipping NULLs.
+pub fn concat(args: Vec<LogicalExpr>) -> LogicalExpr {
+    scalar_function(ScalarFunction::Concat, args)
+}
+
+/// The largest of `args`, ignoring NULLs.
+pub fn greatest(args: Vec<LogicalExpr>) -> LogicalExpr {
+    scalar_function(ScalarFunction::Greatest, args)
+}
+
+/// The smallest of `args`, ignoring NULLs.
+pub fn least(args: Vec<LogicalExpr>) -> LogicalExpr {
+    scalar_function(ScalarFunction::Least, args)
+}
+
+pub fn regexp_like(expr: LogicalExpr, pattern: LogicalExpr) -> LogicalExpr {
+    scalar_function(ScalarFunction::RegexpLike, vec![expr, pattern])
+}
+
+pub fn regexp_replace(
+    expr: LogicalExpr,
+    pattern: LogicalExpr,
+    replacement: LogicalExpr,
+) -> LogicalExpr {
+    scalar_function(ScalarFunction::RegexpReplace, vec![
+        expr,
+        pattern,
+        replacement,
+    ])
+}
+
+pub fn regexp_extract(expr: LogicalExpr, pattern: LogicalExpr, group: LogicalExpr) -> LogicalExpr {
+    scalar_function(ScalarFunction::RegexpExtract, vec![expr, pattern, group])
+}
+
+fn aggregate_expr(fun: AggregateFunction, expr: LogicalExpr, distinct: bool) -> LogicalExpr {
+    LogicalExpr::Aggregate(AggregateExpr::new(fun, Arc::new(expr), distinct))
+}
+
+pub fn count(expr: LogicalExpr) -> LogicalExpr {
+    aggregate_expr(AggregateFunction::Count, expr, false)
+}
+
+pub fn count_distinct(expr: LogicalExpr) -> LogicalExpr {
+    aggregate_expr(AggregateFunction::Count, expr, true)
+}
+
+pub fn sum(expr: LogicalExpr) -> LogicalExpr {
+    aggregate_expr(AggregateFunction::Sum, expr, false)
+}
+
+pub fn min(expr: LogicalExpr) -> LogicalExpr {
+    aggregate_expr(AggregateFunction::Min, expr, false)
+}
+
+pub fn max(expr: LogicalExpr) -> LogicalExpr {
+    aggregate_expr(AggregateFunction::Max, expr, false)
+}
+
+pub fn avg(expr: LogicalExpr) -> LogicalExpr {
+    aggregate_expr(AggregateFunction::Avg, expr, false)
+}
+
+/// Starts a window function call, e.g.
+/// `row_number().partition_by(vec![col("c1")]).order_by(vec![col("c2").sort(true, false)]).build()`.
+pub fn window_function(fun: WindowFunction, args: Vec<LogicalExpr>) -> WindowBuilder {
+    WindowBuilder {
+        fun,
+        args,
+        partition_by: vec![],
+        order_by: vec![],
+        frame: None,
+    }
+}
+
+/// Computes an aggregate over the window frame of every row, e.g.
+/// `window_aggregate(AggregateFunction::Sum, col("c3"))` for a running total.
+pub fn window_aggregate(fun: AggregateFunction, expr: LogicalExpr) -> WindowBuilder {
+    window_function(WindowFunction::Aggregate(fun), vec![expr])
+}
+
+pub fn row_number() -> WindowBuilder {
+    window_function(WindowFunction::RowNumber, vec![])
+}
+
+pub fn rank() -> WindowBuilder {
+    window_function(WindowFunction::Rank, vec![])
+}
+
+pub fn dense_rank() -> WindowBuilder {
+    window_function(WindowFunction::DenseRank, vec![])
+}
+
+/// Splits every partition into `buckets` groups of nearly equal size and
+/// numbers them from 1.
+pub fn ntile(buckets: i64) -> WindowBuilder {
+    window_function(WindowFunction::Ntile, vec![lit(buckets)])
+}
+
+/// The value of `expr` `offset` rows before the current one, or `default`
+/// (NULL if not given) when there is no such row in the partition.
+pub fn lag(expr: LogicalExpr, offset: i64, default: Option<LogicalExpr>) -> WindowBuilder {
+    let mut args = vec![expr, lit(offset)];
+    args.extend(default);
+    window_function(WindowFunction::Lag, args)
+}
+
+/// The value of `expr` `offset` rows after the current one, or `default`
+/// (NULL if not given) when there is no such row in the partition.
+pub fn lead(expr: LogicalExpr, offset: i64, default: Option<LogicalExpr>) -> WindowBuilder {
+    let mut args = vec![expr, lit(offset)];
+    args.extend(default);
+    window_function(WindowFunction::Lead, args)
+}
+
+pub fn first_value(expr: LogicalExpr) -> WindowBuilder {
+    window_function(WindowFunction::FirstValue, vec![expr])
+}
+
+pub fn last_value(expr: LogicalExpr) -> WindowBuilder {
+    window_function(WindowFunction::LastValue, vec![expr])
+}
+
+/// Builds a [`WindowExpr`], see [`window_function`].
+#[derive(Debug, Clone)]
+pub struct WindowBuilder {
+    fun: WindowFunction,
+    args: Vec<LogicalExpr>,
+    partition_by: Vec<LogicalExpr>,
+    order_by: Vec<SortExpr>,
+    frame: Option<WindowFrame>,
+}
+
+impl WindowBuilder {
+    pub fn partition_by(mut self, exprs: Vec<LogicalExpr>) -> Self {
+        self.partition_by = exprs;
+        self
+    }
+
+    pub fn order_by(mut self, exprs: Vec<SortExpr>) -> Self {
+        self.order_by = exprs;
+        self
+    }
+
+    pub fn frame(mut self, frame: WindowFrame) -> Self {
+        self.frame = Some(frame);
+        self
+    }
+
+    /// Finishes the call. Without an explicit frame the one of
+    /// [`WindowFrame::default_for`] the ordering is used.
+    pub fn build(self) -> LogicalExpr {
+        let frame = self
+            .frame
+            .unwrap_or_else(|| WindowFrame::default_for(&self.order_by));
+        LogicalExpr::WindowFunction(WindowExpr::new(
+            self.fun,
+            self.args.into_iter().map(Arc::new).collect(),
+            self.partition_by.into_iter().map(Arc::new).collect(),
+            self.order_by,
+            frame,
+        ))
+    }
+}
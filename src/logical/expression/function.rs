@@ -0,0 +1,620 @@
+use std::{fmt::Display, sync::Arc};
+
+use arrow_schema::{DataType, TimeUnit};
+
+use super::expr::LogicalExpr;
+use crate::datatypes::coercion::{comparison_operand_type, has_text};
+use crate::error::{Result, plan_err};
+use crate::logical::schema::QualifiedSchema;
+
+/// The kind of value a function argument accepts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArgKind {
+    /// A Utf8 or LargeUtf8 string, which keeps its type. NULL is read as Utf8.
+    String,
+    /// Any integer, read as Int64.
+    Integer,
+    /// A string, number, boolean or temporal value, read as Utf8.
+    Text,
+    /// An integer or floating point number, which keeps its type. Float16 is
+    /// read as Float32 and NULL as Int64.
+    Numeric,
+    /// An integer, floating point or decimal number, read as Float64.
+    Float,
+    /// A timestamp, which keeps its type. Dates, strings and NULL are read
+    /// as timestamps in microseconds.
+    Timestamp,
+    /// A date, time or timestamp, which keeps its type. Strings and NULL are
+    /// read as timestamps in microseconds.
+    DateTime,
+}
+
+impl ArgKind {
+    /// Returns the type an argument of type `data_type` is cast to, or `None`
+    /// if it is not accepted.
+    pub fn coerce(&self, data_type: &DataType) -> Option<DataType> {
+        use DataType::*;
+
+        match (self, data_type) {
+            (ArgKind::String, Utf8 | LargeUtf8) => Some(data_type.clone()),
+            (ArgKind::String, Null) => Some(Utf8),
+            (ArgKind::Integer, t) if t.is_integer() || t == &Null => Some(Int64),
+            (ArgKind::Text, t) if has_text(t) => Some(Utf8),
+            (ArgKind::Numeric, t) if t.is_integer() || matches!(t, Float32 | Float64) => {
+                Some(t.clone())
+            }
+            (ArgKind::Numeric, Float16) => Some(Float32),
+            (ArgKind::Numeric, Null) => Some(Int64),
+            (ArgKind::Float, t)
+                if t.is_integer() || t.is_floating() || matches!(t, Decimal128(..) | Null) =>
+            {
+                Some(Float64)
+            }
+            (ArgKind::Timestamp, Timestamp(..)) => Some(data_type.clone()),
+            (ArgKind::Timestamp, Date32 | Utf8 | Null) => {
+                Some(Timestamp(TimeUnit::Microsecond, None))
+            }
+            (ArgKind::DateTime, Date32 | Time64(_) | Timestamp(..)) => Some(data_type.clone()),
+            (ArgKind::DateTime, Utf8 | Null) => Some(Timestamp(TimeUnit::Microsecond, None)),
+            _ => None,
+        }
+    }
+}
+
+/// The arguments a [`ScalarFunction`] accepts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Signature {
+    /// One argument of each kind in `args`, of which the ones after the
+    /// first `required` may be omitted.
+    Exact { args: Vec<ArgKind>, required: usize },
+    /// One or more arguments of the same kind.
+    Variadic(ArgKind),
+    /// One or more arguments that are cast to a common type and compared.
+    Comparable,
+}
+
+impl Signature {
+    /// A signature without optional arguments.
+    pub fn exact(args: Vec<ArgKind>) -> Self {
+        let required = args.len();
+        Signature::Exact { args, required }
+    }
+
+    /// A signature whose last `optional` arguments may be omitted.
+    pub fn optional(args: Vec<ArgKind>, optional: usize) -> Self {
+        let required = args.len() - optional;
+        Signature::Exact { args, required }
+    }
+}
+
+/// A function computing one value per row from the values of its
+/// arguments in that row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ScalarFunction {
+    /// `upper(str)`, converts `str` to upper case.
+    Upper,
+    /// `lower(str)`, converts `str` to lower case.
+    Lower,
+    /// `trim(str[, characters])`, removes the longest prefix and suffix made
+    /// up of `characters`, spaces by default.
+    Trim,
+    /// `ltrim(str[, characters])`, like [`ScalarFunction::Trim`] but only
+    /// removes the prefix.
+    Ltrim,
+    /// `rtrim(str[, characters])`, like [`ScalarFunction::Trim`] but only
+    /// removes the suffix.
+    Rtrim,
+    /// `length(str)`, the number of characters in `str`.
+    Length,
+    /// `substr(str, start[, length])`, the `length` characters starting at
+    /// the 1-based position `start`, or all remaining characters.
+    Substr,
+    /// `concat(value, ...)`, the text of all values joined together. NULL
+    /// values are skipped, unlike with `||`.
+    Concat,
+    /// `replace(str, from, to)`, replaces every occurrence of `from` by `to`.
+    Replace,
+    /// `split_part(str, delimiter, n)`, the `n`th field of `str` split at
+    /// `delimiter`, counting from the end if `n` is negative, or an empty
+    /// string if there are not that many fields.
+    SplitPart,
+    /// `starts_with(str, prefix)`, whether `str` begins with `prefix`.
+    StartsWith,
+    /// `lpad(str, length[, fill])`, prepends `fill`, a space by default, until
+    /// `str` is `length` characters long. Longer values are truncated.
+    Lpad,
+    /// `rpad(str, length[, fill])`, like [`ScalarFunction::Lpad`] but appends
+    /// the padding.
+    Rpad,
+    /// `strpos(str, substring)`, the 1-based position of the first occurrence
+    /// of `substring`, or 0 if there is none.
+    Strpos,
+    /// `reverse(str)`, the characters of `str` in reverse order.
+    Reverse,
+    /// `abs(x)`, the absolute value of `x`.
+    Abs,
+    /// `ceil(x)`, the smallest integral value not less than `x`.
+    Ceil,
+    /// `floor(x)`, the largest integral value not greater than `x`.
+    Floor,
+    /// `round(x[, digits])`, rounds `x` half away from zero to `digits`
+    /// decimal places, 0 by default. Negative `digits` round to tens,
+    /// hundreds and so on.
+    Round,
+    /// `trunc(x[, digits])`, like [`ScalarFunction::Round`] but rounds toward
+    /// zero.
+    Trunc,
+    /// `sign(x)`, -1, 0 or 1 depending on the sign of `x`.
+    Sign,
+    /// `sqrt(x)`, the square root of `x`.
+    Sqrt,
+    /// `power(x, y)`, `x` raised to the power of `y`.
+    Power,
+    /// `exp(x)`, e raised to the power of `x`.
+    Exp,
+    /// `ln(x)`, the natural logarithm of `x`.
+    Ln,
+    /// `log10(x)`, the base 10 logarithm of `x`.
+    Log10,
+    /// `sin(x)`, the sine of `x` radians.
+    Sin,
+    /// `cos(x)`, the cosine of `x` radians.
+    Cos,
+    /// `tan(x)`, the tangent of `x` radians.
+    Tan,
+    /// `asin(x)`, the arc sine of `x` in radians.
+    Asin,
+    /// `acos(x)`, the arc cosine of `x` in radians.
+    Acos,
+    /// `atan(x)`, the arc tangent of `x` in radians.
+    Atan,
+    /// `atan2(y, x)`, the angle in radians between the positive x axis and
+    /// the point `(x, y)`.
+    Atan2,
+    /// `pi()`, the constant π.
+    Pi,
+    /// `greatest(value, ...)`, the largest of the values, ignoring NULLs.
+    Greatest,
+    /// `least(value, ...)`, the smallest of the values, ignoring NULLs.
+    Least,
+    /// `random()`, a different value in [0, 1) for every row.
+    Random,
+    /// `date_trunc(unit, timestamp)`, rounds `timestamp` down to the start of
+    /// its `unit`, e.g. `'hour'` or `'month'`. Weeks start on Monday.
+    DateTrunc,
+    /// `date_part(field, value)`, the `field` of a date, time or timestamp,
+    /// e.g. `'year'`, `'dow'` or `'epoch'`. Also written as
+    /// `EXTRACT(field FROM value)`.
+    DatePart,
+    /// `now()`, the time the query started, as a UTC timestamp.
+    Now,
+    /// `current_date()`, the UTC date the query started.
+    CurrentDate,
+    /// `to_timestamp(str[, format])`, parses `str` as a timestamp, either in
+    /// ISO 8601 form or with a `strftime` style `format` such as
+    /// `'%d/%m/%Y %H:%M'`.
+    ToTimestamp,
+    /// `regexp_like(str, pattern[, flags])`, whether `str` contains a match of
+    /// the regular expression `pattern`.
+    RegexpLike,
+    /// `regexp_replace(str, pattern, replacement[, flags])`, replaces the
+    /// first match, or every match with the `g` flag. `replacement` may
+    /// refer to capture groups as `$1` or `${name}`.
+    RegexpReplace,
+    /// `regexp_extract(str, pattern[, group])`, the text matched by capture
+    /// group `group`, 1 by default, or NULL if `pattern` does not match.
+    RegexpExtract,
+}
+
+impl ScalarFunction {
+    /// Looks up a function by its SQL name, ignoring case.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Some(match name.to_lowercase().as_str() {
+            "upper" => ScalarFunction::Upper,
+            "lower" => ScalarFunction::Lower,
+            "trim" | "btrim" => ScalarFunction::Trim,
+            "ltrim" => ScalarFunction::Ltrim,
+            "rtrim" => ScalarFunction::Rtrim,
+            "length" | "char_length" | "character_length" => ScalarFunction::Length,
+            "substr" | "substring" => ScalarFunction::Substr,
+            "concat" => ScalarFunction::Concat,
+            "replace" => ScalarFunction::Replace,
+            "split_part" => ScalarFunction::SplitPart,
+            "starts_with" => ScalarFunction::StartsWith,
+            "lpad" => ScalarFunction::Lpad,
+            "rpad" => ScalarFunction::Rpad,
+            "strpos" => ScalarFunction::Strpos,
+            "reverse" => ScalarFunction::Reverse,
+            "abs" => ScalarFunction::Abs,
+            "ceil" | "ceiling" => ScalarFunction::Ceil,
+            "floor" => ScalarFunction::Floor,
+            "round" => ScalarFunction::Round,
+            "trunc" | "truncate" => ScalarFunction::Trunc,
+            "sign" => ScalarFunction::Sign,
+            "sqrt" => ScalarFunction::Sqrt,
+            "power" | "pow" => ScalarFunction::Power,
+            "exp" => ScalarFunction::Exp,
+            "ln" => ScalarFunction::Ln,
+            "log10" => ScalarFunction::Log10,
+            "sin" => ScalarFunction::Sin,
+            "cos" => ScalarFunction::Cos,
+            "tan" => ScalarFunction::Tan,
+            "asin" => ScalarFunction::Asin,
+            "acos" => ScalarFunction::Acos,
+            "atan" => ScalarFunction::Atan,
+            "atan2" => ScalarFunction::Atan2,
+            "pi" => ScalarFunction::Pi,
+            "greatest" => ScalarFunction::Greatest,
+            "least" => ScalarFunction::Least,
+            "random" => ScalarFunction::Random,
+            "date_trunc" => ScalarFunction::DateTrunc,
+            "date_part" => ScalarFunction::DatePart,
+            "now" | "current_timestamp" => ScalarFunction::Now,
+            "current_date" => ScalarFunction::CurrentDate,
+            "to_timestamp" => ScalarFunction::ToTimestamp,
+            "regexp_like" => ScalarFunction::RegexpLike,
+            "regexp_replace" => ScalarFunction::RegexpReplace,
+            "regexp_extract" => ScalarFunction::RegexpExtract,
+            _ => return None,
+        })
+    }
+
+    /// Returns the arguments the function accepts.
+    pub fn signature(&self) -> Signature {
+        use ArgKind::*;
+
+        match self {
+            ScalarFunction::Upper
+            | ScalarFunction::Lower
+            | ScalarFunction::Length
+            | ScalarFunction::Reverse => Signature::exact(vec![String]),
+            ScalarFunction::Trim | ScalarFunction::Ltrim | ScalarFunction::Rtrim => {
+                Signature::optional(vec![String, String], 1)
+            }
+            ScalarFunction::Substr => Signature::optional(vec![String, Integer, Integer], 1),
+            ScalarFunction::Concat => Signature::Variadic(Text),
+            ScalarFunction::Replace => Signature::exact(vec![String, String, String]),
+            ScalarFunction::SplitPart => Signature::exact(vec![String, String, Integer]),
+            ScalarFunction::StartsWith | ScalarFunction::Strpos => {
+                Signature::exact(vec![String, String])
+            }
+            ScalarFunction::Lpad | ScalarFunction::Rpad => {
+                Signature::optional(vec![String, Integer, String], 1)
+            }
+            ScalarFunction::RegexpLike => Signature::optional(vec![String, String, String], 1),
+            ScalarFunction::RegexpReplace => {
+                Signature::optional(vec![String, String, String, String], 1)
+            }
+            ScalarFunction::RegexpExtract => Signature::optional(vec![String, String, Integer], 1),
+            ScalarFunction::Abs
+            | ScalarFunction::Ceil
+            | ScalarFunction::Floor
+            | ScalarFunction::Sign => Signature::exact(vec![Numeric]),
+            ScalarFunction::Round | ScalarFunction::Trunc => {
+                Signature::optional(vec![Numeric, Integer], 1)
+            }
+            ScalarFunction::Sqrt
+            | ScalarFunction::Exp
+            | ScalarFunction::Ln
+            | ScalarFunction::Log10
+            | ScalarFunction::Sin
+            | ScalarFunction::Cos
+            | ScalarFunction::Tan
+            | ScalarFunction::Asin
+            | ScalarFunction::Acos
+            | ScalarFunction::Atan => Signature::exact(vec![Float]),
+            ScalarFunction::Power | ScalarFunction::Atan2 => Signature::exact(vec![Float, Float]),
+            ScalarFunction::Pi
+            | ScalarFunction::Random
+            | ScalarFunction::Now
+            | ScalarFunction::CurrentDate => Signature::exact(vec![]),
+            ScalarFunction::DateTrunc => Signature::exact(vec![String, Timestamp]),
+            ScalarFunction::DatePart => Signature::exact(vec![String, DateTime]),
+            ScalarFunction::ToTimestamp => Signature::optional(vec![String, String], 1),
+            ScalarFunction::Greatest | ScalarFunction::Least => Signature::Comparable,
+        }
+    }
+
+    /// Returns the types the arguments must be cast to before the function is
+    /// called, or an error if the function cannot be called with arguments
+    /// of types `args`.
+    pub fn coerce_types(&self, args: &[DataType]) -> Result<Vec<DataType>> {
+        let kinds = match self.signature() {
+            Signature::Exact {
+                args: kinds,
+                required,
+            } => {
+                if args.len() < required || args.len() > kinds.len() {
+                    return if required == kinds.len() {
+                        plan_err!(
+                            "function {} expects exactly {} argument{}, got {}",
+                            self,
+                            required,
+                            if required == 1 { "" } else { "s" },
+                            args.len()
+                        )
+                    } else {
+                        plan_err!(
+                            "function {} expects {} to {} arguments, got {}",
+                            self,
+                            required,
+                            kinds.len(),
+                            args.len()
+                        )
+                    };
+                }
+                kinds
+            }
+            Signature::Variadic(kind) => {
+                if args.is_empty() {
+                    return plan_err!("function {} expects at least one argument", self);
+                }
+                vec![kind; args.len()]
+            }
+            Signature::Comparable => {
+                if args.is_empty() {
+                    return plan_err!("function {} expects at least one argument", self);
+                }
+                let common = comparison_operand_type(args).or_else(|_| self.invalid_args(args))?;
+                return Ok(vec![common; args.len()]);
+            }
+        };
+        args.iter()
+            .zip(kinds)
+            .map(|(t, kind)| match kind.coerce(t) {
+                Some(t) => Ok(t),
+                None => self.invalid_args(args),
+            })
+            .collect()
+    }
+
+    /// Returns the result type of the function when called with arguments of
+    /// types `args`.
+    pub fn return_type(&self, args: &[DataType]) -> Result<DataType> {
+        let args = self.coerce_types(args)?;
+        Ok(match self {
+            ScalarFunction::StartsWith | ScalarFunction::RegexpLike => DataType::Boolean,
+            ScalarFunction::Length | ScalarFunction::Strpos => DataType::Int64,
+            ScalarFunction::Sqrt
+            | ScalarFunction::Power
+            | ScalarFunction::Exp
+            | ScalarFunction::Ln
+            | ScalarFunction::Log10
+            | ScalarFunction::Sin
+            | ScalarFunction::Cos
+            | ScalarFunction::Tan
+            | ScalarFunction::Asin
+            | ScalarFunction::Acos
+            | ScalarFunction::Atan
+            | ScalarFunction::Atan2
+            | ScalarFunction::Pi
+            | ScalarFunction::Random
+            | ScalarFunction::DatePart => DataType::Float64,
+            ScalarFunction::Concat => DataType::Utf8,
+            ScalarFunction::Now => {
+                DataType::Timestamp(TimeUnit::Microsecond, Some("+00:00".into()))
+            }
+            ScalarFunction::CurrentDate => DataType::Date32,
+            ScalarFunction::ToTimestamp => DataType::Timestamp(TimeUnit::Microsecond, None),
+            ScalarFunction::DateTrunc => args[1].clone(),
+            ScalarFunction::Upper
+            | ScalarFunction::Lower
+            | ScalarFunction::Trim
+            | ScalarFunction::Ltrim
+            | ScalarFunction::Rtrim
+            | ScalarFunction::Substr
+            | ScalarFunction::Replace
+            | ScalarFunction::SplitPart
+            | ScalarFunction::Lpad
+            | ScalarFunction::Rpad
+            | ScalarFunction::Reverse
+            | ScalarFunction::RegexpReplace
+            | ScalarFunction::RegexpExtract
+            | ScalarFunction::Abs
+            | ScalarFunction::Ceil
+            | ScalarFunction::Floor
+            | ScalarFunction::Round
+            | ScalarFunction::Trunc
+            | ScalarFunction::Sign
+            | ScalarFunction::Greatest
+            | ScalarFunction::Least => args[0].clone(),
+        })
+    }
+
+    /// Whether the function may return different values for the same
+    /// arguments, so calls must not be evaluated ahead of time. `now()` is
+    /// not: it is evaluated once so that it is the same for every row.
+    pub fn is_volatile(&self) -> bool {
+        matches!(self, ScalarFunction::Random)
+    }
+
+    /// Whether the function is evaluated with regular expressions.
+    pub fn is_regexp(&self) -> bool {
+        matches!(
+            self,
+            ScalarFunction::RegexpLike
+                | ScalarFunction::RegexpReplace
+                | ScalarFunction::RegexpExtract
+        )
+    }
+
+    fn invalid_args<T>(&self, args: &[DataType]) -> Result<T> {
+        plan_err!(
+            "function {} does not support arguments of types {}",
+            self,
+            args.iter()
+                .map(|t| t.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        )
+    }
+}
+
+impl Display for ScalarFunction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScalarFunction::Upper => write!(f, "UPPER"),
+            ScalarFunction::Lower => write!(f, "LOWER"),
+            ScalarFunction::Trim => write!(f, "TRIM"),
+            ScalarFunction::Ltrim => write!(f, "LTRIM"),
+            ScalarFunction::Rtrim => write!(f, "RTRIM"),
+            ScalarFunction::Length => write!(f, "LENGTH"),
+            ScalarFunction::Substr => write!(f, "SUBSTR"),
+            ScalarFunction::Concat => write!(f, "CONCAT"),
+            ScalarFunction::Replace => write!(f, "REPLACE"),
+            ScalarFunction::SplitPart => write!(f, "SPLIT_PART"),
+            ScalarFunction::StartsWith => write!(f, "STARTS_WITH"),
+            ScalarFunction::Lpad => write!(f, "LPAD"),
+            ScalarFunction::Rpad => write!(f, "RPAD"),
+            ScalarFunction::Strpos => write!(f, "STRPOS"),
+            ScalarFunction::Reverse => write!(f, "REVERSE"),
+            ScalarFunction::Abs => write!(f, "ABS"),
+            ScalarFunction::Ceil => write!(f, "CEIL"),
+            ScalarFunction::Floor => write!(f, "FLOOR"),
+            ScalarFunction::Round => write!(f, "ROUND"),
+            ScalarFunction::Trunc => write!(f, "TRUNC"),
+            ScalarFunction::Sign => write!(f, "SIGN"),
+            ScalarFunction::Sqrt => write!(f, "SQRT"),
+            ScalarFunction::Power => write!(f, "POWER"),
+            ScalarFunction::Exp => write!(f, "EXP"),
+            ScalarFunction::Ln => write!(f, "LN"),
+            ScalarFunction::Log10 => write!(f, "LOG10"),
+            ScalarFunction::Sin => write!(f, "SIN"),
+            ScalarFunction::Cos => write!(f, "COS"),
+            ScalarFunction::Tan => write!(f, "TAN"),
+            ScalarFunction::Asin => write!(f, "ASIN"),
+            ScalarFunction::Acos => write!(f, "ACOS"),
+            ScalarFunction::Atan => write!(f, "ATAN"),
+            ScalarFunction::Atan2 => write!(f, "ATAN2"),
+            ScalarFunction::Pi => write!(f, "PI"),
+            ScalarFunction::Greatest => write!(f, "GREATEST"),
+            ScalarFunction::Least => write!(f, "LEAST"),
+            ScalarFunction::Random => write!(f, "RANDOM"),
+            ScalarFunction::DateTrunc => write!(f, "DATE_TRUNC"),
+            ScalarFunction::DatePart => write!(f, "DATE_PART"),
+            ScalarFunction::Now => write!(f, "NOW"),
+            ScalarFunction::CurrentDate => write!(f, "CURRENT_DATE"),
+            ScalarFunction::ToTimestamp => write!(f, "TO_TIMESTAMP"),
+            ScalarFunction::RegexpLike => write!(f, "REGEXP_LIKE"),
+            ScalarFunction::RegexpReplace => write!(f, "REGEXP_REPLACE"),
+            ScalarFunction::RegexpExtract => write!(f, "REGEXP_EXTRACT"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ScalarFunctionExpr {
+    pub fun: ScalarFunction,
+    pub args: Vec<Arc<LogicalExpr>>,
+}
+
+impl ScalarFunctionExpr {
+    pub fn new(fun: ScalarFunction, args: Vec<Arc<LogicalExpr>>) -> Self {
+        Self { fun, args }
+    }
+
+    pub fn data_type(&self, schema: &QualifiedSchema) -> Result<DataType> {
+        let types = self
+            .args
+            .iter()
+            .map(|e| e.data_type(schema))
+            .collect::<Result<Vec<_>>>()?;
+        self.fun.return_type(&types)
+    }
+
+    pub(crate) fn format(&self, f: impl Fn(&LogicalExpr) -> String) -> String {
+        let args = self.args.iter().map(|e| f(e)).collect::<Vec<_>>();
+        format!("{}({})", self.fun, args.join(", "))
+    }
+}
+
+impl Display for ScalarFunctionExpr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.format(|e| e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow_schema::DataType::*;
+
+    use super::*;
+
+    #[test]
+    fn test_signatures() -> Result<()> {
+        assert_eq!(
+            ScalarFunction::from_name("SUBSTRING"),
+            Some(ScalarFunction::Substr)
+        );
+        assert_eq!(
+            ScalarFunction::Substr.coerce_types(&[LargeUtf8, Int32])?,
+            vec![LargeUtf8, Int64]
+        );
+        assert_eq!(
+            ScalarFunction::Substr.return_type(&[LargeUtf8, Int32])?,
+            LargeUtf8
+        );
+        assert_eq!(
+            ScalarFunction::Concat.coerce_types(&[Utf8, Int32, Null])?,
+            vec![Utf8, Utf8, Utf8]
+        );
+        assert_eq!(ScalarFunction::Strpos.return_type(&[Null, Utf8])?, Int64);
+
+        let err = ScalarFunction::Upper
+            .return_type(&[Utf8, Utf8])
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "plan error: function UPPER expects exactly 1 argument, got 2"
+        );
+        let err = ScalarFunction::Lpad.return_type(&[Utf8]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "plan error: function LPAD expects 2 to 3 arguments, got 1"
+        );
+        assert!(ScalarFunction::Concat.return_type(&[]).is_err());
+        assert!(ScalarFunction::Length.return_type(&[Int64]).is_err());
+        assert!(
+            ScalarFunction::SplitPart
+                .return_type(&[Utf8, Utf8, Float64])
+                .is_err()
+        );
+
+        assert_eq!(ScalarFunction::Round.return_type(&[Int32, Int8])?, Int32);
+        assert_eq!(ScalarFunction::Abs.return_type(&[Float16])?, Float32);
+        assert_eq!(ScalarFunction::Sqrt.coerce_types(&[UInt8])?, vec![Float64]);
+        assert_eq!(
+            ScalarFunction::Greatest.coerce_types(&[Int8, Null, Float32])?,
+            vec![Float64, Float64, Float64]
+        );
+        assert_eq!(ScalarFunction::Random.return_type(&[])?, Float64);
+        assert!(ScalarFunction::Random.return_type(&[Int64]).is_err());
+        assert!(ScalarFunction::Abs.return_type(&[Utf8]).is_err());
+        assert!(
+            ScalarFunction::Greatest
+                .return_type(&[Int64, Boolean])
+                .is_err()
+        );
+
+        let micros = Timestamp(TimeUnit::Microsecond, None);
+        assert_eq!(
+            ScalarFunction::DateTrunc.coerce_types(&[Utf8, Date32])?,
+            vec![Utf8, micros.clone()]
+        );
+        assert_eq!(
+            ScalarFunction::DateTrunc.return_type(&[Utf8, Timestamp(TimeUnit::Second, None)])?,
+            Timestamp(TimeUnit::Second, None)
+        );
+        assert_eq!(
+            ScalarFunction::DatePart.coerce_types(&[Utf8, Date32])?,
+            vec![Utf8, Date32]
+        );
+        assert_eq!(ScalarFunction::ToTimestamp.return_type(&[Utf8])?, micros);
+        assert!(
+            ScalarFunction::DatePart
+                .return_type(&[Utf8, Int64])
+                .is_err()
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,71 @@
+use std::sync::Arc;
+
+use super::expr::{LIKE_PRECEDENCE, LogicalExpr, format_operand};
+
+/// `expr [NOT] LIKE pattern [ESCAPE 'c']`, or `ILIKE` when
+/// `case_insensitive` is set. In the pattern `%` matches any sequence of
+/// characters and `_` any single character; the escape character, `\` by
+/// default, makes the next character match itself.
+///
+/// The same node describes `SIMILAR TO`, whose pattern is a SQL regular
+/// expression.
+#[derive(Debug, Clone)]
+pub struct Like {
+    pub negated: bool,
+    pub expr: Arc<LogicalExpr>,
+    pub pattern: Arc<LogicalExpr>,
+    pub escape_char: Option<char>,
+    pub case_insensitive: bool,
+}
+
+impl Like {
+    pub fn new(
+        negated: bool,
+        expr: Arc<LogicalExpr>,
+        pattern: Arc<LogicalExpr>,
+        escape_char: Option<char>,
+        case_insensitive: bool,
+    ) -> Self {
+        Self {
+            negated,
+            expr,
+            pattern,
+            escape_char,
+            case_insensitive,
+        }
+    }
+
+    /// Formats the expression with `op` as the operator, rendering the
+    /// operands with `f`.
+    pub(crate) fn format(&self, op: &str, f: impl Fn(&LogicalExpr) -> String) -> String {
+        let mut s = format!(
+            "{} {}{} {}",
+            format_operand(&self.expr, LIKE_PRECEDENCE + 1, &f),
+            if self.negated { "NOT " } else { "" },
+            op,
+            format_operand(&self.pattern, LIKE_PRECEDENCE + 1, &f)
+        );
+        if let Some(c) = self.escape_char {
+            s += &format!(" ESCAPE '{}'", c);
+        }
+        s
+    }
+
+    /// Returns a copy whose operands are replaced by `f(operand)`.
+    pub(crate) fn map_operands(&self, f: impl Fn(&LogicalExpr) -> LogicalExpr) -> Self {
+        Self {
+            expr: Arc::new(f(&self.expr)),
+            pattern: Arc::new(f(&self.pattern)),
+            ..self.clone()
+        }
+    }
+
+    /// The operator of a `LIKE` node, `LIKE` or `ILIKE`.
+    pub(crate) fn like_op(&self) -> &'static str {
+        if self.case_insensitive {
+            "ILIKE"
+        } else {
+            "LIKE"
+        }
+    }
+}
@@ -1,6 +1,14 @@
 pub mod aggregate;
+pub mod alias;
 pub mod binary;
+pub mod cast;
 pub mod column;
+pub mod conditional;
 pub mod expr;
 pub mod expr_fn;
+pub mod function;
+pub mod like;
+pub mod predicate;
 pub mod sort;
+pub mod unary;
+pub mod window;
@@ -0,0 +1,80 @@
+use std::{fmt::Display, sync::Arc};
+
+use super::expr::{BETWEEN_PRECEDENCE, LogicalExpr, format_operand};
+
+/// `expr [NOT] IN (list)`. Follows SQL semantics: when `expr` matches no
+/// item and `expr` or any item is NULL, the result is NULL.
+#[derive(Debug, Clone)]
+pub struct InList {
+    pub expr: Arc<LogicalExpr>,
+    pub list: Vec<Arc<LogicalExpr>>,
+    pub negated: bool,
+}
+
+impl InList {
+    pub fn new(expr: Arc<LogicalExpr>, list: Vec<Arc<LogicalExpr>>, negated: bool) -> Self {
+        Self {
+            expr,
+            list,
+            negated,
+        }
+    }
+
+    pub(crate) fn format(&self, f: impl Fn(&LogicalExpr) -> String) -> String {
+        let list = self.list.iter().map(|e| f(e)).collect::<Vec<_>>();
+        format!(
+            "{} {}IN ({})",
+            format_operand(&self.expr, BETWEEN_PRECEDENCE + 1, &f),
+            if self.negated { "NOT " } else { "" },
+            list.join(", ")
+        )
+    }
+}
+
+impl Display for InList {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.format(|e| e.to_string()))
+    }
+}
+
+/// `expr [NOT] BETWEEN low AND high`, the same as
+/// `expr >= low AND expr <= high`.
+#[derive(Debug, Clone)]
+pub struct Between {
+    pub expr: Arc<LogicalExpr>,
+    pub negated: bool,
+    pub low: Arc<LogicalExpr>,
+    pub high: Arc<LogicalExpr>,
+}
+
+impl Between {
+    pub fn new(
+        expr: Arc<LogicalExpr>,
+        negated: bool,
+        low: Arc<LogicalExpr>,
+        high: Arc<LogicalExpr>,
+    ) -> Self {
+        Self {
+            expr,
+            negated,
+            low,
+            high,
+        }
+    }
+
+    pub(crate) fn format(&self, f: impl Fn(&LogicalExpr) -> String) -> String {
+        format!(
+            "{} {}BETWEEN {} AND {}",
+            format_operand(&self.expr, BETWEEN_PRECEDENCE + 1, &f),
+            if self.negated { "NOT " } else { "" },
+            format_operand(&self.low, BETWEEN_PRECEDENCE + 1, &f),
+            format_operand(&self.high, BETWEEN_PRECEDENCE + 1, &f)
+        )
+    }
+}
+
+impl Display for Between {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.format(|e| e.to_string()))
+    }
+}
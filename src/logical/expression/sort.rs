@@ -0,0 +1,50 @@
+use std::{fmt::Display, sync::Arc};
+
+use super::expr::LogicalExpr;
+
+/// An ordering key of a sort, e.g. `c2 DESC NULLS FIRST`.
+#[derive(Debug, Clone)]
+pub struct SortExpr {
+    pub expr: Arc<LogicalExpr>,
+    pub asc: bool,
+    /// Whether NULLs are placed before all other values.
+    pub nulls_first: bool,
+}
+
+impl SortExpr {
+    pub fn new(expr: Arc<LogicalExpr>, asc: bool, nulls_first: bool) -> Self {
+        Self {
+            expr,
+            asc,
+            nulls_first,
+        }
+    }
+
+    /// Returns a copy of this key that orders by `expr` instead.
+    pub fn with_expr(&self, expr: LogicalExpr) -> Self {
+        Self {
+            expr: Arc::new(expr),
+            ..self.clone()
+        }
+    }
+
+    /// Formats the key with `f` formatting the expression.
+    pub fn format(&self, f: impl Fn(&LogicalExpr) -> String) -> String {
+        format!(
+            "{} {} {}",
+            f(&self.expr),
+            if self.asc { "ASC" } else { "DESC" },
+            if self.nulls_first {
+                "NULLS FIRST"
+            } else {
+                "NULLS LAST"
+            }
+        )
+    }
+}
+
+impl Display for SortExpr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.format(|e| e.to_string()))
+    }
+}
@@ -0,0 +1,34 @@
+use std::{fmt::Display, sync::Arc};
+
+use crate::datatypes::operator::UnaryOperator;
+
+use super::expr::{LogicalExpr, format_operand};
+
+/// Applies `op` to a single operand, e.g. `NOT x`, `-x` or `x IS NULL`.
+#[derive(Debug, Clone)]
+pub struct UnaryExpr {
+    pub op: UnaryOperator,
+    pub expr: Arc<LogicalExpr>,
+}
+
+impl UnaryExpr {
+    pub fn new(op: UnaryOperator, expr: Arc<LogicalExpr>) -> Self {
+        Self { op, expr }
+    }
+
+    /// Formats the expression, rendering the operand with `f`.
+    pub(crate) fn format(&self, f: impl Fn(&LogicalExpr) -> String) -> String {
+        let expr = format_operand(&self.expr, self.op.precedence(), &f);
+        match self.op {
+            UnaryOperator::Negative => format!("-{}", expr),
+            op if op.is_prefix() => format!("{} {}", op, expr),
+            op => format!("{} {}", expr, op),
+        }
+    }
+}
+
+impl Display for UnaryExpr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.format(|e| e.to_string()))
+    }
+}
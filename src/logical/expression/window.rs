@@ -0,0 +1,376 @@
+use std::{fmt::Display, sync::Arc};
+
+use arrow_schema::DataType;
+
+use super::{aggregate::AggregateFunction, expr::LogicalExpr, sort::SortExpr};
+use crate::datatypes::values::ScalarValue;
+use crate::error::{Result, plan_err};
+use crate::logical::schema::QualifiedSchema;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum WindowFunction {
+    RowNumber,
+    Rank,
+    DenseRank,
+    /// `LAG(expr [, offset [, default]])`
+    Lag,
+    /// `LEAD(expr [, offset [, default]])`
+    Lead,
+    FirstValue,
+    LastValue,
+    /// `NTILE(buckets)`
+    Ntile,
+    Aggregate(AggregateFunction),
+}
+
+impl WindowFunction {
+    /// Returns the function called `name` in SQL, if any. Aggregates are
+    /// not included.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "row_number" => WindowFunction::RowNumber,
+            "rank" => WindowFunction::Rank,
+            "dense_rank" => WindowFunction::DenseRank,
+            "lag" => WindowFunction::Lag,
+            "lead" => WindowFunction::Lead,
+            "first_value" => WindowFunction::FirstValue,
+            "last_value" => WindowFunction::LastValue,
+            "ntile" => WindowFunction::Ntile,
+            _ => return None,
+        })
+    }
+
+    /// Whether the value of a row depends on its window frame. Ranking and
+    /// offset functions always look at the whole partition.
+    pub fn uses_frame(&self) -> bool {
+        matches!(
+            self,
+            WindowFunction::FirstValue | WindowFunction::LastValue | WindowFunction::Aggregate(_)
+        )
+    }
+
+    /// Returns the result type of the function when applied to arguments of
+    /// `arg_types`. Ranks and bucket numbers are `Int64`, offset and value
+    /// functions return the type of their first argument.
+    pub fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        let (min_args, max_args) = match self {
+            WindowFunction::RowNumber | WindowFunction::Rank | WindowFunction::DenseRank => (0, 0),
+            WindowFunction::Lag | WindowFunction::Lead => (1, 3),
+            WindowFunction::FirstValue
+            | WindowFunction::LastValue
+            | WindowFunction::Ntile
+            | WindowFunction::Aggregate(_) => (1, 1),
+        };
+        if arg_types.len() < min_args || arg_types.len() > max_args {
+            let expected = if min_args == max_args {
+                min_args.to_string()
+            } else {
+                format!("{} to {}", min_args, max_args)
+            };
+            return plan_err!(
+                "window function {} expects {} arguments, got {}",
+                self,
+                expected,
+                arg_types.len()
+            );
+        }
+
+        match self {
+            WindowFunction::RowNumber
+            | WindowFunction::Rank
+            | WindowFunction::DenseRank
+            | WindowFunction::Ntile => Ok(DataType::Int64),
+            WindowFunction::Lag
+            | WindowFunction::Lead
+            | WindowFunction::FirstValue
+            | WindowFunction::LastValue => Ok(arg_types[0].clone()),
+            WindowFunction::Aggregate(fun) => fun.return_type(&arg_types[0]),
+        }
+    }
+}
+
+impl Display for WindowFunction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WindowFunction::RowNumber => write!(f, "ROW_NUMBER"),
+            WindowFunction::Rank => write!(f, "RANK"),
+            WindowFunction::DenseRank => write!(f, "DENSE_RANK"),
+            WindowFunction::Lag => write!(f, "LAG"),
+            WindowFunction::Lead => write!(f, "LEAD"),
+            WindowFunction::FirstValue => write!(f, "FIRST_VALUE"),
+            WindowFunction::LastValue => write!(f, "LAST_VALUE"),
+            WindowFunction::Ntile => write!(f, "NTILE"),
+            WindowFunction::Aggregate(fun) => write!(f, "{}", fun),
+        }
+    }
+}
+
+/// Whether the offsets of a window frame count rows or differences of the
+/// ordering key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WindowFrameUnits {
+    Rows,
+    Range,
+}
+
+impl Display for WindowFrameUnits {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WindowFrameUnits::Rows => write!(f, "ROWS"),
+            WindowFrameUnits::Range => write!(f, "RANGE"),
+        }
+    }
+}
+
+/// One end of a window frame. An offset of `None` is `UNBOUNDED`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WindowFrameBound {
+    Preceding(Option<u64>),
+    CurrentRow,
+    Following(Option<u64>),
+}
+
+impl WindowFrameBound {
+    /// Orders the bounds from the start of the partition to its end, so a
+    /// frame must not start at a bound ranked after its end.
+    fn rank(&self) -> u8 {
+        match self {
+            WindowFrameBound::Preceding(None) => 0,
+            WindowFrameBound::Preceding(Some(_)) => 1,
+            WindowFrameBound::CurrentRow => 2,
+            WindowFrameBound::Following(Some(_)) => 3,
+            WindowFrameBound::Following(None) => 4,
+        }
+    }
+
+    fn has_offset(&self) -> bool {
+        matches!(
+            self,
+            WindowFrameBound::Preceding(Some(_)) | WindowFrameBound::Following(Some(_))
+        )
+    }
+}
+
+impl Display for WindowFrameBound {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WindowFrameBound::Preceding(None) => write!(f, "UNBOUNDED PRECEDING"),
+            WindowFrameBound::Preceding(Some(n)) => write!(f, "{} PRECEDING", n),
+            WindowFrameBound::CurrentRow => write!(f, "CURRENT ROW"),
+            WindowFrameBound::Following(Some(n)) => write!(f, "{} FOLLOWING", n),
+            WindowFrameBound::Following(None) => write!(f, "UNBOUNDED FOLLOWING"),
+        }
+    }
+}
+
+/// The rows of a partition an aggregate, `FIRST_VALUE` or `LAST_VALUE` sees
+/// for the current row, e.g. `ROWS BETWEEN 2 PRECEDING AND CURRENT ROW`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct WindowFrame {
+    pub units: WindowFrameUnits,
+    pub start: WindowFrameBound,
+    pub end: WindowFrameBound,
+}
+
+impl WindowFrame {
+    /// Fails if the frame starts after it ends, e.g. `CURRENT ROW AND 1
+    /// PRECEDING`, or is unbounded in the wrong direction.
+    pub fn try_new(
+        units: WindowFrameUnits,
+        start: WindowFrameBound,
+        end: WindowFrameBound,
+    ) -> Result<Self> {
+        if start == WindowFrameBound::Following(None) {
+            return plan_err!("a window frame cannot start at UNBOUNDED FOLLOWING");
+        }
+        if end == WindowFrameBound::Preceding(None) {
+            return plan_err!("a window frame cannot end at UNBOUNDED PRECEDING");
+        }
+        if start.rank() > end.rank() {
+            return plan_err!("window frame {} starts after it ends", Self {
+                units,
+                start,
+                end
+            });
+        }
+        Ok(Self { units, start, end })
+    }
+
+    /// The frame used when none is given: every row up to the last peer of
+    /// the current row with an `ORDER BY`, the whole partition without one.
+    pub fn default_for(order_by: &[SortExpr]) -> Self {
+        if order_by.is_empty() {
+            Self {
+                units: WindowFrameUnits::Rows,
+                start: WindowFrameBound::Preceding(None),
+                end: WindowFrameBound::Following(None),
+            }
+        } else {
+            Self {
+                units: WindowFrameUnits::Range,
+                start: WindowFrameBound::Preceding(None),
+                end: WindowFrameBound::CurrentRow,
+            }
+        }
+    }
+
+    /// Whether the frame bounds are distances between ordering keys rather
+    /// than peer groups.
+    pub fn is_range_with_offset(&self) -> bool {
+        self.units == WindowFrameUnits::Range && (self.start.has_offset() || self.end.has_offset())
+    }
+}
+
+impl Display for WindowFrame {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} BETWEEN {} AND {}", self.units, self.start, self.end)
+    }
+}
+
+/// A window function call, e.g.
+/// `SUM(c3) OVER (PARTITION BY c1 ORDER BY c2 ROWS BETWEEN 1 PRECEDING AND CURRENT ROW)`.
+#[derive(Debug, Clone)]
+pub struct WindowExpr {
+    pub fun: WindowFunction,
+    pub args: Vec<Arc<LogicalExpr>>,
+    pub partition_by: Vec<Arc<LogicalExpr>>,
+    pub order_by: Vec<SortExpr>,
+    pub frame: WindowFrame,
+}
+
+impl WindowExpr {
+    pub fn new(
+        fun: WindowFunction,
+        args: Vec<Arc<LogicalExpr>>,
+        partition_by: Vec<Arc<LogicalExpr>>,
+        order_by: Vec<SortExpr>,
+        frame: WindowFrame,
+    ) -> Self {
+        Self {
+            fun,
+            args,
+            partition_by,
+            order_by,
+            frame,
+        }
+    }
+
+    /// Returns the result type of the call. The offset of `LAG`/`LEAD` and
+    /// the bucket count of `NTILE` must be integer literals, and a `RANGE`
+    /// frame with offsets needs a single numeric ordering key.
+    pub fn data_type(&self, schema: &QualifiedSchema) -> Result<DataType> {
+        let arg_types = self
+            .args
+            .iter()
+            .map(|e| e.data_type(schema))
+            .collect::<Result<Vec<_>>>()?;
+        let return_type = self.fun.return_type(&arg_types)?;
+
+        match self.fun {
+            WindowFunction::Lag | WindowFunction::Lead
+                if self.args.len() > 1 && integer_literal(&self.args[1]).is_none() =>
+            {
+                return plan_err!(
+                    "the offset of {} must be an integer literal, got {}",
+                    self.fun,
+                    self.args[1]
+                );
+            }
+            WindowFunction::Ntile if integer_literal(&self.args[0]).is_none_or(|n| n <= 0) => {
+                return plan_err!(
+                    "the bucket count of NTILE must be a positive integer literal, got {}",
+                    self.args[0]
+                );
+            }
+            _ => {}
+        }
+
+        if self.fun.uses_frame() && self.frame.is_range_with_offset() {
+            let [key] = self.order_by.as_slice() else {
+                return plan_err!(
+                    "{} with offsets requires exactly one ORDER BY expression",
+                    self.frame
+                );
+            };
+            let key_type = key.expr.data_type(schema)?;
+            if !key_type.is_numeric() {
+                return plan_err!(
+                    "{} with offsets requires a numeric ORDER BY expression, got {}",
+                    self.frame,
+                    key_type
+                );
+            }
+        }
+        Ok(return_type)
+    }
+
+    /// Ranks and bucket numbers are never NULL, and neither is `COUNT`.
+    pub fn nullable(&self) -> bool {
+        !matches!(
+            self.fun,
+            WindowFunction::RowNumber
+                | WindowFunction::Rank
+                | WindowFunction::DenseRank
+                | WindowFunction::Ntile
+                | WindowFunction::Aggregate(AggregateFunction::Count)
+        )
+    }
+
+    /// Formats the call with `f` formatting the nested expressions. The
+    /// frame is left out for functions that do not use it.
+    pub fn format(&self, f: impl Fn(&LogicalExpr) -> String) -> String {
+        let args = self
+            .args
+            .iter()
+            .map(|e| f(e))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let mut over = vec![];
+        if !self.partition_by.is_empty() {
+            let partition_by = self
+                .partition_by
+                .iter()
+                .map(|e| f(e))
+                .collect::<Vec<_>>()
+                .join(", ");
+            over.push(format!("PARTITION BY {}", partition_by));
+        }
+        if !self.order_by.is_empty() {
+            let order_by = self
+                .order_by
+                .iter()
+                .map(|e| e.format(&f))
+                .collect::<Vec<_>>()
+                .join(", ");
+            over.push(format!("ORDER BY {}", order_by));
+        }
+        if self.fun.uses_frame() {
+            over.push(self.frame.to_string());
+        }
+        format!("{}({}) OVER ({})", self.fun, args, over.join(" "))
+    }
+}
+
+impl Display for WindowExpr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.format(|e| e.to_string()))
+    }
+}
+
+/// Returns the value of an integer literal.
+pub fn integer_literal(expr: &LogicalExpr) -> Option<i64> {
+    let LogicalExpr::Literal(value) = expr else {
+        return None;
+    };
+    match value {
+        ScalarValue::Int8(v) => v.map(i64::from),
+        ScalarValue::Int16(v) => v.map(i64::from),
+        ScalarValue::Int32(v) => v.map(i64::from),
+        ScalarValue::Int64(v) => *v,
+        ScalarValue::Uint8(v) => v.map(i64::from),
+        ScalarValue::Uint16(v) => v.map(i64::from),
+        ScalarValue::Uint32(v) => v.map(i64::from),
+        ScalarValue::Uint64(v) => v.and_then(|v| i64::try_from(v).ok()),
+        _ => None,
+    }
+}
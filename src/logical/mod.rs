@@ -1,3 +1,4 @@
 pub mod expression;
 pub mod plan;
+pub mod schema;
 // 编写一个测试case
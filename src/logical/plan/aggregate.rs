@@ -1,3 +1,83 @@
+use std::{fmt::Display, sync::Arc};
+
+use arrow_schema::Field;
+
+use crate::{
+    error::{Result, plan_err},
+    logical::{
+        expression::{aggregate::AggregateFunction, expr::LogicalExpr},
+        schema::{QualifiedSchema, QualifiedSchemaRef, find_duplicate_name},
+    },
+};
+
+use super::logical_plan::LogicalPlan;
+
 #[derive(Debug, Clone)]
+pub struct Aggregate {
+    pub input: Arc<LogicalPlan>,
+    pub group_exprs: Vec<LogicalExpr>,
+    /// Each entry must be a [`LogicalExpr::Aggregate`].
+    pub aggr_exprs: Vec<LogicalExpr>,
+    /// The group columns followed by the aggregate columns. Group columns
+    /// that are plain column references keep their qualifier.
+    pub schema: QualifiedSchemaRef,
+}
+
+impl Aggregate {
+    pub fn try_new(
+        input: Arc<LogicalPlan>,
+        group_exprs: Vec<LogicalExpr>,
+        aggr_exprs: Vec<LogicalExpr>,
+    ) -> Result<Self> {
+        let input_schema = input.schema();
+        let mut fields = Vec::with_capacity(group_exprs.len() + aggr_exprs.len());
+        for expr in group_exprs.iter() {
+            let (qualifier, field) = expr.to_qualified_field(&input_schema)?;
+            fields.push((qualifier, Arc::new(field.with_nullable(true))));
+        }
+        for expr in aggr_exprs.iter() {
+            let LogicalExpr::Aggregate(aggr) = expr else {
+                return plan_err!("expected an aggregate expression, got {}", expr);
+            };
+            // COUNT never produces NULL, every other aggregate does on empty input.
+            let nullable = aggr.fun != AggregateFunction::Count;
+            fields.push((
+                None,
+                Arc::new(Field::new(
+                    expr.name(),
+                    aggr.data_type(&input_schema)?,
+                    nullable,
+                )),
+            ));
+        }
+        if let Some(i) = find_duplicate_name(&fields) {
+            let expr = group_exprs.iter().chain(aggr_exprs.iter()).nth(i).unwrap();
+            return plan_err!("aggregate contains duplicate column '{}'", expr);
+        }
+
+        Ok(Self {
+            input,
+            group_exprs,
+            aggr_exprs,
+            schema: Arc::new(QualifiedSchema::try_new(fields)?),
+        })
+    }
+}
 
-pub struct Aggregate {}
+impl Display for Aggregate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let join = |exprs: &[LogicalExpr]| {
+            exprs
+                .iter()
+                .map(|e| e.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        write!(
+            f,
+            "Aggregate: groupBy=[{}], aggr=[{}]",
+            join(&self.group_exprs),
+            join(&self.aggr_exprs)
+        )
+    }
+}
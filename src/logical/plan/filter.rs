@@ -2,7 +2,7 @@ use std::{fmt::Display, sync::Arc};
 
 use crate::logical::expression::expr::LogicalExpr;
 
-use super::plan::LogicalPlan;
+use super::logical_plan::LogicalPlan;
 
 #[derive(Debug, Clone)]
 pub struct Filter {
@@ -0,0 +1,160 @@
+use std::{fmt::Display, sync::Arc};
+
+use crate::{
+    datatypes::{coercion::binary_result_type, operator::Operator},
+    error::Result,
+    logical::{
+        expression::expr::LogicalExpr,
+        schema::{QualifiedSchema, QualifiedSchemaRef},
+    },
+};
+
+use super::logical_plan::LogicalPlan;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum JoinType {
+    Inner,
+    /// All rows of the left side, with NULLs for unmatched right rows.
+    Left,
+    /// All rows of the right side, with NULLs for unmatched left rows.
+    Right,
+    /// All rows of both sides.
+    Full,
+    /// Left rows that have at least one match; only left columns are returned.
+    LeftSemi,
+    /// Left rows that have no match; only left columns are returned.
+    LeftAnti,
+    /// Right rows that have at least one match; only right columns are
+    /// returned.
+    RightSemi,
+    /// Right rows that have no match; only right columns are returned.
+    RightAnti,
+}
+
+impl JoinType {
+    /// Whether the output contains the left columns.
+    pub fn outputs_left(&self) -> bool {
+        !matches!(self, JoinType::RightSemi | JoinType::RightAnti)
+    }
+
+    /// Whether the output contains the right columns.
+    pub fn outputs_right(&self) -> bool {
+        !matches!(self, JoinType::LeftSemi | JoinType::LeftAnti)
+    }
+}
+
+impl Display for JoinType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            JoinType::Inner => "Inner",
+            JoinType::Left => "Left",
+            JoinType::Right => "Right",
+            JoinType::Full => "Full",
+            JoinType::LeftSemi => "LeftSemi",
+            JoinType::LeftAnti => "LeftAnti",
+            JoinType::RightSemi => "RightSemi",
+            JoinType::RightAnti => "RightAnti",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Returns the output schema of a join. Columns of a side that may be filled
+/// with NULLs for unmatched rows become nullable. Fails if both sides have a
+/// column with the same qualified name.
+pub fn join_schema(
+    left: &QualifiedSchema,
+    right: &QualifiedSchema,
+    join_type: JoinType,
+) -> Result<QualifiedSchema> {
+    let nullable_left = matches!(join_type, JoinType::Right | JoinType::Full);
+    let nullable_right = matches!(join_type, JoinType::Left | JoinType::Full);
+    let fields = |schema: &QualifiedSchema, nullable: bool| {
+        schema
+            .iter()
+            .map(|(qualifier, f)| {
+                let nullable = nullable || f.is_nullable();
+                (
+                    qualifier,
+                    Arc::new(f.as_ref().clone().with_nullable(nullable)),
+                )
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let mut output = vec![];
+    if join_type.outputs_left() {
+        output.extend(fields(left, nullable_left));
+    }
+    if join_type.outputs_right() {
+        output.extend(fields(right, nullable_right));
+    }
+    QualifiedSchema::try_new(output)
+}
+
+/// Joins `left` and `right` on equal keys. `filter` is evaluated on every
+/// pair of rows with equal keys; only pairs for which it is true match.
+#[derive(Debug, Clone)]
+pub struct Join {
+    pub left: Arc<LogicalPlan>,
+    pub right: Arc<LogicalPlan>,
+    /// Pairs of a left key and the right key it must be equal to.
+    pub on: Vec<(LogicalExpr, LogicalExpr)>,
+    /// A predicate over the columns of both sides.
+    pub filter: Option<LogicalExpr>,
+    pub join_type: JoinType,
+    pub schema: QualifiedSchemaRef,
+}
+
+impl Join {
+    pub fn try_new(
+        left: Arc<LogicalPlan>,
+        right: Arc<LogicalPlan>,
+        on: Vec<(LogicalExpr, LogicalExpr)>,
+        filter: Option<LogicalExpr>,
+        join_type: JoinType,
+    ) -> Result<Self> {
+        let left_schema = left.schema();
+        let right_schema = right.schema();
+
+        // Also rejects sides with the same qualified column, e.g. a self join
+        // without aliases, for semi and anti joins.
+        let both = left_schema.join(&right_schema)?;
+        for (l, r) in on.iter() {
+            binary_result_type(
+                &l.data_type(&left_schema)?,
+                &Operator::Eq,
+                &r.data_type(&right_schema)?,
+            )?;
+        }
+        if let Some(filter) = &filter {
+            filter.data_type(&both)?;
+        }
+
+        let schema = join_schema(&left_schema, &right_schema, join_type)?;
+        Ok(Self {
+            left,
+            right,
+            on,
+            filter,
+            join_type,
+            schema: Arc::new(schema),
+        })
+    }
+}
+
+impl Display for Join {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let on = self
+            .on
+            .iter()
+            .map(|(l, r)| format!("{} = {}", l, r))
+            .collect::<Vec<_>>()
+            .join(", ");
+        write!(f, "Join: type={}, on=[{}]", self.join_type, on)?;
+        if let Some(filter) = &self.filter {
+            write!(f, ", filter={}", filter)?;
+        }
+        Ok(())
+    }
+}
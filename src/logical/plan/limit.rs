@@ -0,0 +1,28 @@
+use std::{fmt::Display, sync::Arc};
+
+use super::logical_plan::LogicalPlan;
+
+/// Skips the first `skip` rows of `input` and returns at most `fetch` of the
+/// remaining ones, e.g. `LIMIT 10 OFFSET 5`.
+#[derive(Debug, Clone)]
+pub struct Limit {
+    pub input: Arc<LogicalPlan>,
+    pub skip: usize,
+    /// The maximum number of rows to return, unbounded if `None`.
+    pub fetch: Option<usize>,
+}
+
+impl Limit {
+    pub fn new(input: Arc<LogicalPlan>, skip: usize, fetch: Option<usize>) -> Self {
+        Self { input, skip, fetch }
+    }
+}
+
+impl Display for Limit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.fetch {
+            Some(fetch) => write!(f, "Limit: skip={}, fetch={}", self.skip, fetch),
+            None => write!(f, "Limit: skip={}, fetch=None", self.skip),
+        }
+    }
+}
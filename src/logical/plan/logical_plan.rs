@@ -0,0 +1,133 @@
+use std::fmt::Display;
+
+use std::sync::Arc;
+
+use crate::{
+    error::{Result, internal_err},
+    logical::schema::QualifiedSchemaRef,
+};
+
+use super::{
+    aggregate::Aggregate, filter::Filter, join::Join, limit::Limit, projection::Projection,
+    scan::Scan, sort::Sort, subquery_alias::SubqueryAlias, window::Window,
+};
+
+#[derive(Debug, Clone)]
+pub enum LogicalPlan {
+    Scan(Scan),
+    Filter(Filter),
+    Projection(Projection),
+    Aggregate(Aggregate),
+    Sort(Sort),
+    Limit(Limit),
+    Join(Join),
+    SubqueryAlias(SubqueryAlias),
+    Window(Window),
+}
+
+impl LogicalPlan {
+    pub fn children(&self) -> Vec<&LogicalPlan> {
+        match self {
+            LogicalPlan::Scan(_) => vec![],
+            LogicalPlan::Filter(selection) => vec![&selection.input],
+            LogicalPlan::Projection(projection) => vec![&projection.input],
+            LogicalPlan::Aggregate(aggregate) => vec![&aggregate.input],
+            LogicalPlan::Sort(sort) => vec![&sort.input],
+            LogicalPlan::Limit(limit) => vec![&limit.input],
+            LogicalPlan::Join(join) => vec![&join.left, &join.right],
+            LogicalPlan::SubqueryAlias(alias) => vec![&alias.input],
+            LogicalPlan::Window(window) => vec![&window.input],
+        }
+    }
+
+    /// Returns a copy of this plan whose inputs are replaced by `inputs`,
+    /// which must be given in the order returned by [`Self::children`].
+    pub fn with_new_inputs(&self, inputs: Vec<LogicalPlan>) -> Result<LogicalPlan> {
+        let expected = self.children().len();
+        if inputs.len() != expected {
+            return internal_err!("expected {} inputs, got {}", expected, inputs.len());
+        }
+        let mut inputs = inputs.into_iter().map(Arc::new);
+        Ok(match self {
+            LogicalPlan::Scan(_) => self.clone(),
+            LogicalPlan::Filter(plan) => {
+                LogicalPlan::Filter(Filter::new(inputs.next().unwrap(), plan.predicate.clone()))
+            }
+            LogicalPlan::Projection(plan) => LogicalPlan::Projection(Projection::try_new(
+                inputs.next().unwrap(),
+                plan.exprs.clone(),
+            )?),
+            LogicalPlan::Aggregate(plan) => LogicalPlan::Aggregate(Aggregate::try_new(
+                inputs.next().unwrap(),
+                plan.group_exprs.clone(),
+                plan.aggr_exprs.clone(),
+            )?),
+            LogicalPlan::Sort(plan) => {
+                LogicalPlan::Sort(Sort::new(inputs.next().unwrap(), plan.exprs.clone()))
+            }
+            LogicalPlan::Limit(plan) => {
+                LogicalPlan::Limit(Limit::new(inputs.next().unwrap(), plan.skip, plan.fetch))
+            }
+            LogicalPlan::Join(plan) => LogicalPlan::Join(Join::try_new(
+                inputs.next().unwrap(),
+                inputs.next().unwrap(),
+                plan.on.clone(),
+                plan.filter.clone(),
+                plan.join_type,
+            )?),
+            LogicalPlan::SubqueryAlias(plan) => LogicalPlan::SubqueryAlias(SubqueryAlias::try_new(
+                inputs.next().unwrap(),
+                plan.alias.clone(),
+            )?),
+            LogicalPlan::Window(plan) => LogicalPlan::Window(Window::try_new(
+                inputs.next().unwrap(),
+                plan.window_exprs.clone(),
+            )?),
+        })
+    }
+
+    pub fn schema(&self) -> QualifiedSchemaRef {
+        match self {
+            LogicalPlan::Scan(plan) => plan.schema(),
+            LogicalPlan::Projection(plan) => plan.schema.clone(),
+            LogicalPlan::Filter(plan) => plan.input.schema(),
+            LogicalPlan::Aggregate(plan) => plan.schema.clone(),
+            LogicalPlan::Sort(plan) => plan.input.schema(),
+            LogicalPlan::Limit(plan) => plan.input.schema(),
+            LogicalPlan::Join(plan) => plan.schema.clone(),
+            LogicalPlan::SubqueryAlias(plan) => plan.schema.clone(),
+            LogicalPlan::Window(plan) => plan.schema.clone(),
+        }
+    }
+}
+
+impl Display for LogicalPlan {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", format_plan(self, 0))
+    }
+}
+fn format_plan(plan: &LogicalPlan, indent: usize) -> String {
+    let mut s = String::new();
+    for _ in 0..indent {
+        s.push('\t');
+    }
+    let cur_plan = match plan {
+        LogicalPlan::Scan(scan) => scan.to_string(),
+        LogicalPlan::Filter(selection) => selection.to_string(),
+        LogicalPlan::Projection(projection) => projection.to_string(),
+        LogicalPlan::Aggregate(aggregate) => aggregate.to_string(),
+        LogicalPlan::Sort(sort) => sort.to_string(),
+        LogicalPlan::Limit(limit) => limit.to_string(),
+        LogicalPlan::Join(join) => join.to_string(),
+        LogicalPlan::SubqueryAlias(alias) => alias.to_string(),
+        LogicalPlan::Window(window) => window.to_string(),
+    };
+
+    s.push_str(cur_plan.as_str());
+    s.push('\n');
+
+    for child in plan.children() {
+        s.push_str(format_plan(child, indent + 1).as_str());
+    }
+    s
+}
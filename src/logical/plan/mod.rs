@@ -1,13 +1,24 @@
 mod aggregate;
 mod filter;
-mod plan;
+mod join;
+mod limit;
+mod logical_plan;
 mod projection;
 mod scan;
+mod sort;
+mod subquery_alias;
+mod window;
 
+pub use aggregate::*;
 pub use filter::*;
-pub use plan::*;
+pub use join::*;
+pub use limit::*;
+pub use logical_plan::*;
 pub use projection::*;
 pub use scan::*;
+pub use sort::*;
+pub use subquery_alias::*;
+pub use window::*;
 
 #[cfg(test)]
 mod test {
@@ -15,14 +26,15 @@ mod test {
 
     use crate::{
         datasource::MemDataSource,
+        error::Result,
         logical::{
             expression::expr_fn::{col, lit},
-            plan::{filter::Filter, plan::LogicalPlan, projection::Projection, scan::Scan},
+            plan::{filter::Filter, logical_plan::LogicalPlan, projection::Projection, scan::Scan},
         },
     };
 
     #[test]
-    fn test_build_plan_manually() {
+    fn test_build_plan_manually() -> Result<()> {
         let scan = LogicalPlan::Scan(Scan::new(
             "testdata/csv/simple.csv",
             MemDataSource::new_arc(),
@@ -31,21 +43,22 @@ mod test {
 
         let filter = LogicalPlan::Filter(Filter::new(Arc::new(scan), col("c1").eq(lit(1))));
 
-        let projection = LogicalPlan::Projection(Projection::new(Arc::new(filter), vec![
+        let projection = LogicalPlan::Projection(Projection::try_new(Arc::new(filter), vec![
             col("c1"),
             col("c2"),
-        ]));
+        ])?);
 
         assert_eq!(
             projection.to_string().as_str(),
             "Projection: c1, c2\n".to_string()
                 + "\tFilter: c1 = 1\n"
                 + "\t\tScan: testdata/csv/simple.csv; projection=None\n"
-        )
+        );
+        Ok(())
     }
 
     #[test]
-    fn test_build_plan_manually2() {
+    fn test_build_plan_manually2() -> Result<()> {
         let scan = LogicalPlan::Scan(Scan::new(
             "testdata/csv/simple.csv",
             MemDataSource::new_arc(),
@@ -54,16 +67,17 @@ mod test {
 
         let filter = LogicalPlan::Filter(Filter::new(Arc::new(scan), col("c1").eq(lit(1))));
 
-        let projection = LogicalPlan::Projection(Projection::new(Arc::new(filter), vec![
+        let projection = LogicalPlan::Projection(Projection::try_new(Arc::new(filter), vec![
             col("c1"),
             col("c2"),
-        ]));
+        ])?);
 
         assert_eq!(
             projection.to_string().as_str(),
             "Projection: c1, c2\n".to_string()
                 + "\tFilter: c1 = 1\n"
                 + "\t\tScan: testdata/csv/simple.csv; projection=[\"c1\", \"c2\"]\n"
-        )
+        );
+        Ok(())
     }
 }
@@ -1,27 +1,49 @@
 use std::{fmt::Display, sync::Arc};
 
-use arrow::datatypes::SchemaRef;
+use crate::{
+    error::{Result, plan_err},
+    logical::{
+        expression::expr::LogicalExpr,
+        schema::{QualifiedSchema, QualifiedSchemaRef, find_duplicate_name},
+    },
+};
 
-use crate::logical::expression::expr::LogicalExpr;
-
-use super::plan::LogicalPlan;
+use super::logical_plan::LogicalPlan;
 
 #[derive(Debug, Clone)]
 
 pub struct Projection {
     pub input: Arc<LogicalPlan>,
     pub exprs: Vec<LogicalExpr>,
-    // pub schema: SchemaRef,
+    /// One field per expression. Bare column references keep their
+    /// qualifier, every other expression is named by [`LogicalExpr::name`].
+    pub schema: QualifiedSchemaRef,
 }
 
 impl Projection {
-    pub fn new(input: Arc<LogicalPlan>, exprs: Vec<LogicalExpr>) -> Self {
-        Self {
+    /// Fails if an expression does not resolve against the input, or if two
+    /// output columns could not be told apart by name.
+    pub fn try_new(input: Arc<LogicalPlan>, exprs: Vec<LogicalExpr>) -> Result<Self> {
+        let input_schema = input.schema();
+        let fields = exprs
+            .iter()
+            .map(|expr| {
+                let (qualifier, field) = expr.to_qualified_field(&input_schema)?;
+                Ok((qualifier, Arc::new(field)))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        if let Some(i) = find_duplicate_name(&fields) {
+            return plan_err!(
+                "projection contains duplicate column '{}', use an alias to rename it",
+                exprs[i]
+            );
+        }
+
+        Ok(Self {
             input,
             exprs,
-            // schema: input.schema().clone(),
-            // schema: SchemaRef::defa
-        }
+            schema: Arc::new(QualifiedSchema::try_new(fields)?),
+        })
     }
 }
 
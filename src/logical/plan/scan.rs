@@ -1,13 +1,28 @@
 use std::{fmt::Display, sync::Arc};
 
-use crate::datasource::DataSource;
+use crate::{
+    datasource::{DataSource, project_schema},
+    logical::{
+        expression::expr::LogicalExpr,
+        schema::{QualifiedSchema, QualifiedSchemaRef},
+    },
+};
 
 #[derive(Debug, Clone)]
 
 pub struct Scan {
     pub path: String,
+    /// The name the columns of the scan are qualified with: the table name or
+    /// its alias. `None` leaves them unqualified.
+    pub qualifier: Option<String>,
     pub source: Arc<dyn DataSource>,
     pub projection: Option<Vec<String>>, // 为什么不用expr
+    /// Predicates pushed into the scan; only rows matching all of them are
+    /// returned.
+    pub filters: Vec<LogicalExpr>,
+    /// The maximum number of rows the scan has to return, pushed down from a
+    /// limit. Only set when there are no `filters`.
+    pub fetch: Option<usize>,
 }
 
 impl Scan {
@@ -18,37 +33,101 @@ impl Scan {
     ) -> Self {
         Self {
             path: path.into(),
+            qualifier: None,
             source,
             projection,
+            filters: vec![],
+            fetch: None,
         }
     }
+
+    pub fn with_qualifier(mut self, qualifier: impl Into<String>) -> Self {
+        self.qualifier = Some(qualifier.into());
+        self
+    }
+
+    pub fn with_filters(mut self, filters: Vec<LogicalExpr>) -> Self {
+        self.filters = filters;
+        self
+    }
+
+    /// Returns the schema of the rows produced by the scan.
+    pub fn schema(&self) -> QualifiedSchemaRef {
+        let schema = project_schema(&self.source.schema(), self.projection.as_ref());
+        Arc::new(QualifiedSchema::from_schema(
+            self.qualifier.as_deref(),
+            schema,
+        ))
+    }
+
+    /// Returns the columns that must be read from the source: the projected
+    /// columns plus the columns referenced by `filters`, in source order.
+    pub fn read_columns(&self) -> Option<Vec<String>> {
+        let projection = self.projection.as_ref()?;
+        let schema = self.source.schema();
+        let read_columns = schema
+            .fields()
+            .iter()
+            .map(|f| f.name())
+            .filter(|name| {
+                projection.contains(name)
+                    || self
+                        .filters
+                        .iter()
+                        .any(|e| e.columns().iter().any(|c| &c.name == *name))
+            })
+            .cloned()
+            .collect();
+        Some(read_columns)
+    }
 }
 
 impl Display for Scan {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Scan: {}", self.path)?;
+        match &self.qualifier {
+            Some(qualifier) if *qualifier != self.path => write!(f, " AS {}", qualifier)?,
+            _ => {}
+        }
         match self.projection {
-            None => write!(f, "Scan: {}; projection=None", self.path),
-            Some(ref projection) => {
-                write!(f, "Scan: {}; projection={:?}", self.path, projection)
-            }
+            None => write!(f, "; projection=None")?,
+            Some(ref projection) => write!(f, "; projection={:?}", projection)?,
+        }
+        if !self.filters.is_empty() {
+            let filters = self
+                .filters
+                .iter()
+                .map(|e| e.to_string())
+                .collect::<Vec<_>>()
+                .join(", ");
+            write!(f, "; filters=[{}]", filters)?;
+        }
+        if let Some(fetch) = self.fetch {
+            write!(f, "; fetch={}", fetch)?;
         }
+        Ok(())
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::datasource::MemDataSource;
+
     #[test]
     fn test_scan_display_none() {
-        // let scan = Scan::new("scan", , None);
-        // println!("{}", scan);
-
-        // assert_eq!(format!("{}", scan), "Scan: scan; projection=None");
-        // let scan = Scan::new(
-        //     "scan",
-        //     "source".to_string(),
-        //     Some(vec!["a".to_string(), "b".to_string()]),
-        // );
-        // assert_eq!(format!("{}", scan), r#"Scan: scan; projection=["a", "b"]"#);
+        let scan = Scan::new("scan", MemDataSource::new_arc(), None);
+        assert_eq!(format!("{}", scan), "Scan: scan; projection=None");
+
+        let scan = Scan::new(
+            "scan",
+            MemDataSource::new_arc(),
+            Some(vec!["a".to_string(), "b".to_string()]),
+        );
+        assert_eq!(format!("{}", scan), r#"Scan: scan; projection=["a", "b"]"#);
+
+        let scan = Scan::new("scan", MemDataSource::new_arc(), None).with_qualifier("s");
+        assert_eq!(format!("{}", scan), "Scan: scan AS s; projection=None");
+        assert_eq!(scan.schema().qualifier(0), Some("s"));
     }
 }
@@ -0,0 +1,31 @@
+use std::{fmt::Display, sync::Arc};
+
+use crate::logical::expression::sort::SortExpr;
+
+use super::logical_plan::LogicalPlan;
+
+/// Orders the rows of `input` by `exprs`, the first key being the most
+/// significant one.
+#[derive(Debug, Clone)]
+pub struct Sort {
+    pub input: Arc<LogicalPlan>,
+    pub exprs: Vec<SortExpr>,
+}
+
+impl Sort {
+    pub fn new(input: Arc<LogicalPlan>, exprs: Vec<SortExpr>) -> Self {
+        Self { input, exprs }
+    }
+}
+
+impl Display for Sort {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let exprs = self
+            .exprs
+            .iter()
+            .map(|e| e.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        write!(f, "Sort: {}", exprs)
+    }
+}
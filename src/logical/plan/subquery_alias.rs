@@ -0,0 +1,42 @@
+use std::{fmt::Display, sync::Arc};
+
+use crate::{
+    error::Result,
+    logical::schema::{QualifiedSchema, QualifiedSchemaRef},
+};
+
+use super::logical_plan::LogicalPlan;
+
+/// Gives the rows of `input` a relation name, e.g. `s` in
+/// `FROM (SELECT ...) AS s`. Every output column is qualified by `alias`.
+#[derive(Debug, Clone)]
+pub struct SubqueryAlias {
+    pub input: Arc<LogicalPlan>,
+    pub alias: String,
+    pub schema: QualifiedSchemaRef,
+}
+
+impl SubqueryAlias {
+    pub fn try_new(input: Arc<LogicalPlan>, alias: impl Into<String>) -> Result<Self> {
+        let alias = alias.into();
+        let input_schema = input.schema();
+        let schema = QualifiedSchema::try_new(
+            input_schema
+                .fields()
+                .iter()
+                .map(|f| (Some(alias.clone()), f.clone()))
+                .collect(),
+        )?;
+        Ok(Self {
+            input,
+            alias,
+            schema: Arc::new(schema),
+        })
+    }
+}
+
+impl Display for SubqueryAlias {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "SubqueryAlias: {}", self.alias)
+    }
+}
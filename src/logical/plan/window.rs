@@ -0,0 +1,55 @@
+use std::{fmt::Display, sync::Arc};
+
+use crate::{
+    error::{Result, plan_err},
+    logical::{
+        expression::expr::LogicalExpr,
+        schema::{QualifiedSchema, QualifiedSchemaRef},
+    },
+};
+
+use super::logical_plan::LogicalPlan;
+
+/// Computes `window_exprs` for every row of `input`, which keeps its rows
+/// and columns.
+#[derive(Debug, Clone)]
+pub struct Window {
+    pub input: Arc<LogicalPlan>,
+    /// Each entry must be a [`LogicalExpr::WindowFunction`], possibly
+    /// aliased.
+    pub window_exprs: Vec<LogicalExpr>,
+    /// The input columns followed by one column per window expression.
+    pub schema: QualifiedSchemaRef,
+}
+
+impl Window {
+    pub fn try_new(input: Arc<LogicalPlan>, window_exprs: Vec<LogicalExpr>) -> Result<Self> {
+        let input_schema = input.schema();
+        let mut fields = input_schema.iter().collect::<Vec<_>>();
+        for expr in window_exprs.iter() {
+            if !matches!(expr.unalias(), LogicalExpr::WindowFunction(_)) {
+                return plan_err!("expected a window expression, got {}", expr);
+            }
+            let (qualifier, field) = expr.to_qualified_field(&input_schema)?;
+            fields.push((qualifier, Arc::new(field)));
+        }
+
+        Ok(Self {
+            input,
+            window_exprs,
+            schema: Arc::new(QualifiedSchema::try_new(fields)?),
+        })
+    }
+}
+
+impl Display for Window {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let exprs = self
+            .window_exprs
+            .iter()
+            .map(|e| e.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        write!(f, "Window: [{}]", exprs)
+    }
+}
@@ -0,0 +1,198 @@
+use std::{collections::HashSet, sync::Arc};
+
+use arrow_schema::{Field, FieldRef, Fields, Schema, SchemaRef};
+
+use crate::{
+    error::{DBError, Result, schema_err},
+    logical::expression::column::Column,
+};
+
+pub type QualifiedSchemaRef = Arc<QualifiedSchema>;
+
+/// The schema of a logical plan. Every field may be qualified by the name of
+/// the relation it comes from, so `t1.c1` and `t2.c1` can coexist after a
+/// join.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QualifiedSchema {
+    qualifiers: Vec<Option<String>>,
+    schema: SchemaRef,
+}
+
+impl QualifiedSchema {
+    /// Fails if two fields have the same name and qualifier.
+    pub fn try_new(fields: Vec<(Option<String>, FieldRef)>) -> Result<Self> {
+        let mut seen = HashSet::new();
+        for (qualifier, field) in fields.iter() {
+            if !seen.insert((qualifier.as_deref(), field.name().as_str())) {
+                return schema_err!(
+                    "duplicate column '{}'",
+                    qualified_name(qualifier.as_deref(), field.name())
+                );
+            }
+        }
+        let (qualifiers, fields): (Vec<_>, Vec<_>) = fields.into_iter().unzip();
+        Ok(Self {
+            qualifiers,
+            schema: Arc::new(Schema::new(fields)),
+        })
+    }
+
+    /// Qualifies every field of `schema` with `qualifier`.
+    pub fn from_schema(qualifier: Option<&str>, schema: SchemaRef) -> Self {
+        Self {
+            qualifiers: vec![qualifier.map(String::from); schema.fields().len()],
+            schema,
+        }
+    }
+
+    /// Returns the fields of `self` followed by the fields of `other`.
+    pub fn join(&self, other: &QualifiedSchema) -> Result<Self> {
+        Self::try_new(self.iter().chain(other.iter()).collect())
+    }
+
+    /// Returns the plain Arrow schema, without qualifiers.
+    pub fn as_arrow(&self) -> &SchemaRef {
+        &self.schema
+    }
+
+    pub fn fields(&self) -> &Fields {
+        self.schema.fields()
+    }
+
+    pub fn field(&self, i: usize) -> &Field {
+        self.schema.field(i)
+    }
+
+    pub fn qualifier(&self, i: usize) -> Option<&str> {
+        self.qualifiers[i].as_deref()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Option<String>, FieldRef)> + '_ {
+        self.qualifiers
+            .iter()
+            .cloned()
+            .zip(self.fields().iter().cloned())
+    }
+
+    /// Returns a reference to every field, qualified when the field is.
+    pub fn columns(&self) -> Vec<Column> {
+        self.qualifiers
+            .iter()
+            .zip(self.fields().iter())
+            .map(|(qualifier, f)| Column::new_qualified(qualifier.clone(), f.name()))
+            .collect()
+    }
+
+    /// Finds the field `column` refers to. An unqualified column matches a
+    /// field of any relation, so it is ambiguous when several relations have
+    /// a field with that name.
+    pub fn index_of_column(&self, column: &Column) -> Result<usize> {
+        let matches = (0..self.fields().len())
+            .filter(|&i| {
+                self.field(i).name() == &column.name
+                    && match &column.relation {
+                        Some(relation) => self.qualifier(i) == Some(relation.as_str()),
+                        None => true,
+                    }
+            })
+            .collect::<Vec<_>>();
+        match matches.as_slice() {
+            [i] => Ok(*i),
+            [] => Err(DBError::ColumnNotFound {
+                name: column.to_string(),
+                candidates: self.qualified_names(),
+            }),
+            _ => Err(DBError::AmbiguousColumn {
+                name: column.to_string(),
+                candidates: matches
+                    .iter()
+                    .map(|&i| qualified_name(self.qualifier(i), self.field(i).name()))
+                    .collect(),
+            }),
+        }
+    }
+
+    pub fn field_from_column(&self, column: &Column) -> Result<&Field> {
+        Ok(self.field(self.index_of_column(column)?))
+    }
+
+    pub fn has_column(&self, column: &Column) -> bool {
+        self.index_of_column(column).is_ok()
+    }
+
+    /// Returns `relation.name` for every field, or just `name` when the field
+    /// is unqualified.
+    pub fn qualified_names(&self) -> Vec<String> {
+        (0..self.fields().len())
+            .map(|i| qualified_name(self.qualifier(i), self.field(i).name()))
+            .collect()
+    }
+}
+
+/// Returns the index of the first field that cannot be told apart from an
+/// earlier one: both have the same name and either the same qualifier or
+/// one of them is unqualified, so an unqualified reference to the name
+/// would be ambiguous either way.
+pub(crate) fn find_duplicate_name(fields: &[(Option<String>, FieldRef)]) -> Option<usize> {
+    (0..fields.len()).find(|&i| {
+        let (qualifier, field) = &fields[i];
+        fields[..i].iter().any(|(other_qualifier, other)| {
+            other.name() == field.name()
+                && (qualifier.is_none()
+                    || other_qualifier.is_none()
+                    || qualifier == other_qualifier)
+        })
+    })
+}
+
+fn qualified_name(qualifier: Option<&str>, name: &str) -> String {
+    match qualifier {
+        Some(qualifier) => format!("{}.{}", qualifier, name),
+        None => name.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow_schema::DataType;
+
+    use super::*;
+
+    fn schema(qualifier: &str) -> QualifiedSchema {
+        let schema = Schema::new(vec![
+            Field::new("a", DataType::Int32, false),
+            Field::new("b", DataType::Utf8, true),
+        ]);
+        QualifiedSchema::from_schema(Some(qualifier), Arc::new(schema))
+    }
+
+    #[test]
+    fn test_resolve_columns() -> Result<()> {
+        let schema = schema("t1").join(&schema("t2"))?;
+        assert_eq!(
+            schema.index_of_column(&Column::new_qualified(Some("t2"), "a"))?,
+            2
+        );
+        assert_eq!(
+            schema.index_of_column(&Column::new_qualified(Some("t1"), "b"))?,
+            1
+        );
+
+        let err = schema.index_of_column(&Column::new("a")).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "column reference 'a' is ambiguous, candidates: t1.a, t2.a"
+        );
+        let err = schema
+            .index_of_column(&Column::new_qualified(Some("t3"), "a"))
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "column 't3.a' not found, available columns: t1.a, t1.b, t2.a, t2.b"
+        );
+
+        let err = schema.join(&schema).unwrap_err();
+        assert_eq!(err.to_string(), "schema error: duplicate column 't1.a'");
+        Ok(())
+    }
+}
@@ -0,0 +1,423 @@
+use std::sync::Arc;
+
+use arrow::array::{RecordBatch, RecordBatchOptions};
+use arrow_schema::Schema;
+
+use super::{OptimizerRule, optimize_children};
+use crate::{
+    datatypes::{operator::Operator, values::ScalarValue},
+    error::Result,
+    execution::{Planner, SessionConfig},
+    logical::{
+        expression::{alias::Alias, binary::BinaryExpr, expr::LogicalExpr},
+        plan::{Aggregate, Filter, Join, LogicalPlan, Projection, Scan, Sort, Window},
+        schema::QualifiedSchema,
+    },
+    physical::expr::ColumnarValue,
+};
+
+/// Evaluates sub-expressions that do not depend on the input rows, e.g.
+/// `1 + 2` becomes `3`, and removes operations that cannot change their
+/// operand, e.g. `x AND true` becomes `x` and `x * 1` becomes `x`.
+///
+/// Constants whose evaluation fails, such as `1 / 0`, are left alone so the
+/// error is raised when the query runs. Projections keep their output names,
+/// so `c1 + 0` is rewritten to `c1 AS c1 + 0`.
+pub struct ConstantFolding;
+
+impl OptimizerRule for ConstantFolding {
+    fn try_optimize(&self, plan: &LogicalPlan) -> Result<Option<LogicalPlan>> {
+        let new_plan = optimize_children(plan, |child| self.try_optimize(child))?;
+        let plan_ref = new_plan.as_ref().unwrap_or(plan);
+        Ok(simplify_plan(plan_ref)?.or(new_plan))
+    }
+}
+
+fn simplify_plan(plan: &LogicalPlan) -> Result<Option<LogicalPlan>> {
+    let new_plan = match plan {
+        LogicalPlan::Scan(scan) => {
+            let schema =
+                QualifiedSchema::from_schema(scan.qualifier.as_deref(), scan.source.schema());
+            let filters = scan
+                .filters
+                .iter()
+                .map(|e| simplify_expr(e, &schema))
+                .filter(|e| !is_true(e))
+                .collect::<Vec<_>>();
+            let any_changed = filters.len() != scan.filters.len()
+                || scan
+                    .filters
+                    .iter()
+                    .zip(filters.iter())
+                    .any(|(a, b)| changed(a, b));
+            any_changed.then(|| {
+                LogicalPlan::Scan(Scan {
+                    filters,
+                    ..scan.clone()
+                })
+            })
+        }
+        LogicalPlan::Filter(filter) => {
+            let predicate = simplify_expr(&filter.predicate, &filter.input.schema());
+            if is_true(&predicate) {
+                // The filter keeps every row.
+                return Ok(Some(filter.input.as_ref().clone()));
+            }
+            changed(&filter.predicate, &predicate)
+                .then(|| LogicalPlan::Filter(Filter::new(filter.input.clone(), predicate)))
+        }
+        LogicalPlan::Projection(projection) => {
+            let schema = projection.input.schema();
+            let exprs = projection
+                .exprs
+                .iter()
+                .map(|e| keep_name(e, simplify_expr(e, &schema)))
+                .collect::<Vec<_>>();
+            if !any_changed(&projection.exprs, &exprs) {
+                return Ok(None);
+            }
+            Some(LogicalPlan::Projection(Projection::try_new(
+                projection.input.clone(),
+                exprs,
+            )?))
+        }
+        LogicalPlan::Sort(sort) => {
+            let schema = sort.input.schema();
+            let exprs = sort
+                .exprs
+                .iter()
+                .map(|e| e.with_expr(simplify_expr(&e.expr, &schema)))
+                .collect::<Vec<_>>();
+            let any_changed = sort
+                .exprs
+                .iter()
+                .zip(exprs.iter())
+                .any(|(a, b)| changed(&a.expr, &b.expr));
+            any_changed.then(|| LogicalPlan::Sort(Sort::new(sort.input.clone(), exprs)))
+        }
+        LogicalPlan::Join(join) => {
+            let left_schema = join.left.schema();
+            let right_schema = join.right.schema();
+            let on = join
+                .on
+                .iter()
+                .map(|(l, r)| {
+                    (
+                        simplify_expr(l, &left_schema),
+                        simplify_expr(r, &right_schema),
+                    )
+                })
+                .collect::<Vec<_>>();
+            let filter_schema = left_schema.join(&right_schema)?;
+            let filter = join
+                .filter
+                .as_ref()
+                .map(|e| simplify_expr(e, &filter_schema))
+                .filter(|e| !is_true(e));
+
+            let on_changed = join
+                .on
+                .iter()
+                .zip(on.iter())
+                .any(|((l1, r1), (l2, r2))| changed(l1, l2) || changed(r1, r2));
+            let filter_changed = match (&join.filter, &filter) {
+                (Some(old), Some(new)) => changed(old, new),
+                (old, new) => old.is_some() != new.is_some(),
+            };
+            if !on_changed && !filter_changed {
+                return Ok(None);
+            }
+            Some(LogicalPlan::Join(Join::try_new(
+                join.left.clone(),
+                join.right.clone(),
+                on,
+                filter,
+                join.join_type,
+            )?))
+        }
+        LogicalPlan::Aggregate(aggregate) => {
+            let schema = aggregate.input.schema();
+            let group_exprs = aggregate
+                .group_exprs
+                .iter()
+                .map(|e| keep_name(e, simplify_expr(e, &schema)))
+                .collect::<Vec<_>>();
+            // Aggregates cannot be aliased, so their arguments are only
+            // simplified when the name stays the same.
+            let aggr_exprs = aggregate
+                .aggr_exprs
+                .iter()
+                .map(|e| {
+                    let new_expr = simplify_expr(e, &schema);
+                    if new_expr.name() == e.name() {
+                        new_expr
+                    } else {
+                        e.clone()
+                    }
+                })
+                .collect::<Vec<_>>();
+            if !any_changed(&aggregate.group_exprs, &group_exprs)
+                && !any_changed(&aggregate.aggr_exprs, &aggr_exprs)
+            {
+                return Ok(None);
+            }
+            Some(LogicalPlan::Aggregate(Aggregate::try_new(
+                aggregate.input.clone(),
+                group_exprs,
+                aggr_exprs,
+            )?))
+        }
+        LogicalPlan::Window(window) => {
+            // Like aggregates, window functions are only simplified when
+            // their name stays the same.
+            let schema = window.input.schema();
+            let window_exprs = window
+                .window_exprs
+                .iter()
+                .map(|e| {
+                    let new_expr = simplify_expr(e, &schema);
+                    if new_expr.name() == e.name() {
+                        new_expr
+                    } else {
+                        e.clone()
+                    }
+                })
+                .collect::<Vec<_>>();
+            if !any_changed(&window.window_exprs, &window_exprs) {
+                return Ok(None);
+            }
+            Some(LogicalPlan::Window(Window::try_new(
+                window.input.clone(),
+                window_exprs,
+            )?))
+        }
+        LogicalPlan::Limit(_) | LogicalPlan::SubqueryAlias(_) => None,
+    };
+    Ok(new_plan)
+}
+
+/// Every rewrite removes or replaces a node, which always shows up in the
+/// `Display` output.
+fn changed(old: &LogicalExpr, new: &LogicalExpr) -> bool {
+    old.to_string() != new.to_string()
+}
+
+fn any_changed(old: &[LogicalExpr], new: &[LogicalExpr]) -> bool {
+    old.iter().zip(new.iter()).any(|(a, b)| changed(a, b))
+}
+
+/// Aliases `new` to the name of `old` if simplifying renamed it.
+fn keep_name(old: &LogicalExpr, new: LogicalExpr) -> LogicalExpr {
+    if new.name() == old.name() {
+        new
+    } else {
+        LogicalExpr::Alias(Alias::new(Arc::new(new), old.name()))
+    }
+}
+
+/// Simplifies `expr` bottom-up, so `(1 + 2) * x` first becomes `3 * x`.
+pub fn simplify_expr(expr: &LogicalExpr, schema: &QualifiedSchema) -> LogicalExpr {
+    expr.transform_up(&|e| match e {
+        LogicalExpr::Binary(binary) => {
+            evaluate_constant(e).or_else(|| simplify_binary(e, binary, schema))
+        }
+        // Aggregates, window functions and aliases of constants keep their
+        // names.
+        LogicalExpr::Column(_)
+        | LogicalExpr::Literal(_)
+        | LogicalExpr::Aggregate(_)
+        | LogicalExpr::WindowFunction(_)
+        | LogicalExpr::Alias(_) => None,
+        _ => evaluate_constant(e),
+    })
+}
+
+/// Evaluates `expr` if all of its children are literals. Returns `None` if
+/// the evaluation fails, e.g. because of a division by zero.
+fn evaluate_constant(expr: &LogicalExpr) -> Option<LogicalExpr> {
+    if !expr.children().into_iter().all(is_literal) {
+        return None;
+    }
+    if let LogicalExpr::ScalarFunction(e) = expr
+        && e.fun.is_volatile()
+    {
+        return None;
+    }
+
+    // Overflow is always checked here, so a constant that only fits with
+    // wrapping arithmetic is left to the session's overflow mode.
+    let config = SessionConfig::default();
+    let schema = QualifiedSchema::from_schema(None, Arc::new(Schema::empty()));
+    let physical_expr = Planner::new(&config)
+        .create_physical_expr(&schema, expr)
+        .ok()?;
+    // A single row, so constants evaluated as arrays have exactly one value.
+    let options = RecordBatchOptions::new().with_row_count(Some(1));
+    let batch =
+        RecordBatch::try_new_with_options(schema.as_arrow().clone(), vec![], &options).ok()?;
+    let value = match physical_expr.evalate(&batch).ok()? {
+        ColumnarValue::Scalar(value) => value,
+        ColumnarValue::Array(array) => ScalarValue::try_from_array(array.as_ref(), 0).ok()?,
+    };
+    Some(LogicalExpr::Literal(value))
+}
+
+/// Removes operations that return one of their operands unchanged. The
+/// remaining operand must already have the type of the result, which holds
+/// once type coercion has run.
+fn simplify_binary(
+    expr: &LogicalExpr,
+    binary: &BinaryExpr,
+    schema: &QualifiedSchema,
+) -> Option<LogicalExpr> {
+    let (lhs, rhs) = (binary.lhs.as_ref(), binary.rhs.as_ref());
+    let result = match binary.op {
+        // `NULL AND false` is false and `NULL OR true` is true, so these hold
+        // for NULL operands as well.
+        Operator::And => match (as_bool(lhs), as_bool(rhs)) {
+            (Some(true), _) => rhs,
+            (_, Some(true)) => lhs,
+            (Some(false), _) => lhs,
+            (_, Some(false)) => rhs,
+            _ => return None,
+        },
+        Operator::Or => match (as_bool(lhs), as_bool(rhs)) {
+            (Some(false), _) => rhs,
+            (_, Some(false)) => lhs,
+            (Some(true), _) => lhs,
+            (_, Some(true)) => rhs,
+            _ => return None,
+        },
+        Operator::Plus if is_number(rhs, 0) => lhs,
+        Operator::Plus if is_number(lhs, 0) => rhs,
+        Operator::Minus if is_number(rhs, 0) => lhs,
+        Operator::Multiply if is_number(rhs, 1) => lhs,
+        Operator::Multiply if is_number(lhs, 1) => rhs,
+        // `NULL * 0` is NULL and `inf * 0.0` is NaN, so the operand must be a
+        // non-nullable integer.
+        Operator::Multiply if is_number(rhs, 0) && is_non_null_integer(lhs, schema) => rhs,
+        Operator::Multiply if is_number(lhs, 0) && is_non_null_integer(rhs, schema) => lhs,
+        Operator::Divide if is_number(rhs, 1) => lhs,
+        _ => return None,
+    };
+    let result_type = expr.data_type(schema).ok()?;
+    (result.data_type(schema).ok()? == result_type).then(|| result.clone())
+}
+
+fn is_literal(expr: &LogicalExpr) -> bool {
+    matches!(expr, LogicalExpr::Literal(_))
+}
+
+fn is_true(expr: &LogicalExpr) -> bool {
+    as_bool(expr) == Some(true)
+}
+
+fn as_bool(expr: &LogicalExpr) -> Option<bool> {
+    match expr {
+        LogicalExpr::Literal(ScalarValue::Boolean(v)) => *v,
+        _ => None,
+    }
+}
+
+/// Whether `expr` is a numeric literal equal to `value`.
+fn is_number(expr: &LogicalExpr, value: u8) -> bool {
+    let LogicalExpr::Literal(v) = expr else {
+        return false;
+    };
+    match v {
+        ScalarValue::Int8(Some(v)) => *v as i16 == value as i16,
+        ScalarValue::Int16(Some(v)) => *v == value as i16,
+        ScalarValue::Int32(Some(v)) => *v == value as i32,
+        ScalarValue::Int64(Some(v)) => *v == value as i64,
+        ScalarValue::Uint8(Some(v)) => *v == value,
+        ScalarValue::Uint16(Some(v)) => *v == value as u16,
+        ScalarValue::Uint32(Some(v)) => *v == value as u32,
+        ScalarValue::Uint64(Some(v)) => *v == value as u64,
+        ScalarValue::Float32(Some(v)) => *v == value as f32,
+        ScalarValue::Float64(Some(v)) => *v == value as f64,
+        _ => false,
+    }
+}
+
+fn is_non_null_integer(expr: &LogicalExpr, schema: &QualifiedSchema) -> bool {
+    let is_integer = expr.data_type(schema).is_ok_and(|t| t.is_integer());
+    is_integer && expr.nullable(schema).is_ok_and(|nullable| !nullable)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        datasource::{DataSource, MemDataSource},
+        logical::{
+            expression::expr_fn::{abs, col, lit, random},
+            plan::Scan,
+        },
+    };
+
+    fn schema() -> QualifiedSchema {
+        QualifiedSchema::from_schema(Some("t"), MemDataSource::new_arc().schema())
+    }
+
+    #[test]
+    fn test_fold_constants() {
+        let schema = schema();
+        let cases = [
+            (lit(1).plus(lit(2)).multiply(col("c2")), "3 * c2"),
+            (
+                lit(1).plus(lit(2)).gt(lit(2)).and(col("c2").gt(lit(0))),
+                "c2 > 0",
+            ),
+            (lit(7).modulo(lit(2)).eq(lit(1)), "true"),
+            // Left to fail when the query runs.
+            (lit(1).divide(lit(0)), "1 / 0"),
+            (lit(i32::MAX).plus(lit(1)), "2147483647 + 1"),
+            (abs(lit(-3)).multiply(col("c2")), "3 * c2"),
+            // Every row gets another value.
+            (random().lt(lit(0.5)), "RANDOM() < 0.5"),
+        ];
+        for (expr, expected) in cases {
+            assert_eq!(simplify_expr(&expr, &schema).to_string(), expected);
+        }
+    }
+
+    #[test]
+    fn test_simplify_identities() {
+        let schema = schema();
+        let cases = [
+            (col("c2").gt(lit(1)).and(lit(true)), "c2 > 1"),
+            (lit(false).and(col("c2").gt(lit(1))), "false"),
+            (col("c2").gt(lit(1)).or(lit(false)), "c2 > 1"),
+            (lit(true).or(col("c2").gt(lit(1))), "true"),
+            (lit(0).plus(col("c2")).multiply(lit(1)).divide(lit(1)), "c2"),
+            (col("c2").minus(lit(0)), "c2"),
+            // c3 is nullable, and NULL * 0 is NULL.
+            (col("c3").multiply(lit(0)), "c3 * 0"),
+            // The operand has another type than the result.
+            (col("c2").plus(lit(0_i64)), "c2 + 0"),
+        ];
+        for (expr, expected) in cases {
+            assert_eq!(simplify_expr(&expr, &schema).to_string(), expected);
+        }
+    }
+
+    #[test]
+    fn test_fold_plan() -> Result<()> {
+        let scan =
+            LogicalPlan::Scan(Scan::new("t", MemDataSource::new_arc(), None).with_qualifier("t"));
+        let filter = LogicalPlan::Filter(Filter::new(Arc::new(scan), lit(1).eq(lit(1))));
+        let plan = LogicalPlan::Projection(Projection::try_new(Arc::new(filter), vec![
+            col("c2").plus(lit(0)),
+            lit(2).multiply(lit(3)).alias("six"),
+        ])?);
+
+        let plan = ConstantFolding.try_optimize(&plan)?.unwrap();
+        assert_eq!(
+            plan.to_string(),
+            "Projection: c2 AS c2 + 0, 6 AS six\n".to_string() + "\tScan: t; projection=None\n"
+        );
+        assert_eq!(plan.schema().qualified_names(), vec!["c2 + 0", "six"]);
+        assert!(ConstantFolding.try_optimize(&plan)?.is_none());
+        Ok(())
+    }
+}
@@ -0,0 +1,56 @@
+use std::sync::Arc;
+
+use super::{OptimizerRule, optimize_children};
+use crate::{
+    error::Result,
+    logical::plan::{Limit, LogicalPlan, Projection, Scan},
+};
+
+/// Moves limits below projections, merges adjacent limits and lets scans
+/// without filters stop reading once enough rows have been produced.
+pub struct LimitPushDown;
+
+impl OptimizerRule for LimitPushDown {
+    fn try_optimize(&self, plan: &LogicalPlan) -> Result<Option<LogicalPlan>> {
+        let LogicalPlan::Limit(limit) = plan else {
+            return optimize_children(plan, |child| self.try_optimize(child));
+        };
+
+        match limit.input.as_ref() {
+            LogicalPlan::Limit(inner) => {
+                // The outer limit skips rows of what the inner one returns.
+                let available = inner.fetch.map(|fetch| fetch.saturating_sub(limit.skip));
+                let fetch = match (available, limit.fetch) {
+                    (Some(a), Some(b)) => Some(a.min(b)),
+                    (a, b) => a.or(b),
+                };
+                Ok(Some(LogicalPlan::Limit(Limit::new(
+                    inner.input.clone(),
+                    inner.skip + limit.skip,
+                    fetch,
+                ))))
+            }
+            LogicalPlan::Projection(projection) => {
+                let input = Limit::new(projection.input.clone(), limit.skip, limit.fetch);
+                Ok(Some(LogicalPlan::Projection(Projection::try_new(
+                    Arc::new(LogicalPlan::Limit(input)),
+                    projection.exprs.clone(),
+                )?)))
+            }
+            // Filters run after the source has produced its rows, so a scan
+            // with filters cannot know when to stop.
+            LogicalPlan::Scan(scan) if scan.filters.is_empty() => {
+                let fetch = limit.fetch.map(|fetch| fetch + limit.skip);
+                if fetch.is_none() || scan.fetch == fetch {
+                    return Ok(None);
+                }
+                let scan = LogicalPlan::Scan(Scan {
+                    fetch,
+                    ..scan.clone()
+                });
+                Ok(Some(plan.with_new_inputs(vec![scan])?))
+            }
+            _ => optimize_children(plan, |child| self.try_optimize(child)),
+        }
+    }
+}
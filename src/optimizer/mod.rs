@@ -0,0 +1,379 @@
+mod constant_folding;
+mod limit_pushdown;
+mod predicate_pushdown;
+mod projection_pushdown;
+mod type_coercion;
+
+use std::sync::Arc;
+
+pub use constant_folding::ConstantFolding;
+pub use limit_pushdown::LimitPushDown;
+pub use predicate_pushdown::PredicatePushDown;
+pub use projection_pushdown::ProjectionPushDown;
+pub use type_coercion::TypeCoercion;
+
+use crate::{
+    datatypes::operator::Operator,
+    error::Result,
+    logical::{expression::expr::LogicalExpr, plan::LogicalPlan},
+};
+
+/// A rewrite of a [`LogicalPlan`] into an equivalent, cheaper plan.
+pub trait OptimizerRule {
+    /// Returns the rewritten plan, or `None` if the rule does not apply.
+    fn try_optimize(&self, plan: &LogicalPlan) -> Result<Option<LogicalPlan>>;
+}
+
+/// Runs a list of [`OptimizerRule`]s over a plan until none of them applies
+/// any more, or `max_passes` is reached.
+pub struct Optimizer {
+    rules: Vec<Arc<dyn OptimizerRule>>,
+    max_passes: usize,
+}
+
+impl Default for Optimizer {
+    fn default() -> Self {
+        // Type coercion runs first so the other rules see well-typed plans,
+        // and constants are folded before predicates are pushed down.
+        Self::with_rules(vec![
+            Arc::new(TypeCoercion),
+            Arc::new(ConstantFolding),
+            Arc::new(PredicatePushDown),
+            Arc::new(ProjectionPushDown),
+            Arc::new(LimitPushDown),
+        ])
+    }
+}
+
+impl Optimizer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_rules(rules: Vec<Arc<dyn OptimizerRule>>) -> Self {
+        Self {
+            rules,
+            max_passes: 16,
+        }
+    }
+
+    pub fn optimize(&self, plan: &LogicalPlan) -> Result<LogicalPlan> {
+        let mut plan = plan.clone();
+        for _ in 0..self.max_passes {
+            let mut changed = false;
+            for rule in self.rules.iter() {
+                if let Some(new_plan) = rule.try_optimize(&plan)? {
+                    plan = new_plan;
+                    changed = true;
+                }
+            }
+            if !changed {
+                break;
+            }
+        }
+        Ok(plan)
+    }
+}
+
+/// Applies `f` to every input of `plan`, rebuilding the plan if any input
+/// was rewritten.
+pub(crate) fn optimize_children(
+    plan: &LogicalPlan,
+    f: impl Fn(&LogicalPlan) -> Result<Option<LogicalPlan>>,
+) -> Result<Option<LogicalPlan>> {
+    let mut changed = false;
+    let mut inputs = vec![];
+    for child in plan.children() {
+        match f(child)? {
+            Some(new_child) => {
+                changed = true;
+                inputs.push(new_child);
+            }
+            None => inputs.push(child.clone()),
+        }
+    }
+    if !changed {
+        return Ok(None);
+    }
+    Ok(Some(plan.with_new_inputs(inputs)?))
+}
+
+/// Splits `a AND b AND c` into `[a, b, c]`.
+pub(crate) fn split_conjunction(expr: &LogicalExpr) -> Vec<LogicalExpr> {
+    match expr {
+        LogicalExpr::Binary(e) if e.op == Operator::And => {
+            let mut exprs = split_conjunction(&e.lhs);
+            exprs.extend(split_conjunction(&e.rhs));
+            exprs
+        }
+        _ => vec![expr.clone()],
+    }
+}
+
+/// Combines predicates with `AND`, the inverse of [`split_conjunction`].
+pub(crate) fn conjunction(exprs: Vec<LogicalExpr>) -> Option<LogicalExpr> {
+    exprs.into_iter().reduce(|acc, e| acc.and(e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        datasource::MemDataSource,
+        logical::{
+            expression::{
+                expr_fn::{col, lit, scalar_function, sum, window_function},
+                function::ScalarFunction,
+                window::WindowFunction,
+            },
+            plan::{
+                Aggregate, Filter, Join, JoinType, Limit, Projection, Scan, SubqueryAlias, Window,
+            },
+        },
+    };
+
+    fn scan() -> Arc<LogicalPlan> {
+        Arc::new(LogicalPlan::Scan(Scan::new(
+            "t",
+            MemDataSource::new_arc(),
+            None,
+        )))
+    }
+
+    fn optimize(plan: LogicalPlan) -> Result<String> {
+        Ok(Optimizer::new().optimize(&plan)?.to_string())
+    }
+
+    #[test]
+    fn test_push_filter_and_projection_into_scan() -> Result<()> {
+        let filter = LogicalPlan::Filter(Filter::new(scan(), col("c3").gt(lit(1))));
+        let plan = LogicalPlan::Projection(Projection::try_new(Arc::new(filter), vec![
+            col("c1"),
+            col("c2"),
+        ])?);
+        assert_eq!(
+            optimize(plan)?,
+            "Projection: c1, c2\n".to_string()
+                + "\tScan: t; projection=[\"c1\", \"c2\"]; filters=[c3 > 1]\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_push_filter_below_projection() -> Result<()> {
+        let projection = LogicalPlan::Projection(Projection::try_new(scan(), vec![
+            col("c1"),
+            col("c2").plus(lit(1)),
+        ])?);
+        let plan = LogicalPlan::Filter(Filter::new(
+            Arc::new(projection),
+            col("c2 + 1").gt(lit(2)).and(col("c1").eq(lit("a"))),
+        ));
+        assert_eq!(
+            optimize(plan)?,
+            "Projection: c1, c2 + 1\n".to_string()
+                + "\tScan: t; projection=[\"c1\", \"c2\"]; filters=[c2 + 1 > 2, c1 = 'a']\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_keep_volatile_filter_above_projection() -> Result<()> {
+        let random = scalar_function(ScalarFunction::Random, vec![]);
+        let projection = LogicalPlan::Projection(Projection::try_new(scan(), vec![
+            col("c1"),
+            random.alias("r"),
+        ])?);
+        let plan = LogicalPlan::Filter(Filter::new(
+            Arc::new(projection),
+            col("r").lt(lit(0.5)).and(col("c1").eq(lit("a"))),
+        ));
+        // Pushing `r < 0.5` would filter on another random value than the
+        // one returned.
+        assert_eq!(
+            optimize(plan)?,
+            "Filter: r < 0.5\n".to_string()
+                + "\tProjection: c1, RANDOM() AS r\n"
+                + "\t\tScan: t; projection=[\"c1\"]; filters=[c1 = 'a']\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_push_filter_below_window() -> Result<()> {
+        let row_number = window_function(WindowFunction::RowNumber, vec![])
+            .partition_by(vec![col("c1")])
+            .build();
+        let window = Window::try_new(scan(), vec![row_number])?;
+        let random = scalar_function(ScalarFunction::Random, vec![]);
+        let plan = LogicalPlan::Filter(Filter::new(
+            Arc::new(LogicalPlan::Window(window)),
+            random.lt(lit(0.5)).and(col("c1").eq(lit("a"))),
+        ));
+        // Only conjuncts on partition columns remove whole partitions.
+        assert_eq!(
+            optimize(plan)?,
+            "Filter: RANDOM() < 0.5\n".to_string()
+                + "\tWindow: [ROW_NUMBER() OVER (PARTITION BY c1)]\n"
+                + "\t\tScan: t; projection=None; filters=[c1 = 'a']\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_push_filter_below_aggregate() -> Result<()> {
+        let aggregate = Aggregate::try_new(scan(), vec![col("c1")], vec![sum(col("c2"))])?;
+        let plan = LogicalPlan::Filter(Filter::new(
+            Arc::new(LogicalPlan::Aggregate(aggregate)),
+            col("c1").eq(lit("a")).and(col("SUM(c2)").gt(lit(1_i64))),
+        ));
+        assert_eq!(
+            optimize(plan)?,
+            "Filter: SUM(c2) > 1\n".to_string()
+                + "\tAggregate: groupBy=[c1], aggr=[SUM(c2)]\n"
+                + "\t\tScan: t; projection=[\"c1\", \"c2\"]; filters=[c1 = 'a']\n"
+        );
+
+        // Predicates without columns decide whether any group is returned,
+        // and a global aggregate returns a row even for empty input.
+        let aggregate = Aggregate::try_new(scan(), vec![col("c1")], vec![sum(col("c2"))])?;
+        let plan = LogicalPlan::Filter(Filter::new(
+            Arc::new(LogicalPlan::Aggregate(aggregate)),
+            lit(1).eq(lit(0)),
+        ));
+        assert_eq!(
+            optimize(plan)?,
+            "Filter: false\n".to_string()
+                + "\tAggregate: groupBy=[c1], aggr=[SUM(c2)]\n"
+                + "\t\tScan: t; projection=[\"c1\", \"c2\"]\n"
+        );
+        let aggregate = Aggregate::try_new(scan(), vec![], vec![sum(col("c2"))])?;
+        let plan = LogicalPlan::Filter(Filter::new(
+            Arc::new(LogicalPlan::Aggregate(aggregate)),
+            lit(1).eq(lit(0)),
+        ));
+        assert_eq!(
+            optimize(plan)?,
+            "Filter: false\n".to_string()
+                + "\tAggregate: groupBy=[], aggr=[SUM(c2)]\n"
+                + "\t\tScan: t; projection=[\"c2\"]\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_push_limit_into_scan() -> Result<()> {
+        let projection =
+            LogicalPlan::Projection(Projection::try_new(scan(), vec![col("c1"), col("c2")])?);
+        let limit = LogicalPlan::Limit(Limit::new(Arc::new(projection), 1, Some(10)));
+        let plan = LogicalPlan::Limit(Limit::new(Arc::new(limit), 2, Some(5)));
+        assert_eq!(
+            optimize(plan)?,
+            "Projection: c1, c2\n".to_string()
+                + "\tLimit: skip=3, fetch=5\n"
+                + "\t\tScan: t; projection=[\"c1\", \"c2\"]; fetch=8\n"
+        );
+
+        // A limit must not be pushed into a scan that filters rows.
+        let filter = LogicalPlan::Filter(Filter::new(scan(), col("c2").gt(lit(1))));
+        let plan = LogicalPlan::Limit(Limit::new(Arc::new(filter), 0, Some(1)));
+        assert_eq!(
+            optimize(plan)?,
+            "Limit: skip=0, fetch=1\n".to_string()
+                + "\tScan: t; projection=None; filters=[c2 > 1]\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_push_down_into_join() -> Result<()> {
+        let batch = arrow::array::record_batch!(("d1", Utf8, ["x", "y"]), ("d2", Int64, [1, 2]))?;
+        let source = Arc::new(MemDataSource::try_new(batch.schema(), vec![batch])?);
+        let right = Arc::new(LogicalPlan::Scan(Scan::new("u", source, None)));
+        let join = Join::try_new(
+            scan(),
+            right,
+            vec![(col("c2"), col("d2"))],
+            None,
+            JoinType::Left,
+        )?;
+        let filter = LogicalPlan::Filter(Filter::new(
+            Arc::new(LogicalPlan::Join(join)),
+            col("c3").gt(lit(1)).and(col("d2").gt(lit(1))),
+        ));
+        let plan = LogicalPlan::Projection(Projection::try_new(Arc::new(filter), vec![
+            col("c1"),
+            col("d1"),
+        ])?);
+        // Only the preserved left side of a left join may be filtered first.
+        assert_eq!(
+            optimize(plan)?,
+            "Projection: c1, d1\n".to_string()
+                + "\tFilter: d2 > 1\n"
+                + "\t\tJoin: type=Left, on=[CAST(c2 AS Int64) = d2]\n"
+                + "\t\t\tScan: t; projection=[\"c1\", \"c2\"]; filters=[c3 > 1]\n"
+                + "\t\t\tScan: u; projection=[\"d1\", \"d2\"]\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_push_down_into_self_join() -> Result<()> {
+        let scan = |qualifier: &str| {
+            Arc::new(LogicalPlan::Scan(
+                Scan::new("t", MemDataSource::new_arc(), None).with_qualifier(qualifier),
+            ))
+        };
+        let join = Join::try_new(
+            scan("a"),
+            scan("b"),
+            vec![(col("a.c2"), col("b.c2"))],
+            None,
+            JoinType::Inner,
+        )?;
+        let plan = LogicalPlan::Projection(Projection::try_new(
+            Arc::new(LogicalPlan::Join(join)),
+            vec![col("a.c1"), col("b.c3")],
+        )?);
+        // Both sides have the same column names, but each keeps only its own.
+        assert_eq!(
+            optimize(plan)?,
+            "Projection: a.c1, b.c3\n".to_string()
+                + "\tJoin: type=Inner, on=[a.c2 = b.c2]\n"
+                + "\t\tScan: t AS a; projection=[\"c1\", \"c2\"]\n"
+                + "\t\tScan: t AS b; projection=[\"c2\", \"c3\"]\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_push_filter_below_alias() -> Result<()> {
+        let scan =
+            LogicalPlan::Scan(Scan::new("t", MemDataSource::new_arc(), None).with_qualifier("t"));
+        let projection = LogicalPlan::Projection(Projection::try_new(Arc::new(scan), vec![
+            col("t.c1"),
+            col("c2").plus(lit(1)).alias("x"),
+        ])?);
+        let alias = SubqueryAlias::try_new(Arc::new(projection), "s")?;
+        let plan = LogicalPlan::Filter(Filter::new(
+            Arc::new(LogicalPlan::SubqueryAlias(alias)),
+            col("s.c1").eq(lit("a")).and(col("s.x").gt(lit(1))),
+        ));
+        // References to the alias become references to the input columns, and
+        // aliased columns are replaced by the expressions they name.
+        assert_eq!(
+            optimize(plan)?,
+            "SubqueryAlias: s\n".to_string()
+                + "\tProjection: t.c1, c2 + 1 AS x\n"
+                + "\t\tScan: t; projection=[\"c1\", \"c2\"]; filters=[t.c1 = 'a', c2 + 1 > 1]\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_optimize_is_idempotent() -> Result<()> {
+        let plan = Optimizer::new().optimize(scan().as_ref())?;
+        assert_eq!(plan.to_string(), "Scan: t; projection=None\n");
+        Ok(())
+    }
+}
@@ -0,0 +1,252 @@
+use std::{collections::HashSet, sync::Arc};
+
+use super::{OptimizerRule, conjunction, optimize_children, split_conjunction};
+use crate::{
+    error::Result,
+    logical::{
+        expression::{column::Column, expr::LogicalExpr},
+        plan::{Filter, Join, JoinType, LogicalPlan},
+        schema::QualifiedSchema,
+    },
+};
+
+/// Moves filters as close to the data as possible: below projections and
+/// sorts, below aggregates when they only reference group columns, below
+/// window functions when they only reference partition columns, into the
+/// side of a join whose rows they select, and finally into the scan itself.
+pub struct PredicatePushDown;
+
+impl OptimizerRule for PredicatePushDown {
+    fn try_optimize(&self, plan: &LogicalPlan) -> Result<Option<LogicalPlan>> {
+        let LogicalPlan::Filter(filter) = plan else {
+            return optimize_children(plan, |child| self.try_optimize(child));
+        };
+
+        match filter.input.as_ref() {
+            LogicalPlan::Scan(scan) => {
+                let mut filters = scan.filters.clone();
+                filters.extend(split_conjunction(&filter.predicate));
+                Ok(Some(LogicalPlan::Scan(scan.clone().with_filters(filters))))
+            }
+            LogicalPlan::Filter(inner) => {
+                let predicate = inner.predicate.clone().and(filter.predicate.clone());
+                Ok(Some(LogicalPlan::Filter(Filter::new(
+                    inner.input.clone(),
+                    predicate,
+                ))))
+            }
+            LogicalPlan::Projection(projection) => {
+                // Rewrite references to projected columns into the expressions
+                // that compute them, so the predicate can run on the input.
+                // Conjuncts that would copy a volatile expression such as
+                // `random()` stay above, so they see the projected values.
+                let schema = filter.input.schema();
+                let (pushable, kept): (Vec<_>, Vec<_>) = split_conjunction(&filter.predicate)
+                    .into_iter()
+                    .map(|e| (replace_columns(&e, &schema, &projection.exprs), e))
+                    .partition(|(replaced, _)| !replaced.is_volatile());
+                if pushable.is_empty() {
+                    return optimize_children(plan, |child| self.try_optimize(child));
+                }
+
+                let input = LogicalPlan::Filter(Filter::new(
+                    projection.input.clone(),
+                    conjunction(pushable.into_iter().map(|(e, _)| e).collect()).unwrap(),
+                ));
+                let new_plan = plan_with_input(&filter.input, input)?;
+                let kept = kept.into_iter().map(|(_, e)| e).collect();
+                Ok(Some(match conjunction(kept) {
+                    Some(predicate) => {
+                        LogicalPlan::Filter(Filter::new(Arc::new(new_plan), predicate))
+                    }
+                    None => new_plan,
+                }))
+            }
+            // Filtering before a limit would change which rows are returned.
+            LogicalPlan::Limit(_) => optimize_children(plan, |child| self.try_optimize(child)),
+            LogicalPlan::Sort(sort) => {
+                // Filtering first means fewer rows to sort.
+                let input =
+                    LogicalPlan::Filter(Filter::new(sort.input.clone(), filter.predicate.clone()));
+                Ok(Some(plan_with_input(&filter.input, input)?))
+            }
+            LogicalPlan::SubqueryAlias(alias) => {
+                // Requalify the columns with the relations of the input.
+                let exprs = alias
+                    .input
+                    .schema()
+                    .columns()
+                    .into_iter()
+                    .map(LogicalExpr::Column)
+                    .collect::<Vec<_>>();
+                let predicate = replace_columns(&filter.predicate, &alias.schema, &exprs);
+                let input = LogicalPlan::Filter(Filter::new(alias.input.clone(), predicate));
+                Ok(Some(plan_with_input(&filter.input, input)?))
+            }
+            LogicalPlan::Join(join) => match push_into_join(join, &filter.predicate) {
+                Some(new_plan) => Ok(Some(new_plan)),
+                None => optimize_children(plan, |child| self.try_optimize(child)),
+            },
+            // A global aggregate returns one row even for empty input, so
+            // filtering its input is never the same as filtering its output.
+            LogicalPlan::Aggregate(aggregate) if aggregate.group_exprs.is_empty() => {
+                optimize_children(plan, |child| self.try_optimize(child))
+            }
+            LogicalPlan::Aggregate(aggregate) => {
+                let is_group_column = |c: &Column| {
+                    aggregate
+                        .schema
+                        .index_of_column(c)
+                        .is_ok_and(|i| i < aggregate.group_exprs.len())
+                };
+                // Conjuncts without columns, such as `1 = 0`, decide whether
+                // the groups are returned at all and stay above.
+                let (pushable, kept): (Vec<_>, Vec<_>) = split_conjunction(&filter.predicate)
+                    .into_iter()
+                    .partition(|e| {
+                        let columns = e.columns();
+                        !columns.is_empty() && columns.into_iter().all(is_group_column)
+                    });
+                if pushable.is_empty() {
+                    return optimize_children(plan, |child| self.try_optimize(child));
+                }
+
+                let pushable = pushable
+                    .iter()
+                    .map(|e| replace_columns(e, &aggregate.schema, &aggregate.group_exprs))
+                    .collect();
+                let input = LogicalPlan::Filter(Filter::new(
+                    aggregate.input.clone(),
+                    conjunction(pushable).unwrap(),
+                ));
+                let new_plan = plan_with_input(&filter.input, input)?;
+                Ok(Some(match conjunction(kept) {
+                    Some(predicate) => {
+                        LogicalPlan::Filter(Filter::new(Arc::new(new_plan), predicate))
+                    }
+                    None => new_plan,
+                }))
+            }
+            LogicalPlan::Window(window) => {
+                // Rows are only compared within their partition, so removing
+                // whole partitions first does not change the other rows.
+                let input_schema = window.input.schema();
+                let partition_columns = window
+                    .window_exprs
+                    .iter()
+                    .map(|e| match e.unalias() {
+                        LogicalExpr::WindowFunction(e) => e
+                            .partition_by
+                            .iter()
+                            .filter_map(|e| match e.as_ref() {
+                                LogicalExpr::Column(c) => input_schema.index_of_column(c).ok(),
+                                _ => None,
+                            })
+                            .collect::<HashSet<_>>(),
+                        _ => HashSet::new(),
+                    })
+                    .reduce(|a, b| a.intersection(&b).copied().collect())
+                    .unwrap_or_default();
+                let is_partition_column = |c: &Column| {
+                    window
+                        .schema
+                        .index_of_column(c)
+                        .is_ok_and(|i| partition_columns.contains(&i))
+                };
+                // As for aggregates, conjuncts without columns stay above.
+                let (pushable, kept): (Vec<_>, Vec<_>) = split_conjunction(&filter.predicate)
+                    .into_iter()
+                    .partition(|e| {
+                        let columns = e.columns();
+                        !columns.is_empty() && columns.into_iter().all(is_partition_column)
+                    });
+                if pushable.is_empty() {
+                    return optimize_children(plan, |child| self.try_optimize(child));
+                }
+
+                let input = LogicalPlan::Filter(Filter::new(
+                    window.input.clone(),
+                    conjunction(pushable).unwrap(),
+                ));
+                let new_plan = plan_with_input(&filter.input, input)?;
+                Ok(Some(match conjunction(kept) {
+                    Some(predicate) => {
+                        LogicalPlan::Filter(Filter::new(Arc::new(new_plan), predicate))
+                    }
+                    None => new_plan,
+                }))
+            }
+        }
+    }
+}
+
+/// Pushes the conjuncts of `predicate` that only reference one side of
+/// `join` into that side. A side that is padded with NULLs for unmatched
+/// rows keeps its conjuncts above the join, because filtering it first would
+/// turn removed rows into NULL-padded ones. Returns `None` when nothing can be
+/// pushed.
+fn push_into_join(join: &Join, predicate: &LogicalExpr) -> Option<LogicalPlan> {
+    let push_left = matches!(
+        join.join_type,
+        JoinType::Inner | JoinType::Left | JoinType::LeftSemi | JoinType::LeftAnti
+    );
+    let push_right = matches!(
+        join.join_type,
+        JoinType::Inner | JoinType::Right | JoinType::RightSemi | JoinType::RightAnti
+    );
+    let left_schema = join.left.schema();
+    let right_schema = join.right.schema();
+
+    let mut left = vec![];
+    let mut right = vec![];
+    let mut kept = vec![];
+    for e in split_conjunction(predicate) {
+        let columns = e.columns();
+        if push_left && columns.iter().all(|c| left_schema.has_column(c)) {
+            left.push(e);
+        } else if push_right && columns.iter().all(|c| right_schema.has_column(c)) {
+            right.push(e);
+        } else {
+            kept.push(e);
+        }
+    }
+    if left.is_empty() && right.is_empty() {
+        return None;
+    }
+
+    let with_filter = |input: &Arc<LogicalPlan>, exprs: Vec<LogicalExpr>| match conjunction(exprs) {
+        Some(predicate) => Arc::new(LogicalPlan::Filter(Filter::new(input.clone(), predicate))),
+        None => input.clone(),
+    };
+    let join = Join {
+        left: with_filter(&join.left, left),
+        right: with_filter(&join.right, right),
+        ..join.clone()
+    };
+    let join = LogicalPlan::Join(join);
+    Some(match conjunction(kept) {
+        Some(predicate) => LogicalPlan::Filter(Filter::new(Arc::new(join), predicate)),
+        None => join,
+    })
+}
+
+fn plan_with_input(plan: &LogicalPlan, input: LogicalPlan) -> Result<LogicalPlan> {
+    plan.with_new_inputs(vec![input])
+}
+
+/// Replaces every column that refers to the `i`-th field of `schema` with
+/// `exprs[i]`, the expression that computes it.
+fn replace_columns(
+    predicate: &LogicalExpr,
+    schema: &QualifiedSchema,
+    exprs: &[LogicalExpr],
+) -> LogicalExpr {
+    predicate.transform(&|e| match e {
+        LogicalExpr::Column(column) => {
+            let index = schema.index_of_column(column).ok()?;
+            // The alias names the output column, not the value below it.
+            exprs.get(index).map(|e| e.unalias().clone())
+        }
+        _ => None,
+    })
+}
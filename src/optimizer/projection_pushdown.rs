@@ -0,0 +1,157 @@
+use std::collections::HashSet;
+
+use super::{OptimizerRule, optimize_children};
+use crate::{
+    error::Result,
+    logical::{
+        expression::{column::Column, expr::LogicalExpr},
+        plan::{LogicalPlan, Scan},
+        schema::QualifiedSchema,
+    },
+};
+
+/// Restricts every scan to the columns that are actually referenced by the
+/// operators above it.
+pub struct ProjectionPushDown;
+
+impl OptimizerRule for ProjectionPushDown {
+    fn try_optimize(&self, plan: &LogicalPlan) -> Result<Option<LogicalPlan>> {
+        push_down(plan, None)
+    }
+}
+
+/// `required` holds the columns of `plan`'s output that are used by its
+/// parent, or `None` if all of them are. Columns are qualified the way the
+/// output schema qualifies them, so `t1.id` and `t2.id` stay apart.
+fn push_down(
+    plan: &LogicalPlan,
+    required: Option<&HashSet<Column>>,
+) -> Result<Option<LogicalPlan>> {
+    match plan {
+        LogicalPlan::Scan(scan) => {
+            let Some(required) = required else {
+                return Ok(None);
+            };
+            let schema = scan.source.schema();
+            let columns =
+                QualifiedSchema::from_schema(scan.qualifier.as_deref(), schema.clone()).columns();
+            let mut projection = columns
+                .into_iter()
+                .filter(|c| required.contains(c))
+                .map(|c| c.name)
+                .collect::<Vec<_>>();
+            // Keep one column so the scan still reports the number of rows.
+            if projection.is_empty() && !schema.fields().is_empty() {
+                projection.push(schema.field(0).name().clone());
+            }
+            if scan.projection.as_ref() == Some(&projection) {
+                return Ok(None);
+            }
+            let new_scan = Scan {
+                projection: Some(projection),
+                ..scan.clone()
+            };
+            Ok(Some(LogicalPlan::Scan(new_scan)))
+        }
+        LogicalPlan::Filter(filter) => {
+            let required = required.map(|required| {
+                let schema = filter.input.schema();
+                let mut required = required.clone();
+                required.extend(columns(std::slice::from_ref(&filter.predicate), &schema));
+                required
+            });
+            optimize_children(plan, |child| push_down(child, required.as_ref()))
+        }
+        LogicalPlan::Projection(projection) => {
+            let required = columns(&projection.exprs, &projection.input.schema());
+            optimize_children(plan, |child| push_down(child, Some(&required)))
+        }
+        LogicalPlan::Limit(_) => optimize_children(plan, |child| push_down(child, required)),
+        LogicalPlan::SubqueryAlias(alias) => {
+            // The alias only requalifies the columns of its input.
+            let required = required.map(|required| {
+                alias
+                    .schema
+                    .columns()
+                    .into_iter()
+                    .zip(alias.input.schema().columns())
+                    .filter(|(outer, _)| required.contains(outer))
+                    .map(|(_, inner)| inner)
+                    .collect::<HashSet<_>>()
+            });
+            optimize_children(plan, |child| push_down(child, required.as_ref()))
+        }
+        LogicalPlan::Sort(sort) => {
+            let required = required.map(|required| {
+                let mut required = required.clone();
+                let exprs = sort
+                    .exprs
+                    .iter()
+                    .map(|e| e.expr.as_ref().clone())
+                    .collect::<Vec<_>>();
+                required.extend(columns(&exprs, &sort.input.schema()));
+                required
+            });
+            optimize_children(plan, |child| push_down(child, required.as_ref()))
+        }
+        LogicalPlan::Join(join) => {
+            // Both sides may have columns of the same name, e.g. in a
+            // self-join, so each side only gets the columns of its schema.
+            let left_schema = join.left.schema();
+            let right_schema = join.right.schema();
+            let required = required.map(|required| -> Result<_> {
+                let mut required = required.clone();
+                let (left_keys, right_keys): (Vec<_>, Vec<_>) = join.on.iter().cloned().unzip();
+                required.extend(columns(&left_keys, &left_schema));
+                required.extend(columns(&right_keys, &right_schema));
+                let filter_schema = left_schema.join(&right_schema)?;
+                required.extend(columns(join.filter.as_slice(), &filter_schema));
+                Ok(required)
+            });
+            let required = required.transpose()?;
+            let side = |schema: &QualifiedSchema| {
+                required.as_ref().map(|required| {
+                    schema
+                        .columns()
+                        .into_iter()
+                        .filter(|c| required.contains(c))
+                        .collect::<HashSet<_>>()
+                })
+            };
+            let (left, right) = (side(&left_schema), side(&right_schema));
+            optimize_children(plan, |child| {
+                if std::ptr::eq(child, join.left.as_ref()) {
+                    push_down(child, left.as_ref())
+                } else {
+                    push_down(child, right.as_ref())
+                }
+            })
+        }
+        LogicalPlan::Aggregate(aggregate) => {
+            let schema = aggregate.input.schema();
+            let mut required = columns(&aggregate.group_exprs, &schema);
+            required.extend(columns(&aggregate.aggr_exprs, &schema));
+            optimize_children(plan, |child| push_down(child, Some(&required)))
+        }
+        LogicalPlan::Window(window) => {
+            let required = required.map(|required| {
+                let mut required = required.clone();
+                required.extend(columns(&window.window_exprs, &window.input.schema()));
+                required
+            });
+            optimize_children(plan, |child| push_down(child, required.as_ref()))
+        }
+    }
+}
+
+/// Returns the fields of `schema` that `exprs` reference, qualified the way
+/// `schema` qualifies them.
+fn columns(exprs: &[LogicalExpr], schema: &QualifiedSchema) -> HashSet<Column> {
+    let fields = schema.columns();
+    exprs
+        .iter()
+        .flat_map(|e| e.columns())
+        .filter_map(|c| schema.index_of_column(c).ok())
+        .map(|i| fields[i].clone())
+        .collect()
+}
@@ -0,0 +1,615 @@
+use std::sync::Arc;
+
+use arrow::compute::{CastOptions, can_cast_types, cast_with_options};
+use arrow_schema::DataType;
+
+use super::{OptimizerRule, optimize_children};
+use crate::{
+    datatypes::{
+        coercion::{
+            binary_operand_type, binary_operand_types, comparison_operand_type, string_type,
+        },
+        operator::Operator,
+        values::ScalarValue,
+    },
+    error::{Result, plan_err},
+    logical::{
+        expression::{
+            aggregate::AggregateExpr,
+            alias::Alias,
+            binary::BinaryExpr,
+            cast::Cast,
+            conditional::{Case, Coalesce},
+            expr::LogicalExpr,
+            function::ScalarFunctionExpr,
+            like::Like,
+            predicate::{Between, InList},
+            unary::UnaryExpr,
+            window::{WindowExpr, WindowFunction},
+        },
+        plan::{Aggregate, Filter, Join, LogicalPlan, Projection, Scan, Sort, Window},
+        schema::QualifiedSchema,
+    },
+};
+
+/// Casts the operands of every binary expression to a common type, e.g.
+/// `c1 + 1.5` becomes `CAST(c1 AS Float64) + 1.5` for an integer `c1`.
+/// Operands that cannot be combined are reported as plan errors.
+pub struct TypeCoercion;
+
+impl OptimizerRule for TypeCoercion {
+    fn try_optimize(&self, plan: &LogicalPlan) -> Result<Option<LogicalPlan>> {
+        let new_plan = optimize_children(plan, |child| self.try_optimize(child))?;
+        let plan_ref = new_plan.as_ref().unwrap_or(plan);
+        Ok(coerce_plan(plan_ref)?.or(new_plan))
+    }
+}
+
+fn coerce_plan(plan: &LogicalPlan) -> Result<Option<LogicalPlan>> {
+    let new_plan = match plan {
+        LogicalPlan::Scan(scan) => {
+            let schema =
+                QualifiedSchema::from_schema(scan.qualifier.as_deref(), scan.source.schema());
+            let filters = coerce_exprs(&scan.filters, &schema)?;
+            filters.map(|filters| {
+                LogicalPlan::Scan(Scan {
+                    filters,
+                    ..scan.clone()
+                })
+            })
+        }
+        LogicalPlan::Filter(filter) => {
+            let schema = filter.input.schema();
+            let predicate = coerce_expr(&filter.predicate, &schema)?;
+            let data_type = predicate.data_type(&schema)?;
+            if !matches!(data_type, DataType::Boolean | DataType::Null) {
+                return plan_err!(
+                    "filter predicate must be a boolean expression, got {} of type {}",
+                    filter.predicate,
+                    data_type
+                );
+            }
+            changed(&filter.predicate, &predicate)
+                .then(|| LogicalPlan::Filter(Filter::new(filter.input.clone(), predicate)))
+        }
+        LogicalPlan::Projection(projection) => {
+            let schema = projection.input.schema();
+            let exprs = coerce_exprs(&projection.exprs, &schema)?;
+            exprs
+                .map(|exprs| Projection::try_new(projection.input.clone(), exprs))
+                .transpose()?
+                .map(LogicalPlan::Projection)
+        }
+        LogicalPlan::Limit(_) | LogicalPlan::SubqueryAlias(_) => None,
+        LogicalPlan::Window(window) => {
+            let schema = window.input.schema();
+            let window_exprs = coerce_exprs(&window.window_exprs, &schema)?;
+            window_exprs
+                .map(|exprs| Window::try_new(window.input.clone(), exprs))
+                .transpose()?
+                .map(LogicalPlan::Window)
+        }
+        LogicalPlan::Sort(sort) => {
+            let schema = sort.input.schema();
+            let exprs = sort
+                .exprs
+                .iter()
+                .map(|e| e.expr.as_ref().clone())
+                .collect::<Vec<_>>();
+            coerce_exprs(&exprs, &schema)?.map(|exprs| {
+                let exprs = sort
+                    .exprs
+                    .iter()
+                    .zip(exprs)
+                    .map(|(sort_expr, expr)| sort_expr.with_expr(expr))
+                    .collect();
+                LogicalPlan::Sort(Sort::new(sort.input.clone(), exprs))
+            })
+        }
+        LogicalPlan::Join(join) => {
+            let left_schema = join.left.schema();
+            let right_schema = join.right.schema();
+            // Both keys of a pair are cast to the type they are compared as.
+            let on = join
+                .on
+                .iter()
+                .map(|(l, r)| {
+                    let l = coerce_expr(l, &left_schema)?;
+                    let r = coerce_expr(r, &right_schema)?;
+                    let l_type = l.data_type(&left_schema)?;
+                    let r_type = r.data_type(&right_schema)?;
+                    let key_type = binary_operand_type(&l_type, &Operator::Eq, &r_type)?;
+                    check_string_comparison(&[&l], &key_type, &left_schema)?;
+                    check_string_comparison(&[&r], &key_type, &right_schema)?;
+                    Ok((
+                        cast_if_needed(l, &l_type, &key_type),
+                        cast_if_needed(r, &r_type, &key_type),
+                    ))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            let filter_schema = left_schema.join(&right_schema)?;
+            let filter = join
+                .filter
+                .as_ref()
+                .map(|e| coerce_expr(e, &filter_schema))
+                .transpose()?;
+
+            let on_changed = join
+                .on
+                .iter()
+                .zip(on.iter())
+                .any(|((l1, r1), (l2, r2))| changed(l1, l2) || changed(r1, r2));
+            let filter_changed = match (&join.filter, &filter) {
+                (Some(old), Some(new)) => changed(old, new),
+                _ => false,
+            };
+            if !on_changed && !filter_changed {
+                return Ok(None);
+            }
+            Some(LogicalPlan::Join(Join::try_new(
+                join.left.clone(),
+                join.right.clone(),
+                on,
+                filter,
+                join.join_type,
+            )?))
+        }
+        LogicalPlan::Aggregate(aggregate) => {
+            let schema = aggregate.input.schema();
+            let group_exprs = coerce_exprs(&aggregate.group_exprs, &schema)?;
+            let aggr_exprs = coerce_exprs(&aggregate.aggr_exprs, &schema)?;
+            if group_exprs.is_none() && aggr_exprs.is_none() {
+                return Ok(None);
+            }
+            Some(LogicalPlan::Aggregate(Aggregate::try_new(
+                aggregate.input.clone(),
+                group_exprs.unwrap_or_else(|| aggregate.group_exprs.clone()),
+                aggr_exprs.unwrap_or_else(|| aggregate.aggr_exprs.clone()),
+            )?))
+        }
+    };
+    Ok(new_plan)
+}
+
+/// Returns the coerced expressions, or `None` if none of them changed.
+fn coerce_exprs(
+    exprs: &[LogicalExpr],
+    schema: &QualifiedSchema,
+) -> Result<Option<Vec<LogicalExpr>>> {
+    let new_exprs = exprs
+        .iter()
+        .map(|e| coerce_expr(e, schema))
+        .collect::<Result<Vec<_>>>()?;
+    let any_changed = exprs
+        .iter()
+        .zip(new_exprs.iter())
+        .any(|(a, b)| changed(a, b));
+    Ok(any_changed.then_some(new_exprs))
+}
+
+/// Casts only ever add nodes, which always shows up in the `Display` output.
+fn changed(old: &LogicalExpr, new: &LogicalExpr) -> bool {
+    old.to_string() != new.to_string()
+}
+
+fn coerce_expr(expr: &LogicalExpr, schema: &QualifiedSchema) -> Result<LogicalExpr> {
+    Ok(match expr {
+        LogicalExpr::Column(_) | LogicalExpr::Literal(_) => expr.clone(),
+        LogicalExpr::Binary(e) => {
+            let lhs = coerce_expr(&e.lhs, schema)?;
+            let rhs = coerce_expr(&e.rhs, schema)?;
+            let lhs_type = lhs.data_type(schema)?;
+            let rhs_type = rhs.data_type(schema)?;
+            let (lhs_operand, rhs_operand) = binary_operand_types(&lhs_type, &e.op, &rhs_type)?;
+            if e.op.is_comparison() {
+                check_string_comparison(&[&lhs, &rhs], &lhs_operand, schema)?;
+            }
+            LogicalExpr::Binary(BinaryExpr::new(
+                Arc::new(cast_if_needed(lhs, &lhs_type, &lhs_operand)),
+                e.op.clone(),
+                Arc::new(cast_if_needed(rhs, &rhs_type, &rhs_operand)),
+            ))
+        }
+        LogicalExpr::Unary(e) => {
+            let new_expr = LogicalExpr::Unary(UnaryExpr::new(
+                e.op,
+                Arc::new(coerce_expr(&e.expr, schema)?),
+            ));
+            // Reject unsupported operand types, e.g. NOT over integers.
+            new_expr.data_type(schema)?;
+            new_expr
+        }
+        LogicalExpr::InList(e) => {
+            let mut exprs = coerce_comparison(&expr.children(), schema)?.into_iter();
+            let value = exprs.next().expect("IN has a value");
+            LogicalExpr::InList(InList::new(value, exprs.collect(), e.negated))
+        }
+        LogicalExpr::Between(e) => {
+            let [value, low, high] = coerce_comparison(&expr.children(), schema)?
+                .try_into()
+                .expect("BETWEEN has three operands");
+            LogicalExpr::Between(Between::new(value, e.negated, low, high))
+        }
+        LogicalExpr::Like(e) => LogicalExpr::Like(coerce_like(expr, e, schema)?),
+        LogicalExpr::SimilarTo(e) => LogicalExpr::SimilarTo(coerce_like(expr, e, schema)?),
+        LogicalExpr::ScalarFunction(e) => {
+            let args = e
+                .args
+                .iter()
+                .map(|e| coerce_expr(e, schema))
+                .collect::<Result<Vec<_>>>()?;
+            let types = args
+                .iter()
+                .map(|e| e.data_type(schema))
+                .collect::<Result<Vec<_>>>()?;
+            let arg_types = e.fun.coerce_types(&types)?;
+            let args = args
+                .into_iter()
+                .zip(types.iter().zip(arg_types.iter()))
+                .map(|(arg, (from, to))| Arc::new(cast_if_needed(arg, from, to)))
+                .collect();
+            LogicalExpr::ScalarFunction(ScalarFunctionExpr::new(e.fun, args))
+        }
+        LogicalExpr::Cast(e) => {
+            let expr = coerce_expr(&e.expr, schema)?;
+            check_cast(&expr.data_type(schema)?, &e.data_type)?;
+            LogicalExpr::Cast(Cast {
+                expr: Arc::new(expr),
+                ..e.clone()
+            })
+        }
+        LogicalExpr::TryCast(e) => {
+            let expr = coerce_expr(&e.expr, schema)?;
+            check_cast(&expr.data_type(schema)?, &e.data_type)?;
+            expr.try_cast_to(e.data_type.clone())
+        }
+        LogicalExpr::Alias(e) => LogicalExpr::Alias(Alias::new(
+            Arc::new(coerce_expr(&e.expr, schema)?),
+            e.name.clone(),
+        )),
+        LogicalExpr::Aggregate(e) => {
+            let new_expr = LogicalExpr::Aggregate(AggregateExpr::new(
+                e.fun.clone(),
+                Arc::new(coerce_expr(&e.expr, schema)?),
+                e.distinct,
+            ));
+            // Reject unsupported input types, e.g. SUM over strings.
+            new_expr.data_type(schema)?;
+            new_expr
+        }
+        LogicalExpr::Case(e) => coerce_case(e, schema)?,
+        LogicalExpr::Coalesce(e) => {
+            let args = e
+                .args
+                .iter()
+                .map(|e| coerce_expr(e, schema))
+                .collect::<Result<Vec<_>>>()?;
+            let coalesce =
+                LogicalExpr::Coalesce(Coalesce::new(args.iter().cloned().map(Arc::new).collect()));
+            let data_type = coalesce.data_type(schema)?;
+            let args = args
+                .into_iter()
+                .map(|arg| cast_to_type(arg, &data_type, schema).map(Arc::new))
+                .collect::<Result<Vec<_>>>()?;
+            LogicalExpr::Coalesce(Coalesce::new(args))
+        }
+        LogicalExpr::WindowFunction(e) => coerce_window(e, schema)?,
+    })
+}
+
+/// Casts the default value of `LAG`/`LEAD` to the type of the values it
+/// stands in for.
+fn coerce_window(window: &WindowExpr, schema: &QualifiedSchema) -> Result<LogicalExpr> {
+    let coerce = |e: &Arc<LogicalExpr>| coerce_expr(e, schema).map(Arc::new);
+    let mut args = window.args.iter().map(coerce).collect::<Result<Vec<_>>>()?;
+    if matches!(window.fun, WindowFunction::Lag | WindowFunction::Lead) && args.len() == 3 {
+        let value_type = args[0].data_type(schema)?;
+        let default_type = args[2].data_type(schema)?;
+        check_cast(&default_type, &value_type)?;
+        let default = args[2].as_ref().clone();
+        args[2] = Arc::new(cast_if_needed(default, &default_type, &value_type));
+    }
+    let partition_by = window
+        .partition_by
+        .iter()
+        .map(coerce)
+        .collect::<Result<Vec<_>>>()?;
+    let order_by = window
+        .order_by
+        .iter()
+        .map(|e| Ok(e.with_expr(coerce_expr(&e.expr, schema)?)))
+        .collect::<Result<Vec<_>>>()?;
+    let new_expr = LogicalExpr::WindowFunction(WindowExpr::new(
+        window.fun.clone(),
+        args,
+        partition_by,
+        order_by,
+        window.frame,
+    ));
+    // Reject unsupported arguments, e.g. SUM over strings.
+    new_expr.data_type(schema)?;
+    Ok(new_expr)
+}
+
+/// Casts the base and `WHEN` values of a simple `CASE` to the type they are
+/// compared as, and every result to the type of the `CASE`. The conditions
+/// of a searched `CASE` must be boolean.
+fn coerce_case(case: &Case, schema: &QualifiedSchema) -> Result<LogicalExpr> {
+    let coerce = |e: &Arc<LogicalExpr>| coerce_expr(e, schema);
+    let expr = case.expr.as_ref().map(coerce).transpose()?;
+    let whens = case
+        .when_then
+        .iter()
+        .map(|(when, _)| coerce(when))
+        .collect::<Result<Vec<_>>>()?;
+    let thens = case
+        .when_then
+        .iter()
+        .map(|(_, then)| coerce(then))
+        .collect::<Result<Vec<_>>>()?;
+    let else_expr = case.else_expr.as_ref().map(coerce).transpose()?;
+
+    let (expr, whens) = match expr {
+        Some(expr) => {
+            let mut compare_type = expr.data_type(schema)?;
+            for when in whens.iter() {
+                let when_type = when.data_type(schema)?;
+                compare_type = binary_operand_type(&compare_type, &Operator::Eq, &when_type)?;
+            }
+            let operands = std::iter::once(&expr).chain(&whens).collect::<Vec<_>>();
+            check_string_comparison(&operands, &compare_type, schema)?;
+            let whens = whens
+                .into_iter()
+                .map(|when| cast_to_type(when, &compare_type, schema))
+                .collect::<Result<Vec<_>>>()?;
+            (Some(cast_to_type(expr, &compare_type, schema)?), whens)
+        }
+        None => {
+            for when in whens.iter() {
+                let data_type = when.data_type(schema)?;
+                if !matches!(data_type, DataType::Boolean | DataType::Null) {
+                    return plan_err!(
+                        "CASE condition must be a boolean expression, got {} of type {}",
+                        when,
+                        data_type
+                    );
+                }
+            }
+            (None, whens)
+        }
+    };
+
+    let when_then = whens
+        .into_iter()
+        .map(Arc::new)
+        .zip(thens.into_iter().map(Arc::new))
+        .collect::<Vec<_>>();
+    let case = Case::new(expr.map(Arc::new), when_then, else_expr.map(Arc::new));
+    // Fails if the results have no common type.
+    let data_type = LogicalExpr::Case(case.clone()).data_type(schema)?;
+    let cast = |e: &Arc<LogicalExpr>| -> Result<Arc<LogicalExpr>> {
+        Ok(Arc::new(cast_to_type(
+            e.as_ref().clone(),
+            &data_type,
+            schema,
+        )?))
+    };
+    let when_then = case
+        .when_then
+        .iter()
+        .map(|(when, then)| Ok((when.clone(), cast(then)?)))
+        .collect::<Result<Vec<_>>>()?;
+    let else_expr = case.else_expr.as_ref().map(cast).transpose()?;
+    Ok(LogicalExpr::Case(Case::new(
+        case.expr, when_then, else_expr,
+    )))
+}
+
+/// Checks that the value and the pattern of `LIKE` or `SIMILAR TO` are
+/// strings and casts NULLs to strings. A pattern of a different string type
+/// than the value is converted when the query runs, since literals can only
+/// hold `Utf8`.
+fn coerce_like(expr: &LogicalExpr, like: &Like, schema: &QualifiedSchema) -> Result<Like> {
+    let value = coerce_expr(&like.expr, schema)?;
+    let pattern = coerce_expr(&like.pattern, schema)?;
+    let value_type = value.data_type(schema)?;
+    let pattern_type = pattern.data_type(schema)?;
+    if string_type(&value_type, &pattern_type).is_none() {
+        return plan_err!(
+            "cannot apply '{}' to {} and {}",
+            expr,
+            value_type,
+            pattern_type
+        );
+    }
+    let string = |t: &DataType| match t {
+        DataType::Null => DataType::Utf8,
+        t => t.clone(),
+    };
+    Ok(Like {
+        expr: Arc::new(cast_if_needed(value, &value_type, &string(&value_type))),
+        pattern: Arc::new(cast_if_needed(
+            pattern,
+            &pattern_type,
+            &string(&pattern_type),
+        )),
+        ..like.clone()
+    })
+}
+
+/// Casts `exprs` to the type they are compared as, e.g. the value and the
+/// items of an `IN` list.
+fn coerce_comparison(
+    exprs: &[&LogicalExpr],
+    schema: &QualifiedSchema,
+) -> Result<Vec<Arc<LogicalExpr>>> {
+    let exprs = exprs
+        .iter()
+        .map(|e| coerce_expr(e, schema))
+        .collect::<Result<Vec<_>>>()?;
+    let types = exprs
+        .iter()
+        .map(|e| e.data_type(schema))
+        .collect::<Result<Vec<_>>>()?;
+    let compare_type = comparison_operand_type(&types)?;
+    check_string_comparison(&exprs.iter().collect::<Vec<_>>(), &compare_type, schema)?;
+    Ok(exprs
+        .into_iter()
+        .zip(types.iter())
+        .map(|(e, from)| Arc::new(cast_if_needed(e, from, &compare_type)))
+        .collect())
+}
+
+fn cast_to_type(expr: LogicalExpr, to: &DataType, schema: &QualifiedSchema) -> Result<LogicalExpr> {
+    let from = expr.data_type(schema)?;
+    Ok(cast_if_needed(expr, &from, to))
+}
+
+fn cast_if_needed(expr: LogicalExpr, from: &DataType, to: &DataType) -> LogicalExpr {
+    if from == to {
+        expr
+    } else {
+        LogicalExpr::Cast(Cast::new_implicit(Arc::new(expr), to.clone()))
+    }
+}
+
+/// Strings are only compared with numbers when they are literals, e.g.
+/// `c2 = '5'`, which are parsed here so a malformed one is reported while
+/// planning. Any other string would only fail to convert once the query
+/// runs, so it must be cast explicitly.
+fn check_string_comparison(
+    operands: &[&LogicalExpr],
+    compare_type: &DataType,
+    schema: &QualifiedSchema,
+) -> Result<()> {
+    if !compare_type.is_numeric() {
+        return Ok(());
+    }
+    for expr in operands {
+        let data_type = expr.data_type(schema)?;
+        if !matches!(data_type, DataType::Utf8 | DataType::LargeUtf8) {
+            continue;
+        }
+        match expr {
+            LogicalExpr::Literal(ScalarValue::String(Some(value))) => {
+                let options = CastOptions {
+                    safe: false,
+                    ..Default::default()
+                };
+                let array = ScalarValue::String(Some(value.clone())).to_array(1);
+                if cast_with_options(&array, compare_type, &options).is_err() {
+                    return plan_err!("cannot parse '{}' as {}", value, compare_type);
+                }
+            }
+            LogicalExpr::Literal(_) => {}
+            _ => {
+                return plan_err!(
+                    "cannot compare {} of type {} with {}, cast it explicitly",
+                    expr,
+                    data_type,
+                    compare_type
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Rejects conversions Arrow has no kernel for, e.g. from Boolean to Date32.
+/// Whether a particular value can be converted is only known when the query
+/// runs.
+fn check_cast(from: &DataType, to: &DataType) -> Result<()> {
+    if can_cast_types(from, to) {
+        Ok(())
+    } else {
+        plan_err!("cannot cast {} to {}", from, to)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        datasource::MemDataSource,
+        error::DBError,
+        logical::expression::expr_fn::{col, lit},
+    };
+
+    fn scan() -> Arc<LogicalPlan> {
+        Arc::new(LogicalPlan::Scan(Scan::new(
+            "t",
+            MemDataSource::new_arc(),
+            None,
+        )))
+    }
+
+    #[test]
+    fn test_insert_casts() -> Result<()> {
+        let filter = LogicalPlan::Filter(Filter::new(scan(), col("c2").gt(lit(1_i64))));
+        let plan = LogicalPlan::Projection(Projection::try_new(Arc::new(filter), vec![
+            col("c1"),
+            col("c3").plus(lit(1.5)),
+        ])?);
+        let plan = TypeCoercion.try_optimize(&plan)?.unwrap();
+        assert_eq!(
+            plan.to_string(),
+            "Projection: c1, CAST(c3 AS Float64) + 1.5\n".to_string()
+                + "\tFilter: CAST(c2 AS Int64) > 1\n"
+                + "\t\tScan: t; projection=None\n"
+        );
+        // The output keeps the name of the original expression.
+        let schema = plan.schema();
+        let field = schema.field(1);
+        assert_eq!(field.name(), "c3 + 1.5");
+        assert_eq!(field.data_type(), &DataType::Float64);
+
+        assert!(TypeCoercion.try_optimize(&plan)?.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_types() {
+        let plan = LogicalPlan::Filter(Filter::new(scan(), col("c1").plus(lit(1))));
+        let err = TypeCoercion.try_optimize(&plan).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "plan error: cannot apply '+' to Utf8 and Int32"
+        );
+
+        let plan = LogicalPlan::Filter(Filter::new(scan(), col("c2").plus(lit(1))));
+        let err = TypeCoercion.try_optimize(&plan).unwrap_err();
+        assert!(matches!(err, DBError::Plan(_)));
+    }
+
+    #[test]
+    fn test_string_comparisons() -> Result<()> {
+        // String literals are read as numbers.
+        let filter = col("c2")
+            .eq(lit("2"))
+            .and(col("c3").in_list(vec![lit("10")], false));
+        let plan = LogicalPlan::Filter(Filter::new(scan(), filter));
+        assert_eq!(
+            TypeCoercion.try_optimize(&plan)?.unwrap().to_string(),
+            "Filter: c2 = CAST('2' AS Int32) AND c3 IN (CAST('10' AS Int32))\n".to_string()
+                + "\tScan: t; projection=None\n"
+        );
+
+        let plan = LogicalPlan::Filter(Filter::new(scan(), col("c2").lt(lit("x"))));
+        let err = TypeCoercion.try_optimize(&plan).unwrap_err();
+        assert_eq!(err.to_string(), "plan error: cannot parse 'x' as Int32");
+
+        // A string column would only fail once a value is not a number.
+        let plan = LogicalPlan::Filter(Filter::new(scan(), col("c1").eq(lit(1))));
+        let err = TypeCoercion.try_optimize(&plan).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "plan error: cannot compare c1 of type Utf8 with Int32, cast it explicitly"
+        );
+        let plan = LogicalPlan::Filter(Filter::new(scan(), col("c1").between(lit(1), col("c2"))));
+        assert!(matches!(
+            TypeCoercion.try_optimize(&plan),
+            Err(DBError::Plan(_))
+        ));
+        Ok(())
+    }
+}
@@ -0,0 +1,415 @@
+use std::{collections::HashSet, fmt::Debug};
+
+use arrow::{
+    array::{Array, ArrayRef, AsArray},
+    compute::{
+        self, cast,
+        kernels::{aggregate::sum_checked, boolean::is_not_null},
+    },
+    datatypes::{
+        ArrowNativeTypeOp, Date32Type, Decimal128Type, DecimalType, Float32Type, Float64Type,
+        Int8Type, Int16Type, Int32Type, Int64Type, Time64MicrosecondType, Time64NanosecondType,
+        TimestampMicrosecondType, TimestampMillisecondType, TimestampNanosecondType,
+        TimestampSecondType, UInt8Type, UInt16Type, UInt32Type, UInt64Type,
+    },
+    row::{OwnedRow, RowConverter, SortField},
+};
+use arrow_schema::{DataType, TimeUnit};
+
+use crate::{
+    datatypes::values::ScalarValue,
+    error::{DBError, Result, exec_err, internal_err},
+};
+
+/// Accumulates the values of a single group and produces its aggregate value.
+pub trait Accumulator: Debug + Send {
+    /// Updates the state with a batch of input values.
+    fn update_batch(&mut self, values: &ArrayRef) -> Result<()>;
+
+    /// Returns the final aggregate value.
+    fn evaluate(&mut self) -> Result<ScalarValue>;
+
+    /// Removes a batch of values that were added before, so that a window
+    /// frame can slide without starting over. Only called if
+    /// [`Accumulator::supports_retract`] returns true.
+    fn retract_batch(&mut self, _values: &ArrayRef) -> Result<()> {
+        internal_err!("accumulator cannot retract values")
+    }
+
+    fn supports_retract(&self) -> bool {
+        false
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct CountAccumulator {
+    count: i64,
+}
+
+impl Accumulator for CountAccumulator {
+    fn update_batch(&mut self, values: &ArrayRef) -> Result<()> {
+        self.count += (values.len() - values.logical_null_count()) as i64;
+        Ok(())
+    }
+
+    fn evaluate(&mut self) -> Result<ScalarValue> {
+        Ok(ScalarValue::Int64(Some(self.count)))
+    }
+
+    fn retract_batch(&mut self, values: &ArrayRef) -> Result<()> {
+        self.count -= (values.len() - values.logical_null_count()) as i64;
+        Ok(())
+    }
+
+    fn supports_retract(&self) -> bool {
+        true
+    }
+}
+
+/// Sums values after widening them to `Int64`, `UInt64`, `Float64` or a
+/// wider decimal. Integer overflow, and sums with more digits than the
+/// decimal precision, are reported as errors. Retracting floats may leave
+/// rounding errors in the sum.
+#[derive(Debug)]
+pub struct SumAccumulator {
+    sum: ScalarValue,
+    /// The number of non-NULL values in the sum, which is NULL without any.
+    count: u64,
+}
+
+impl SumAccumulator {
+    pub fn try_new(data_type: &DataType) -> Result<Self> {
+        Ok(Self {
+            sum: ScalarValue::try_new_null(data_type)?,
+            count: 0,
+        })
+    }
+
+    /// Adds the sum of `values`, or subtracts it if `negate` is set.
+    fn add_batch(&mut self, values: &ArrayRef, negate: bool) -> Result<()> {
+        let values = cast(values, &self.sum.data_type())?;
+        self.sum = match &self.sum {
+            ScalarValue::Int64(v) => ScalarValue::Int64(add_option(
+                *v,
+                sum_checked(values.as_primitive::<Int64Type>())?,
+                negate,
+            )?),
+            ScalarValue::Uint64(v) => ScalarValue::Uint64(add_option(
+                *v,
+                sum_checked(values.as_primitive::<UInt64Type>())?,
+                negate,
+            )?),
+            ScalarValue::Float64(v) => ScalarValue::Float64(add_option(
+                *v,
+                compute::sum(values.as_primitive::<Float64Type>()),
+                negate,
+            )?),
+            ScalarValue::Decimal128(v, precision, scale) => {
+                let sum = add_option(
+                    *v,
+                    sum_checked(values.as_primitive::<Decimal128Type>())?,
+                    negate,
+                )?;
+                if let Some(sum) = sum {
+                    Decimal128Type::validate_decimal_precision(sum, *precision)
+                        .map_err(|e| DBError::Execution(format!("decimal overflow: {}", e)))?;
+                }
+                ScalarValue::Decimal128(sum, *precision, *scale)
+            }
+            other => return exec_err!("SUM does not support type {}", other.data_type()),
+        };
+        let count = (values.len() - values.null_count()) as u64;
+        if negate {
+            self.count -= count;
+        } else {
+            self.count += count;
+        }
+        Ok(())
+    }
+}
+
+fn add_option<T: ArrowNativeTypeOp>(
+    lhs: Option<T>,
+    rhs: Option<T>,
+    negate: bool,
+) -> Result<Option<T>> {
+    Ok(match (lhs, rhs) {
+        (l, Some(r)) if negate => Some(l.unwrap_or(T::ZERO).sub_checked(r)?),
+        (Some(l), Some(r)) => Some(l.add_checked(r)?),
+        (l, r) => l.or(r),
+    })
+}
+
+impl Accumulator for SumAccumulator {
+    fn update_batch(&mut self, values: &ArrayRef) -> Result<()> {
+        self.add_batch(values, false)
+    }
+
+    fn evaluate(&mut self) -> Result<ScalarValue> {
+        if self.count == 0 {
+            return ScalarValue::try_new_null(&self.sum.data_type());
+        }
+        Ok(self.sum.clone())
+    }
+
+    fn retract_batch(&mut self, values: &ArrayRef) -> Result<()> {
+        self.add_batch(values, true)
+    }
+
+    fn supports_retract(&self) -> bool {
+        true
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct AvgAccumulator {
+    sum: f64,
+    count: u64,
+}
+
+impl Accumulator for AvgAccumulator {
+    fn update_batch(&mut self, values: &ArrayRef) -> Result<()> {
+        let values = cast(values, &DataType::Float64)?;
+        self.sum += compute::sum(values.as_primitive::<Float64Type>()).unwrap_or_default();
+        self.count += (values.len() - values.null_count()) as u64;
+        Ok(())
+    }
+
+    fn evaluate(&mut self) -> Result<ScalarValue> {
+        if self.count == 0 {
+            return Ok(ScalarValue::Float64(None));
+        }
+        Ok(ScalarValue::Float64(Some(self.sum / self.count as f64)))
+    }
+
+    fn retract_batch(&mut self, values: &ArrayRef) -> Result<()> {
+        let values = cast(values, &DataType::Float64)?;
+        self.sum -= compute::sum(values.as_primitive::<Float64Type>()).unwrap_or_default();
+        self.count -= (values.len() - values.null_count()) as u64;
+        Ok(())
+    }
+
+    fn supports_retract(&self) -> bool {
+        true
+    }
+}
+
+/// Averages decimals exactly, rounding the result half away from zero to
+/// the scale of the return type.
+#[derive(Debug)]
+pub struct DecimalAvgAccumulator {
+    sum: i128,
+    count: i128,
+    /// The scale of the input values, which `sum` is kept at.
+    input_scale: i8,
+    precision: u8,
+    scale: i8,
+}
+
+impl DecimalAvgAccumulator {
+    pub fn try_new(input_type: &DataType, return_type: &DataType) -> Result<Self> {
+        match (input_type, return_type) {
+            (DataType::Decimal128(_, input_scale), DataType::Decimal128(precision, scale)) => {
+                Ok(Self {
+                    sum: 0,
+                    count: 0,
+                    input_scale: *input_scale,
+                    precision: *precision,
+                    scale: *scale,
+                })
+            }
+            _ => exec_err!("cannot average {} as {}", input_type, return_type),
+        }
+    }
+}
+
+impl Accumulator for DecimalAvgAccumulator {
+    fn update_batch(&mut self, values: &ArrayRef) -> Result<()> {
+        let values = values.as_primitive::<Decimal128Type>();
+        if let Some(sum) = sum_checked(values)? {
+            self.sum = self.sum.add_checked(sum)?;
+        }
+        self.count += (values.len() - values.null_count()) as i128;
+        Ok(())
+    }
+
+    fn evaluate(&mut self) -> Result<ScalarValue> {
+        if self.count == 0 {
+            return Ok(ScalarValue::Decimal128(None, self.precision, self.scale));
+        }
+        let factor = 10_i128.pow_checked((self.scale - self.input_scale) as u32)?;
+        let numerator = self.sum.mul_checked(factor)?;
+        let (quotient, remainder) = (numerator / self.count, numerator % self.count);
+        let avg = if remainder.abs() * 2 >= self.count {
+            quotient + numerator.signum()
+        } else {
+            quotient
+        };
+        Ok(ScalarValue::Decimal128(
+            Some(avg),
+            self.precision,
+            self.scale,
+        ))
+    }
+
+    fn retract_batch(&mut self, values: &ArrayRef) -> Result<()> {
+        let values = values.as_primitive::<Decimal128Type>();
+        if let Some(sum) = sum_checked(values)? {
+            self.sum = self.sum.sub_checked(sum)?;
+        }
+        self.count -= (values.len() - values.null_count()) as i128;
+        Ok(())
+    }
+
+    fn supports_retract(&self) -> bool {
+        true
+    }
+}
+
+macro_rules! min_max_array {
+    ($values:expr, $op:ident, $bool_op:ident, $str_op:ident) => {{
+        let values = $values;
+        match values.data_type() {
+            DataType::Null => ScalarValue::Null,
+            DataType::Boolean => ScalarValue::Boolean(compute::$bool_op(values.as_boolean())),
+            DataType::Int8 => ScalarValue::Int8(compute::$op(values.as_primitive::<Int8Type>())),
+            DataType::Int16 => ScalarValue::Int16(compute::$op(values.as_primitive::<Int16Type>())),
+            DataType::Int32 => ScalarValue::Int32(compute::$op(values.as_primitive::<Int32Type>())),
+            DataType::Int64 => ScalarValue::Int64(compute::$op(values.as_primitive::<Int64Type>())),
+            DataType::UInt8 => ScalarValue::Uint8(compute::$op(values.as_primitive::<UInt8Type>())),
+            DataType::UInt16 => {
+                ScalarValue::Uint16(compute::$op(values.as_primitive::<UInt16Type>()))
+            }
+            DataType::UInt32 => {
+                ScalarValue::Uint32(compute::$op(values.as_primitive::<UInt32Type>()))
+            }
+            DataType::UInt64 => {
+                ScalarValue::Uint64(compute::$op(values.as_primitive::<UInt64Type>()))
+            }
+            DataType::Float32 => {
+                ScalarValue::Float32(compute::$op(values.as_primitive::<Float32Type>()))
+            }
+            DataType::Float64 => {
+                ScalarValue::Float64(compute::$op(values.as_primitive::<Float64Type>()))
+            }
+            DataType::Decimal128(precision, scale) => ScalarValue::Decimal128(
+                compute::$op(values.as_primitive::<Decimal128Type>()),
+                *precision,
+                *scale,
+            ),
+            DataType::Utf8 => ScalarValue::String(
+                compute::$str_op(values.as_string::<i32>()).map(|v| v.to_string()),
+            ),
+            DataType::Date32 => {
+                ScalarValue::Date32(compute::$op(values.as_primitive::<Date32Type>()))
+            }
+            DataType::Time64(unit) => {
+                let value = match unit {
+                    TimeUnit::Microsecond => {
+                        compute::$op(values.as_primitive::<Time64MicrosecondType>())
+                    }
+                    _ => compute::$op(values.as_primitive::<Time64NanosecondType>()),
+                };
+                ScalarValue::Time64(value, *unit)
+            }
+            DataType::Timestamp(unit, tz) => {
+                let value = match unit {
+                    TimeUnit::Second => compute::$op(values.as_primitive::<TimestampSecondType>()),
+                    TimeUnit::Millisecond => {
+                        compute::$op(values.as_primitive::<TimestampMillisecondType>())
+                    }
+                    TimeUnit::Microsecond => {
+                        compute::$op(values.as_primitive::<TimestampMicrosecondType>())
+                    }
+                    TimeUnit::Nanosecond => {
+                        compute::$op(values.as_primitive::<TimestampNanosecondType>())
+                    }
+                };
+                ScalarValue::Timestamp(value, *unit, tz.clone())
+            }
+            other => return exec_err!("MIN/MAX does not support type {}", other),
+        }
+    }};
+}
+
+#[derive(Debug)]
+pub struct MinMaxAccumulator {
+    is_min: bool,
+    value: ScalarValue,
+}
+
+impl MinMaxAccumulator {
+    pub fn try_new(data_type: &DataType, is_min: bool) -> Result<Self> {
+        Ok(Self {
+            is_min,
+            value: ScalarValue::try_new_null(data_type)?,
+        })
+    }
+
+    fn min_max(&self, values: &dyn Array) -> Result<ScalarValue> {
+        Ok(if self.is_min {
+            min_max_array!(values, min, min_boolean, min_string)
+        } else {
+            min_max_array!(values, max, max_boolean, max_string)
+        })
+    }
+}
+
+impl Accumulator for MinMaxAccumulator {
+    fn update_batch(&mut self, values: &ArrayRef) -> Result<()> {
+        let batch_value = self.min_max(values.as_ref())?;
+        if batch_value.is_null() {
+            return Ok(());
+        }
+        if self.value.is_null() {
+            self.value = batch_value;
+            return Ok(());
+        }
+        let candidates = compute::concat(&[
+            self.value.to_array(1).as_ref(),
+            batch_value.to_array(1).as_ref(),
+        ])?;
+        self.value = self.min_max(candidates.as_ref())?;
+        Ok(())
+    }
+
+    fn evaluate(&mut self) -> Result<ScalarValue> {
+        Ok(self.value.clone())
+    }
+}
+
+/// Deduplicates the non-NULL input values and feeds them to `inner` once the
+/// final value is requested, e.g. for `COUNT(DISTINCT c1)`.
+#[derive(Debug)]
+pub struct DistinctAccumulator {
+    converter: RowConverter,
+    values: HashSet<OwnedRow>,
+    inner: Box<dyn Accumulator>,
+}
+
+impl DistinctAccumulator {
+    pub fn try_new(data_type: &DataType, inner: Box<dyn Accumulator>) -> Result<Self> {
+        Ok(Self {
+            converter: RowConverter::new(vec![SortField::new(data_type.clone())])?,
+            values: HashSet::new(),
+            inner,
+        })
+    }
+}
+
+impl Accumulator for DistinctAccumulator {
+    fn update_batch(&mut self, values: &ArrayRef) -> Result<()> {
+        let values = compute::filter(values, &is_not_null(values)?)?;
+        let rows = self.converter.convert_columns(&[values])?;
+        self.values.extend(rows.iter().map(|row| row.owned()));
+        Ok(())
+    }
+
+    fn evaluate(&mut self) -> Result<ScalarValue> {
+        let arrays = self
+            .converter
+            .convert_rows(self.values.iter().map(|row| row.row()))?;
+        self.inner.update_batch(&arrays[0])?;
+        self.inner.evaluate()
+    }
+}
@@ -0,0 +1,65 @@
+use std::sync::Arc;
+
+use arrow::array::{ArrayRef, RecordBatch};
+use arrow_schema::DataType;
+
+use super::{
+    PhysicalExpression,
+    accumulator::{
+        Accumulator, AvgAccumulator, CountAccumulator, DecimalAvgAccumulator, DistinctAccumulator,
+        MinMaxAccumulator, SumAccumulator,
+    },
+};
+use crate::{error::Result, logical::expression::aggregate::AggregateFunction};
+
+#[derive(Debug)]
+pub struct AggregateExpr {
+    fun: AggregateFunction,
+    expr: Arc<PhysicalExpression>,
+    distinct: bool,
+    /// The type of the values produced by `expr`.
+    input_type: DataType,
+}
+
+impl AggregateExpr {
+    pub fn new(
+        fun: AggregateFunction,
+        expr: Arc<PhysicalExpression>,
+        distinct: bool,
+        input_type: DataType,
+    ) -> Self {
+        Self {
+            fun,
+            expr,
+            distinct,
+            input_type,
+        }
+    }
+
+    /// Evaluates the aggregate's argument against a batch.
+    pub fn evaluate_input(&self, batch: &RecordBatch) -> Result<ArrayRef> {
+        self.expr.evalate(batch)?.into_array(batch.num_rows())
+    }
+
+    /// Creates a fresh accumulator for one group.
+    pub fn create_accumulator(&self) -> Result<Box<dyn Accumulator>> {
+        let return_type = self.fun.return_type(&self.input_type)?;
+        let accumulator: Box<dyn Accumulator> = match self.fun {
+            AggregateFunction::Count => Box::new(CountAccumulator::default()),
+            AggregateFunction::Sum => Box::new(SumAccumulator::try_new(&return_type)?),
+            AggregateFunction::Min => Box::new(MinMaxAccumulator::try_new(&return_type, true)?),
+            AggregateFunction::Max => Box::new(MinMaxAccumulator::try_new(&return_type, false)?),
+            AggregateFunction::Avg if matches!(return_type, DataType::Decimal128(..)) => Box::new(
+                DecimalAvgAccumulator::try_new(&self.input_type, &return_type)?,
+            ),
+            AggregateFunction::Avg => Box::new(AvgAccumulator::default()),
+        };
+        if self.distinct {
+            return Ok(Box::new(DistinctAccumulator::try_new(
+                &self.input_type,
+                accumulator,
+            )?));
+        }
+        Ok(accumulator)
+    }
+}
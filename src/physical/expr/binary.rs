@@ -1,28 +1,47 @@
 use std::sync::Arc;
 
 use crate::{
-    datatypes::{operator::Operator, values::ScalarValue},
-    error::Result,
+    datatypes::operator::Operator,
+    datatypes::values::ScalarValue,
+    error::{DBError, Result},
 };
 
 use arrow::{
-    array::{ArrayRef, Datum, RecordBatch},
-    compute::kernels::numeric::{add_wrapping, div, mul_wrapping, sub_wrapping},
+    array::{ArrayRef, BooleanArray, Datum, RecordBatch},
+    compute::kernels::{
+        boolean::{and_kleene, or_kleene},
+        cmp::{distinct, eq, gt, gt_eq, lt, lt_eq, neq, not_distinct},
+        concat_elements::concat_elements_dyn,
+        numeric::{add, add_wrapping, div, mul, mul_wrapping, rem, sub, sub_wrapping},
+    },
 };
 use arrow_schema::ArrowError;
 
-use super::{ColumnarValue, PhysicalExpression};
+use super::{ColumnarValue, PhysicalExpression, to_boolean_array};
 
 #[derive(Debug)]
 pub struct BinaryExpr {
     lhs: Arc<PhysicalExpression>,
     op: Operator,
     rhs: Arc<PhysicalExpression>,
+    /// Whether integer `+`, `-` and `*` fail on overflow instead of wrapping
+    /// around. Division and modulo are always checked.
+    fail_on_overflow: bool,
 }
 
 impl BinaryExpr {
     pub fn new(lhs: Arc<PhysicalExpression>, op: Operator, rhs: Arc<PhysicalExpression>) -> Self {
-        Self { lhs, op, rhs }
+        Self {
+            lhs,
+            op,
+            rhs,
+            fail_on_overflow: true,
+        }
+    }
+
+    pub fn with_fail_on_overflow(mut self, fail_on_overflow: bool) -> Self {
+        self.fail_on_overflow = fail_on_overflow;
+        self
     }
 
     pub fn evalate(&self, batch: &RecordBatch) -> Result<ColumnarValue> {
@@ -30,20 +49,48 @@ impl BinaryExpr {
         let rhs = self.rhs.evalate(batch)?;
 
         match self.op.clone() {
-            // Eq => Self::apply_cmp(&lhs, &rhs, eq),
-            // NotEq => Self::apply_cmp(&lhs, &rhs, neq),
-            // Lt => Self::apply_cmp(&lhs, &rhs, lt),
-            // LtEq => Self::apply_cmp(&lhs, &rhs, lt_eq),
-            // Gt => Self::apply_cmp(&lhs, &rhs, gt),
-            // GtEq => Self::apply_cmp(&lhs, &rhs, gt_eq),
-            Operator::Plus => Self::apply(&lhs, &rhs, add_wrapping),
-            // Minus => Self::apply(&lhs, &rhs, sub_wrapping),
-            // Multiply => Self::apply(&lhs, &rhs, mul_wrapping),
-            // Divide => Self::apply(&lhs, &rhs, div),
-            _ => unimplemented!(),
+            Operator::Eq => Self::apply_cmp(&lhs, &rhs, eq),
+            Operator::NotEq => Self::apply_cmp(&lhs, &rhs, neq),
+            Operator::Lt => Self::apply_cmp(&lhs, &rhs, lt),
+            Operator::LtEq => Self::apply_cmp(&lhs, &rhs, lt_eq),
+            Operator::Gt => Self::apply_cmp(&lhs, &rhs, gt),
+            Operator::GtEq => Self::apply_cmp(&lhs, &rhs, gt_eq),
+            Operator::IsDistinctFrom => Self::apply_cmp(&lhs, &rhs, distinct),
+            Operator::IsNotDistinctFrom => Self::apply_cmp(&lhs, &rhs, not_distinct),
+            Operator::And => Self::apply_boolean(lhs, rhs, batch.num_rows(), and_kleene),
+            Operator::Or => Self::apply_boolean(lhs, rhs, batch.num_rows(), or_kleene),
+            Operator::StringConcat => Self::apply_arrays(lhs, rhs, batch.num_rows(), |l, r| {
+                Ok(concat_elements_dyn(l, r)?)
+            }),
+            Operator::Plus if self.fail_on_overflow => Self::apply_arithmetic(&lhs, &rhs, add),
+            Operator::Plus => Self::apply_arithmetic(&lhs, &rhs, add_wrapping),
+            Operator::Minus if self.fail_on_overflow => Self::apply_arithmetic(&lhs, &rhs, sub),
+            Operator::Minus => Self::apply_arithmetic(&lhs, &rhs, sub_wrapping),
+            Operator::Multiply if self.fail_on_overflow => Self::apply_arithmetic(&lhs, &rhs, mul),
+            Operator::Multiply => Self::apply_arithmetic(&lhs, &rhs, mul_wrapping),
+            Operator::Divide => Self::apply_arithmetic(&lhs, &rhs, div),
+            Operator::Modulo => Self::apply_arithmetic(&lhs, &rhs, rem),
         }
     }
 
+    /// Applies an arithmetic kernel. Integer overflow and division by zero are
+    /// reported as execution errors, since they depend on the data.
+    fn apply_arithmetic(
+        lhs: &ColumnarValue,
+        rhs: &ColumnarValue,
+        f: impl Fn(&dyn Datum, &dyn Datum) -> std::result::Result<ArrayRef, ArrowError>,
+    ) -> Result<ColumnarValue> {
+        Self::apply(lhs, rhs, f).map_err(|e| match e {
+            DBError::ArrowError(ArrowError::DivideByZero) => {
+                DBError::Execution("division by zero".to_string())
+            }
+            DBError::ArrowError(ArrowError::ArithmeticOverflow(msg)) => {
+                DBError::Execution(format!("integer overflow: {}", msg))
+            }
+            e => e,
+        })
+    }
+
     fn apply(
         lhs: &ColumnarValue,
         rhs: &ColumnarValue,
@@ -55,12 +102,251 @@ impl BinaryExpr {
             (Array(l), Array(r)) => Ok(Array(f(&l.as_ref(), &r.as_ref())?)),
             (Scalar(l), Array(r)) => Ok(Array(f(&l.to_scalar()?, &r.as_ref())?)),
             (Array(l), Scalar(r)) => Ok(Array(f(&l.as_ref(), &r.to_scalar()?)?)),
-            // (Scalar(l), Scalar(r)) => {
-            //     let arr = f(&l.to_scalar()?, &r.to_scalar()?)?;
-            //     let value = ScalarValue::try_from_array(arr.as_ref(), 0)?;
-            //     Ok(Scalar(value))
-            // }
-            (Scalar(l), Scalar(r)) => unimplemented!(),
+            (Scalar(l), Scalar(r)) => {
+                let array = f(&l.to_scalar()?, &r.to_scalar()?)?;
+                Ok(Scalar(ScalarValue::try_from_array(array.as_ref(), 0)?))
+            }
+        }
+    }
+
+    /// Applies a comparison kernel. A NULL on either side yields NULL.
+    pub(crate) fn apply_cmp(
+        lhs: &ColumnarValue,
+        rhs: &ColumnarValue,
+        f: impl Fn(&dyn Datum, &dyn Datum) -> std::result::Result<BooleanArray, ArrowError>,
+    ) -> Result<ColumnarValue> {
+        Self::apply(lhs, rhs, |l, r| Ok(Arc::new(f(l, r)?) as ArrayRef))
+    }
+
+    /// Applies a boolean kernel using SQL three-valued logic, e.g.
+    /// `NULL AND false` is `false` while `NULL AND true` is `NULL`.
+    fn apply_boolean(
+        lhs: ColumnarValue,
+        rhs: ColumnarValue,
+        num_rows: usize,
+        f: impl Fn(&BooleanArray, &BooleanArray) -> std::result::Result<BooleanArray, ArrowError>,
+    ) -> Result<ColumnarValue> {
+        Self::apply_arrays(lhs, rhs, num_rows, |l, r| {
+            let array = f(&to_boolean_array(l)?, &to_boolean_array(r)?)?;
+            Ok(Arc::new(array))
+        })
+    }
+
+    /// Applies a kernel that only accepts arrays, expanding scalar operands
+    /// into arrays first.
+    fn apply_arrays(
+        lhs: ColumnarValue,
+        rhs: ColumnarValue,
+        num_rows: usize,
+        f: impl Fn(&ArrayRef, &ArrayRef) -> Result<ArrayRef>,
+    ) -> Result<ColumnarValue> {
+        let scalars = matches!(
+            (&lhs, &rhs),
+            (ColumnarValue::Scalar(_), ColumnarValue::Scalar(_))
+        );
+        let num_rows = if scalars { 1 } else { num_rows };
+        let array = f(&lhs.into_array(num_rows)?, &rhs.into_array(num_rows)?)?;
+        if scalars {
+            let value = ScalarValue::try_from_array(&array, 0)?;
+            return Ok(ColumnarValue::Scalar(value));
+        }
+        Ok(ColumnarValue::Array(array))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::array::{AsArray, Int32Array, record_batch};
+
+    use super::*;
+    use crate::{
+        datatypes::values::ScalarValue,
+        physical::expr::{column::ColumnExpr, literal::LiteralExpr},
+    };
+
+    fn column(name: &str, index: usize) -> Arc<PhysicalExpression> {
+        Arc::new(PhysicalExpression::Column(ColumnExpr::new(name, index)))
+    }
+
+    fn literal(value: ScalarValue) -> Arc<PhysicalExpression> {
+        Arc::new(PhysicalExpression::Literal(LiteralExpr::new(value)))
+    }
+
+    fn evaluate(expr: BinaryExpr, batch: &RecordBatch) -> Result<BooleanArray> {
+        let array = expr.evalate(batch)?.into_array(batch.num_rows())?;
+        Ok(array.as_boolean().clone())
+    }
+
+    #[test]
+    fn test_comparison() -> Result<()> {
+        let batch = record_batch!(("a", Int32, [Some(1), None, Some(3)]))?;
+
+        let expr = BinaryExpr::new(
+            column("a", 0),
+            Operator::Gt,
+            literal(ScalarValue::Int32(Some(1))),
+        );
+        let ret = evaluate(expr, &batch)?;
+        assert_eq!(ret, BooleanArray::from(vec![Some(false), None, Some(true)]));
+
+        let expr = BinaryExpr::new(
+            literal(ScalarValue::Int32(Some(3))),
+            Operator::Eq,
+            column("a", 0),
+        );
+        let ret = evaluate(expr, &batch)?;
+        assert_eq!(ret, BooleanArray::from(vec![Some(false), None, Some(true)]));
+        Ok(())
+    }
+
+    #[test]
+    fn test_three_valued_logic() -> Result<()> {
+        let batch = record_batch!(
+            ("a", Boolean, [Some(true), Some(false), None, None, None]),
+            ("b", Boolean, [None, None, Some(true), Some(false), None])
+        )?;
+
+        let expr = BinaryExpr::new(column("a", 0), Operator::And, column("b", 1));
+        let ret = evaluate(expr, &batch)?;
+        assert_eq!(
+            ret,
+            BooleanArray::from(vec![None, Some(false), None, Some(false), None])
+        );
+
+        let expr = BinaryExpr::new(column("a", 0), Operator::Or, column("b", 1));
+        let ret = evaluate(expr, &batch)?;
+        assert_eq!(
+            ret,
+            BooleanArray::from(vec![Some(true), None, Some(true), None, None])
+        );
+
+        let expr = BinaryExpr::new(column("a", 0), Operator::And, literal(ScalarValue::Null));
+        let ret = evaluate(expr, &batch)?;
+        assert_eq!(
+            ret,
+            BooleanArray::from(vec![None, Some(false), None, None, None])
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_boolean_operand_type() -> Result<()> {
+        let batch = RecordBatch::try_from_iter(vec![(
+            "a",
+            Arc::new(Int32Array::from(vec![1, 2])) as ArrayRef,
+        )])?;
+        let expr = BinaryExpr::new(
+            column("a", 0),
+            Operator::And,
+            literal(ScalarValue::Boolean(Some(true))),
+        );
+        assert!(expr.evalate(&batch).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_arithmetic() -> Result<()> {
+        let batch = record_batch!(
+            ("a", Int32, [Some(7), None, Some(-7)]),
+            ("b", Int32, [Some(2), Some(1), Some(3)])
+        )?;
+        let eval = |op| -> Result<ArrayRef> {
+            let expr = BinaryExpr::new(column("a", 0), op, column("b", 1));
+            expr.evalate(&batch)?.into_array(batch.num_rows())
+        };
+        let expected = [
+            (Operator::Plus, [Some(9), None, Some(-4)]),
+            (Operator::Minus, [Some(5), None, Some(-10)]),
+            (Operator::Multiply, [Some(14), None, Some(-21)]),
+            (Operator::Divide, [Some(3), None, Some(-2)]),
+            (Operator::Modulo, [Some(1), None, Some(-1)]),
+        ];
+        for (op, values) in expected {
+            let ret = eval(op.clone())?;
+            assert_eq!(
+                ret.as_primitive(),
+                &Int32Array::from(values.to_vec()),
+                "{}",
+                op
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_overflow_and_division_by_zero() -> Result<()> {
+        let batch = record_batch!(("a", Int32, [i32::MAX, 0]))?;
+        let one = || literal(ScalarValue::Int32(Some(1)));
+
+        let expr = BinaryExpr::new(column("a", 0), Operator::Plus, one());
+        let err = expr.evalate(&batch).unwrap_err();
+        assert!(matches!(err, DBError::Execution(_)), "{}", err);
+
+        let expr =
+            BinaryExpr::new(column("a", 0), Operator::Plus, one()).with_fail_on_overflow(false);
+        let ret = expr.evalate(&batch)?.into_array(batch.num_rows())?;
+        assert_eq!(ret.as_primitive(), &Int32Array::from(vec![i32::MIN, 1]));
+
+        // Division is checked even when overflow wraps.
+        for op in [Operator::Divide, Operator::Modulo] {
+            let expr = BinaryExpr::new(one(), op, column("a", 0)).with_fail_on_overflow(false);
+            let err = expr.evalate(&batch).unwrap_err();
+            assert_eq!(err.to_string(), "execution error: division by zero");
         }
+
+        let batch = record_batch!(("a", Float64, [1.0, 0.0]))?;
+        let expr = BinaryExpr::new(
+            column("a", 0),
+            Operator::Divide,
+            literal(ScalarValue::Float64(Some(0.0))),
+        );
+        let ret = expr.evalate(&batch)?.into_array(batch.num_rows())?;
+        let ret = ret.as_primitive::<arrow::datatypes::Float64Type>();
+        assert_eq!(ret.value(0), f64::INFINITY);
+        assert!(ret.value(1).is_nan());
+        Ok(())
+    }
+
+    #[test]
+    fn test_scalar_operands() -> Result<()> {
+        let batch = record_batch!(("a", Int32, [1, 2, 3]))?;
+        let expr = BinaryExpr::new(
+            literal(ScalarValue::Int64(Some(6))),
+            Operator::Divide,
+            literal(ScalarValue::Int64(Some(4))),
+        );
+        let ColumnarValue::Scalar(value) = expr.evalate(&batch)? else {
+            panic!("expected a scalar");
+        };
+        assert_eq!(value, ScalarValue::Int64(Some(1)));
+
+        let expr = BinaryExpr::new(
+            literal(ScalarValue::Boolean(Some(true))),
+            Operator::And,
+            literal(ScalarValue::Null),
+        );
+        assert_eq!(
+            expr.evalate(&batch)?
+                .into_array(batch.num_rows())?
+                .as_boolean(),
+            &BooleanArray::from(vec![None, None, None])
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_string_concat() -> Result<()> {
+        let batch = record_batch!(("a", Utf8, [Some("x"), None]))?;
+        let expr = BinaryExpr::new(
+            column("a", 0),
+            Operator::StringConcat,
+            literal(ScalarValue::String(Some("-1".to_string()))),
+        );
+        let ret = expr.evalate(&batch)?.into_array(batch.num_rows())?;
+        assert_eq!(
+            ret.as_string::<i32>(),
+            &arrow::array::StringArray::from(vec![Some("x-1"), None])
+        );
+        Ok(())
     }
 }
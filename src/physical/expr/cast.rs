@@ -0,0 +1,110 @@
+use std::sync::Arc;
+
+use arrow::{
+    array::RecordBatch,
+    compute::{CastOptions, cast_with_options},
+};
+use arrow_schema::DataType;
+
+use super::{ColumnarValue, PhysicalExpression};
+use crate::{datatypes::values::ScalarValue, error::Result};
+
+/// Values that cannot be converted are reported as errors instead of being
+/// turned into NULLs.
+const CAST_OPTIONS: CastOptions<'static> = CastOptions {
+    safe: false,
+    format_options: arrow::util::display::FormatOptions::new(),
+};
+
+/// Values that cannot be converted become NULL.
+const TRY_CAST_OPTIONS: CastOptions<'static> = CastOptions {
+    safe: true,
+    format_options: arrow::util::display::FormatOptions::new(),
+};
+
+#[derive(Debug)]
+pub struct CastExpr {
+    expr: Arc<PhysicalExpression>,
+    data_type: DataType,
+}
+
+impl CastExpr {
+    pub fn new(expr: Arc<PhysicalExpression>, data_type: DataType) -> Self {
+        Self { expr, data_type }
+    }
+
+    pub fn evalate(&self, batch: &RecordBatch) -> Result<ColumnarValue> {
+        cast_value(self.expr.evalate(batch)?, &self.data_type, &CAST_OPTIONS)
+    }
+}
+
+#[derive(Debug)]
+pub struct TryCastExpr {
+    expr: Arc<PhysicalExpression>,
+    data_type: DataType,
+}
+
+impl TryCastExpr {
+    pub fn new(expr: Arc<PhysicalExpression>, data_type: DataType) -> Self {
+        Self { expr, data_type }
+    }
+
+    pub fn evalate(&self, batch: &RecordBatch) -> Result<ColumnarValue> {
+        cast_value(
+            self.expr.evalate(batch)?,
+            &self.data_type,
+            &TRY_CAST_OPTIONS,
+        )
+    }
+}
+
+fn cast_value(
+    value: ColumnarValue,
+    data_type: &DataType,
+    options: &CastOptions,
+) -> Result<ColumnarValue> {
+    match value {
+        ColumnarValue::Array(array) => Ok(ColumnarValue::Array(cast_with_options(
+            &array, data_type, options,
+        )?)),
+        ColumnarValue::Scalar(value) => {
+            let array = cast_with_options(&value.to_array(1), data_type, options)?;
+            let value = ScalarValue::try_from_array(array.as_ref(), 0)?;
+            Ok(ColumnarValue::Scalar(value))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::array::{AsArray, Int64Array, record_batch};
+    use arrow::datatypes::Int64Type;
+
+    use super::*;
+    use crate::physical::expr::column::ColumnExpr;
+
+    #[test]
+    fn test_cast() -> Result<()> {
+        let batch = record_batch!(("a", Utf8, [Some("1"), None, Some("x")]))?;
+        let column = Arc::new(PhysicalExpression::Column(ColumnExpr::new("a", 0)));
+
+        let expr = CastExpr::new(column, DataType::Int64);
+        assert!(expr.evalate(&batch).is_err());
+
+        let column = Arc::new(PhysicalExpression::Column(ColumnExpr::new("a", 0)));
+        let expr = TryCastExpr::new(column, DataType::Int64);
+        let ret = expr.evalate(&batch)?.into_array(batch.num_rows())?;
+        assert_eq!(
+            ret.as_primitive::<Int64Type>(),
+            &Int64Array::from(vec![Some(1), None, None])
+        );
+
+        let batch = batch.slice(0, 2);
+        let ret = expr.evalate(&batch)?.into_array(batch.num_rows())?;
+        assert_eq!(
+            ret.as_primitive::<Int64Type>(),
+            &Int64Array::from(vec![Some(1), None])
+        );
+        Ok(())
+    }
+}
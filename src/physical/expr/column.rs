@@ -1,6 +1,6 @@
 use std::sync::Arc;
 
-use crate::error::Result;
+use crate::error::{Result, internal_err};
 use arrow::array::RecordBatch;
 
 use super::ColumnarValue;
@@ -22,8 +22,34 @@ impl ColumnExpr {
     }
 
     pub fn evalate(&self, batch: &RecordBatch) -> Result<ColumnarValue> {
-        let array = Arc::new(batch.column(self.index).clone());
+        let Some(array) = batch.columns().get(self.index) else {
+            return internal_err!(
+                "column '{}' at index {} is out of range for a batch with {} columns",
+                self.name,
+                self.index,
+                batch.num_columns()
+            );
+        };
 
-        Ok(ColumnarValue::Array(array))
+        Ok(ColumnarValue::Array(Arc::clone(array)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::array::record_batch;
+
+    use super::*;
+
+    #[test]
+    fn test_column_out_of_range() -> Result<()> {
+        let batch = record_batch!(("a", Int32, [1, 2]))?;
+        assert!(ColumnExpr::new("a", 0).evalate(&batch).is_ok());
+        let err = ColumnExpr::new("b", 1).evalate(&batch).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "internal error: column 'b' at index 1 is out of range for a batch with 1 columns"
+        );
+        Ok(())
     }
 }
@@ -0,0 +1,224 @@
+use std::sync::Arc;
+
+use arrow::{
+    array::{ArrayRef, AsArray, BooleanArray, RecordBatch, new_null_array},
+    compute::{
+        filter, filter_record_batch, is_null,
+        kernels::{boolean::and_not, cmp::eq, zip::zip},
+    },
+};
+use arrow_schema::DataType;
+
+use super::{ColumnarValue, PhysicalExpression, null_as_false, scatter, to_boolean_array};
+use crate::error::Result;
+
+/// Evaluates a `CASE` expression. Every `WHEN` is only evaluated on the rows
+/// no earlier branch selected and every `THEN` only on the rows its `WHEN`
+/// selected, so `CASE WHEN x <> 0 THEN 1 / x END` never divides by zero.
+#[derive(Debug)]
+pub struct CaseExpr {
+    expr: Option<Arc<PhysicalExpression>>,
+    when_then: Vec<(Arc<PhysicalExpression>, Arc<PhysicalExpression>)>,
+    else_expr: Option<Arc<PhysicalExpression>>,
+    data_type: DataType,
+}
+
+impl CaseExpr {
+    pub fn new(
+        expr: Option<Arc<PhysicalExpression>>,
+        when_then: Vec<(Arc<PhysicalExpression>, Arc<PhysicalExpression>)>,
+        else_expr: Option<Arc<PhysicalExpression>>,
+        data_type: DataType,
+    ) -> Self {
+        Self {
+            expr,
+            when_then,
+            else_expr,
+            data_type,
+        }
+    }
+
+    pub fn evalate(&self, batch: &RecordBatch) -> Result<ColumnarValue> {
+        let num_rows = batch.num_rows();
+        let base = self
+            .expr
+            .as_ref()
+            .map(|e| e.evalate(batch)?.into_array(num_rows))
+            .transpose()?;
+        let mut result = new_null_array(&self.data_type, num_rows);
+        // The rows no branch has selected yet.
+        let mut remaining = BooleanArray::from(vec![true; num_rows]);
+
+        for (when, then) in self.when_then.iter() {
+            if remaining.true_count() == 0 {
+                break;
+            }
+            let rows = filter_record_batch(batch, &remaining)?;
+            let when_value = when.evalate(&rows)?.into_array(rows.num_rows())?;
+            let matched = match &base {
+                Some(base) => eq(&filter(base, &remaining)?, &when_value)?,
+                None => to_boolean_array(&when_value)?,
+            };
+            // A NULL condition does not select the row.
+            let selected =
+                null_as_false(scatter(&remaining, &null_as_false(&matched))?.as_boolean());
+            if selected.true_count() == 0 {
+                continue;
+            }
+
+            let value = evaluate_selection(then, batch, &selected)?;
+            result = zip(&selected, &value, &result)?;
+            remaining = and_not(&remaining, &selected)?;
+        }
+
+        if let Some(else_expr) = &self.else_expr
+            && remaining.true_count() > 0
+        {
+            let value = evaluate_selection(else_expr, batch, &remaining)?;
+            result = zip(&remaining, &value, &result)?;
+        }
+        Ok(ColumnarValue::Array(result))
+    }
+}
+
+/// Returns the first argument that is not NULL. Every argument is only
+/// evaluated on the rows all earlier arguments are NULL for.
+#[derive(Debug)]
+pub struct CoalesceExpr {
+    args: Vec<Arc<PhysicalExpression>>,
+}
+
+impl CoalesceExpr {
+    pub fn new(args: Vec<Arc<PhysicalExpression>>) -> Self {
+        Self { args }
+    }
+
+    pub fn evalate(&self, batch: &RecordBatch) -> Result<ColumnarValue> {
+        let num_rows = batch.num_rows();
+        let (first, rest) = self
+            .args
+            .split_first()
+            .expect("COALESCE has at least one argument");
+        let mut result = first.evalate(batch)?.into_array(num_rows)?;
+        for arg in rest {
+            let remaining = is_null(&result)?;
+            if remaining.true_count() == 0 {
+                break;
+            }
+            let value = evaluate_selection(arg, batch, &remaining)?;
+            result = zip(&remaining, &value, &result)?;
+        }
+        Ok(ColumnarValue::Array(result))
+    }
+}
+
+/// Evaluates `expr` on the rows of `batch` selected by `mask` only. The result
+/// has a row for every row of `batch`, rows outside of `mask` are NULL.
+fn evaluate_selection(
+    expr: &PhysicalExpression,
+    batch: &RecordBatch,
+    mask: &BooleanArray,
+) -> Result<ArrayRef> {
+    let rows = filter_record_batch(batch, mask)?;
+    let value = expr.evalate(&rows)?.into_array(rows.num_rows())?;
+    scatter(mask, &value)
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::array::{Int32Array, StringArray, record_batch};
+
+    use super::*;
+    use crate::{
+        datatypes::{operator::Operator, values::ScalarValue},
+        physical::expr::{binary::BinaryExpr, column::ColumnExpr, literal::LiteralExpr},
+    };
+
+    fn column(name: &str, index: usize) -> Arc<PhysicalExpression> {
+        Arc::new(PhysicalExpression::Column(ColumnExpr::new(name, index)))
+    }
+
+    fn lit(value: ScalarValue) -> Arc<PhysicalExpression> {
+        Arc::new(PhysicalExpression::Literal(LiteralExpr::new(value)))
+    }
+
+    fn binary(
+        lhs: Arc<PhysicalExpression>,
+        op: Operator,
+        rhs: Arc<PhysicalExpression>,
+    ) -> Arc<PhysicalExpression> {
+        Arc::new(PhysicalExpression::Binary(BinaryExpr::new(lhs, op, rhs)))
+    }
+
+    #[test]
+    fn test_case() -> Result<()> {
+        let batch = record_batch!(("a", Int32, [Some(2), Some(0), None, Some(-4)]))?;
+
+        // CASE WHEN a <> 0 THEN 8 / a END
+        let expr = CaseExpr::new(
+            None,
+            vec![(
+                binary(
+                    column("a", 0),
+                    Operator::NotEq,
+                    lit(ScalarValue::Int32(Some(0))),
+                ),
+                binary(
+                    lit(ScalarValue::Int32(Some(8))),
+                    Operator::Divide,
+                    column("a", 0),
+                ),
+            )],
+            None,
+            DataType::Int32,
+        );
+        let ret = expr.evalate(&batch)?.into_array(batch.num_rows())?;
+        assert_eq!(
+            ret.as_primitive(),
+            &Int32Array::from(vec![Some(4), None, None, Some(-2)])
+        );
+
+        // CASE a WHEN 2 THEN 'two' WHEN 0 THEN 'zero' ELSE 'other' END
+        let string = |s: &str| lit(ScalarValue::String(Some(s.to_string())));
+        let expr = CaseExpr::new(
+            Some(column("a", 0)),
+            vec![
+                (lit(ScalarValue::Int32(Some(2))), string("two")),
+                (lit(ScalarValue::Int32(Some(0))), string("zero")),
+            ],
+            Some(string("other")),
+            DataType::Utf8,
+        );
+        let ret = expr.evalate(&batch)?.into_array(batch.num_rows())?;
+        assert_eq!(
+            ret.as_string::<i32>(),
+            &StringArray::from(vec!["two", "zero", "other", "other"])
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_coalesce() -> Result<()> {
+        let batch = record_batch!(
+            ("a", Int32, [Some(1), None, None]),
+            ("b", Int32, [Some(0), Some(0), None])
+        )?;
+
+        // COALESCE(a, 10 / b, 7) only divides on the rows where a is NULL.
+        let expr = CoalesceExpr::new(vec![
+            column("a", 0),
+            binary(
+                lit(ScalarValue::Int32(Some(10))),
+                Operator::Divide,
+                column("b", 1),
+            ),
+            lit(ScalarValue::Int32(Some(7))),
+        ]);
+        assert!(expr.evalate(&batch).is_err());
+
+        let batch = batch.slice(0, 1);
+        let ret = expr.evalate(&batch)?.into_array(batch.num_rows())?;
+        assert_eq!(ret.as_primitive(), &Int32Array::from(vec![Some(1)]));
+        Ok(())
+    }
+}
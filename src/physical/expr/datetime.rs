@@ -0,0 +1,395 @@
+use std::{str::FromStr, sync::Arc};
+
+use arrow::{
+    array::{
+        Array, ArrayRef, AsArray, Float64Array, Float64Builder, PrimitiveArray, StringArray,
+        TimestampMicrosecondArray, timezone::Tz,
+    },
+    compute::{
+        CastOptions, binary, cast, cast_with_options,
+        kernels::temporal::{self, DatePart},
+    },
+    datatypes::{
+        ArrowTimestampType, Date32Type, Float64Type, TimeUnit, TimestampMicrosecondType,
+        TimestampMillisecondType, TimestampNanosecondType, TimestampSecondType,
+    },
+};
+use arrow_schema::DataType;
+use chrono::{Datelike, Days, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Timelike, Utc};
+
+use crate::{
+    datatypes::values::ScalarValue,
+    error::{DBError, Result, exec_err, internal_err},
+    logical::expression::function::ScalarFunction,
+};
+
+/// Evaluates the date and time function `fun` on the arrays of its
+/// arguments, which have been cast to the types its signature asks for.
+pub(crate) fn evaluate(
+    fun: ScalarFunction,
+    args: &[ArrayRef],
+    num_rows: usize,
+) -> Result<ArrayRef> {
+    match fun {
+        ScalarFunction::Now => {
+            let now = Utc::now().timestamp_micros();
+            let value =
+                ScalarValue::Timestamp(Some(now), TimeUnit::Microsecond, Some("+00:00".into()));
+            Ok(value.to_array(num_rows))
+        }
+        ScalarFunction::CurrentDate => {
+            let today = Date32Type::from_naive_date(Utc::now().date_naive());
+            Ok(ScalarValue::Date32(Some(today)).to_array(num_rows))
+        }
+        ScalarFunction::DateTrunc => date_trunc(&strings(&args[0])?, &args[1]),
+        ScalarFunction::DatePart => date_part(&strings(&args[0])?, &args[1]),
+        ScalarFunction::ToTimestamp => {
+            let formats = args.get(1).map(strings).transpose()?;
+            to_timestamp(&strings(&args[0])?, formats.as_ref())
+        }
+        other => internal_err!("{} is not a date and time function", other),
+    }
+}
+
+/// String arguments may be LargeUtf8, but the values read here are short.
+fn strings(array: &ArrayRef) -> Result<StringArray> {
+    Ok(cast(array, &DataType::Utf8)?.as_string::<i32>().clone())
+}
+
+fn date_trunc(units: &StringArray, values: &ArrayRef) -> Result<ArrayRef> {
+    let DataType::Timestamp(unit, tz) = values.data_type() else {
+        return internal_err!("date_trunc expects a timestamp, got {}", values.data_type());
+    };
+    let tz = tz.as_deref().map(Tz::from_str).transpose()?;
+    Ok(match unit {
+        TimeUnit::Second => Arc::new(truncate_timestamps(
+            units,
+            values.as_primitive::<TimestampSecondType>(),
+            tz,
+        )?),
+        TimeUnit::Millisecond => Arc::new(truncate_timestamps(
+            units,
+            values.as_primitive::<TimestampMillisecondType>(),
+            tz,
+        )?),
+        TimeUnit::Microsecond => Arc::new(truncate_timestamps(
+            units,
+            values.as_primitive::<TimestampMicrosecondType>(),
+            tz,
+        )?),
+        TimeUnit::Nanosecond => Arc::new(truncate_timestamps(
+            units,
+            values.as_primitive::<TimestampNanosecondType>(),
+            tz,
+        )?),
+    })
+}
+
+/// Timestamps with a time zone are truncated in that zone, e.g. to its
+/// local midnight for `'day'`.
+fn truncate_timestamps<T: ArrowTimestampType>(
+    units: &StringArray,
+    values: &PrimitiveArray<T>,
+    tz: Option<Tz>,
+) -> Result<PrimitiveArray<T>> {
+    let truncated = (0..values.len())
+        .map(|i| {
+            if values.is_null(i) || units.is_null(i) {
+                return Ok(None);
+            }
+            let unit = units.value(i);
+            let value = match tz {
+                None => values
+                    .value_as_datetime(i)
+                    .map(|local| truncate(local, unit))
+                    .transpose()?
+                    .flatten()
+                    .and_then(T::make_value),
+                Some(tz) => values
+                    .value_as_datetime_with_tz(i, tz)
+                    .map(|local| truncate(local.naive_local(), unit))
+                    .transpose()?
+                    .flatten()
+                    .and_then(|local| tz.from_local_datetime(&local).earliest())
+                    .and_then(|truncated| T::make_value(truncated.naive_utc())),
+            };
+            match value {
+                Some(value) => Ok(Some(value)),
+                None => exec_err!("timestamp out of range in date_trunc"),
+            }
+        })
+        .collect::<Result<PrimitiveArray<T>>>()?;
+    Ok(truncated.with_timezone_opt(values.timezone().map(Arc::<str>::from)))
+}
+
+/// Rounds `value` down to the start of its `unit`.
+fn truncate(value: NaiveDateTime, unit: &str) -> Result<Option<NaiveDateTime>> {
+    let date = value.date();
+    let start_of_day = |date: NaiveDate| Some(date.and_time(NaiveTime::MIN));
+    let nanos = value.nanosecond();
+    Ok(match unit.to_ascii_lowercase().as_str() {
+        "microsecond" => value.with_nanosecond(nanos / 1_000 * 1_000),
+        "millisecond" => value.with_nanosecond(nanos / 1_000_000 * 1_000_000),
+        "second" => value.with_nanosecond(0),
+        "minute" => date.and_hms_opt(value.hour(), value.minute(), 0),
+        "hour" => date.and_hms_opt(value.hour(), 0, 0),
+        "day" => start_of_day(date),
+        "week" => {
+            let days = date.weekday().num_days_from_monday();
+            date.checked_sub_days(Days::new(days.into()))
+                .and_then(start_of_day)
+        }
+        "month" => date.with_day(1).and_then(start_of_day),
+        "quarter" => NaiveDate::from_ymd_opt(date.year(), date.month0() / 3 * 3 + 1, 1)
+            .and_then(start_of_day),
+        "year" => NaiveDate::from_ymd_opt(date.year(), 1, 1).and_then(start_of_day),
+        other => return exec_err!("unsupported date_trunc unit '{}'", other),
+    })
+}
+
+fn date_part(fields: &StringArray, values: &ArrayRef) -> Result<ArrayRef> {
+    // The field is nearly always a constant, so each field is extracted from
+    // the whole array at once and the rows pick the values of theirs.
+    let mut parts: Vec<(String, Float64Array)> = vec![];
+    let mut result = Float64Builder::with_capacity(values.len());
+    for i in 0..values.len() {
+        if fields.is_null(i) {
+            result.append_null();
+            continue;
+        }
+        let field = fields.value(i).to_ascii_lowercase();
+        let index = match parts.iter().position(|(f, _)| f == &field) {
+            Some(index) => index,
+            None => {
+                let part = extract(&field, values)?;
+                parts.push((field, part));
+                parts.len() - 1
+            }
+        };
+        let part = &parts[index].1;
+        result.append_option(part.is_valid(i).then(|| part.value(i)));
+    }
+    Ok(Arc::new(result.finish()))
+}
+
+/// Extracts `field` from every value. Seconds include the fraction, so
+/// `'second'` of `12:00:01.5` is 1.5, and `'epoch'` is the number of
+/// seconds since 1970-01-01 00:00:00 UTC.
+fn extract(field: &str, values: &ArrayRef) -> Result<Float64Array> {
+    let part = |part: DatePart| -> Result<Float64Array> {
+        let array = cast(&temporal::date_part(values, part)?, &DataType::Float64)?;
+        Ok(array.as_primitive::<Float64Type>().clone())
+    };
+    match field {
+        "year" => part(DatePart::Year),
+        "quarter" => part(DatePart::Quarter),
+        "month" => part(DatePart::Month),
+        "week" => part(DatePart::Week),
+        "day" => part(DatePart::Day),
+        "hour" => part(DatePart::Hour),
+        "minute" => part(DatePart::Minute),
+        "dow" => part(DatePart::DayOfWeekSunday0),
+        "doy" => part(DatePart::DayOfYear),
+        "second" | "millisecond" | "microsecond" => {
+            let nanos_per_unit = match field {
+                "second" => 1e9,
+                "millisecond" => 1e6,
+                _ => 1e3,
+            };
+            let seconds = part(DatePart::Second)?;
+            let nanos = part(DatePart::Nanosecond)?;
+            Ok(binary(&seconds, &nanos, |s, n| {
+                (s * 1e9 + n) / nanos_per_unit
+            })?)
+        }
+        "epoch" => epoch(values),
+        other => exec_err!("unsupported date_part field '{}'", other),
+    }
+}
+
+fn epoch(values: &ArrayRef) -> Result<Float64Array> {
+    let units_per_second = match values.data_type() {
+        DataType::Date32 => {
+            let days = cast(values, &DataType::Float64)?;
+            return Ok(days.as_primitive::<Float64Type>().unary(|d| d * 86_400.0));
+        }
+        DataType::Timestamp(unit, _) | DataType::Time64(unit) => match unit {
+            TimeUnit::Second => 1.0,
+            TimeUnit::Millisecond => 1e3,
+            TimeUnit::Microsecond => 1e6,
+            TimeUnit::Nanosecond => 1e9,
+        },
+        other => return internal_err!("cannot extract epoch from {}", other),
+    };
+    let raw = cast(&cast(values, &DataType::Int64)?, &DataType::Float64)?;
+    Ok(raw
+        .as_primitive::<Float64Type>()
+        .unary(|v| v / units_per_second))
+}
+
+fn to_timestamp(values: &StringArray, formats: Option<&StringArray>) -> Result<ArrayRef> {
+    let Some(formats) = formats else {
+        // ISO 8601 strings, with or without an offset, are parsed like casts.
+        let options = CastOptions {
+            safe: false,
+            ..Default::default()
+        };
+        let data_type = DataType::Timestamp(TimeUnit::Microsecond, None);
+        return cast_with_options(values, &data_type, &options)
+            .map_err(|e| DBError::Execution(e.to_string()));
+    };
+    let parsed = values
+        .iter()
+        .zip(formats.iter())
+        .map(|(value, format)| {
+            let (Some(value), Some(format)) = (value, format) else {
+                return Ok(None);
+            };
+            // Formats without a time of day yield midnight.
+            let parsed = NaiveDateTime::parse_from_str(value, format).or_else(|_| {
+                NaiveDate::parse_from_str(value, format).map(|d| d.and_time(NaiveTime::MIN))
+            });
+            match parsed {
+                Ok(parsed) => Ok(Some(parsed.and_utc().timestamp_micros())),
+                Err(_) => exec_err!(
+                    "cannot parse '{}' as a timestamp with format '{}'",
+                    value,
+                    format
+                ),
+            }
+        })
+        .collect::<Result<TimestampMicrosecondArray>>()?;
+    Ok(Arc::new(parsed))
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::array::{Date32Array, TimestampSecondArray};
+
+    use super::*;
+
+    fn string(value: &str, num_rows: usize) -> ArrayRef {
+        Arc::new(StringArray::from(vec![value; num_rows]))
+    }
+
+    fn timestamps(values: &[&str]) -> Result<ArrayRef> {
+        let values: ArrayRef = Arc::new(StringArray::from(values.to_vec()));
+        Ok(cast(&values, &DataType::Timestamp(TimeUnit::Second, None))?)
+    }
+
+    fn to_strings(array: &ArrayRef) -> Result<Vec<String>> {
+        let strings = cast(array, &DataType::Utf8)?;
+        Ok(strings
+            .as_string::<i32>()
+            .iter()
+            .map(|s| s.unwrap_or("NULL").to_string())
+            .collect())
+    }
+
+    #[test]
+    fn test_date_trunc() -> Result<()> {
+        let values = timestamps(&["2024-05-15T13:45:30", "2024-12-31T23:59:59"])?;
+
+        let truncate = |unit: &str| -> Result<Vec<String>> {
+            let ret = evaluate(
+                ScalarFunction::DateTrunc,
+                &[string(unit, 2), values.clone()],
+                2,
+            )?;
+            assert_eq!(ret.data_type(), values.data_type());
+            to_strings(&ret)
+        };
+        assert_eq!(truncate("minute")?, vec![
+            "2024-05-15T13:45:00",
+            "2024-12-31T23:59:00"
+        ]);
+        assert_eq!(truncate("DAY")?, vec![
+            "2024-05-15T00:00:00",
+            "2024-12-31T00:00:00"
+        ]);
+        assert_eq!(truncate("week")?, vec![
+            "2024-05-13T00:00:00",
+            "2024-12-30T00:00:00"
+        ]);
+        assert_eq!(truncate("quarter")?, vec![
+            "2024-04-01T00:00:00",
+            "2024-10-01T00:00:00"
+        ]);
+        assert!(matches!(truncate("fortnight"), Err(DBError::Execution(_))));
+
+        // Truncated to midnight in +02:00, which is 22:00 UTC the day before.
+        let utc: ArrayRef = Arc::new(
+            TimestampSecondArray::from(vec![Some(1_715_781_600), None]).with_timezone("+02:00"),
+        );
+        let ret = evaluate(ScalarFunction::DateTrunc, &[string("day", 2), utc], 2)?;
+        assert_eq!(
+            ret.as_primitive::<TimestampSecondType>(),
+            &TimestampSecondArray::from(vec![Some(1_715_724_000), None]).with_timezone("+02:00")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_date_part() -> Result<()> {
+        let values = timestamps(&["2024-05-19T13:45:30", "1970-01-02T00:00:00"])?;
+        let fields: ArrayRef = Arc::new(StringArray::from(vec!["dow", "epoch"]));
+        let ret = evaluate(ScalarFunction::DatePart, &[fields, values.clone()], 2)?;
+        assert_eq!(
+            ret.as_primitive::<Float64Type>(),
+            &Float64Array::from(vec![0.0, 86_400.0])
+        );
+
+        let micros = cast(&values, &DataType::Timestamp(TimeUnit::Microsecond, None))?;
+        let micros = micros
+            .as_primitive::<TimestampMicrosecondType>()
+            .unary::<_, TimestampMicrosecondType>(|v| v + 250_000);
+        let ret = evaluate(
+            ScalarFunction::DatePart,
+            &[string("second", 2), Arc::new(micros)],
+            2,
+        )?;
+        assert_eq!(
+            ret.as_primitive::<Float64Type>(),
+            &Float64Array::from(vec![30.25, 0.25])
+        );
+
+        let dates: ArrayRef = Arc::new(Date32Array::from(vec![Some(19_723), None]));
+        let ret = evaluate(ScalarFunction::DatePart, &[string("doy", 2), dates], 2)?;
+        assert_eq!(
+            ret.as_primitive::<Float64Type>(),
+            &Float64Array::from(vec![Some(1.0), None])
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_to_timestamp() -> Result<()> {
+        let values: ArrayRef = Arc::new(StringArray::from(vec![Some("2024-01-02 03:04:05"), None]));
+        let ret = evaluate(ScalarFunction::ToTimestamp, &[values], 2)?;
+        assert_eq!(to_strings(&ret)?, vec!["2024-01-02T03:04:05", "NULL"]);
+
+        let values = string("02/01/2024", 1);
+        let ret = evaluate(
+            ScalarFunction::ToTimestamp,
+            &[values.clone(), string("%d/%m/%Y", 1)],
+            1,
+        )?;
+        assert_eq!(to_strings(&ret)?, vec!["2024-01-02T00:00:00"]);
+
+        let err = evaluate(
+            ScalarFunction::ToTimestamp,
+            &[values.clone(), string("%Y-%m-%d", 1)],
+            1,
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "execution error: cannot parse '02/01/2024' as a timestamp with format '%Y-%m-%d'"
+        );
+        assert!(matches!(
+            evaluate(ScalarFunction::ToTimestamp, &[values], 1),
+            Err(DBError::Execution(_))
+        ));
+        Ok(())
+    }
+}
@@ -0,0 +1,67 @@
+use std::sync::Arc;
+
+use arrow::array::RecordBatch;
+
+use super::{ColumnarValue, PhysicalExpression, datetime, math, string};
+use crate::{
+    error::{Result, internal_err},
+    logical::expression::function::ScalarFunction,
+};
+
+/// Evaluates a [`ScalarFunction`] on whole arrays. The regular expression
+/// functions are evaluated by [`RegexpExpr`](super::regexp::RegexpExpr)
+/// instead, which compiles their patterns.
+#[derive(Debug)]
+pub struct ScalarFunctionExpr {
+    fun: ScalarFunction,
+    args: Vec<Arc<PhysicalExpression>>,
+}
+
+impl ScalarFunctionExpr {
+    pub fn new(fun: ScalarFunction, args: Vec<Arc<PhysicalExpression>>) -> Self {
+        Self { fun, args }
+    }
+
+    pub fn evalate(&self, batch: &RecordBatch) -> Result<ColumnarValue> {
+        let num_rows = batch.num_rows();
+        let args = self
+            .args
+            .iter()
+            .map(|e| e.evalate(batch)?.into_array(num_rows))
+            .collect::<Result<Vec<_>>>()?;
+        let result = match self.fun {
+            fun if fun.is_regexp() => {
+                return internal_err!("{} must be planned as a regular expression", fun);
+            }
+            ScalarFunction::Abs
+            | ScalarFunction::Ceil
+            | ScalarFunction::Floor
+            | ScalarFunction::Round
+            | ScalarFunction::Trunc
+            | ScalarFunction::Sign
+            | ScalarFunction::Sqrt
+            | ScalarFunction::Power
+            | ScalarFunction::Exp
+            | ScalarFunction::Ln
+            | ScalarFunction::Log10
+            | ScalarFunction::Sin
+            | ScalarFunction::Cos
+            | ScalarFunction::Tan
+            | ScalarFunction::Asin
+            | ScalarFunction::Acos
+            | ScalarFunction::Atan
+            | ScalarFunction::Atan2
+            | ScalarFunction::Pi
+            | ScalarFunction::Greatest
+            | ScalarFunction::Least
+            | ScalarFunction::Random => math::evaluate(self.fun, &args, num_rows)?,
+            ScalarFunction::DateTrunc
+            | ScalarFunction::DatePart
+            | ScalarFunction::Now
+            | ScalarFunction::CurrentDate
+            | ScalarFunction::ToTimestamp => datetime::evaluate(self.fun, &args, num_rows)?,
+            _ => string::evaluate(self.fun, &args)?,
+        };
+        Ok(ColumnarValue::Array(result))
+    }
+}
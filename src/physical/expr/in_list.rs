@@ -0,0 +1,176 @@
+use std::{collections::HashSet, sync::Arc};
+
+use arrow::{
+    array::{Array, ArrayRef, BooleanArray, RecordBatch, new_empty_array},
+    compute::{
+        cast, concat,
+        kernels::{boolean::not, boolean::or_kleene, cmp::eq},
+    },
+    row::{RowConverter, SortField},
+};
+use arrow_schema::DataType;
+
+use super::{ColumnarValue, PhysicalExpression};
+use crate::{datatypes::values::ScalarValue, error::Result};
+
+/// Evaluates `expr [NOT] IN (list)` with SQL semantics: a value that matches
+/// no item is NULL rather than false if it is NULL itself or any item is.
+#[derive(Debug)]
+pub struct InListExpr {
+    expr: Arc<PhysicalExpression>,
+    list: InListValues,
+    negated: bool,
+}
+
+#[derive(Debug)]
+enum InListValues {
+    /// Items compared with the value one by one.
+    Exprs(Vec<Arc<PhysicalExpression>>),
+    /// Constant items, looked up in a hash set so long lists cost the same
+    /// per row as short ones.
+    Set(InListSet),
+}
+
+/// The items of a constant list, encoded in the Arrow row format so values
+/// of any type can be hashed.
+#[derive(Debug)]
+struct InListSet {
+    converter: RowConverter,
+    values: HashSet<Box<[u8]>>,
+    data_type: DataType,
+    has_null: bool,
+}
+
+impl InListExpr {
+    pub fn new(
+        expr: Arc<PhysicalExpression>,
+        list: Vec<Arc<PhysicalExpression>>,
+        negated: bool,
+    ) -> Self {
+        Self {
+            expr,
+            list: InListValues::Exprs(list),
+            negated,
+        }
+    }
+
+    /// Creates an `IN` over constant `values`, which are compared as
+    /// `data_type`, the type of `expr`.
+    pub fn try_new_with_values(
+        expr: Arc<PhysicalExpression>,
+        values: &[ScalarValue],
+        data_type: DataType,
+        negated: bool,
+    ) -> Result<Self> {
+        let arrays = values
+            .iter()
+            .map(|v| cast(&v.to_array(1), &data_type))
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        let arrays = arrays.iter().map(|a| a.as_ref()).collect::<Vec<_>>();
+        let array = if arrays.is_empty() {
+            new_empty_array(&data_type)
+        } else {
+            concat(&arrays)?
+        };
+
+        let converter = RowConverter::new(vec![SortField::new(data_type.clone())])?;
+        let rows = converter.convert_columns(std::slice::from_ref(&array))?;
+        let values = (0..array.len())
+            .filter(|&i| array.is_valid(i))
+            .map(|i| rows.row(i).as_ref().into())
+            .collect();
+        Ok(Self {
+            expr,
+            list: InListValues::Set(InListSet {
+                converter,
+                values,
+                data_type,
+                has_null: array.null_count() > 0,
+            }),
+            negated,
+        })
+    }
+
+    pub fn evalate(&self, batch: &RecordBatch) -> Result<ColumnarValue> {
+        let num_rows = batch.num_rows();
+        let value = self.expr.evalate(batch)?.into_array(num_rows)?;
+        let found = match &self.list {
+            InListValues::Exprs(list) => {
+                // OR with three-valued logic gives the NULL semantics of IN.
+                let mut found = BooleanArray::from(vec![false; num_rows]);
+                for item in list {
+                    let item = item.evalate(batch)?.into_array(num_rows)?;
+                    found = or_kleene(&found, &eq(&value, &item)?)?;
+                }
+                found
+            }
+            InListValues::Set(set) => set.contains(&value)?,
+        };
+        let result = if self.negated { not(&found)? } else { found };
+        Ok(ColumnarValue::Array(Arc::new(result)))
+    }
+}
+
+impl InListSet {
+    fn contains(&self, value: &ArrayRef) -> Result<BooleanArray> {
+        let value = cast(value, &self.data_type)?;
+        let rows = self
+            .converter
+            .convert_columns(std::slice::from_ref(&value))?;
+        Ok((0..value.len())
+            .map(|i| {
+                if value.is_null(i) {
+                    None
+                } else if self.values.contains(rows.row(i).as_ref()) {
+                    Some(true)
+                } else if self.has_null {
+                    None
+                } else {
+                    Some(false)
+                }
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::array::{AsArray, record_batch};
+
+    use super::*;
+    use crate::physical::expr::{column::ColumnExpr, literal::LiteralExpr};
+
+    #[test]
+    fn test_in_list() -> Result<()> {
+        let batch = record_batch!(("a", Int32, [Some(1), Some(2), None]))?;
+        let column = || Arc::new(PhysicalExpression::Column(ColumnExpr::new("a", 0)));
+        let evaluate = |expr: InListExpr| -> Result<BooleanArray> {
+            let array = expr.evalate(&batch)?.into_array(batch.num_rows())?;
+            Ok(array.as_boolean().clone())
+        };
+        let values = |values: &[ScalarValue], negated| {
+            InListExpr::try_new_with_values(column(), values, DataType::Int32, negated)
+        };
+        let exprs = |values: &[ScalarValue], negated| {
+            let list = values
+                .iter()
+                .map(|v| Arc::new(PhysicalExpression::Literal(LiteralExpr::new(v.clone()))))
+                .collect();
+            InListExpr::new(column(), list, negated)
+        };
+
+        let list = [ScalarValue::Int32(Some(1)), ScalarValue::Int32(Some(3))];
+        let with_null = [ScalarValue::Int32(Some(1)), ScalarValue::Int32(None)];
+        for (list, negated, expected) in [
+            (&list, false, vec![Some(true), Some(false), None]),
+            (&list, true, vec![Some(false), Some(true), None]),
+            (&with_null, false, vec![Some(true), None, None]),
+            (&with_null, true, vec![Some(false), None, None]),
+        ] {
+            let expected = BooleanArray::from(expected);
+            assert_eq!(evaluate(values(list, negated)?)?, expected);
+            assert_eq!(evaluate(exprs(list, negated))?, expected);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,168 @@
+use std::sync::Arc;
+
+use arrow::{
+    array::{Array, ArrayRef, AsArray, RecordBatch, Scalar, StringArray},
+    compute::{
+        cast,
+        kernels::comparison::{ilike, like, nilike, nlike},
+    },
+};
+use arrow_schema::DataType;
+
+use super::{ColumnarValue, PhysicalExpression, binary::BinaryExpr};
+use crate::{
+    datatypes::values::ScalarValue,
+    error::{Result, exec_err},
+};
+
+/// Evaluates `LIKE` and `ILIKE` with Arrow's string kernels, which use `\`
+/// as the escape character. Patterns with another escape character are
+/// rewritten to use `\` first.
+#[derive(Debug)]
+pub struct LikeExpr {
+    negated: bool,
+    case_insensitive: bool,
+    expr: Arc<PhysicalExpression>,
+    pattern: Arc<PhysicalExpression>,
+    escape_char: Option<char>,
+}
+
+impl LikeExpr {
+    pub fn new(
+        negated: bool,
+        case_insensitive: bool,
+        expr: Arc<PhysicalExpression>,
+        pattern: Arc<PhysicalExpression>,
+        escape_char: Option<char>,
+    ) -> Self {
+        Self {
+            negated,
+            case_insensitive,
+            expr,
+            pattern,
+            escape_char,
+        }
+    }
+
+    pub fn evalate(&self, batch: &RecordBatch) -> Result<ColumnarValue> {
+        let value = self.expr.evalate(batch)?;
+        let pattern = match self.pattern.evalate(batch)? {
+            ColumnarValue::Array(array) => ColumnarValue::Array(self.prepare_pattern(&array)?),
+            ColumnarValue::Scalar(value) => {
+                let array = self.prepare_pattern(&value.to_array(1))?;
+                ColumnarValue::Scalar(ScalarValue::try_from_array(array.as_ref(), 0)?)
+            }
+        };
+        let f = match (self.negated, self.case_insensitive) {
+            (false, false) => like,
+            (true, false) => nlike,
+            (false, true) => ilike,
+            (true, true) => nilike,
+        };
+        match (&value, &pattern) {
+            // The pattern of a large string column must be a large string too,
+            // which `ScalarValue` cannot hold.
+            (ColumnarValue::Array(array), ColumnarValue::Scalar(pattern))
+                if array.data_type() != &pattern.data_type() =>
+            {
+                let pattern = cast(&pattern.to_array(1), array.data_type())?;
+                Ok(ColumnarValue::Array(Arc::new(f(
+                    array,
+                    &Scalar::new(pattern),
+                )?)))
+            }
+            (ColumnarValue::Array(array), ColumnarValue::Array(pattern))
+                if array.data_type() != pattern.data_type() =>
+            {
+                let pattern = cast(pattern, array.data_type())?;
+                Ok(ColumnarValue::Array(Arc::new(f(array, &pattern)?)))
+            }
+            _ => BinaryExpr::apply_cmp(&value, &pattern, f),
+        }
+    }
+
+    fn prepare_pattern(&self, pattern: &ArrayRef) -> Result<ArrayRef> {
+        match self.escape_char {
+            Some(escape_char) if escape_char != '\\' => {
+                let pattern = cast(pattern, &DataType::Utf8)?;
+                let escaped = pattern
+                    .as_string::<i32>()
+                    .iter()
+                    .map(|p| p.map(|p| escape_pattern(p, escape_char)).transpose())
+                    .collect::<Result<StringArray>>()?;
+                Ok(Arc::new(escaped))
+            }
+            _ => Ok(pattern.clone()),
+        }
+    }
+}
+
+/// Rewrites a `LIKE` pattern using `escape_char` into one using `\`.
+fn escape_pattern(pattern: &str, escape_char: char) -> Result<String> {
+    let mut escaped = String::with_capacity(pattern.len());
+    let mut chars = pattern.chars();
+    while let Some(c) = chars.next() {
+        if c == escape_char {
+            let Some(next) = chars.next() else {
+                return exec_err!("LIKE pattern '{}' ends with the escape character", pattern);
+            };
+            escaped.push('\\');
+            escaped.push(next);
+        } else if c == '\\' {
+            escaped.push_str("\\\\");
+        } else {
+            escaped.push(c);
+        }
+    }
+    Ok(escaped)
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::array::{BooleanArray, LargeStringArray, record_batch};
+    use arrow_schema::{Field, Schema};
+
+    use super::*;
+    use crate::physical::expr::{column::ColumnExpr, literal::LiteralExpr};
+
+    fn evaluate(
+        batch: &RecordBatch,
+        pattern: &str,
+        escape_char: Option<char>,
+        case_insensitive: bool,
+    ) -> Result<BooleanArray> {
+        let column = Arc::new(PhysicalExpression::Column(ColumnExpr::new("a", 0)));
+        let pattern = ScalarValue::String(Some(pattern.to_string()));
+        let pattern = Arc::new(PhysicalExpression::Literal(LiteralExpr::new(pattern)));
+        let expr = LikeExpr::new(false, case_insensitive, column, pattern, escape_char);
+        let array = expr.evalate(batch)?.into_array(batch.num_rows())?;
+        Ok(array.as_boolean().clone())
+    }
+
+    #[test]
+    fn test_like() -> Result<()> {
+        let batch = record_batch!(("a", Utf8, [Some("50%"), Some("500"), Some("Abc"), None]))?;
+        assert_eq!(
+            evaluate(&batch, "50!%", Some('!'), false)?,
+            BooleanArray::from(vec![Some(true), Some(false), Some(false), None])
+        );
+        assert_eq!(
+            evaluate(&batch, "50%", None, false)?,
+            BooleanArray::from(vec![Some(true), Some(true), Some(false), None])
+        );
+        assert_eq!(
+            evaluate(&batch, "a_c", None, true)?,
+            BooleanArray::from(vec![Some(false), Some(false), Some(true), None])
+        );
+        assert!(evaluate(&batch, "50!", Some('!'), false).is_err());
+
+        let schema = Schema::new(vec![Field::new("a", DataType::LargeUtf8, true)]);
+        let array = LargeStringArray::from(vec!["error: disk full", "ok"]);
+        let batch = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(array)])?;
+        assert_eq!(
+            evaluate(&batch, "error:%", None, false)?,
+            BooleanArray::from(vec![true, false])
+        );
+        Ok(())
+    }
+}
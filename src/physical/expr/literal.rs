@@ -14,6 +14,10 @@ impl LiteralExpr {
         Self { value }
     }
 
+    pub fn value(&self) -> &ScalarValue {
+        &self.value
+    }
+
     pub fn evalate(&self, _batch: &RecordBatch) -> Result<ColumnarValue> {
         Ok(ColumnarValue::Scalar(self.value.clone()))
     }
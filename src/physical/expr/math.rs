@@ -0,0 +1,368 @@
+use std::{
+    f64::consts::PI,
+    hash::{BuildHasher, Hasher, RandomState},
+    sync::Arc,
+};
+
+use arrow::{
+    array::{
+        Array, ArrayRef, ArrowNativeTypeOp, AsArray, Float64Array, Int64Array, PrimitiveArray,
+    },
+    compute::{
+        binary, cast,
+        kernels::{
+            boolean::{is_null, or},
+            cmp::{gt, lt},
+            zip::zip,
+        },
+        try_binary,
+    },
+    datatypes::{
+        ArrowPrimitiveType, Float64Type, Int8Type, Int16Type, Int32Type, Int64Type, UInt8Type,
+        UInt16Type, UInt32Type, UInt64Type,
+    },
+};
+use arrow_schema::{ArrowError, DataType};
+
+use super::null_as_false;
+use crate::{
+    error::{DBError, Result, internal_err},
+    logical::expression::function::ScalarFunction,
+};
+
+/// Applies the generic function `$f` to the integer array `$values`, passing
+/// `$args` along, and returns the result as an [`ArrayRef`].
+macro_rules! integer_kernel {
+    ($values:expr, $f:ident $(, $arg:expr)*) => {
+        match $values.data_type() {
+            DataType::Int8 => Arc::new($f($values.as_primitive::<Int8Type>() $(, $arg)*)?) as ArrayRef,
+            DataType::Int16 => Arc::new($f($values.as_primitive::<Int16Type>() $(, $arg)*)?),
+            DataType::Int32 => Arc::new($f($values.as_primitive::<Int32Type>() $(, $arg)*)?),
+            DataType::Int64 => Arc::new($f($values.as_primitive::<Int64Type>() $(, $arg)*)?),
+            DataType::UInt8 => Arc::new($f($values.as_primitive::<UInt8Type>() $(, $arg)*)?),
+            DataType::UInt16 => Arc::new($f($values.as_primitive::<UInt16Type>() $(, $arg)*)?),
+            DataType::UInt32 => Arc::new($f($values.as_primitive::<UInt32Type>() $(, $arg)*)?),
+            DataType::UInt64 => Arc::new($f($values.as_primitive::<UInt64Type>() $(, $arg)*)?),
+            other => return internal_err!("expected an integer, got {}", other),
+        }
+    };
+}
+
+/// Evaluates the math function `fun` on the arrays of its arguments, which
+/// have been cast to the types its signature asks for. NULL arguments yield
+/// NULL, except for `greatest` and `least`, which skip them.
+pub(crate) fn evaluate(
+    fun: ScalarFunction,
+    args: &[ArrayRef],
+    num_rows: usize,
+) -> Result<ArrayRef> {
+    let result = match fun {
+        ScalarFunction::Pi => Ok(Arc::new(Float64Array::from_value(PI, num_rows)) as ArrayRef),
+        ScalarFunction::Random => Ok(Arc::new(random(num_rows)) as ArrayRef),
+        ScalarFunction::Greatest => extreme(args, true),
+        ScalarFunction::Least => extreme(args, false),
+        ScalarFunction::Sqrt => float_unary(&args[0], f64::sqrt),
+        ScalarFunction::Exp => float_unary(&args[0], f64::exp),
+        ScalarFunction::Ln => float_unary(&args[0], f64::ln),
+        ScalarFunction::Log10 => float_unary(&args[0], f64::log10),
+        ScalarFunction::Sin => float_unary(&args[0], f64::sin),
+        ScalarFunction::Cos => float_unary(&args[0], f64::cos),
+        ScalarFunction::Tan => float_unary(&args[0], f64::tan),
+        ScalarFunction::Asin => float_unary(&args[0], f64::asin),
+        ScalarFunction::Acos => float_unary(&args[0], f64::acos),
+        ScalarFunction::Atan => float_unary(&args[0], f64::atan),
+        ScalarFunction::Power => float_binary(&args[0], &args[1], f64::powf),
+        ScalarFunction::Atan2 => float_binary(&args[0], &args[1], f64::atan2),
+        ScalarFunction::Abs
+        | ScalarFunction::Ceil
+        | ScalarFunction::Floor
+        | ScalarFunction::Round
+        | ScalarFunction::Trunc
+        | ScalarFunction::Sign => numeric(fun, args),
+        other => internal_err!("{} is not a math function", other),
+    };
+    result.map_err(|e| match e {
+        DBError::ArrowError(ArrowError::ArithmeticOverflow(msg)) => {
+            DBError::Execution(format!("integer overflow: {}", msg))
+        }
+        e => e,
+    })
+}
+
+/// Evaluates a function whose result has the type of its first argument.
+/// Floating point values are computed as Float64.
+fn numeric(fun: ScalarFunction, args: &[ArrayRef]) -> Result<ArrayRef> {
+    let values = &args[0];
+    let digits = match args.get(1) {
+        Some(digits) => cast(digits, &DataType::Int64)?
+            .as_primitive::<Int64Type>()
+            .clone(),
+        None => Int64Array::from_value(0, values.len()),
+    };
+    if values.data_type().is_integer() {
+        return Ok(match fun {
+            ScalarFunction::Abs => integer_kernel!(values, abs),
+            ScalarFunction::Sign => integer_kernel!(values, sign),
+            ScalarFunction::Round => integer_kernel!(values, round_integers, &digits, false),
+            ScalarFunction::Trunc => integer_kernel!(values, round_integers, &digits, true),
+            // Integers are already integral.
+            _ => values.clone(),
+        });
+    }
+
+    let result = match fun {
+        ScalarFunction::Abs => float_unary(values, f64::abs)?,
+        ScalarFunction::Ceil => float_unary(values, f64::ceil)?,
+        ScalarFunction::Floor => float_unary(values, f64::floor)?,
+        ScalarFunction::Sign => float_unary(values, |v| {
+            // Keeps zeros and NaN, unlike `f64::signum`.
+            if v > 0.0 {
+                1.0
+            } else if v < 0.0 {
+                -1.0
+            } else {
+                v
+            }
+        })?,
+        ScalarFunction::Round => round_floats(values, &digits, f64::round)?,
+        ScalarFunction::Trunc => round_floats(values, &digits, f64::trunc)?,
+        other => return internal_err!("{} is not a numeric function", other),
+    };
+    Ok(cast(&result, values.data_type())?)
+}
+
+fn abs<T: ArrowPrimitiveType>(values: &PrimitiveArray<T>) -> Result<PrimitiveArray<T>> {
+    Ok(values.try_unary(|v| {
+        if v.is_lt(T::Native::ZERO) {
+            v.neg_checked()
+        } else {
+            Ok(v)
+        }
+    })?)
+}
+
+fn sign<T: ArrowPrimitiveType>(values: &PrimitiveArray<T>) -> Result<PrimitiveArray<T>> {
+    Ok(values.unary(|v| {
+        if v.is_lt(T::Native::ZERO) {
+            T::Native::ZERO.sub_wrapping(T::Native::ONE)
+        } else if v.is_gt(T::Native::ZERO) {
+            T::Native::ONE
+        } else {
+            T::Native::ZERO
+        }
+    }))
+}
+
+/// Rounds integers to a negative number of `digits`, e.g. 1250 to 1300 for
+/// -2. Other digits leave the values unchanged.
+fn round_integers<T>(
+    values: &PrimitiveArray<T>,
+    digits: &Int64Array,
+    trunc: bool,
+) -> Result<PrimitiveArray<T>>
+where
+    T: ArrowPrimitiveType,
+    T::Native: Into<i128> + TryFrom<i128>,
+{
+    Ok(try_binary(values, digits, |v, digits| {
+        if digits >= 0 {
+            return Ok(v);
+        }
+        // Every integer is smaller than half of a factor too large for i128.
+        let rounded = match 10_i128.checked_pow(digits.unsigned_abs().min(u32::MAX as u64) as u32) {
+            None => 0,
+            Some(factor) => {
+                let v: i128 = v.into();
+                let truncated = v / factor * factor;
+                let remainder = (v - truncated).abs();
+                if !trunc && remainder >= factor - remainder {
+                    truncated + v.signum() * factor
+                } else {
+                    truncated
+                }
+            }
+        };
+        T::Native::try_from(rounded).map_err(|_| {
+            ArrowError::ArithmeticOverflow(format!(
+                "{} does not fit into {}",
+                rounded,
+                T::DATA_TYPE
+            ))
+        })
+    })?)
+}
+
+/// Rounds floating point values to `digits` decimal places with `f`.
+fn round_floats(values: &ArrayRef, digits: &Int64Array, f: fn(f64) -> f64) -> Result<ArrayRef> {
+    let values = cast(values, &DataType::Float64)?;
+    let result: Float64Array =
+        binary(values.as_primitive::<Float64Type>(), digits, |v, digits| {
+            let factor = 10_f64.powi(digits.clamp(-300, 300) as i32);
+            let scaled = v * factor;
+            // Values too large to scale have no digits to round away.
+            if scaled.is_finite() {
+                f(scaled) / factor
+            } else {
+                v
+            }
+        })?;
+    Ok(Arc::new(result))
+}
+
+fn float_unary(values: &ArrayRef, f: impl Fn(f64) -> f64) -> Result<ArrayRef> {
+    let values = cast(values, &DataType::Float64)?;
+    let result: Float64Array = values.as_primitive::<Float64Type>().unary(f);
+    Ok(Arc::new(result))
+}
+
+fn float_binary(lhs: &ArrayRef, rhs: &ArrayRef, f: impl Fn(f64, f64) -> f64) -> Result<ArrayRef> {
+    let lhs = cast(lhs, &DataType::Float64)?;
+    let rhs = cast(rhs, &DataType::Float64)?;
+    let result: Float64Array = binary(
+        lhs.as_primitive::<Float64Type>(),
+        rhs.as_primitive::<Float64Type>(),
+        f,
+    )?;
+    Ok(Arc::new(result))
+}
+
+/// Returns the greatest or least non-NULL value of every row.
+fn extreme(args: &[ArrayRef], greatest: bool) -> Result<ArrayRef> {
+    let mut result = args[0].clone();
+    if result.data_type() == &DataType::Null {
+        return Ok(result);
+    }
+    for arg in &args[1..] {
+        let better = if greatest {
+            gt(arg, &result)?
+        } else {
+            lt(arg, &result)?
+        };
+        let replace = or(&null_as_false(&better), &is_null(&result)?)?;
+        result = zip(&replace, arg, &result)?;
+    }
+    Ok(result)
+}
+
+/// Returns `n` values uniformly distributed in [0, 1). The generator is
+/// seeded from the randomly keyed hasher of the standard library, so every
+/// call yields different values.
+fn random(n: usize) -> Float64Array {
+    let mut state = RandomState::new().build_hasher().finish();
+    Float64Array::from_iter_values((0..n).map(|_| {
+        // SplitMix64, keeping the 53 bits a Float64 can represent exactly.
+        state = state.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^= z >> 31;
+        (z >> 11) as f64 / (1_u64 << 53) as f64
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::array::{Float32Array, Int8Array, record_batch};
+
+    use super::*;
+
+    fn ints(values: &[Option<i64>]) -> ArrayRef {
+        Arc::new(Int64Array::from(values.to_vec()))
+    }
+
+    #[test]
+    fn test_numeric_functions() -> Result<()> {
+        let batch = record_batch!(
+            ("i", Int8, [Some(-124), Some(0), Some(45), None]),
+            ("f", Float32, [Some(-2.5), Some(0.0), Some(1.25), None])
+        )?;
+        let (i, f) = (batch.column(0).clone(), batch.column(1).clone());
+
+        let ret = evaluate(ScalarFunction::Abs, std::slice::from_ref(&i), 4)?;
+        assert_eq!(
+            ret.as_primitive::<Int8Type>(),
+            &Int8Array::from(vec![Some(124), Some(0), Some(45), None])
+        );
+        let ret = evaluate(ScalarFunction::Sign, std::slice::from_ref(&f), 4)?;
+        assert_eq!(
+            ret.as_primitive::<arrow::datatypes::Float32Type>(),
+            &Float32Array::from(vec![Some(-1.0), Some(0.0), Some(1.0), None])
+        );
+        let ret = evaluate(ScalarFunction::Round, std::slice::from_ref(&f), 4)?;
+        assert_eq!(
+            ret.as_primitive::<arrow::datatypes::Float32Type>(),
+            &Float32Array::from(vec![Some(-3.0), Some(0.0), Some(1.0), None])
+        );
+        let ret = evaluate(
+            ScalarFunction::Trunc,
+            &[f.clone(), ints(&[Some(0), None, Some(1), Some(1)])],
+            4,
+        )?;
+        assert_eq!(
+            ret.as_primitive::<arrow::datatypes::Float32Type>(),
+            &Float32Array::from(vec![Some(-2.0), None, Some(1.2), None])
+        );
+        let ret = evaluate(ScalarFunction::Round, &[i.clone(), ints(&[Some(-1); 4])], 4)?;
+        assert_eq!(
+            ret.as_primitive::<Int8Type>(),
+            &Int8Array::from(vec![Some(-120), Some(0), Some(50), None])
+        );
+        let ret = evaluate(ScalarFunction::Ceil, std::slice::from_ref(&f), 4)?;
+        assert_eq!(
+            ret.as_primitive::<arrow::datatypes::Float32Type>(),
+            &Float32Array::from(vec![Some(-2.0), Some(0.0), Some(2.0), None])
+        );
+
+        let min: ArrayRef = Arc::new(Int8Array::from(vec![-128]));
+        let err = evaluate(ScalarFunction::Abs, &[min], 1).unwrap_err();
+        assert!(matches!(err, DBError::Execution(_)), "{}", err);
+        let min: ArrayRef = Arc::new(Int8Array::from(vec![-125]));
+        let err = evaluate(ScalarFunction::Round, &[min, ints(&[Some(-1)])], 1).unwrap_err();
+        assert!(matches!(err, DBError::Execution(_)), "{}", err);
+        Ok(())
+    }
+
+    #[test]
+    fn test_float_functions() -> Result<()> {
+        let x: ArrayRef = Arc::new(Float64Array::from(vec![Some(4.0), Some(-1.0), None]));
+        let ret = evaluate(ScalarFunction::Sqrt, std::slice::from_ref(&x), 3)?;
+        let ret = ret.as_primitive::<Float64Type>();
+        assert_eq!(ret.value(0), 2.0);
+        assert!(ret.value(1).is_nan());
+        assert!(ret.is_null(2));
+
+        let y: ArrayRef = Arc::new(Float64Array::from(vec![Some(0.5), Some(3.0), Some(1.0)]));
+        let ret = evaluate(ScalarFunction::Power, &[x.clone(), y], 3)?;
+        assert_eq!(
+            ret.as_primitive::<Float64Type>(),
+            &Float64Array::from(vec![Some(2.0), Some(-1.0), None])
+        );
+
+        let ret = evaluate(ScalarFunction::Random, &[], 1000)?;
+        let ret = ret.as_primitive::<Float64Type>();
+        assert_eq!(ret.len(), 1000);
+        assert!(ret.values().iter().all(|v| (0.0..1.0).contains(v)));
+        assert!(ret.values().iter().any(|v| *v != ret.value(0)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_greatest_least() -> Result<()> {
+        let args = [
+            ints(&[Some(1), None, Some(5), None]),
+            ints(&[Some(3), Some(2), None, None]),
+            ints(&[Some(2), Some(-1), Some(4), None]),
+        ];
+        let ret = evaluate(ScalarFunction::Greatest, &args, 4)?;
+        assert_eq!(
+            ret.as_primitive::<Int64Type>(),
+            &Int64Array::from(vec![Some(3), Some(2), Some(5), None])
+        );
+        let ret = evaluate(ScalarFunction::Least, &args, 4)?;
+        assert_eq!(
+            ret.as_primitive::<Int64Type>(),
+            &Int64Array::from(vec![Some(1), Some(-1), Some(4), None])
+        );
+        Ok(())
+    }
+}
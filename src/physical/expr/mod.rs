@@ -1,9 +1,29 @@
+pub mod accumulator;
+pub mod aggregate;
 pub mod binary;
+pub mod cast;
 pub mod column;
+pub mod conditional;
+pub mod datetime;
+pub mod function;
+pub mod in_list;
+pub mod like;
 pub mod literal;
+pub mod math;
+pub mod regexp;
+pub mod string;
+pub mod unary;
+pub mod window;
 
-use crate::{datatypes::values::ScalarValue, error::Result};
-use arrow::array::{ArrayRef, RecordBatch};
+use crate::{
+    datatypes::values::ScalarValue,
+    error::{Result, exec_err},
+};
+use arrow::{
+    array::{Array, ArrayRef, AsArray, BooleanArray, RecordBatch, UInt32Array},
+    compute::{prep_null_mask_filter, take},
+};
+use arrow_schema::DataType;
 
 #[derive(Debug)]
 pub enum ColumnarValue {
@@ -28,16 +48,68 @@ impl ColumnarValue {
 #[derive(Debug)]
 pub enum PhysicalExpression {
     Binary(binary::BinaryExpr),
+    Case(conditional::CaseExpr),
+    Cast(cast::CastExpr),
+    Coalesce(conditional::CoalesceExpr),
+    TryCast(cast::TryCastExpr),
     Column(column::ColumnExpr),
+    InList(in_list::InListExpr),
+    Like(like::LikeExpr),
     Literal(literal::LiteralExpr),
+    Regexp(regexp::RegexpExpr),
+    ScalarFunction(function::ScalarFunctionExpr),
+    Unary(unary::UnaryExpr),
 }
 
 impl PhysicalExpression {
     pub fn evalate(&self, batch: &RecordBatch) -> Result<ColumnarValue> {
         match self {
             PhysicalExpression::Binary(expr) => expr.evalate(batch),
+            PhysicalExpression::Case(expr) => expr.evalate(batch),
+            PhysicalExpression::Cast(expr) => expr.evalate(batch),
+            PhysicalExpression::Coalesce(expr) => expr.evalate(batch),
+            PhysicalExpression::TryCast(expr) => expr.evalate(batch),
             PhysicalExpression::Column(expr) => expr.evalate(batch),
+            PhysicalExpression::InList(expr) => expr.evalate(batch),
+            PhysicalExpression::Like(expr) => expr.evalate(batch),
             PhysicalExpression::Literal(expr) => expr.evalate(batch),
+            PhysicalExpression::Regexp(expr) => expr.evalate(batch),
+            PhysicalExpression::ScalarFunction(expr) => expr.evalate(batch),
+            PhysicalExpression::Unary(expr) => expr.evalate(batch),
         }
     }
 }
+
+/// Spreads `values`, which has a row for every row selected by `mask`, over
+/// all rows of `mask`. Rows outside of `mask` become NULL.
+pub(crate) fn scatter(mask: &BooleanArray, values: &dyn Array) -> Result<ArrayRef> {
+    let mut next = 0;
+    let indices = mask
+        .iter()
+        .map(|selected| {
+            selected.unwrap_or(false).then(|| {
+                next += 1;
+                next - 1
+            })
+        })
+        .collect::<UInt32Array>();
+    Ok(take(values, &indices, None)?)
+}
+
+/// Reads a boolean operand. A NULL-typed operand is all NULL.
+pub(crate) fn to_boolean_array(array: &ArrayRef) -> Result<BooleanArray> {
+    match array.data_type() {
+        DataType::Boolean => Ok(array.as_boolean().clone()),
+        DataType::Null => Ok(BooleanArray::new_null(array.len())),
+        other => exec_err!("expected a boolean operand, got {}", other),
+    }
+}
+
+/// Replaces the NULLs of `mask` with false.
+pub(crate) fn null_as_false(mask: &BooleanArray) -> BooleanArray {
+    if mask.null_count() == 0 {
+        mask.clone()
+    } else {
+        prep_null_mask_filter(mask)
+    }
+}
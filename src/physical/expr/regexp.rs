@@ -0,0 +1,361 @@
+use std::{collections::HashMap, sync::Arc};
+
+use arrow::{
+    array::{
+        Array, ArrayRef, AsArray, BooleanBuilder, GenericStringArray, GenericStringBuilder,
+        Int64Array, OffsetSizeTrait, RecordBatch, StringArray,
+    },
+    compute::cast,
+    datatypes::Int64Type,
+};
+use arrow_schema::DataType;
+use regex::Regex;
+
+use super::{ColumnarValue, PhysicalExpression};
+use crate::{
+    datatypes::values::ScalarValue,
+    error::{DBError, Result, exec_err, internal_err},
+    logical::expression::function::ScalarFunction,
+};
+
+/// The syntax of the patterns matched by a [`RegexpExpr`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PatternSyntax {
+    /// A regular expression as understood by the `regex` crate.
+    Regex,
+    /// A SQL `SIMILAR TO` pattern, which must match the whole value. The
+    /// escape character makes the next character match itself.
+    SimilarTo { escape_char: Option<char> },
+}
+
+/// A compiled pattern and whether every match is replaced, from the `g` flag.
+type CompiledPattern = (Regex, bool);
+
+/// Evaluates the regular expression functions. A pattern given as a literal
+/// is compiled once when the expression is created, other patterns once per
+/// distinct pattern in a batch.
+#[derive(Debug)]
+pub struct RegexpExpr {
+    fun: ScalarFunction,
+    args: Vec<Arc<PhysicalExpression>>,
+    syntax: PatternSyntax,
+    compiled: Option<CompiledPattern>,
+}
+
+impl RegexpExpr {
+    pub fn try_new(
+        fun: ScalarFunction,
+        args: Vec<Arc<PhysicalExpression>>,
+        syntax: PatternSyntax,
+    ) -> Result<Self> {
+        let mut expr = Self {
+            fun,
+            args,
+            syntax,
+            compiled: None,
+        };
+        let literal = |arg: Option<&Arc<PhysicalExpression>>| match arg.map(|e| e.as_ref()) {
+            Some(PhysicalExpression::Literal(e)) => match e.value() {
+                ScalarValue::String(Some(s)) => Some(Some(s.clone())),
+                _ => None,
+            },
+            Some(_) => None,
+            // No flags were given.
+            None => Some(None),
+        };
+        let pattern = literal(expr.args.get(1)).flatten();
+        let flags = expr
+            .flags_index()
+            .map_or(Some(None), |i| literal(expr.args.get(i)));
+        if let (Some(pattern), Some(flags)) = (pattern, flags) {
+            expr.compiled = Some(expr.compile(&pattern, flags.as_deref())?);
+        }
+        Ok(expr)
+    }
+
+    pub fn evalate(&self, batch: &RecordBatch) -> Result<ColumnarValue> {
+        let num_rows = batch.num_rows();
+        let args = self
+            .args
+            .iter()
+            .map(|e| e.evalate(batch)?.into_array(num_rows))
+            .collect::<Result<Vec<_>>>()?;
+        let result = match args[0].data_type() {
+            DataType::Utf8 => self.evaluate_strings(args[0].as_string::<i32>(), &args)?,
+            DataType::LargeUtf8 => self.evaluate_strings(args[0].as_string::<i64>(), &args)?,
+            other => return internal_err!("{} expects a string, got {}", self.fun, other),
+        };
+        Ok(ColumnarValue::Array(result))
+    }
+
+    fn evaluate_strings<O: OffsetSizeTrait>(
+        &self,
+        values: &GenericStringArray<O>,
+        args: &[ArrayRef],
+    ) -> Result<ArrayRef> {
+        let utf8 = |i: usize| -> Result<Option<ArrayRef>> {
+            args.get(i)
+                .map(|a| Ok(cast(a, &DataType::Utf8)?))
+                .transpose()
+        };
+        let flags = self.flags_index().map(utf8).transpose()?.flatten();
+        let patterns = utf8(1)?.expect("a pattern");
+        let mut patterns = Patterns {
+            expr: self,
+            patterns: patterns.as_string(),
+            flags: flags.as_ref().map(|f| f.as_string()),
+            cache: HashMap::new(),
+        };
+
+        Ok(match self.fun {
+            ScalarFunction::RegexpLike => {
+                let mut builder = BooleanBuilder::with_capacity(values.len());
+                for (i, value) in values.iter().enumerate() {
+                    match (value, patterns.get(i)?) {
+                        (Some(value), Some((regex, _))) => {
+                            builder.append_value(regex.is_match(value))
+                        }
+                        _ => builder.append_null(),
+                    }
+                }
+                Arc::new(builder.finish())
+            }
+            ScalarFunction::RegexpReplace => {
+                let replacements = utf8(2)?.expect("a replacement");
+                let replacements: &StringArray = replacements.as_string();
+                let mut builder = GenericStringBuilder::<O>::new();
+                for (i, value) in values.iter().enumerate() {
+                    let replacement = replacements.is_valid(i).then(|| replacements.value(i));
+                    match (value, patterns.get(i)?, replacement) {
+                        (Some(value), Some((regex, global)), Some(replacement)) => {
+                            let limit = if global { 0 } else { 1 };
+                            builder.append_value(regex.replacen(value, limit, replacement));
+                        }
+                        _ => builder.append_null(),
+                    }
+                }
+                Arc::new(builder.finish())
+            }
+            ScalarFunction::RegexpExtract => {
+                let groups = args
+                    .get(2)
+                    .map(|a| a.as_primitive::<Int64Type>().clone())
+                    .unwrap_or_else(|| Int64Array::from_value(1, values.len()));
+                let mut builder = GenericStringBuilder::<O>::new();
+                for (i, value) in values.iter().enumerate() {
+                    let group = groups.is_valid(i).then(|| groups.value(i));
+                    let (Some(value), Some((regex, _)), Some(group)) =
+                        (value, patterns.get(i)?, group)
+                    else {
+                        builder.append_null();
+                        continue;
+                    };
+                    if group < 0 || group as usize >= regex.captures_len() {
+                        return exec_err!(
+                            "regexp_extract group {} does not exist in pattern '{}'",
+                            group,
+                            regex
+                        );
+                    }
+                    let matched = regex
+                        .captures(value)
+                        .and_then(|captures| captures.get(group as usize));
+                    builder.append_option(matched.map(|m| m.as_str()));
+                }
+                Arc::new(builder.finish())
+            }
+            other => return internal_err!("{} is not a regular expression function", other),
+        })
+    }
+
+    /// Returns the position of the flags argument.
+    fn flags_index(&self) -> Option<usize> {
+        match self.fun {
+            ScalarFunction::RegexpLike => Some(2),
+            ScalarFunction::RegexpReplace => Some(3),
+            _ => None,
+        }
+    }
+
+    fn compile(&self, pattern: &str, flags: Option<&str>) -> Result<CompiledPattern> {
+        let mut inline_flags = String::new();
+        let mut global = false;
+        for flag in flags.unwrap_or_default().chars() {
+            match flag {
+                'i' | 'm' | 's' | 'x' => inline_flags.push(flag),
+                'g' if self.fun == ScalarFunction::RegexpReplace => global = true,
+                _ => return exec_err!("{} does not support the flag '{}'", self.fun, flag),
+            }
+        }
+        let pattern = match self.syntax {
+            PatternSyntax::Regex => pattern.to_string(),
+            PatternSyntax::SimilarTo { escape_char } => similar_to_regex(pattern, escape_char)?,
+        };
+        let pattern = if inline_flags.is_empty() {
+            pattern
+        } else {
+            format!("(?{}){}", inline_flags, pattern)
+        };
+        let regex = Regex::new(&pattern)
+            .map_err(|e| DBError::Execution(format!("invalid regular expression: {}", e)))?;
+        Ok((regex, global))
+    }
+}
+
+/// Resolves the compiled pattern of every row.
+struct Patterns<'a> {
+    expr: &'a RegexpExpr,
+    patterns: &'a StringArray,
+    flags: Option<&'a StringArray>,
+    cache: HashMap<(&'a str, Option<&'a str>), CompiledPattern>,
+}
+
+impl Patterns<'_> {
+    /// Returns `None` if the pattern or the flags of row `i` are NULL.
+    fn get(&mut self, i: usize) -> Result<Option<CompiledPattern>> {
+        if let Some(compiled) = &self.expr.compiled {
+            return Ok(Some(compiled.clone()));
+        }
+        if self.patterns.is_null(i) || self.flags.is_some_and(|f| f.is_null(i)) {
+            return Ok(None);
+        }
+        let key = (self.patterns.value(i), self.flags.map(|f| f.value(i)));
+        if let Some(compiled) = self.cache.get(&key) {
+            return Ok(Some(compiled.clone()));
+        }
+        let compiled = self.expr.compile(key.0, key.1)?;
+        self.cache.insert(key, compiled.clone());
+        Ok(Some(compiled))
+    }
+}
+
+/// Translates a `SIMILAR TO` pattern into a regular expression matching the
+/// whole value. `%` and `_` are the `LIKE` wildcards, while `|`, `*`, `+`,
+/// `?`, `{m,n}`, `()` and `[]` keep their regular expression meaning.
+fn similar_to_regex(pattern: &str, escape_char: Option<char>) -> Result<String> {
+    let escape_char = escape_char.unwrap_or('\\');
+    let mut regex = String::from("^(?:");
+    let mut chars = pattern.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            c if c == escape_char => {
+                let Some(next) = chars.next() else {
+                    return exec_err!(
+                        "SIMILAR TO pattern '{}' ends with the escape character",
+                        pattern
+                    );
+                };
+                regex.push_str(&regex::escape(&next.to_string()));
+            }
+            '%' => regex.push_str(".*"),
+            '_' => regex.push('.'),
+            '|' | '*' | '+' | '?' | '{' | '}' | '(' | ')' | '[' | ']' => regex.push(c),
+            c => regex.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    regex.push_str(")$");
+    Ok(regex)
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::array::{BooleanArray, record_batch};
+
+    use super::*;
+    use crate::physical::expr::{column::ColumnExpr, literal::LiteralExpr};
+
+    fn column(index: usize) -> Arc<PhysicalExpression> {
+        Arc::new(PhysicalExpression::Column(ColumnExpr::new("", index)))
+    }
+
+    fn string(s: &str) -> Arc<PhysicalExpression> {
+        let value = ScalarValue::String(Some(s.to_string()));
+        Arc::new(PhysicalExpression::Literal(LiteralExpr::new(value)))
+    }
+
+    fn evaluate(
+        fun: ScalarFunction,
+        args: Vec<Arc<PhysicalExpression>>,
+        syntax: PatternSyntax,
+        batch: &RecordBatch,
+    ) -> Result<ArrayRef> {
+        let expr = RegexpExpr::try_new(fun, args, syntax)?;
+        expr.evalate(batch)?.into_array(batch.num_rows())
+    }
+
+    #[test]
+    fn test_regexp() -> Result<()> {
+        let batch = record_batch!(
+            ("msg", Utf8, [Some("GET /a 200"), Some("POST /b 500"), None]),
+            ("pattern", Utf8, [Some("^GET"), Some("^get"), Some("x")])
+        )?;
+
+        let ret = evaluate(
+            ScalarFunction::RegexpLike,
+            vec![column(0), column(1), string("i")],
+            PatternSyntax::Regex,
+            &batch,
+        )?;
+        assert_eq!(
+            ret.as_boolean(),
+            &BooleanArray::from(vec![Some(true), Some(false), None])
+        );
+
+        let ret = evaluate(
+            ScalarFunction::RegexpReplace,
+            vec![column(0), string("[0-9]"), string("#"), string("g")],
+            PatternSyntax::Regex,
+            &batch,
+        )?;
+        assert_eq!(
+            ret.as_string::<i32>(),
+            &StringArray::from(vec![Some("GET /a ###"), Some("POST /b ###"), None])
+        );
+
+        let group = ScalarValue::Int64(Some(3));
+        let ret = evaluate(
+            ScalarFunction::RegexpExtract,
+            vec![
+                column(0),
+                string(r"(\w+) (/\w+)"),
+                Arc::new(PhysicalExpression::Literal(LiteralExpr::new(group))),
+            ],
+            PatternSyntax::Regex,
+            &batch,
+        );
+        assert!(ret.is_err(), "the pattern has two groups");
+        let ret = evaluate(
+            ScalarFunction::RegexpExtract,
+            vec![column(0), string(r"(\w+) (/\w+) 2\d+")],
+            PatternSyntax::Regex,
+            &batch,
+        )?;
+        assert_eq!(
+            ret.as_string::<i32>(),
+            &StringArray::from(vec![Some("GET"), None, None])
+        );
+
+        let ret = evaluate(
+            ScalarFunction::RegexpLike,
+            vec![column(0), string("(GET|PUT) %!_?2__")],
+            PatternSyntax::SimilarTo {
+                escape_char: Some('!'),
+            },
+            &batch,
+        )?;
+        assert_eq!(
+            ret.as_boolean(),
+            &BooleanArray::from(vec![Some(true), Some(false), None])
+        );
+
+        assert!(
+            RegexpExpr::try_new(
+                ScalarFunction::RegexpLike,
+                vec![column(0), string("(")],
+                PatternSyntax::Regex
+            )
+            .is_err()
+        );
+        Ok(())
+    }
+}
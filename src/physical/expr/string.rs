@@ -0,0 +1,363 @@
+use std::{fmt::Write, iter, sync::Arc};
+
+use arrow::{
+    array::{
+        Array, ArrayAccessor, ArrayRef, AsArray, BooleanArray, GenericStringArray, Int64Array,
+        OffsetSizeTrait, StringArray, StringBuilder,
+    },
+    compute::cast,
+    datatypes::Int64Type,
+};
+use arrow_schema::DataType;
+
+use crate::{
+    error::{DBError, Result, exec_err, internal_err},
+    logical::expression::function::ScalarFunction,
+};
+
+/// Evaluates the string function `fun` on the arrays of its arguments, which
+/// have been cast to the types its signature asks for.
+pub(crate) fn evaluate(fun: ScalarFunction, args: &[ArrayRef]) -> Result<ArrayRef> {
+    if fun == ScalarFunction::Concat {
+        return concat(args);
+    }
+    match args[0].data_type() {
+        DataType::Utf8 => evaluate_strings(fun, args[0].as_string::<i32>(), args),
+        DataType::LargeUtf8 => evaluate_strings(fun, args[0].as_string::<i64>(), args),
+        other => internal_err!("{} expects a string, got {}", fun, other),
+    }
+}
+
+fn evaluate_strings<O: OffsetSizeTrait>(
+    fun: ScalarFunction,
+    values: &GenericStringArray<O>,
+    args: &[ArrayRef],
+) -> Result<ArrayRef> {
+    match fun {
+        ScalarFunction::Upper => {
+            map_rows::<_, _, GenericStringArray<O>>(values, |_, v| Ok(Some(v.to_uppercase())))
+        }
+        ScalarFunction::Lower => {
+            map_rows::<_, _, GenericStringArray<O>>(values, |_, v| Ok(Some(v.to_lowercase())))
+        }
+        ScalarFunction::Trim | ScalarFunction::Ltrim | ScalarFunction::Rtrim => {
+            let characters = string_arg(args, 1, " ")?;
+            map_rows::<_, _, GenericStringArray<O>>(values, |i, v| {
+                let Some(characters) = at(&characters, i) else {
+                    return Ok(None);
+                };
+                let trimmed = |c: char| characters.contains(c);
+                Ok(Some(match fun {
+                    ScalarFunction::Trim => v.trim_matches(trimmed),
+                    ScalarFunction::Ltrim => v.trim_start_matches(trimmed),
+                    _ => v.trim_end_matches(trimmed),
+                }))
+            })
+        }
+        ScalarFunction::Length => {
+            map_rows::<_, _, Int64Array>(values, |_, v| Ok(Some(v.chars().count() as i64)))
+        }
+        ScalarFunction::Substr => {
+            let starts = int_arg(args, 1, 1)?;
+            let lengths = int_arg(args, 2, i64::MAX)?;
+            map_rows::<_, _, GenericStringArray<O>>(values, |i, v| {
+                let (Some(start), Some(length)) = (at(&starts, i), at(&lengths, i)) else {
+                    return Ok(None);
+                };
+                if length < 0 {
+                    return exec_err!("substr length must not be negative, got {}", length);
+                }
+                // Positions before the first character count towards the
+                // length, so `substr('abc', 0, 2)` is 'a'.
+                let begin = start.saturating_sub(1);
+                let end = begin.saturating_add(length).max(0) as usize;
+                let begin = begin.max(0) as usize;
+                Ok(Some(
+                    v.chars()
+                        .skip(begin)
+                        .take(end.saturating_sub(begin))
+                        .collect::<String>(),
+                ))
+            })
+        }
+        ScalarFunction::Replace => {
+            let from = string_arg(args, 1, "")?;
+            let to = string_arg(args, 2, "")?;
+            map_rows::<_, _, GenericStringArray<O>>(values, |i, v| {
+                let (Some(from), Some(to)) = (at(&from, i), at(&to, i)) else {
+                    return Ok(None);
+                };
+                // An empty pattern would match between every character.
+                Ok(Some(if from.is_empty() {
+                    v.to_string()
+                } else {
+                    v.replace(from, to)
+                }))
+            })
+        }
+        ScalarFunction::SplitPart => {
+            let delimiters = string_arg(args, 1, "")?;
+            let fields = int_arg(args, 2, 1)?;
+            map_rows::<_, _, GenericStringArray<O>>(values, |i, v| {
+                let (Some(delimiter), Some(n)) = (at(&delimiters, i), at(&fields, i)) else {
+                    return Ok(None);
+                };
+                let parts = if delimiter.is_empty() {
+                    vec![v]
+                } else {
+                    v.split(delimiter).collect()
+                };
+                let index = match n {
+                    0 => return exec_err!("split_part field position must not be zero"),
+                    n if n > 0 => n - 1,
+                    n => parts.len() as i64 + n,
+                };
+                let part = usize::try_from(index).ok().and_then(|i| parts.get(i));
+                Ok(Some(part.copied().unwrap_or_default()))
+            })
+        }
+        ScalarFunction::StartsWith => {
+            let prefixes = string_arg(args, 1, "")?;
+            map_rows::<_, _, BooleanArray>(values, |i, v| {
+                Ok(at(&prefixes, i).map(|prefix| v.starts_with(prefix)))
+            })
+        }
+        ScalarFunction::Lpad | ScalarFunction::Rpad => {
+            let lengths = int_arg(args, 1, 0)?;
+            let fills = string_arg(args, 2, " ")?;
+            map_rows::<_, _, GenericStringArray<O>>(values, |i, v| {
+                let (Some(length), Some(fill)) = (at(&lengths, i), at(&fills, i)) else {
+                    return Ok(None);
+                };
+                let length = length.max(0) as usize;
+                let chars = v.chars().count();
+                if chars >= length {
+                    return Ok(Some(v.chars().take(length).collect::<String>()));
+                }
+                if fill.is_empty() {
+                    return Ok(Some(v.to_string()));
+                }
+                let padding = fill.chars().cycle().take(length - chars);
+                Ok(Some(if fun == ScalarFunction::Lpad {
+                    padding.chain(v.chars()).collect()
+                } else {
+                    v.chars().chain(padding).collect()
+                }))
+            })
+        }
+        ScalarFunction::Strpos => {
+            let substrings = string_arg(args, 1, "")?;
+            map_rows::<_, _, Int64Array>(values, |i, v| {
+                Ok(at(&substrings, i).map(|substring| {
+                    v.find(substring)
+                        .map_or(0, |offset| v[..offset].chars().count() as i64 + 1)
+                }))
+            })
+        }
+        ScalarFunction::Reverse => map_rows::<_, _, GenericStringArray<O>>(values, |_, v| {
+            Ok(Some(v.chars().rev().collect::<String>()))
+        }),
+        other => internal_err!("{} is not a string function", other),
+    }
+}
+
+/// Joins the values of every row, skipping NULLs.
+fn concat(args: &[ArrayRef]) -> Result<ArrayRef> {
+    let args = args
+        .iter()
+        .map(|a| Ok(cast(a, &DataType::Utf8)?))
+        .collect::<Result<Vec<_>>>()?;
+    let args = args
+        .iter()
+        .map(|a| a.as_string::<i32>())
+        .collect::<Vec<_>>();
+    let mut builder = StringBuilder::new();
+    for i in 0..args[0].len() {
+        for arg in &args {
+            if let Some(value) = at(*arg, i) {
+                builder
+                    .write_str(value)
+                    .map_err(|e| DBError::Execution(e.to_string()))?;
+            }
+        }
+        builder.append_value("");
+    }
+    Ok(Arc::new(builder.finish()))
+}
+
+/// Calls `f` with the index and the value of every row of `values` that is
+/// not NULL and collects the results into an array of type `A`. NULL rows
+/// stay NULL.
+fn map_rows<'a, O, T, A>(
+    values: &'a GenericStringArray<O>,
+    mut f: impl FnMut(usize, &'a str) -> Result<Option<T>>,
+) -> Result<ArrayRef>
+where
+    O: OffsetSizeTrait,
+    A: FromIterator<Option<T>> + Array + 'static,
+{
+    let array = values
+        .iter()
+        .enumerate()
+        .map(|(i, v)| match v {
+            Some(v) => f(i, v),
+            None => Ok(None),
+        })
+        .collect::<Result<A>>()?;
+    Ok(Arc::new(array))
+}
+
+/// Reads argument `i` as Utf8, or `default` in every row if it was omitted.
+fn string_arg(args: &[ArrayRef], i: usize, default: &str) -> Result<StringArray> {
+    match args.get(i) {
+        Some(arg) => Ok(cast(arg, &DataType::Utf8)?.as_string::<i32>().clone()),
+        None => Ok(StringArray::from_iter_values(iter::repeat_n(
+            default,
+            args[0].len(),
+        ))),
+    }
+}
+
+/// Reads argument `i` as Int64, or `default` in every row if it was omitted.
+fn int_arg(args: &[ArrayRef], i: usize, default: i64) -> Result<Int64Array> {
+    match args.get(i) {
+        Some(arg) => Ok(cast(arg, &DataType::Int64)?
+            .as_primitive::<Int64Type>()
+            .clone()),
+        None => Ok(Int64Array::from_value(default, args[0].len())),
+    }
+}
+
+/// Returns the value of row `i`, or `None` if it is NULL.
+fn at<A: ArrayAccessor>(array: A, i: usize) -> Option<A::Item> {
+    array.is_valid(i).then(|| array.value(i))
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::array::{LargeStringArray, record_batch};
+
+    use super::*;
+
+    fn strings(values: &[Option<&str>]) -> ArrayRef {
+        Arc::new(StringArray::from(values.to_vec()))
+    }
+
+    fn ints(values: &[Option<i64>]) -> ArrayRef {
+        Arc::new(Int64Array::from(values.to_vec()))
+    }
+
+    #[test]
+    fn test_string_functions() -> Result<()> {
+        let batch = record_batch!(("s", Utf8, [Some("  Ab cd "), None, Some("Émile")]))?;
+        let s = batch.column(0).clone();
+
+        let ret = evaluate(ScalarFunction::Upper, std::slice::from_ref(&s))?;
+        assert_eq!(
+            ret.as_string::<i32>(),
+            &StringArray::from(vec![Some("  AB CD "), None, Some("ÉMILE")])
+        );
+        let ret = evaluate(ScalarFunction::Trim, std::slice::from_ref(&s))?;
+        assert_eq!(
+            ret.as_string::<i32>(),
+            &StringArray::from(vec![Some("Ab cd"), None, Some("Émile")])
+        );
+        let ret = evaluate(ScalarFunction::Rtrim, &[
+            s.clone(),
+            strings(&[Some(" d"), Some(" "), Some("el")]),
+        ])?;
+        assert_eq!(
+            ret.as_string::<i32>(),
+            &StringArray::from(vec![Some("  Ab c"), None, Some("Émi")])
+        );
+        let ret = evaluate(ScalarFunction::Length, std::slice::from_ref(&s))?;
+        assert_eq!(
+            ret.as_primitive::<Int64Type>(),
+            &Int64Array::from(vec![Some(8), None, Some(5)])
+        );
+        let ret = evaluate(ScalarFunction::Reverse, std::slice::from_ref(&s))?;
+        assert_eq!(
+            ret.as_string::<i32>(),
+            &StringArray::from(vec![Some(" dc bA  "), None, Some("elimÉ")])
+        );
+        let ret = evaluate(ScalarFunction::Strpos, &[
+            s.clone(),
+            strings(&[Some("cd"), Some("a"), Some("le")]),
+        ])?;
+        assert_eq!(
+            ret.as_primitive::<Int64Type>(),
+            &Int64Array::from(vec![Some(6), None, Some(4)])
+        );
+        let ret = evaluate(ScalarFunction::Concat, &[
+            s.clone(),
+            ints(&[Some(1), Some(2), None]),
+        ])?;
+        assert_eq!(
+            ret.as_string::<i32>(),
+            &StringArray::from(vec![Some("  Ab cd 1"), Some("2"), Some("Émile")])
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_substr_and_padding() -> Result<()> {
+        let s: ArrayRef = Arc::new(LargeStringArray::from(vec!["hello"; 4]));
+
+        let ret = evaluate(ScalarFunction::Substr, &[
+            s.clone(),
+            ints(&[Some(2), Some(0), Some(-5), None]),
+            ints(&[Some(3), Some(2), Some(100), Some(1)]),
+        ])?;
+        assert_eq!(
+            ret.as_string::<i64>(),
+            &LargeStringArray::from(vec![Some("ell"), Some("h"), Some("hello"), None])
+        );
+        let ret = evaluate(ScalarFunction::Substr, &[s.clone(), ints(&[Some(4); 4])])?;
+        assert_eq!(ret.as_string::<i64>().value(0), "lo");
+        let ret = evaluate(ScalarFunction::Substr, &[
+            s.clone(),
+            ints(&[Some(1); 4]),
+            ints(&[Some(-1); 4]),
+        ]);
+        assert!(ret.is_err());
+
+        let ret = evaluate(ScalarFunction::Lpad, &[
+            s.clone(),
+            ints(&[Some(8), Some(3), Some(7), Some(-1)]),
+            strings(&[Some("ab"), Some("x"), Some(""), Some("x")]),
+        ])?;
+        assert_eq!(
+            ret.as_string::<i64>(),
+            &LargeStringArray::from(vec!["abahello", "hel", "hello", ""])
+        );
+        let ret = evaluate(ScalarFunction::Rpad, &[s.clone(), ints(&[Some(6); 4])])?;
+        assert_eq!(ret.as_string::<i64>().value(0), "hello ");
+
+        let s = strings(&[Some("a,b,,c"), Some("a,b,,c"), Some("a,b,,c"), Some("abc")]);
+        let ret = evaluate(ScalarFunction::SplitPart, &[
+            s.clone(),
+            strings(&[Some(","); 4]),
+            ints(&[Some(2), Some(-1), Some(9), Some(1)]),
+        ])?;
+        assert_eq!(
+            ret.as_string::<i32>(),
+            &StringArray::from(vec!["b", "c", "", "abc"])
+        );
+        let ret = evaluate(ScalarFunction::SplitPart, &[
+            s.clone(),
+            strings(&[Some(","); 4]),
+            ints(&[Some(0); 4]),
+        ]);
+        assert!(ret.is_err());
+        let ret = evaluate(ScalarFunction::Replace, &[
+            s.clone(),
+            strings(&[Some(","), Some(""), None, Some("b")]),
+            strings(&[Some("-"); 4]),
+        ])?;
+        assert_eq!(
+            ret.as_string::<i32>(),
+            &StringArray::from(vec![Some("a-b--c"), Some("a,b,,c"), None, Some("a-c")])
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,154 @@
+use std::sync::Arc;
+
+use arrow::{
+    array::{ArrayRef, RecordBatch},
+    compute::{
+        is_not_null, is_null,
+        kernels::{
+            boolean::not,
+            numeric::{neg, neg_wrapping},
+        },
+    },
+};
+use arrow_schema::{ArrowError, DataType};
+
+use super::{ColumnarValue, PhysicalExpression, null_as_false, to_boolean_array};
+use crate::{
+    datatypes::{operator::UnaryOperator, values::ScalarValue},
+    error::{DBError, Result},
+};
+
+#[derive(Debug)]
+pub struct UnaryExpr {
+    op: UnaryOperator,
+    expr: Arc<PhysicalExpression>,
+    /// Whether negating the smallest value of an integer type fails instead
+    /// of wrapping around.
+    fail_on_overflow: bool,
+}
+
+impl UnaryExpr {
+    pub fn new(op: UnaryOperator, expr: Arc<PhysicalExpression>) -> Self {
+        Self {
+            op,
+            expr,
+            fail_on_overflow: true,
+        }
+    }
+
+    pub fn with_fail_on_overflow(mut self, fail_on_overflow: bool) -> Self {
+        self.fail_on_overflow = fail_on_overflow;
+        self
+    }
+
+    pub fn evalate(&self, batch: &RecordBatch) -> Result<ColumnarValue> {
+        match self.expr.evalate(batch)? {
+            ColumnarValue::Array(array) => Ok(ColumnarValue::Array(self.apply(&array)?)),
+            ColumnarValue::Scalar(value) => {
+                let array = self.apply(&value.to_array(1))?;
+                let value = ScalarValue::try_from_array(array.as_ref(), 0)?;
+                Ok(ColumnarValue::Scalar(value))
+            }
+        }
+    }
+
+    /// The `IS` predicates never return NULL, e.g. `NULL IS FALSE` is false.
+    fn apply(&self, array: &ArrayRef) -> Result<ArrayRef> {
+        use UnaryOperator::*;
+
+        let boolean = || to_boolean_array(array);
+        let result = match self.op {
+            Not => not(&boolean()?)?,
+            // NULL negated is still NULL.
+            Negative if array.data_type() == &DataType::Null => return Ok(array.clone()),
+            Negative if self.fail_on_overflow => {
+                return neg(array).map_err(|e| match e {
+                    ArrowError::ArithmeticOverflow(msg) => {
+                        DBError::Execution(format!("integer overflow: {}", msg))
+                    }
+                    e => e.into(),
+                });
+            }
+            Negative => return Ok(neg_wrapping(array)?),
+            IsNull | IsUnknown => is_null(array)?,
+            IsNotNull | IsNotUnknown => is_not_null(array)?,
+            IsTrue => null_as_false(&boolean()?),
+            IsNotTrue => not(&null_as_false(&boolean()?))?,
+            IsFalse => null_as_false(&not(&boolean()?)?),
+            IsNotFalse => not(&null_as_false(&not(&boolean()?)?))?,
+        };
+        Ok(Arc::new(result))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::array::{AsArray, BooleanArray, Int8Array, record_batch};
+    use arrow::datatypes::Int8Type;
+
+    use super::*;
+    use crate::physical::expr::column::ColumnExpr;
+
+    fn evaluate(op: UnaryOperator, batch: &RecordBatch, index: usize) -> Result<ArrayRef> {
+        let column = Arc::new(PhysicalExpression::Column(ColumnExpr::new("", index)));
+        UnaryExpr::new(op, column)
+            .evalate(batch)?
+            .into_array(batch.num_rows())
+    }
+
+    #[test]
+    fn test_unary() -> Result<()> {
+        let batch = record_batch!(
+            ("b", Boolean, [Some(true), Some(false), None]),
+            ("i", Int8, [Some(1), None, Some(-128)])
+        )?;
+
+        let expected = [
+            (UnaryOperator::Not, vec![Some(false), Some(true), None]),
+            (UnaryOperator::IsNull, vec![
+                Some(false),
+                Some(false),
+                Some(true),
+            ]),
+            (UnaryOperator::IsTrue, vec![
+                Some(true),
+                Some(false),
+                Some(false),
+            ]),
+            (UnaryOperator::IsNotTrue, vec![
+                Some(false),
+                Some(true),
+                Some(true),
+            ]),
+            (UnaryOperator::IsFalse, vec![
+                Some(false),
+                Some(true),
+                Some(false),
+            ]),
+            (UnaryOperator::IsNotFalse, vec![
+                Some(true),
+                Some(false),
+                Some(true),
+            ]),
+            (UnaryOperator::IsNotUnknown, vec![
+                Some(true),
+                Some(true),
+                Some(false),
+            ]),
+        ];
+        for (op, expected) in expected {
+            let ret = evaluate(op, &batch, 0)?;
+            assert_eq!(ret.as_boolean(), &BooleanArray::from(expected), "{}", op);
+        }
+
+        // -(-128) does not fit into an Int8.
+        let err = evaluate(UnaryOperator::Negative, &batch, 1).unwrap_err();
+        assert!(matches!(err, DBError::Execution(_)), "{}", err);
+        let ret = evaluate(UnaryOperator::Negative, &batch.slice(0, 2), 1)?;
+        assert_eq!(
+            ret.as_primitive::<Int8Type>(),
+            &Int8Array::from(vec![Some(-1), None])
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,664 @@
+use std::{ops::Range, sync::Arc};
+
+use arrow::{
+    array::{Array, ArrayRef, AsArray, Int64Array, RecordBatch, UInt32Array, new_empty_array},
+    compute::{
+        SortColumn, cast, concat, is_null, kernels::zip::zip, lexsort_to_indices, partition, take,
+    },
+    datatypes::{Float64Type, Int64Type},
+};
+use arrow_schema::DataType;
+
+use super::{PhysicalExpression, aggregate::AggregateExpr};
+use crate::{
+    datatypes::values::ScalarValue,
+    error::{Result, exec_err},
+    logical::expression::window::{
+        WindowFrame, WindowFrameBound, WindowFrameUnits, WindowFunction,
+    },
+    physical::plan::{PhysicalSortExpr, normalize_nan},
+};
+
+/// Evaluates a window function for every row of a batch. The rows are sorted
+/// by the partition and ordering keys, each partition is evaluated on its
+/// own and the values are returned in the order of the input rows.
+#[derive(Debug)]
+pub struct WindowExpr {
+    fun: WindowFunction,
+    args: Vec<Arc<PhysicalExpression>>,
+    /// The types of the values produced by `args`.
+    arg_types: Vec<DataType>,
+    partition_by: Vec<PhysicalExpression>,
+    order_by: Vec<PhysicalSortExpr>,
+    frame: WindowFrame,
+    return_type: DataType,
+}
+
+impl WindowExpr {
+    pub fn try_new(
+        fun: WindowFunction,
+        args: Vec<Arc<PhysicalExpression>>,
+        arg_types: Vec<DataType>,
+        partition_by: Vec<PhysicalExpression>,
+        order_by: Vec<PhysicalSortExpr>,
+        frame: WindowFrame,
+    ) -> Result<Self> {
+        let return_type = fun.return_type(&arg_types)?;
+        Ok(Self {
+            fun,
+            args,
+            arg_types,
+            partition_by,
+            order_by,
+            frame,
+            return_type,
+        })
+    }
+
+    pub fn evaluate(&self, batch: &RecordBatch) -> Result<ArrayRef> {
+        let num_rows = batch.num_rows();
+        let evaluate = |e: &PhysicalExpression| e.evalate(batch)?.into_array(num_rows);
+        // Computed NaNs are normalized, so that they sort, and are peers, like
+        // any other NaN.
+        let evaluate_key = |e: &PhysicalExpression| Ok(normalize_nan(evaluate(e)?));
+        let partition_keys = self
+            .partition_by
+            .iter()
+            .map(evaluate_key)
+            .collect::<Result<Vec<_>>>()?;
+        let order_keys = self
+            .order_by
+            .iter()
+            .map(|e| evaluate_key(&e.expr))
+            .collect::<Result<Vec<_>>>()?;
+
+        // Sorting makes the rows of every partition, and the peers within it,
+        // adjacent.
+        let sort_columns = partition_keys
+            .iter()
+            .map(|values| SortColumn {
+                values: values.clone(),
+                options: None,
+            })
+            .chain(
+                order_keys
+                    .iter()
+                    .zip(self.order_by.iter())
+                    .map(|(values, e)| SortColumn {
+                        values: values.clone(),
+                        options: Some(e.options),
+                    }),
+            )
+            .collect::<Vec<_>>();
+        let indices = if sort_columns.is_empty() {
+            None
+        } else {
+            Some(lexsort_to_indices(&sort_columns, None)?)
+        };
+        let sorted = |values: ArrayRef| -> Result<ArrayRef> {
+            match &indices {
+                Some(indices) => Ok(take(values.as_ref(), indices, None)?),
+                None => Ok(values),
+            }
+        };
+        let partition_keys = partition_keys
+            .into_iter()
+            .map(sorted)
+            .collect::<Result<Vec<_>>>()?;
+        let order_keys = order_keys
+            .into_iter()
+            .map(sorted)
+            .collect::<Result<Vec<_>>>()?;
+        let args = self
+            .args
+            .iter()
+            .map(|e| sorted(evaluate(e)?))
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut values = vec![];
+        for partition in ranges(&partition_keys, num_rows)? {
+            let slice = |arrays: &[ArrayRef]| {
+                arrays
+                    .iter()
+                    .map(|a| a.slice(partition.start, partition.len()))
+                    .collect::<Vec<_>>()
+            };
+            values.push(self.evaluate_partition(
+                &slice(&args),
+                &slice(&order_keys),
+                partition.len(),
+            )?);
+        }
+        let values = if values.is_empty() {
+            new_empty_array(&self.return_type)
+        } else {
+            concat(&values.iter().map(|v| v.as_ref()).collect::<Vec<_>>())?
+        };
+
+        // Put the values back in the order of the input rows.
+        let Some(indices) = indices else {
+            return Ok(values);
+        };
+        let mut positions = vec![0; num_rows];
+        for (position, row) in indices.values().iter().enumerate() {
+            positions[*row as usize] = position as u32;
+        }
+        Ok(take(values.as_ref(), &UInt32Array::from(positions), None)?)
+    }
+
+    /// Evaluates the function for the `num_rows` rows of one partition, given
+    /// their sorted arguments and ordering keys.
+    fn evaluate_partition(
+        &self,
+        args: &[ArrayRef],
+        order_keys: &[ArrayRef],
+        num_rows: usize,
+    ) -> Result<ArrayRef> {
+        let peers = ranges(order_keys, num_rows)?;
+
+        Ok(match &self.fun {
+            WindowFunction::RowNumber => Arc::new(Int64Array::from_iter_values(
+                (1..=num_rows).map(|n| n as i64),
+            )),
+            WindowFunction::Rank => {
+                Arc::new(Int64Array::from_iter_values(peers.iter().flat_map(
+                    |peer| std::iter::repeat_n(peer.start as i64 + 1, peer.len()),
+                )))
+            }
+            WindowFunction::DenseRank => Arc::new(Int64Array::from_iter_values(
+                peers
+                    .iter()
+                    .enumerate()
+                    .flat_map(|(i, peer)| std::iter::repeat_n(i as i64 + 1, peer.len())),
+            )),
+            WindowFunction::Ntile => {
+                let buckets = constant_arg(&args[0])? as usize;
+                // The first `num_rows % buckets` buckets get one extra row.
+                let size = num_rows / buckets;
+                let extra = num_rows % buckets;
+                let large_rows = extra * (size + 1);
+                Arc::new(Int64Array::from_iter_values((0..num_rows).map(|row| {
+                    let bucket = if row < large_rows {
+                        row / (size + 1)
+                    } else {
+                        extra + (row - large_rows) / size
+                    };
+                    bucket as i64 + 1
+                })))
+            }
+            WindowFunction::Lag | WindowFunction::Lead => {
+                let offset = match args.get(1) {
+                    Some(offset) => constant_arg(offset)?,
+                    None => 1,
+                };
+                let offset = if self.fun == WindowFunction::Lag {
+                    offset.saturating_neg()
+                } else {
+                    offset
+                };
+                let indices = UInt32Array::from_iter((0..num_rows).map(|row| {
+                    let source = (row as i64).checked_add(offset)?;
+                    (0..num_rows as i64)
+                        .contains(&source)
+                        .then_some(source as u32)
+                }));
+                let values = take(args[0].as_ref(), &indices, None)?;
+                match args.get(2) {
+                    Some(default) => zip(&is_null(&indices)?, default, &values)?,
+                    None => values,
+                }
+            }
+            WindowFunction::FirstValue | WindowFunction::LastValue => {
+                let is_first = self.fun == WindowFunction::FirstValue;
+                let frames = self.frames(order_keys, &peers, num_rows)?;
+                let indices = UInt32Array::from_iter(frames.iter().map(|frame| {
+                    match (frame.is_empty(), is_first) {
+                        (true, _) => None,
+                        (false, true) => Some(frame.start as u32),
+                        (false, false) => Some(frame.end as u32 - 1),
+                    }
+                }));
+                take(args[0].as_ref(), &indices, None)?
+            }
+            WindowFunction::Aggregate(fun) => {
+                let aggregate = AggregateExpr::new(
+                    fun.clone(),
+                    self.args[0].clone(),
+                    false,
+                    self.arg_types[0].clone(),
+                );
+                let frames = self.frames(order_keys, &peers, num_rows)?;
+                let mut values = Vec::with_capacity(num_rows);
+                // Frames move forward along the partition, so one accumulator
+                // holds the rows of the current frame: it is given the rows a
+                // frame adds and, for SUM, COUNT and AVG, gives back the rows
+                // it drops. MIN and MAX cannot drop rows and start over
+                // whenever the start of the frame moves, which costs
+                // O(rows * frame size) for sliding frames.
+                let mut accumulator = aggregate.create_accumulator()?;
+                let mut window = 0..0;
+                for frame in frames {
+                    let slides = frame.start > window.start;
+                    if frame.start < window.start
+                        || frame.end < window.end
+                        || (slides && (frame.start > window.end || !accumulator.supports_retract()))
+                    {
+                        accumulator = aggregate.create_accumulator()?;
+                        window = frame.start..frame.start;
+                    } else if slides {
+                        accumulator.retract_batch(
+                            &args[0].slice(window.start, frame.start - window.start),
+                        )?;
+                        window.start = frame.start;
+                    }
+                    if frame.end > window.end {
+                        accumulator
+                            .update_batch(&args[0].slice(window.end, frame.end - window.end))?;
+                        window.end = frame.end;
+                    }
+                    values.push(accumulator.evaluate()?);
+                }
+                scalars_to_array(&values, &self.return_type)?
+            }
+        })
+    }
+
+    /// Returns the frame of every row of a partition as a range of rows.
+    fn frames(
+        &self,
+        order_keys: &[ArrayRef],
+        peers: &[Range<usize>],
+        num_rows: usize,
+    ) -> Result<Vec<Range<usize>>> {
+        let range_keys = if self.frame.is_range_with_offset() {
+            Some(RangeKeys::try_new(&order_keys[0], &self.order_by[0])?)
+        } else {
+            None
+        };
+
+        let mut frames = Vec::with_capacity(num_rows);
+        for peer in peers {
+            for row in peer.clone() {
+                let bound =
+                    |bound: WindowFrameBound, is_start: bool| -> usize {
+                        match (bound, self.frame.units, &range_keys) {
+                            (WindowFrameBound::Preceding(None), ..) => 0,
+                            (WindowFrameBound::Following(None), ..) => num_rows,
+                            (WindowFrameBound::CurrentRow, WindowFrameUnits::Rows, _) => {
+                                if is_start { row } else { row + 1 }
+                            }
+                            (WindowFrameBound::Preceding(Some(n)), WindowFrameUnits::Rows, _) => {
+                                let n = offset(n);
+                                if is_start {
+                                    row.saturating_sub(n)
+                                } else {
+                                    (row + 1).saturating_sub(n)
+                                }
+                            }
+                            (WindowFrameBound::Following(Some(n)), WindowFrameUnits::Rows, _) => {
+                                let n = offset(n);
+                                let end = if is_start { row } else { row + 1 };
+                                end.saturating_add(n).min(num_rows)
+                            }
+                            (WindowFrameBound::Preceding(Some(n)), _, Some(keys)) => {
+                                keys.bound(row, peer, -(n as f64), is_start)
+                            }
+                            (WindowFrameBound::Following(Some(n)), _, Some(keys)) => {
+                                keys.bound(row, peer, n as f64, is_start)
+                            }
+                            // `CURRENT ROW` of a `RANGE` frame includes its peers.
+                            _ => {
+                                if is_start {
+                                    peer.start
+                                } else {
+                                    peer.end
+                                }
+                            }
+                        }
+                    };
+                let start = bound(self.frame.start, true);
+                let end = bound(self.frame.end, false).max(start);
+                frames.push(start..end);
+            }
+        }
+        Ok(frames)
+    }
+}
+
+/// The ordering key of a partition for `RANGE` frames with offsets, as
+/// floating point values that increase along the rows.
+struct RangeKeys {
+    keys: Vec<f64>,
+    /// The rows whose key is not NULL.
+    valid: Range<usize>,
+}
+
+impl RangeKeys {
+    fn try_new(order_key: &ArrayRef, sort_expr: &PhysicalSortExpr) -> Result<Self> {
+        let values = cast(order_key, &DataType::Float64)?;
+        let values = values.as_primitive::<Float64Type>();
+        let sign = if sort_expr.options.descending {
+            -1.0
+        } else {
+            1.0
+        };
+        let null_count = values.null_count();
+        let valid = if sort_expr.options.nulls_first {
+            null_count..values.len()
+        } else {
+            0..values.len() - null_count
+        };
+        Ok(Self {
+            keys: values.values().iter().map(|v| v * sign).collect(),
+            valid,
+        })
+    }
+
+    /// Returns the first row whose key is at least, or for the end of the
+    /// frame greater than, the key of `row` plus `offset`. Rows with a NULL
+    /// key only frame their peers.
+    fn bound(&self, row: usize, peer: &Range<usize>, offset: f64, is_start: bool) -> usize {
+        if !self.valid.contains(&row) {
+            return if is_start { peer.start } else { peer.end };
+        }
+        let target = self.keys[row] + offset;
+        let keys = &self.keys[self.valid.clone()];
+        let position = if is_start {
+            keys.partition_point(|key| *key < target)
+        } else {
+            keys.partition_point(|key| *key <= target)
+        };
+        self.valid.start + position
+    }
+}
+
+/// Splits `num_rows` sorted rows into runs of equal `keys`. Without keys all
+/// rows form a single run.
+fn ranges(keys: &[ArrayRef], num_rows: usize) -> Result<Vec<Range<usize>>> {
+    if keys.is_empty() {
+        return Ok((num_rows > 0).then_some(0..num_rows).into_iter().collect());
+    }
+    Ok(partition(keys)?.ranges())
+}
+
+fn offset(n: u64) -> usize {
+    usize::try_from(n).unwrap_or(usize::MAX)
+}
+
+/// Reads a constant integer argument, such as the offset of `LAG`.
+fn constant_arg(values: &ArrayRef) -> Result<i64> {
+    let values = cast(values, &DataType::Int64)?;
+    let values = values.as_primitive::<Int64Type>();
+    if values.is_empty() || values.is_null(0) {
+        return exec_err!("expected a constant integer argument");
+    }
+    Ok(values.value(0))
+}
+
+fn scalars_to_array(values: &[ScalarValue], data_type: &DataType) -> Result<ArrayRef> {
+    if values.is_empty() {
+        return Ok(new_empty_array(data_type));
+    }
+    let arrays = values.iter().map(|v| v.to_array(1)).collect::<Vec<_>>();
+    Ok(concat(
+        &arrays.iter().map(|a| a.as_ref()).collect::<Vec<_>>(),
+    )?)
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::array::record_batch;
+    use arrow::datatypes::Int64Type;
+
+    use super::*;
+    use crate::logical::expression::aggregate::AggregateFunction;
+    use crate::physical::expr::{column::ColumnExpr, literal::LiteralExpr};
+
+    fn batch() -> RecordBatch {
+        record_batch!(
+            ("g", Utf8, ["a", "a", "b", "a", "b", "b", "a"]),
+            ("v", Int32, [
+                Some(3),
+                Some(1),
+                Some(5),
+                Some(3),
+                None,
+                Some(2),
+                Some(4)
+            ]),
+            ("i", Int32, [0, 1, 2, 3, 4, 5, 6])
+        )
+        .unwrap()
+    }
+
+    fn column(index: usize) -> PhysicalExpression {
+        PhysicalExpression::Column(ColumnExpr::new("", index))
+    }
+
+    fn literal(value: ScalarValue) -> Arc<PhysicalExpression> {
+        Arc::new(PhysicalExpression::Literal(LiteralExpr::new(value)))
+    }
+
+    fn evaluate(
+        fun: WindowFunction,
+        args: Vec<Arc<PhysicalExpression>>,
+        partition_by: Vec<PhysicalExpression>,
+        order_by: Vec<(usize, bool)>,
+        frame: WindowFrame,
+    ) -> Result<Vec<Option<i64>>> {
+        let batch = batch();
+        let arg_types = args
+            .iter()
+            .map(|e| Ok(e.evalate(&batch)?.into_array(1)?.data_type().clone()))
+            .collect::<Result<Vec<_>>>()?;
+        let order_by = order_by
+            .into_iter()
+            .map(|(index, asc)| PhysicalSortExpr::new(column(index), asc, !asc))
+            .collect();
+        let expr = WindowExpr::try_new(fun, args, arg_types, partition_by, order_by, frame)?;
+        let values = cast(&expr.evaluate(&batch)?, &DataType::Int64)?;
+        Ok(values.as_primitive::<Int64Type>().iter().collect())
+    }
+
+    fn rows(start: WindowFrameBound, end: WindowFrameBound) -> WindowFrame {
+        WindowFrame::try_new(WindowFrameUnits::Rows, start, end).unwrap()
+    }
+
+    #[test]
+    fn test_ranking() -> Result<()> {
+        let frame = rows(
+            WindowFrameBound::Preceding(None),
+            WindowFrameBound::CurrentRow,
+        );
+        let ranking = |fun: WindowFunction, args, order_by| {
+            evaluate(fun, args, vec![column(0)], order_by, frame)
+        };
+        let values = |v: Vec<i64>| v.into_iter().map(Some).collect::<Vec<_>>();
+
+        assert_eq!(
+            ranking(WindowFunction::RowNumber, vec![], vec![
+                (1, true),
+                (2, true)
+            ])?,
+            values(vec![2, 1, 2, 3, 3, 1, 4])
+        );
+        assert_eq!(
+            ranking(WindowFunction::Rank, vec![], vec![(1, true)])?,
+            values(vec![2, 1, 2, 2, 3, 1, 4])
+        );
+        assert_eq!(
+            ranking(WindowFunction::DenseRank, vec![], vec![(1, true)])?,
+            values(vec![2, 1, 2, 2, 3, 1, 3])
+        );
+        // Four rows in three buckets put two rows in the first one.
+        assert_eq!(
+            ranking(
+                WindowFunction::Ntile,
+                vec![literal(ScalarValue::Int64(Some(3)))],
+                vec![(1, true), (2, true)]
+            )?,
+            values(vec![1, 1, 2, 2, 3, 1, 3])
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_lag_lead() -> Result<()> {
+        let frame = WindowFrame::default_for(&[]);
+        let v = Arc::new(column(1));
+        assert_eq!(
+            evaluate(
+                WindowFunction::Lag,
+                vec![v.clone()],
+                vec![],
+                vec![(2, true)],
+                frame
+            )?,
+            vec![None, Some(3), Some(1), Some(5), Some(3), None, Some(2)]
+        );
+        assert_eq!(
+            evaluate(
+                WindowFunction::Lead,
+                vec![
+                    v,
+                    literal(ScalarValue::Int64(Some(2))),
+                    literal(ScalarValue::Int32(Some(0)))
+                ],
+                vec![],
+                vec![(2, true)],
+                frame
+            )?,
+            vec![Some(5), Some(3), None, Some(2), Some(4), Some(0), Some(0)]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_frames() -> Result<()> {
+        use WindowFrameBound::*;
+
+        let v = || vec![Arc::new(column(1))];
+        let sum = WindowFunction::Aggregate(AggregateFunction::Sum);
+        assert_eq!(
+            evaluate(
+                sum.clone(),
+                v(),
+                vec![],
+                vec![(2, true)],
+                rows(Preceding(Some(1)), Following(Some(1)))
+            )?,
+            vec![
+                Some(4),
+                Some(9),
+                Some(9),
+                Some(8),
+                Some(5),
+                Some(6),
+                Some(6)
+            ]
+        );
+        // Rows whose key is within 1 below the current one, NULL keys only
+        // framing each other.
+        let range = WindowFrame::try_new(WindowFrameUnits::Range, Preceding(Some(1)), CurrentRow)?;
+        assert_eq!(evaluate(sum, v(), vec![], vec![(1, true)], range)?, vec![
+            Some(8),
+            Some(1),
+            Some(9),
+            Some(8),
+            None,
+            Some(3),
+            Some(10)
+        ]);
+        assert_eq!(
+            evaluate(
+                WindowFunction::FirstValue,
+                v(),
+                vec![],
+                vec![(2, true)],
+                rows(Preceding(Some(2)), CurrentRow)
+            )?,
+            vec![Some(3), Some(3), Some(3), Some(1), Some(5), Some(3), None]
+        );
+        // The last row has nothing following it.
+        assert_eq!(
+            evaluate(
+                WindowFunction::Aggregate(AggregateFunction::Count),
+                v(),
+                vec![],
+                vec![(2, true)],
+                rows(Following(Some(1)), Following(Some(2)))
+            )?,
+            vec![
+                Some(2),
+                Some(2),
+                Some(1),
+                Some(1),
+                Some(2),
+                Some(1),
+                Some(0)
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_sliding_frames() -> Result<()> {
+        use WindowFrameBound::*;
+
+        let aggregate = |fun: AggregateFunction, frame| {
+            evaluate(
+                WindowFunction::Aggregate(fun),
+                vec![Arc::new(column(1))],
+                vec![],
+                vec![(2, true)],
+                frame,
+            )
+        };
+        // Retracting the only non-NULL value leaves a NULL sum.
+        assert_eq!(
+            aggregate(AggregateFunction::Sum, rows(CurrentRow, CurrentRow))?,
+            vec![Some(3), Some(1), Some(5), Some(3), None, Some(2), Some(4)]
+        );
+        assert_eq!(
+            aggregate(AggregateFunction::Avg, rows(Preceding(Some(1)), CurrentRow))?,
+            vec![
+                Some(3),
+                Some(2),
+                Some(3),
+                Some(4),
+                Some(3),
+                Some(2),
+                Some(3)
+            ]
+        );
+        assert_eq!(
+            aggregate(
+                AggregateFunction::Count,
+                rows(Preceding(Some(1)), Following(Some(1)))
+            )?,
+            vec![
+                Some(2),
+                Some(3),
+                Some(3),
+                Some(2),
+                Some(2),
+                Some(2),
+                Some(2)
+            ]
+        );
+        // MAX cannot retract and starts over as the frame slides.
+        assert_eq!(
+            aggregate(AggregateFunction::Max, rows(Preceding(Some(1)), CurrentRow))?,
+            vec![
+                Some(3),
+                Some(3),
+                Some(5),
+                Some(5),
+                Some(3),
+                Some(2),
+                Some(4)
+            ]
+        );
+        Ok(())
+    }
+}
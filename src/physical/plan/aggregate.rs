@@ -0,0 +1,168 @@
+use std::{collections::HashMap, sync::Arc};
+
+use arrow::{
+    array::{ArrayRef, RecordBatch, UInt32Array, new_empty_array},
+    compute::{concat, take},
+    row::{RowConverter, SortField},
+};
+use arrow_schema::SchemaRef;
+
+use super::{PhysicalPlan, RecordBatchStream, row_index::RowIndex};
+use crate::{
+    error::Result,
+    physical::expr::{PhysicalExpression, accumulator::Accumulator, aggregate::AggregateExpr},
+};
+
+/// Groups the input rows by `group_exprs` using a hash table and computes
+/// `aggr_exprs` for every group. The whole input is consumed before the
+/// single output batch is produced.
+pub struct HashAggregateExec {
+    pub input: Arc<PhysicalPlan>,
+    pub group_exprs: Arc<Vec<PhysicalExpression>>,
+    pub aggr_exprs: Arc<Vec<AggregateExpr>>,
+    pub schema: SchemaRef,
+}
+
+impl HashAggregateExec {
+    pub fn new(
+        input: Arc<PhysicalPlan>,
+        group_exprs: Vec<PhysicalExpression>,
+        aggr_exprs: Vec<AggregateExpr>,
+        schema: SchemaRef,
+    ) -> Self {
+        Self {
+            input,
+            group_exprs: Arc::new(group_exprs),
+            aggr_exprs: Arc::new(aggr_exprs),
+            schema,
+        }
+    }
+
+    pub fn execute(&self) -> Result<RecordBatchStream> {
+        let input = self.input.execute()?;
+        let schema = self.schema.clone();
+        let group_exprs = self.group_exprs.clone();
+        let aggr_exprs = self.aggr_exprs.clone();
+        Ok(Box::new(std::iter::once_with(move || {
+            let mut state = GroupState::try_new(&schema, &group_exprs, &aggr_exprs)?;
+            for batch in input {
+                state.update(&batch?)?;
+            }
+            state.finish()
+        })))
+    }
+}
+
+/// Accumulators of every group seen so far.
+struct GroupState<'a> {
+    schema: &'a SchemaRef,
+    group_exprs: &'a [PhysicalExpression],
+    aggr_exprs: &'a [AggregateExpr],
+    converter: RowConverter,
+    /// The encoded key of every group, at the index of its accumulators.
+    groups: RowIndex,
+    accumulators: Vec<Vec<Box<dyn Accumulator>>>,
+}
+
+impl<'a> GroupState<'a> {
+    fn try_new(
+        schema: &'a SchemaRef,
+        group_exprs: &'a [PhysicalExpression],
+        aggr_exprs: &'a [AggregateExpr],
+    ) -> Result<Self> {
+        let sort_fields = schema.fields()[..group_exprs.len()]
+            .iter()
+            .map(|f| SortField::new(f.data_type().clone()))
+            .collect();
+        let converter = RowConverter::new(sort_fields)?;
+        let mut state = Self {
+            schema,
+            group_exprs,
+            aggr_exprs,
+            groups: RowIndex::new(&converter),
+            converter,
+            accumulators: vec![],
+        };
+        // Without GROUP BY there is exactly one group, even for empty input.
+        if group_exprs.is_empty() {
+            state.accumulators.push(state.create_accumulators()?);
+        }
+        Ok(state)
+    }
+
+    fn create_accumulators(&self) -> Result<Vec<Box<dyn Accumulator>>> {
+        self.aggr_exprs
+            .iter()
+            .map(|e| e.create_accumulator())
+            .collect()
+    }
+
+    fn update(&mut self, batch: &RecordBatch) -> Result<()> {
+        let values = self
+            .aggr_exprs
+            .iter()
+            .map(|e| e.evaluate_input(batch))
+            .collect::<Result<Vec<_>>>()?;
+
+        if self.group_exprs.is_empty() {
+            for (accumulator, values) in self.accumulators[0].iter_mut().zip(values.iter()) {
+                accumulator.update_batch(values)?;
+            }
+            return Ok(());
+        }
+
+        let keys = self
+            .group_exprs
+            .iter()
+            .map(|e| e.evalate(batch)?.into_array(batch.num_rows()))
+            .collect::<Result<Vec<_>>>()?;
+        let rows = self.converter.convert_columns(&keys)?;
+
+        // Collect the row indices of every group in this batch so that each
+        // accumulator is updated once per group rather than once per row.
+        let mut batch_groups: HashMap<usize, Vec<u32>> = HashMap::new();
+        for (row_idx, row) in rows.iter().enumerate() {
+            let (group, inserted) = self.groups.get_or_insert(row);
+            if inserted {
+                self.accumulators.push(self.create_accumulators()?);
+            }
+            batch_groups.entry(group).or_default().push(row_idx as u32);
+        }
+
+        for (group, indices) in batch_groups {
+            let indices = UInt32Array::from(indices);
+            for (accumulator, values) in self.accumulators[group].iter_mut().zip(values.iter()) {
+                accumulator.update_batch(&take(values.as_ref(), &indices, None)?)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn finish(mut self) -> Result<RecordBatch> {
+        let mut columns: Vec<ArrayRef> = if self.group_exprs.is_empty() {
+            vec![]
+        } else {
+            self.converter.convert_rows(self.groups.rows())?
+        };
+
+        let num_groups = self.accumulators.len();
+        for (i, field) in self.schema.fields()[self.group_exprs.len()..]
+            .iter()
+            .enumerate()
+        {
+            let values = self
+                .accumulators
+                .iter_mut()
+                .map(|accumulators| Ok(accumulators[i].evaluate()?.to_array(1)))
+                .collect::<Result<Vec<_>>>()?;
+            let column = if num_groups == 0 {
+                new_empty_array(field.data_type())
+            } else {
+                concat(&values.iter().map(|v| v.as_ref()).collect::<Vec<_>>())?
+            };
+            columns.push(column);
+        }
+
+        Ok(RecordBatch::try_new(self.schema.clone(), columns)?)
+    }
+}
@@ -1,22 +1,46 @@
 use std::sync::Arc;
 
-use arrow::array::RecordBatch;
+use arrow::array::{AsArray, RecordBatch};
+use arrow::compute::filter_record_batch;
+use arrow_schema::DataType;
 
-use super::PhysicalPlan;
-use crate::error::Result;
+use super::{PhysicalPlan, RecordBatchStream};
+use crate::error::{Result, exec_err};
 use crate::physical::expr::PhysicalExpression;
 
 pub struct FilterExec {
     pub input: Arc<PhysicalPlan>,
-    pub predicate: PhysicalExpression,
+    pub predicate: Arc<PhysicalExpression>,
 }
 
 impl FilterExec {
     pub fn new(input: Arc<PhysicalPlan>, predicate: PhysicalExpression) -> Self {
-        Self { input, predicate }
+        Self {
+            input,
+            predicate: Arc::new(predicate),
+        }
     }
 
-    pub fn execute(&self) -> Result<RecordBatch> {
-        self.input.execute() // 暂时不对结果做过滤
+    pub fn execute(&self) -> Result<RecordBatchStream> {
+        let predicate = self.predicate.clone();
+        let stream = self
+            .input
+            .execute()?
+            .map(move |batch| filter_batch(&predicate, &batch?));
+        Ok(Box::new(stream))
+    }
+}
+
+/// Keeps the rows for which the predicate is `true`; rows evaluating to
+/// `false` or NULL are dropped.
+pub(crate) fn filter_batch(
+    predicate: &PhysicalExpression,
+    batch: &RecordBatch,
+) -> Result<RecordBatch> {
+    let mask = predicate.evalate(batch)?.into_array(batch.num_rows())?;
+    match mask.data_type() {
+        DataType::Boolean => Ok(filter_record_batch(batch, mask.as_boolean())?),
+        DataType::Null => Ok(batch.slice(0, 0)),
+        other => exec_err!("filter predicate must be boolean, got {}", other),
     }
 }
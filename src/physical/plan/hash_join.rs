@@ -0,0 +1,326 @@
+use std::sync::Arc;
+
+use arrow::{
+    array::{
+        Array, ArrayRef, AsArray, RecordBatch, RecordBatchOptions, UInt32Array, new_null_array,
+    },
+    compute::{cast, concat_batches, filter, take},
+    row::{RowConverter, SortField},
+};
+use arrow_schema::{DataType, Schema, SchemaRef};
+
+use super::{PhysicalPlan, RecordBatchStream, row_index::RowIndex};
+use crate::{
+    error::{Result, exec_err},
+    logical::plan::JoinType,
+    physical::expr::PhysicalExpression,
+};
+
+/// Joins two inputs on equal keys. The whole left input is loaded into a
+/// hash table keyed by the join keys, then the right input is streamed
+/// through it batch by batch. Rows with a NULL key never match.
+pub struct HashJoinExec {
+    pub left: Arc<PhysicalPlan>,
+    pub right: Arc<PhysicalPlan>,
+    /// Pairs of a left key and the right key it must be equal to.
+    pub on: Arc<Vec<(PhysicalExpression, PhysicalExpression)>>,
+    /// Evaluated on the left columns followed by the right columns of every
+    /// pair of rows with equal keys.
+    pub filter: Option<Arc<PhysicalExpression>>,
+    pub join_type: JoinType,
+    pub schema: SchemaRef,
+}
+
+impl HashJoinExec {
+    pub fn new(
+        left: Arc<PhysicalPlan>,
+        right: Arc<PhysicalPlan>,
+        on: Vec<(PhysicalExpression, PhysicalExpression)>,
+        filter: Option<PhysicalExpression>,
+        join_type: JoinType,
+        schema: SchemaRef,
+    ) -> Self {
+        Self {
+            left,
+            right,
+            on: Arc::new(on),
+            filter: filter.map(Arc::new),
+            join_type,
+            schema,
+        }
+    }
+
+    pub fn execute(&self) -> Result<RecordBatchStream> {
+        let left_schema = self.left.schema();
+        let right_schema = self.right.schema();
+        let fields = left_schema
+            .fields()
+            .iter()
+            .chain(right_schema.fields().iter());
+        let intermediate_schema = Schema::new(fields.cloned().collect::<Vec<_>>());
+        Ok(Box::new(HashJoinStream {
+            left: Some(self.left.execute()?),
+            right: self.right.execute()?,
+            left_schema,
+            right_schema,
+            intermediate_schema: Arc::new(intermediate_schema),
+            schema: self.schema.clone(),
+            on: self.on.clone(),
+            filter: self.filter.clone(),
+            join_type: self.join_type,
+            build: None,
+            right_done: false,
+            finished: false,
+        }))
+    }
+}
+
+/// The hash table built from the left input.
+struct BuildSide {
+    batch: RecordBatch,
+    key_types: Vec<DataType>,
+    converter: RowConverter,
+    /// Numbers the distinct keys of the left rows.
+    keys: RowIndex,
+    /// The indices of the left rows that have each key, at its index.
+    matches: Vec<Vec<u32>>,
+    /// Whether each left row has matched at least one right row.
+    visited: Vec<bool>,
+}
+
+struct HashJoinStream {
+    /// Consumed when the hash table is built on the first poll.
+    left: Option<RecordBatchStream>,
+    right: RecordBatchStream,
+    left_schema: SchemaRef,
+    right_schema: SchemaRef,
+    intermediate_schema: SchemaRef,
+    schema: SchemaRef,
+    on: Arc<Vec<(PhysicalExpression, PhysicalExpression)>>,
+    filter: Option<Arc<PhysicalExpression>>,
+    join_type: JoinType,
+    build: Option<BuildSide>,
+    right_done: bool,
+    finished: bool,
+}
+
+impl Iterator for HashJoinStream {
+    type Item = Result<RecordBatch>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        match self.poll() {
+            Ok(Some(batch)) => Some(Ok(batch)),
+            Ok(None) => {
+                self.finished = true;
+                None
+            }
+            Err(err) => {
+                self.finished = true;
+                Some(Err(err))
+            }
+        }
+    }
+}
+
+impl HashJoinStream {
+    fn poll(&mut self) -> Result<Option<RecordBatch>> {
+        if let Some(left) = self.left.take() {
+            self.build = Some(self.build_side(left)?);
+        }
+        while !self.right_done {
+            let Some(batch) = self.right.next() else {
+                self.right_done = true;
+                break;
+            };
+            let output = self.probe(&batch?)?;
+            if output.num_rows() > 0 {
+                return Ok(Some(output));
+            }
+        }
+        // Rows that depend on the matches of the whole right input.
+        self.finished = true;
+        self.emit_left()
+    }
+
+    fn build_side(&self, left: RecordBatchStream) -> Result<BuildSide> {
+        let batches = left.collect::<Result<Vec<_>>>()?;
+        let batch = concat_batches(&self.left_schema, &batches)?;
+        let keys = self
+            .on
+            .iter()
+            .map(|(l, _)| l.evalate(&batch)?.into_array(batch.num_rows()))
+            .collect::<Result<Vec<_>>>()?;
+        let key_types = keys
+            .iter()
+            .map(|k| k.data_type().clone())
+            .collect::<Vec<_>>();
+        let converter = RowConverter::new(
+            key_types
+                .iter()
+                .map(|t| SortField::new(t.clone()))
+                .collect(),
+        )?;
+
+        let rows = converter.convert_columns(&keys)?;
+        let mut index = RowIndex::new(&converter);
+        let mut matches: Vec<Vec<u32>> = vec![];
+        for (i, row) in rows.iter().enumerate() {
+            if keys.iter().any(|k| k.is_null(i)) {
+                continue;
+            }
+            let (key, inserted) = index.get_or_insert(row);
+            if inserted {
+                matches.push(vec![]);
+            }
+            matches[key].push(i as u32);
+        }
+        Ok(BuildSide {
+            visited: vec![false; batch.num_rows()],
+            batch,
+            key_types,
+            converter,
+            keys: index,
+            matches,
+        })
+    }
+
+    fn probe(&mut self, batch: &RecordBatch) -> Result<RecordBatch> {
+        let build = self.build.as_mut().unwrap();
+        let keys = self
+            .on
+            .iter()
+            .zip(build.key_types.iter())
+            .map(|((_, r), data_type)| {
+                let key = r.evalate(batch)?.into_array(batch.num_rows())?;
+                Ok(cast(&key, data_type)?)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let rows = build.converter.convert_columns(&keys)?;
+
+        let mut left_indices = vec![];
+        let mut right_indices = vec![];
+        for (i, row) in rows.iter().enumerate() {
+            if keys.iter().any(|k| k.is_null(i)) {
+                continue;
+            }
+            if let Some(key) = build.keys.get(row) {
+                let matches = &build.matches[key];
+                left_indices.extend_from_slice(matches);
+                right_indices.extend(std::iter::repeat_n(i as u32, matches.len()));
+            }
+        }
+        let mut left_indices = UInt32Array::from(left_indices);
+        let mut right_indices = UInt32Array::from(right_indices);
+
+        if let Some(predicate) = &self.filter {
+            let mut columns = take_columns(&build.batch, &left_indices)?;
+            columns.extend(take_columns(batch, &right_indices)?);
+            let candidates = RecordBatch::try_new(self.intermediate_schema.clone(), columns)?;
+            let mask = predicate
+                .evalate(&candidates)?
+                .into_array(candidates.num_rows())?;
+            let mask = match mask.data_type() {
+                DataType::Boolean => mask.as_boolean().clone(),
+                DataType::Null => vec![false; mask.len()].into(),
+                other => return exec_err!("join filter must be boolean, got {}", other),
+            };
+            left_indices = filter(&left_indices, &mask)?.as_primitive().clone();
+            right_indices = filter(&right_indices, &mask)?.as_primitive().clone();
+        }
+
+        for i in left_indices.values() {
+            build.visited[*i as usize] = true;
+        }
+        let mut right_matched = vec![false; batch.num_rows()];
+        for i in right_indices.values() {
+            right_matched[*i as usize] = true;
+        }
+        let unmatched_right = indices_where(&right_matched, false);
+
+        let build = self.build.as_ref().unwrap();
+        match self.join_type {
+            JoinType::Inner | JoinType::Left => {
+                let mut columns = take_columns(&build.batch, &left_indices)?;
+                columns.extend(take_columns(batch, &right_indices)?);
+                self.output(columns, left_indices.len())
+            }
+            JoinType::Right | JoinType::Full => {
+                let mut matched = take_columns(&build.batch, &left_indices)?;
+                matched.extend(take_columns(batch, &right_indices)?);
+                let mut unmatched = null_columns(&self.left_schema, unmatched_right.len());
+                unmatched.extend(take_columns(batch, &unmatched_right)?);
+                let matched = self.output(matched, left_indices.len())?;
+                let unmatched = self.output(unmatched, unmatched_right.len())?;
+                Ok(concat_batches(&self.schema, &[matched, unmatched])?)
+            }
+            JoinType::RightSemi => {
+                let indices = indices_where(&right_matched, true);
+                self.output(take_columns(batch, &indices)?, indices.len())
+            }
+            JoinType::RightAnti => self.output(
+                take_columns(batch, &unmatched_right)?,
+                unmatched_right.len(),
+            ),
+            // Left rows are returned once the whole right input was seen.
+            JoinType::LeftSemi | JoinType::LeftAnti => {
+                Ok(RecordBatch::new_empty(self.schema.clone()))
+            }
+        }
+    }
+
+    fn emit_left(&self) -> Result<Option<RecordBatch>> {
+        let build = self.build.as_ref().unwrap();
+        let output = match self.join_type {
+            JoinType::Left | JoinType::Full => {
+                let indices = indices_where(&build.visited, false);
+                let mut columns = take_columns(&build.batch, &indices)?;
+                columns.extend(null_columns(&self.right_schema, indices.len()));
+                self.output(columns, indices.len())?
+            }
+            JoinType::LeftSemi | JoinType::LeftAnti => {
+                let indices = indices_where(&build.visited, self.join_type == JoinType::LeftSemi);
+                self.output(take_columns(&build.batch, &indices)?, indices.len())?
+            }
+            _ => return Ok(None),
+        };
+        Ok((output.num_rows() > 0).then_some(output))
+    }
+
+    fn output(&self, columns: Vec<ArrayRef>, num_rows: usize) -> Result<RecordBatch> {
+        let options = RecordBatchOptions::new().with_row_count(Some(num_rows));
+        Ok(RecordBatch::try_new_with_options(
+            self.schema.clone(),
+            columns,
+            &options,
+        )?)
+    }
+}
+
+fn take_columns(batch: &RecordBatch, indices: &UInt32Array) -> Result<Vec<ArrayRef>> {
+    batch
+        .columns()
+        .iter()
+        .map(|c| Ok(take(c.as_ref(), indices, None)?))
+        .collect()
+}
+
+fn null_columns(schema: &Schema, len: usize) -> Vec<ArrayRef> {
+    schema
+        .fields()
+        .iter()
+        .map(|f| new_null_array(f.data_type(), len))
+        .collect()
+}
+
+fn indices_where(flags: &[bool], value: bool) -> UInt32Array {
+    flags
+        .iter()
+        .enumerate()
+        .filter(|(_, flag)| **flag == value)
+        .map(|(i, _)| i as u32)
+        .collect::<Vec<_>>()
+        .into()
+}
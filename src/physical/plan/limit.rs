@@ -0,0 +1,102 @@
+use std::sync::Arc;
+
+use arrow::array::RecordBatch;
+
+use super::{PhysicalPlan, RecordBatchStream};
+use crate::error::Result;
+
+/// Skips the first `skip` rows of the input and returns at most `fetch` of
+/// the remaining ones. The input is not polled any more once `fetch` rows
+/// have been returned.
+pub struct LimitExec {
+    pub input: Arc<PhysicalPlan>,
+    pub skip: usize,
+    pub fetch: Option<usize>,
+}
+
+impl LimitExec {
+    pub fn new(input: Arc<PhysicalPlan>, skip: usize, fetch: Option<usize>) -> Self {
+        Self { input, skip, fetch }
+    }
+
+    pub fn execute(&self) -> Result<RecordBatchStream> {
+        Ok(limit_stream(self.input.execute()?, self.skip, self.fetch))
+    }
+}
+
+/// Applies `skip` and `fetch` to a stream of batches.
+pub fn limit_stream(
+    input: RecordBatchStream,
+    skip: usize,
+    fetch: Option<usize>,
+) -> RecordBatchStream {
+    Box::new(LimitStream { input, skip, fetch })
+}
+
+struct LimitStream {
+    input: RecordBatchStream,
+    /// Rows that still have to be skipped.
+    skip: usize,
+    /// Rows that may still be returned, unbounded if `None`.
+    fetch: Option<usize>,
+}
+
+impl Iterator for LimitStream {
+    type Item = Result<RecordBatch>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.fetch == Some(0) {
+                return None;
+            }
+            let batch = match self.input.next()? {
+                Ok(batch) => batch,
+                Err(err) => return Some(Err(err)),
+            };
+            let num_rows = batch.num_rows();
+            if self.skip >= num_rows {
+                self.skip -= num_rows;
+                continue;
+            }
+            let offset = std::mem::take(&mut self.skip);
+            let mut len = num_rows - offset;
+            if let Some(fetch) = self.fetch.as_mut() {
+                len = len.min(*fetch);
+                *fetch -= len;
+            }
+            return Some(Ok(batch.slice(offset, len)));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use arrow::array::{AsArray, record_batch};
+    use arrow::datatypes::Int32Type;
+
+    use super::*;
+
+    #[test]
+    fn test_limit_stream() -> Result<()> {
+        static PULLED: AtomicUsize = AtomicUsize::new(0);
+        let batches = (0..10)
+            .map(|i| record_batch!(("a", Int32, [i * 3, i * 3 + 1, i * 3 + 2])))
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        let input = batches.into_iter().map(|batch| {
+            PULLED.fetch_add(1, Ordering::SeqCst);
+            Ok(batch)
+        });
+
+        let ret = limit_stream(Box::new(input), 4, Some(3)).collect::<Result<Vec<_>>>()?;
+        let values = ret
+            .iter()
+            .flat_map(|b| b.column(0).as_primitive::<Int32Type>().values().to_vec())
+            .collect::<Vec<_>>();
+        assert_eq!(values, vec![4, 5, 6]);
+        // Rows 0..=6 live in the first three batches; the rest is never read.
+        assert_eq!(PULLED.load(Ordering::SeqCst), 3);
+        Ok(())
+    }
+}
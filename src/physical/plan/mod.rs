@@ -1,26 +1,54 @@
+mod aggregate;
 mod filter;
+mod hash_join;
+mod limit;
 pub mod projection;
+mod row_index;
 mod scan;
+mod sort;
+mod window;
 
 use crate::error::Result;
+pub use aggregate::HashAggregateExec;
 use arrow::array::RecordBatch;
 use arrow_schema::SchemaRef;
 pub use filter::FilterExec;
+pub use hash_join::HashJoinExec;
+pub use limit::{LimitExec, limit_stream};
 pub use projection::ProjectionExec;
 pub use scan::ScanExec;
+pub(crate) use sort::normalize_nan;
+pub use sort::{PhysicalSortExpr, SortExec};
+pub use window::WindowExec;
+
+/// A pull-based stream of [`RecordBatch`]es produced by an operator.
+///
+/// Each call to `next` pulls one batch from the operator, which in turn pulls
+/// from its input, so only the batches currently in flight are held in memory.
+pub type RecordBatchStream = Box<dyn Iterator<Item = Result<RecordBatch>> + Send>;
 
 pub enum PhysicalPlan {
     Projection(ProjectionExec),
     Filter(FilterExec),
     Scan(ScanExec),
+    HashAggregate(HashAggregateExec),
+    Sort(SortExec),
+    Limit(LimitExec),
+    HashJoin(HashJoinExec),
+    Window(WindowExec),
 }
 
 impl PhysicalPlan {
-    pub fn execute(&self) -> Result<RecordBatch> {
+    pub fn execute(&self) -> Result<RecordBatchStream> {
         match self {
             PhysicalPlan::Projection(exec) => exec.execute(),
             PhysicalPlan::Filter(exec) => exec.execute(),
             PhysicalPlan::Scan(exec) => exec.execute(),
+            PhysicalPlan::HashAggregate(exec) => exec.execute(),
+            PhysicalPlan::Sort(exec) => exec.execute(),
+            PhysicalPlan::Limit(exec) => exec.execute(),
+            PhysicalPlan::HashJoin(exec) => exec.execute(),
+            PhysicalPlan::Window(exec) => exec.execute(),
         }
     }
 
@@ -29,6 +57,11 @@ impl PhysicalPlan {
             PhysicalPlan::Projection(exec) => exec.schema.clone(),
             PhysicalPlan::Filter(exec) => exec.input.schema(),
             PhysicalPlan::Scan(exec) => exec.schema(),
+            PhysicalPlan::HashAggregate(exec) => exec.schema.clone(),
+            PhysicalPlan::Sort(exec) => exec.input.schema(),
+            PhysicalPlan::Limit(exec) => exec.input.schema(),
+            PhysicalPlan::HashJoin(exec) => exec.schema.clone(),
+            PhysicalPlan::Window(exec) => exec.schema.clone(),
         }
     }
 }
@@ -5,12 +5,12 @@ use crate::physical::expr::PhysicalExpression;
 use arrow::array::{RecordBatch, RecordBatchOptions};
 use arrow_schema::SchemaRef;
 
-use super::PhysicalPlan;
+use super::{PhysicalPlan, RecordBatchStream};
 
 pub struct ProjectionExec {
     pub input: Arc<PhysicalPlan>,
     pub schema: SchemaRef,
-    pub exprs: Vec<PhysicalExpression>,
+    pub exprs: Arc<Vec<PhysicalExpression>>,
 }
 
 impl ProjectionExec {
@@ -22,18 +22,29 @@ impl ProjectionExec {
         Self {
             input,
             schema,
-            exprs,
+            exprs: Arc::new(exprs),
         }
     }
 
-    pub fn execute(&self) -> Result<RecordBatch> {
-        // self.input.execute()
-        let mut batch = self.input.execute()?;
-        let columns = self
-            .exprs
+    pub fn execute(&self) -> Result<RecordBatchStream> {
+        let schema = self.schema.clone();
+        let exprs = self.exprs.clone();
+        let stream = self
+            .input
+            .execute()?
+            .map(move |batch| Self::project_batch(&schema, &exprs, &batch?));
+        Ok(Box::new(stream))
+    }
+
+    fn project_batch(
+        schema: &SchemaRef,
+        exprs: &[PhysicalExpression],
+        batch: &RecordBatch,
+    ) -> Result<RecordBatch> {
+        let columns = exprs
             .iter()
             .map(|expr| {
-                expr.evalate(&batch)
+                expr.evalate(batch)
                     .and_then(|res| res.into_array(batch.num_rows()))
             })
             .collect::<Result<Vec<_>>>()?;
@@ -41,12 +52,12 @@ impl ProjectionExec {
         if columns.is_empty() {
             let options = RecordBatchOptions::new().with_row_count(Some(batch.num_rows()));
             return Ok(RecordBatch::try_new_with_options(
-                self.schema.clone(),
+                schema.clone(),
                 columns,
                 &options,
             )?);
         }
 
-        Ok(RecordBatch::try_new(self.schema.clone(), columns)?)
+        Ok(RecordBatch::try_new(schema.clone(), columns)?)
     }
 }
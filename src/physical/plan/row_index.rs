@@ -0,0 +1,97 @@
+use std::hash::{BuildHasher, RandomState};
+
+use arrow::row::{Row, RowConverter, Rows};
+use hashbrown::HashTable;
+
+/// Numbers the distinct rows encoded by a [`RowConverter`] in the order they
+/// are first seen. Rows are looked up by their hash and compared in place,
+/// so only a row that was not seen before is copied.
+pub(super) struct RowIndex {
+    /// Every distinct row, at its index.
+    rows: Rows,
+    /// Holds the index of every row in `rows`, keyed by the hash of its
+    /// bytes.
+    table: HashTable<usize>,
+    hasher: RandomState,
+}
+
+impl RowIndex {
+    pub fn new(converter: &RowConverter) -> Self {
+        Self {
+            rows: converter.empty_rows(0, 0),
+            table: HashTable::new(),
+            hasher: RandomState::new(),
+        }
+    }
+
+    /// Returns the index of `row` if it was inserted before.
+    pub fn get(&self, row: Row<'_>) -> Option<usize> {
+        let hash = self.hasher.hash_one(row);
+        self.table.find(hash, |&i| self.rows.row(i) == row).copied()
+    }
+
+    /// Returns the index of `row`, inserting it first if it is new, and
+    /// whether it was inserted.
+    pub fn get_or_insert(&mut self, row: Row<'_>) -> (usize, bool) {
+        let hash = self.hasher.hash_one(row);
+        if let Some(&i) = self.table.find(hash, |&i| self.rows.row(i) == row) {
+            return (i, false);
+        }
+        let i = self.rows.num_rows();
+        self.rows.push(row);
+        let (rows, hasher) = (&self.rows, &self.hasher);
+        self.table
+            .insert_unique(hash, i, |&i| hasher.hash_one(rows.row(i)));
+        (i, true)
+    }
+
+    /// Returns the distinct rows, each at its index.
+    pub fn rows(&self) -> &Rows {
+        &self.rows
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow::{
+        array::{ArrayRef, AsArray, Int32Array, StringArray},
+        row::SortField,
+    };
+    use arrow_schema::DataType;
+
+    use super::*;
+    use crate::error::Result;
+
+    #[test]
+    fn test_row_index() -> Result<()> {
+        let converter = RowConverter::new(vec![
+            SortField::new(DataType::Utf8),
+            SortField::new(DataType::Int32),
+        ])?;
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(StringArray::from(vec![
+                Some("a"),
+                Some("b"),
+                Some("a"),
+                None,
+            ])),
+            Arc::new(Int32Array::from(vec![1, 1, 1, 1])),
+        ];
+        let rows = converter.convert_columns(&columns)?;
+
+        let mut index = RowIndex::new(&converter);
+        let ids = rows
+            .iter()
+            .map(|row| index.get_or_insert(row))
+            .collect::<Vec<_>>();
+        assert_eq!(ids, vec![(0, true), (1, true), (0, false), (2, true)]);
+        let distinct = converter.convert_rows(index.rows())?;
+        assert_eq!(
+            distinct[0].as_string::<i32>(),
+            &StringArray::from(vec![Some("a"), Some("b"), None])
+        );
+        Ok(())
+    }
+}
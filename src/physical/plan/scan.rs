@@ -3,28 +3,76 @@ use std::sync::Arc;
 use arrow::array::RecordBatch;
 use arrow_schema::SchemaRef;
 
-use crate::{datasource::DataSource, error::Result};
+use super::{RecordBatchStream, filter::filter_batch};
+use crate::{
+    datasource::{DataSource, project_schema},
+    error::Result,
+    physical::expr::PhysicalExpression,
+};
 
 pub struct ScanExec {
-    path: String,
     ds: Arc<dyn DataSource>,
     projection: Option<Vec<String>>,
+    /// The columns requested from the source: `projection` plus the columns
+    /// needed to evaluate `filters`.
+    read_columns: Option<Vec<String>>,
+    /// Predicates evaluated against the columns in `read_columns`.
+    filters: Arc<Vec<PhysicalExpression>>,
+    /// The maximum number of rows to read, only used without `filters`.
+    fetch: Option<usize>,
 }
 
 impl ScanExec {
-    pub fn new(path: String, ds: Arc<dyn DataSource>, projection: Option<Vec<String>>) -> Self {
+    pub fn new(
+        ds: Arc<dyn DataSource>,
+        projection: Option<Vec<String>>,
+        read_columns: Option<Vec<String>>,
+        filters: Vec<PhysicalExpression>,
+        fetch: Option<usize>,
+    ) -> Self {
         Self {
-            path,
             ds,
             projection,
+            read_columns,
+            filters: Arc::new(filters),
+            fetch,
         }
     }
 
-    pub fn execute(&self) -> Result<RecordBatch> {
-        self.ds.scan(self.projection.clone())
+    pub fn execute(&self) -> Result<RecordBatchStream> {
+        if self.filters.is_empty() {
+            return self.ds.scan(self.read_columns.clone(), self.fetch);
+        }
+        let stream = self.ds.scan(self.read_columns.clone(), None)?;
+
+        // The source already returns `read_columns`, so only the columns that
+        // were read for the filters alone have to be dropped afterwards.
+        let projection = self.projection.clone();
+        let filters = self.filters.clone();
+        let stream = stream.map(move |batch| {
+            let mut batch = batch?;
+            for filter in filters.iter() {
+                batch = filter_batch(filter, &batch)?;
+            }
+            project_batch(batch, projection.as_ref())
+        });
+        Ok(Box::new(stream))
     }
 
     pub fn schema(&self) -> SchemaRef {
-        self.ds.schema()
+        project_schema(&self.ds.schema(), self.projection.as_ref())
     }
 }
+
+/// Selects the named columns of `batch`.
+fn project_batch(batch: RecordBatch, columns: Option<&Vec<String>>) -> Result<RecordBatch> {
+    let Some(columns) = columns else {
+        return Ok(batch);
+    };
+    let schema = batch.schema();
+    let indices = columns
+        .iter()
+        .map(|name| schema.index_of(name))
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    Ok(batch.project(&indices)?)
+}
@@ -0,0 +1,157 @@
+use std::sync::Arc;
+
+use arrow::{
+    array::{ArrayRef, AsArray, RecordBatch},
+    compute::{SortColumn, SortOptions, concat_batches, lexsort_to_indices, take_record_batch},
+    datatypes::{Float32Type, Float64Type},
+};
+use arrow_schema::DataType;
+
+use super::{PhysicalPlan, RecordBatchStream};
+use crate::{error::Result, physical::expr::PhysicalExpression};
+
+#[derive(Debug)]
+pub struct PhysicalSortExpr {
+    pub expr: PhysicalExpression,
+    pub options: SortOptions,
+}
+
+impl PhysicalSortExpr {
+    pub fn new(expr: PhysicalExpression, asc: bool, nulls_first: bool) -> Self {
+        Self {
+            expr,
+            options: SortOptions {
+                descending: !asc,
+                nulls_first,
+            },
+        }
+    }
+}
+
+/// Sorts the whole input by `exprs`. All input batches are buffered and the
+/// result is produced as a single batch. Floating point values are ordered
+/// by their total order after [`normalize_nan`], so NaN sorts after every
+/// other number.
+pub struct SortExec {
+    pub input: Arc<PhysicalPlan>,
+    pub exprs: Arc<Vec<PhysicalSortExpr>>,
+}
+
+impl SortExec {
+    pub fn new(input: Arc<PhysicalPlan>, exprs: Vec<PhysicalSortExpr>) -> Self {
+        Self {
+            input,
+            exprs: Arc::new(exprs),
+        }
+    }
+
+    pub fn execute(&self) -> Result<RecordBatchStream> {
+        let input = self.input.execute()?;
+        let schema = self.input.schema();
+        let exprs = self.exprs.clone();
+        Ok(Box::new(std::iter::once_with(move || {
+            let batches = input.collect::<Result<Vec<_>>>()?;
+            let batch = concat_batches(&schema, &batches)?;
+            sort_batch(&batch, &exprs)
+        })))
+    }
+}
+
+fn sort_batch(batch: &RecordBatch, exprs: &[PhysicalSortExpr]) -> Result<RecordBatch> {
+    let columns = exprs
+        .iter()
+        .map(|e| {
+            Ok(SortColumn {
+                values: normalize_nan(e.expr.evalate(batch)?.into_array(batch.num_rows())?),
+                options: Some(e.options),
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let indices = lexsort_to_indices(&columns, None)?;
+    Ok(take_record_batch(batch, &indices)?)
+}
+
+/// Replaces every NaN by the positive one. Computed NaNs can have the sign
+/// bit set, which the total order puts before every other number.
+pub(crate) fn normalize_nan(values: ArrayRef) -> ArrayRef {
+    match values.data_type() {
+        DataType::Float32 => Arc::new(
+            values
+                .as_primitive::<Float32Type>()
+                .unary::<_, Float32Type>(|v| if v.is_nan() { f32::NAN } else { v }),
+        ),
+        DataType::Float64 => Arc::new(
+            values
+                .as_primitive::<Float64Type>()
+                .unary::<_, Float64Type>(|v| if v.is_nan() { f64::NAN } else { v }),
+        ),
+        _ => values,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::array::{Array, AsArray, Float64Array, record_batch};
+    use arrow::datatypes::{Float64Type, Int32Type};
+
+    use super::*;
+    use crate::physical::expr::column::ColumnExpr;
+
+    fn sort_expr(index: usize, asc: bool, nulls_first: bool) -> PhysicalSortExpr {
+        PhysicalSortExpr::new(
+            PhysicalExpression::Column(ColumnExpr::new("", index)),
+            asc,
+            nulls_first,
+        )
+    }
+
+    #[test]
+    fn test_sort_batch() -> Result<()> {
+        let batch = record_batch!(
+            ("a", Int32, [Some(1), Some(2), None, Some(1), Some(2)]),
+            ("b", Float64, [
+                Some(1.0),
+                Some(f64::NAN),
+                Some(0.0),
+                None,
+                Some(-1.0)
+            ])
+        )?;
+
+        let sorted = sort_batch(&batch, &[
+            sort_expr(0, false, true),
+            sort_expr(1, true, false),
+        ])?;
+        let a = sorted.column(0).as_primitive::<Int32Type>();
+        assert_eq!(a.iter().collect::<Vec<_>>(), vec![
+            None,
+            Some(2),
+            Some(2),
+            Some(1),
+            Some(1)
+        ]);
+        let b = sorted.column(1).as_primitive::<Float64Type>();
+        assert_eq!(b.value(1), -1.0);
+        assert!(b.value(2).is_nan());
+        assert_eq!(b.value(3), 1.0);
+        assert!(b.is_null(4));
+
+        let sorted = sort_batch(&batch, &[sort_expr(1, true, true)])?;
+        let b = sorted.column(1).as_primitive::<Float64Type>().clone();
+        assert!(b.is_null(0));
+        assert_eq!(b.slice(1, 3), Float64Array::from(vec![-1.0, 0.0, 1.0]));
+        assert!(b.value(4).is_nan());
+
+        // A NaN computed from a negative square root sorts last as well.
+        let nan = std::hint::black_box(-1.0_f64).sqrt();
+        let batch = RecordBatch::try_from_iter(vec![(
+            "b",
+            Arc::new(Float64Array::from(vec![nan, 1.0, -f64::NAN, 0.0])) as ArrayRef,
+        )])?;
+        let sorted = sort_batch(&batch, &[sort_expr(0, true, false)])?;
+        let b = sorted.column(0).as_primitive::<Float64Type>();
+        assert_eq!(b.slice(0, 2), Float64Array::from(vec![0.0, 1.0]));
+        assert!(b.value(2).is_nan() && b.value(3).is_nan());
+        Ok(())
+    }
+}
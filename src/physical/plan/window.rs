@@ -0,0 +1,42 @@
+use std::sync::Arc;
+
+use arrow::{array::RecordBatch, compute::concat_batches};
+use arrow_schema::SchemaRef;
+
+use super::{PhysicalPlan, RecordBatchStream};
+use crate::{error::Result, physical::expr::window::WindowExpr};
+
+/// Appends the value of every window expression to the input rows. All
+/// input batches are buffered, since a partition may span all of them, and
+/// the result is produced as a single batch with the rows in input order.
+pub struct WindowExec {
+    pub input: Arc<PhysicalPlan>,
+    pub window_exprs: Arc<Vec<WindowExpr>>,
+    pub schema: SchemaRef,
+}
+
+impl WindowExec {
+    pub fn new(input: Arc<PhysicalPlan>, window_exprs: Vec<WindowExpr>, schema: SchemaRef) -> Self {
+        Self {
+            input,
+            window_exprs: Arc::new(window_exprs),
+            schema,
+        }
+    }
+
+    pub fn execute(&self) -> Result<RecordBatchStream> {
+        let input = self.input.execute()?;
+        let input_schema = self.input.schema();
+        let schema = self.schema.clone();
+        let window_exprs = self.window_exprs.clone();
+        Ok(Box::new(std::iter::once_with(move || {
+            let batches = input.collect::<Result<Vec<_>>>()?;
+            let batch = concat_batches(&input_schema, &batches)?;
+            let mut columns = batch.columns().to_vec();
+            for expr in window_exprs.iter() {
+                columns.push(expr.evaluate(&batch)?);
+            }
+            Ok(RecordBatch::try_new(schema, columns)?)
+        })))
+    }
+}
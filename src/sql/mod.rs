@@ -0,0 +1,3 @@
+pub mod planner;
+
+pub use planner::{ContextProvider, SqlPlanner};
@@ -0,0 +1,1517 @@
+use std::sync::Arc;
+
+use arrow::compute::{CastOptions, cast_with_options};
+use arrow_schema::{DECIMAL128_MAX_PRECISION, DataType, IntervalUnit, TimeUnit};
+use sqlparser::{
+    ast::{
+        BinaryOperator, CastKind, CeilFloorKind, DataType as SqlDataType, DateTimeField,
+        DuplicateTreatment, ExactNumberInfo, Expr, FunctionArg, FunctionArgExpr, FunctionArguments,
+        GroupByExpr, Interval, Join as SqlJoin, JoinConstraint, JoinOperator, OrderBy, OrderByExpr,
+        Query, Select, SelectItem, SetExpr, Statement, TableAlias, TableFactor, TableWithJoins,
+        TimezoneInfo, TrimWhereField, UnaryOperator, Value,
+        WindowFrameBound as SqlWindowFrameBound, WindowFrameUnits as SqlWindowFrameUnits,
+        WindowType,
+    },
+    dialect::GenericDialect,
+    parser::Parser,
+};
+
+use crate::{
+    catalog::TableReference,
+    datasource::DataSource,
+    datatypes::{operator::Operator, values::ScalarValue},
+    error::{DBError, Result, internal_err, not_impl_err, plan_err},
+    logical::{
+        expression::{
+            aggregate::AggregateFunction,
+            column::Column,
+            conditional::Case,
+            expr::LogicalExpr,
+            expr_fn::{
+                avg, binary_expr, ceil, coalesce, count, count_distinct, date_part, floor,
+                if_then_else, lit, max, min, nullif, regexp_like, scalar_function, strpos, substr,
+                substring, sum, window_function,
+            },
+            function::ScalarFunction,
+            like::Like,
+            sort::SortExpr,
+            window::{WindowFrame, WindowFrameBound, WindowFrameUnits, WindowFunction},
+        },
+        plan::{
+            Aggregate, Filter, Join, JoinType, Limit, LogicalPlan, Projection, Scan, Sort,
+            SubqueryAlias, Window,
+        },
+        schema::QualifiedSchema,
+    },
+    optimizer::{conjunction, split_conjunction},
+};
+
+/// Resolves the table names referenced by a SQL query.
+pub trait ContextProvider {
+    fn get_table(&self, table: &TableReference) -> Option<Arc<dyn DataSource>>;
+}
+
+/// Lowers SQL text into a [`LogicalPlan`].
+pub struct SqlPlanner<'a> {
+    provider: &'a dyn ContextProvider,
+}
+
+impl<'a> SqlPlanner<'a> {
+    pub fn new(provider: &'a dyn ContextProvider) -> Self {
+        Self { provider }
+    }
+
+    pub fn sql_to_plan(&self, sql: &str) -> Result<LogicalPlan> {
+        let mut statements = Parser::parse_sql(&GenericDialect {}, sql)?;
+        if statements.len() != 1 {
+            return plan_err!(
+                "expected exactly one SQL statement, got {}",
+                statements.len()
+            );
+        }
+        match statements.remove(0) {
+            Statement::Query(query) => self.query_to_plan(*query),
+            other => not_impl_err!("unsupported SQL statement: {}", other),
+        }
+    }
+
+    fn query_to_plan(&self, query: Query) -> Result<LogicalPlan> {
+        if query.with.is_some() {
+            return not_impl_err!("unsupported SQL: WITH");
+        }
+        let sort_exprs = match &query.order_by {
+            Some(order_by) => self.order_by_to_sort_exprs(order_by)?,
+            None => vec![],
+        };
+        let mut plan = match *query.body {
+            SetExpr::Select(select) => self.select_to_plan(*select, &sort_exprs)?,
+            other => return not_impl_err!("unsupported SQL query: {}", other),
+        };
+        if !sort_exprs.is_empty() {
+            plan = self.order_by_to_plan(plan, sort_exprs)?;
+        }
+        if !query.limit_by.is_empty() || query.fetch.is_some() {
+            return not_impl_err!("unsupported SQL: LIMIT BY/FETCH");
+        }
+        if query.limit.is_some() || query.offset.is_some() {
+            let skip = match &query.offset {
+                Some(offset) => parse_limit_value(&offset.value)?,
+                None => 0,
+            };
+            let fetch = query.limit.as_ref().map(parse_limit_value).transpose()?;
+            plan = LogicalPlan::Limit(Limit::new(Arc::new(plan), skip, fetch));
+        }
+        Ok(plan)
+    }
+
+    fn order_by_to_sort_exprs(&self, order_by: &OrderBy) -> Result<Vec<SortExpr>> {
+        if order_by.interpolate.is_some() {
+            return not_impl_err!("unsupported SQL: INTERPOLATE");
+        }
+        self.sql_to_sort_exprs(&order_by.exprs)
+    }
+
+    fn sql_to_sort_exprs(&self, exprs: &[OrderByExpr]) -> Result<Vec<SortExpr>> {
+        exprs
+            .iter()
+            .map(|e| {
+                if e.with_fill.is_some() {
+                    return not_impl_err!("unsupported SQL: WITH FILL");
+                }
+                // Like PostgreSQL, NULLs are treated as larger than any value.
+                let asc = e.asc.unwrap_or(true);
+                Ok(self
+                    .sql_to_expr(&e.expr)?
+                    .sort(asc, e.nulls_first.unwrap_or(!asc)))
+            })
+            .collect()
+    }
+
+    /// Sorts the output of a SELECT. Keys are matched against the select list
+    /// first, so `ORDER BY c3 + 1` reuses the computed column; keys that need
+    /// columns which are not selected are sorted below the projection. An
+    /// integer key, as in `ORDER BY 2`, is the position of a selected column.
+    fn order_by_to_plan(
+        &self,
+        plan: LogicalPlan,
+        sort_exprs: Vec<SortExpr>,
+    ) -> Result<LogicalPlan> {
+        let LogicalPlan::Projection(projection) = &plan else {
+            return internal_err!("expected a projection below ORDER BY, got {}", plan);
+        };
+
+        let output_schema = plan.schema();
+        let output_columns = output_schema.columns();
+        let sort_exprs = sort_exprs
+            .into_iter()
+            .map(|e| match e.expr.as_ref() {
+                LogicalExpr::Literal(ScalarValue::Int64(Some(position))) => {
+                    let column = usize::try_from(*position)
+                        .ok()
+                        .and_then(|position| position.checked_sub(1))
+                        .and_then(|index| output_columns.get(index));
+                    match column {
+                        Some(column) => Ok(e.with_expr(LogicalExpr::Column(column.clone()))),
+                        None => {
+                            plan_err!("ORDER BY position {} is not in the select list", position)
+                        }
+                    }
+                }
+                _ => Ok(e),
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let output_exprs = sort_exprs
+            .iter()
+            .map(|e| e.with_expr(resolve_columns(&e.expr, &output_schema)))
+            .collect::<Vec<_>>();
+        let selected = output_exprs
+            .iter()
+            .flat_map(|e| e.expr.columns())
+            .all(|c| output_schema.has_column(c));
+        if selected {
+            return Ok(LogicalPlan::Sort(Sort::new(Arc::new(plan), output_exprs)));
+        }
+
+        // Below the projection, output aliases stand for the expressions they
+        // name.
+        let unalias = |e: &LogicalExpr| -> Option<LogicalExpr> {
+            let LogicalExpr::Column(column) = e else {
+                return None;
+            };
+            projection.exprs.iter().find_map(|expr| match expr {
+                LogicalExpr::Alias(alias)
+                    if column.relation.is_none() && alias.name == column.name =>
+                {
+                    Some(alias.expr.as_ref().clone())
+                }
+                _ => None,
+            })
+        };
+        let input_schema = projection.input.schema();
+        let input_exprs = sort_exprs
+            .iter()
+            .map(|e| e.with_expr(resolve_columns(&e.expr.transform(&unalias), &input_schema)))
+            .collect::<Vec<_>>();
+        let exprs = input_exprs
+            .iter()
+            .map(|e| e.expr.as_ref().clone())
+            .collect::<Vec<_>>();
+        check_columns(&exprs, &input_schema)?;
+        let sort = LogicalPlan::Sort(Sort::new(projection.input.clone(), input_exprs));
+        Ok(LogicalPlan::Projection(Projection::try_new(
+            Arc::new(sort),
+            projection.exprs.clone(),
+        )?))
+    }
+
+    /// Aggregates and window functions used by `sort_exprs` are computed along
+    /// with the ones of the select list, so a query can be ordered by values
+    /// it does not select.
+    fn select_to_plan(&self, select: Select, sort_exprs: &[SortExpr]) -> Result<LogicalPlan> {
+        if select.distinct.is_some() {
+            return not_impl_err!("unsupported SQL: DISTINCT");
+        }
+
+        let mut plan = self.plan_from_tables(&select.from)?;
+
+        if let Some(predicate) = &select.selection {
+            let predicate = self.sql_to_expr(predicate)?;
+            if !find_window_exprs(&predicate).is_empty() {
+                return plan_err!("window functions are not allowed in WHERE");
+            }
+            plan = LogicalPlan::Filter(Filter::new(Arc::new(plan), predicate));
+        }
+
+        let input_schema = plan.schema();
+        let mut select_exprs = vec![];
+        for item in select.projection.iter() {
+            match item {
+                SelectItem::UnnamedExpr(expr) => select_exprs.push(self.sql_to_expr(expr)?),
+                SelectItem::ExprWithAlias { expr, alias } => {
+                    select_exprs.push(self.sql_to_expr(expr)?.alias(alias.value.clone()))
+                }
+                SelectItem::Wildcard(_) => {
+                    select_exprs.extend(input_schema.columns().into_iter().map(LogicalExpr::Column))
+                }
+                SelectItem::QualifiedWildcard(name, _) => {
+                    let relation = name.to_string();
+                    let columns = input_schema
+                        .columns()
+                        .into_iter()
+                        .filter(|c| c.relation.as_ref() == Some(&relation))
+                        .collect::<Vec<_>>();
+                    if columns.is_empty() {
+                        return plan_err!("no relation named '{}' in FROM", relation);
+                    }
+                    select_exprs.extend(columns.into_iter().map(LogicalExpr::Column));
+                }
+            }
+        }
+
+        let group_exprs = match &select.group_by {
+            GroupByExpr::Expressions(exprs, modifiers) if modifiers.is_empty() => exprs
+                .iter()
+                .map(|e| self.sql_to_expr(e))
+                .collect::<Result<Vec<_>>>()?,
+            other => return not_impl_err!("unsupported SQL: {}", other),
+        };
+        let having = select
+            .having
+            .as_ref()
+            .map(|e| self.sql_to_expr(e))
+            .transpose()?;
+        if group_exprs
+            .iter()
+            .chain(having.iter())
+            .any(|e| !find_window_exprs(e).is_empty())
+        {
+            return plan_err!("window functions are not allowed in GROUP BY or HAVING");
+        }
+
+        let mut aggr_exprs: Vec<LogicalExpr> = vec![];
+        let sort_keys = sort_exprs
+            .iter()
+            .map(|e| e.expr.as_ref().clone())
+            .collect::<Vec<_>>();
+        for expr in select_exprs.iter().chain(having.iter()).chain(&sort_keys) {
+            for aggr in find_aggregate_exprs(expr) {
+                if !aggr_exprs.iter().any(|e| e.to_string() == aggr.to_string()) {
+                    aggr_exprs.push(aggr);
+                }
+            }
+        }
+
+        check_columns(&select_exprs, &input_schema)?;
+        check_columns(&group_exprs, &input_schema)?;
+        if group_exprs.is_empty() && aggr_exprs.is_empty() {
+            if having.is_some() {
+                return plan_err!("HAVING requires GROUP BY or an aggregate function");
+            }
+            return window_projection(plan, select_exprs, &sort_keys);
+        }
+
+        // The select list and HAVING are evaluated on top of the aggregate, so
+        // every group expression and aggregate becomes a reference to the
+        // matching output column.
+        let aggregate =
+            Aggregate::try_new(Arc::new(plan), group_exprs.clone(), aggr_exprs.clone())?;
+        let schema = aggregate.schema.clone();
+        let mut plan = LogicalPlan::Aggregate(aggregate);
+        // A grouped column keeps its qualifier, so `t1.c1` and `t2.c1` stay
+        // distinct.
+        let to_output_column = |expr: &LogicalExpr| -> Option<LogicalExpr> {
+            let name = expr.name();
+            let matched = group_exprs
+                .iter()
+                .chain(aggr_exprs.iter())
+                .find(|e| e.name() == name)?;
+            Some(match matched {
+                LogicalExpr::Column(_) => matched.clone(),
+                _ => LogicalExpr::Column(Column::new(name)),
+            })
+        };
+
+        if let Some(having) = having {
+            let predicate = having.transform(&to_output_column);
+            check_columns(std::slice::from_ref(&having), &input_schema)?;
+            check_grouped_columns(std::slice::from_ref(&predicate), &schema)?;
+            plan = LogicalPlan::Filter(Filter::new(Arc::new(plan), predicate));
+        }
+
+        let select_exprs = select_exprs
+            .iter()
+            .map(|e| e.transform(&to_output_column))
+            .collect::<Vec<_>>();
+        check_grouped_columns(&select_exprs, &schema)?;
+        let sort_keys = sort_keys
+            .iter()
+            .map(|e| e.transform(&to_output_column))
+            .collect::<Vec<_>>();
+        window_projection(plan, select_exprs, &sort_keys)
+    }
+
+    fn plan_from_tables(&self, from: &[TableWithJoins]) -> Result<LogicalPlan> {
+        let [table] = from else {
+            return not_impl_err!("unsupported SQL: expected exactly one table in FROM");
+        };
+        let mut plan = self.relation_to_plan(&table.relation)?;
+        for join in table.joins.iter() {
+            let right = self.relation_to_plan(&join.relation)?;
+            plan = self.join_to_plan(plan, right, join)?;
+        }
+        Ok(plan)
+    }
+
+    /// Splits the ON condition into pairs of equal keys, one from each side,
+    /// and a filter made of the remaining conjuncts.
+    fn join_to_plan(
+        &self,
+        left: LogicalPlan,
+        right: LogicalPlan,
+        join: &SqlJoin,
+    ) -> Result<LogicalPlan> {
+        let (join_type, constraint) = match &join.join_operator {
+            JoinOperator::Inner(c) => (JoinType::Inner, c),
+            JoinOperator::LeftOuter(c) => (JoinType::Left, c),
+            JoinOperator::RightOuter(c) => (JoinType::Right, c),
+            JoinOperator::FullOuter(c) => (JoinType::Full, c),
+            JoinOperator::Semi(c) | JoinOperator::LeftSemi(c) => (JoinType::LeftSemi, c),
+            JoinOperator::RightSemi(c) => (JoinType::RightSemi, c),
+            JoinOperator::Anti(c) | JoinOperator::LeftAnti(c) => (JoinType::LeftAnti, c),
+            JoinOperator::RightAnti(c) => (JoinType::RightAnti, c),
+            _ => return not_impl_err!("unsupported SQL join: {}", join),
+        };
+        let JoinConstraint::On(condition) = constraint else {
+            return not_impl_err!("unsupported SQL join constraint: {}", join);
+        };
+
+        let left_schema = left.schema();
+        let right_schema = right.schema();
+        let refers_to = |expr: &LogicalExpr, schema: &QualifiedSchema| {
+            let columns = expr.columns();
+            !columns.is_empty() && columns.iter().all(|c| schema.has_column(c))
+        };
+        let mut on = vec![];
+        let mut filters = vec![];
+        for expr in split_conjunction(&self.sql_to_expr(condition)?) {
+            match &expr {
+                LogicalExpr::Binary(e) if e.op == Operator::Eq => {
+                    let (lhs, rhs) = (e.lhs.as_ref().clone(), e.rhs.as_ref().clone());
+                    if refers_to(&lhs, &left_schema) && refers_to(&rhs, &right_schema) {
+                        on.push((lhs, rhs));
+                    } else if refers_to(&rhs, &left_schema) && refers_to(&lhs, &right_schema) {
+                        on.push((rhs, lhs));
+                    } else {
+                        filters.push(expr);
+                    }
+                }
+                _ => filters.push(expr),
+            }
+        }
+        if on.is_empty() {
+            return not_impl_err!(
+                "unsupported SQL: join without an equality between both sides: {}",
+                join
+            );
+        }
+        Ok(LogicalPlan::Join(Join::try_new(
+            Arc::new(left),
+            Arc::new(right),
+            on,
+            conjunction(filters),
+            join_type,
+        )?))
+    }
+
+    fn relation_to_plan(&self, relation: &TableFactor) -> Result<LogicalPlan> {
+        match relation {
+            TableFactor::Derived {
+                lateral: false,
+                subquery,
+                alias,
+            } => {
+                let plan = self.query_to_plan(subquery.as_ref().clone())?;
+                match table_alias(alias.as_ref())? {
+                    Some(alias) => Ok(LogicalPlan::SubqueryAlias(SubqueryAlias::try_new(
+                        Arc::new(plan),
+                        alias,
+                    )?)),
+                    None => Ok(plan),
+                }
+            }
+            TableFactor::Table {
+                name,
+                alias,
+                args: None,
+                with_hints,
+                version: None,
+                partitions,
+                ..
+            } if with_hints.is_empty() && partitions.is_empty() => {
+                let table = match name.0.as_slice() {
+                    [table] => TableReference::bare(table.value.clone()),
+                    [schema, table] => TableReference::Partial {
+                        schema: schema.value.clone(),
+                        table: table.value.clone(),
+                    },
+                    [catalog, schema, table] => TableReference::Full {
+                        catalog: catalog.value.clone(),
+                        schema: schema.value.clone(),
+                        table: table.value.clone(),
+                    },
+                    _ => return plan_err!("invalid table name '{}'", name),
+                };
+                let source = self
+                    .provider
+                    .get_table(&table)
+                    .ok_or_else(|| DBError::Plan(format!("table '{}' not found", table)))?;
+                // Columns are qualified by the table name unless it is aliased.
+                let qualifier = table_alias(alias.as_ref())?.unwrap_or(table.table().to_string());
+                Ok(LogicalPlan::Scan(
+                    Scan::new(table.to_string(), source, None).with_qualifier(qualifier),
+                ))
+            }
+            other => not_impl_err!("unsupported SQL table: {}", other),
+        }
+    }
+
+    fn sql_to_expr(&self, expr: &Expr) -> Result<LogicalExpr> {
+        match expr {
+            Expr::Identifier(ident) => Ok(LogicalExpr::Column(Column::new(ident.value.clone()))),
+            Expr::CompoundIdentifier(idents) => match idents.as_slice() {
+                [relation, name] => Ok(LogicalExpr::Column(Column::new_qualified(
+                    Some(relation.value.clone()),
+                    name.value.clone(),
+                ))),
+                _ => not_impl_err!("unsupported SQL identifier: {}", expr),
+            },
+            Expr::Value(value) => parse_value(value),
+            Expr::Nested(expr) => self.sql_to_expr(expr),
+            Expr::BinaryOp { left, op, right } => Ok(binary_expr(
+                self.sql_to_expr(left)?,
+                parse_binary_op(op)?,
+                self.sql_to_expr(right)?,
+            )),
+            Expr::UnaryOp {
+                op: UnaryOperator::Plus,
+                expr,
+            } => self.sql_to_expr(expr),
+            Expr::UnaryOp {
+                op: UnaryOperator::Minus,
+                expr,
+            } => match expr.as_ref() {
+                // Parsed as one literal, so the most negative integers fit.
+                Expr::Value(Value::Number(n, _)) => parse_number(&format!("-{}", n)),
+                other => Ok(-self.sql_to_expr(other)?),
+            },
+            Expr::UnaryOp {
+                op: UnaryOperator::Not,
+                expr,
+            } => Ok(!self.sql_to_expr(expr)?),
+            Expr::IsNull(expr) => Ok(self.sql_to_expr(expr)?.is_null()),
+            Expr::IsNotNull(expr) => Ok(self.sql_to_expr(expr)?.is_not_null()),
+            Expr::IsTrue(expr) => Ok(self.sql_to_expr(expr)?.is_true()),
+            Expr::IsNotTrue(expr) => Ok(self.sql_to_expr(expr)?.is_not_true()),
+            Expr::IsFalse(expr) => Ok(self.sql_to_expr(expr)?.is_false()),
+            Expr::IsNotFalse(expr) => Ok(self.sql_to_expr(expr)?.is_not_false()),
+            Expr::IsUnknown(expr) => Ok(self.sql_to_expr(expr)?.is_unknown()),
+            Expr::IsNotUnknown(expr) => Ok(self.sql_to_expr(expr)?.is_not_unknown()),
+            Expr::IsDistinctFrom(lhs, rhs) => Ok(self
+                .sql_to_expr(lhs)?
+                .is_distinct_from(self.sql_to_expr(rhs)?)),
+            Expr::IsNotDistinctFrom(lhs, rhs) => Ok(self
+                .sql_to_expr(lhs)?
+                .is_not_distinct_from(self.sql_to_expr(rhs)?)),
+            Expr::InList {
+                expr,
+                list,
+                negated,
+            } => {
+                let list = list
+                    .iter()
+                    .map(|e| self.sql_to_expr(e))
+                    .collect::<Result<Vec<_>>>()?;
+                Ok(self.sql_to_expr(expr)?.in_list(list, *negated))
+            }
+            Expr::Like {
+                negated,
+                any: false,
+                expr,
+                pattern,
+                escape_char,
+            } => Ok(LogicalExpr::Like(self.sql_to_like(
+                *negated,
+                expr,
+                pattern,
+                escape_char.as_deref(),
+                false,
+            )?)),
+            Expr::ILike {
+                negated,
+                any: false,
+                expr,
+                pattern,
+                escape_char,
+            } => Ok(LogicalExpr::Like(self.sql_to_like(
+                *negated,
+                expr,
+                pattern,
+                escape_char.as_deref(),
+                true,
+            )?)),
+            Expr::SimilarTo {
+                negated,
+                expr,
+                pattern,
+                escape_char,
+            } => Ok(LogicalExpr::SimilarTo(self.sql_to_like(
+                *negated,
+                expr,
+                pattern,
+                escape_char.as_deref(),
+                false,
+            )?)),
+            Expr::RLike {
+                negated,
+                expr,
+                pattern,
+                ..
+            } => {
+                let matches = regexp_like(self.sql_to_expr(expr)?, self.sql_to_expr(pattern)?);
+                Ok(if *negated { !matches } else { matches })
+            }
+            Expr::Substring {
+                expr,
+                substring_from,
+                substring_for,
+                ..
+            } => {
+                let expr = self.sql_to_expr(expr)?;
+                // `SUBSTRING(x FOR n)` starts at the first character.
+                let start = match substring_from {
+                    Some(start) => self.sql_to_expr(start)?,
+                    None => lit(1_i64),
+                };
+                Ok(match substring_for {
+                    Some(length) => substring(expr, start, self.sql_to_expr(length)?),
+                    None => substr(expr, start),
+                })
+            }
+            Expr::Trim {
+                expr,
+                trim_where,
+                trim_what,
+                trim_characters,
+            } => {
+                let fun = match trim_where {
+                    None | Some(TrimWhereField::Both) => ScalarFunction::Trim,
+                    Some(TrimWhereField::Leading) => ScalarFunction::Ltrim,
+                    Some(TrimWhereField::Trailing) => ScalarFunction::Rtrim,
+                };
+                let mut args = vec![self.sql_to_expr(expr)?];
+                match (trim_what.as_deref(), trim_characters.as_deref()) {
+                    (Some(characters), None) | (None, Some([characters])) => {
+                        args.push(self.sql_to_expr(characters)?)
+                    }
+                    (None, None) => {}
+                    _ => return not_impl_err!("unsupported SQL expression: {}", expr),
+                }
+                Ok(scalar_function(fun, args))
+            }
+            Expr::Ceil {
+                expr,
+                field: CeilFloorKind::DateTimeField(DateTimeField::NoDateTime),
+            } => Ok(ceil(self.sql_to_expr(expr)?)),
+            Expr::Floor {
+                expr,
+                field: CeilFloorKind::DateTimeField(DateTimeField::NoDateTime),
+            } => Ok(floor(self.sql_to_expr(expr)?)),
+            Expr::Position { expr, r#in } => {
+                Ok(strpos(self.sql_to_expr(r#in)?, self.sql_to_expr(expr)?))
+            }
+            Expr::Extract { field, expr, .. } => Ok(date_part(
+                lit(date_part_field(field)),
+                self.sql_to_expr(expr)?,
+            )),
+            Expr::TypedString {
+                data_type:
+                    SqlDataType::Decimal(ExactNumberInfo::None)
+                    | SqlDataType::Numeric(ExactNumberInfo::None)
+                    | SqlDataType::Dec(ExactNumberInfo::None),
+                value,
+            } => {
+                // `DECIMAL '1.50'` keeps the digits as written.
+                let digits = value.trim().trim_start_matches(['-', '+']);
+                let (integral, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+                let scale = fraction.len().min(DECIMAL128_MAX_PRECISION as usize);
+                let precision =
+                    (integral.len() + scale).clamp(1, DECIMAL128_MAX_PRECISION as usize);
+                typed_literal(value, &DataType::Decimal128(precision as u8, scale as i8))
+            }
+            Expr::TypedString { data_type, value } => {
+                typed_literal(value, &sql_to_data_type(data_type)?)
+            }
+            Expr::Interval(interval) => interval_literal(interval),
+            Expr::Between {
+                expr,
+                negated,
+                low,
+                high,
+            } => {
+                let expr = self.sql_to_expr(expr)?;
+                let low = self.sql_to_expr(low)?;
+                let high = self.sql_to_expr(high)?;
+                Ok(if *negated {
+                    expr.not_between(low, high)
+                } else {
+                    expr.between(low, high)
+                })
+            }
+            Expr::Cast {
+                kind,
+                expr,
+                data_type,
+                format: None,
+            } => {
+                let expr = self.sql_to_expr(expr)?;
+                let data_type = sql_to_data_type(data_type)?;
+                Ok(match kind {
+                    CastKind::Cast | CastKind::DoubleColon => expr.cast_to(data_type),
+                    CastKind::TryCast | CastKind::SafeCast => expr.try_cast_to(data_type),
+                })
+            }
+            Expr::Function(function) => {
+                if function.filter.is_some() {
+                    return not_impl_err!("unsupported SQL function call: {}", function);
+                }
+                let (args, distinct) = match &function.args {
+                    FunctionArguments::List(list) => (
+                        list.args.as_slice(),
+                        matches!(list.duplicate_treatment, Some(DuplicateTreatment::Distinct)),
+                    ),
+                    // `CURRENT_DATE` and `CURRENT_TIMESTAMP` take no parentheses.
+                    FunctionArguments::None => (&[] as &[FunctionArg], false),
+                    FunctionArguments::Subquery(_) => {
+                        return not_impl_err!("unsupported SQL function call: {}", function);
+                    }
+                };
+                let name = function.name.to_string().to_lowercase();
+                let args = args
+                    .iter()
+                    .map(|arg| match arg {
+                        FunctionArg::Unnamed(FunctionArgExpr::Expr(e)) => self.sql_to_expr(e),
+                        FunctionArg::Unnamed(FunctionArgExpr::Wildcard) if name == "count" => {
+                            Ok(lit(1_i64))
+                        }
+                        other => not_impl_err!("unsupported SQL function argument: {}", other),
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                if let Some(over) = &function.over {
+                    if distinct {
+                        return not_impl_err!("unsupported SQL: DISTINCT in {}", function);
+                    }
+                    return self.sql_to_window_expr(&name, args, over);
+                }
+                if distinct && !matches!(name.as_str(), "count" | "min" | "max") {
+                    return not_impl_err!("unsupported SQL function: {}", function);
+                }
+                if let Some(fun) = ScalarFunction::from_name(&name) {
+                    return Ok(scalar_function(fun, args));
+                }
+                match name.as_str() {
+                    "count" if distinct => {
+                        let [arg] = function_args(&name, args)?;
+                        Ok(count_distinct(arg))
+                    }
+                    "count" => function_args(&name, args).map(|[arg]| count(arg)),
+                    "sum" => function_args(&name, args).map(|[arg]| sum(arg)),
+                    "min" => function_args(&name, args).map(|[arg]| min(arg)),
+                    "max" => function_args(&name, args).map(|[arg]| max(arg)),
+                    "avg" => function_args(&name, args).map(|[arg]| avg(arg)),
+                    "coalesce" if args.is_empty() => {
+                        plan_err!("function coalesce expects at least one argument")
+                    }
+                    "coalesce" => Ok(coalesce(args)),
+                    "nullif" => function_args(&name, args).map(|[lhs, rhs]| nullif(lhs, rhs)),
+                    "if" => function_args(&name, args).map(|[condition, then, otherwise]| {
+                        if_then_else(condition, then, otherwise)
+                    }),
+                    _ => not_impl_err!("unsupported SQL function: {}", function),
+                }
+            }
+            Expr::Case {
+                operand,
+                conditions,
+                results,
+                else_result,
+            } => {
+                let to_expr =
+                    |e: &Expr| -> Result<Arc<LogicalExpr>> { Ok(Arc::new(self.sql_to_expr(e)?)) };
+                let when_then = conditions
+                    .iter()
+                    .zip(results.iter())
+                    .map(|(when, then)| Ok((to_expr(when)?, to_expr(then)?)))
+                    .collect::<Result<Vec<_>>>()?;
+                Ok(LogicalExpr::Case(Case::new(
+                    operand.as_deref().map(to_expr).transpose()?,
+                    when_then,
+                    else_result.as_deref().map(to_expr).transpose()?,
+                )))
+            }
+            other => not_impl_err!("unsupported SQL expression: {}", other),
+        }
+    }
+
+    /// Lowers `name(args) OVER (...)`. Aggregates called with `OVER` are
+    /// computed over the window frame of every row.
+    fn sql_to_window_expr(
+        &self,
+        name: &str,
+        args: Vec<LogicalExpr>,
+        over: &WindowType,
+    ) -> Result<LogicalExpr> {
+        let spec = match over {
+            WindowType::WindowSpec(spec) if spec.window_name.is_none() => spec,
+            other => return not_impl_err!("unsupported SQL window: {}", other),
+        };
+        let fun = match WindowFunction::from_name(name) {
+            Some(fun) => fun,
+            None => WindowFunction::Aggregate(match name {
+                "count" => AggregateFunction::Count,
+                "sum" => AggregateFunction::Sum,
+                "min" => AggregateFunction::Min,
+                "max" => AggregateFunction::Max,
+                "avg" => AggregateFunction::Avg,
+                _ => return not_impl_err!("unsupported SQL window function: {}", name),
+            }),
+        };
+        let partition_by = spec
+            .partition_by
+            .iter()
+            .map(|e| self.sql_to_expr(e))
+            .collect::<Result<Vec<_>>>()?;
+        let order_by = self.sql_to_sort_exprs(&spec.order_by)?;
+        let mut window = window_function(fun, args)
+            .partition_by(partition_by)
+            .order_by(order_by);
+        if let Some(frame) = &spec.window_frame {
+            let units = match frame.units {
+                SqlWindowFrameUnits::Rows => WindowFrameUnits::Rows,
+                SqlWindowFrameUnits::Range => WindowFrameUnits::Range,
+                SqlWindowFrameUnits::Groups => {
+                    return not_impl_err!("unsupported SQL: GROUPS window frames");
+                }
+            };
+            // `ROWS 1 PRECEDING` ends at the current row.
+            let end = match &frame.end_bound {
+                Some(bound) => window_frame_bound(bound)?,
+                None => WindowFrameBound::CurrentRow,
+            };
+            let start = window_frame_bound(&frame.start_bound)?;
+            window = window.frame(WindowFrame::try_new(units, start, end)?);
+        }
+        Ok(window.build())
+    }
+
+    fn sql_to_like(
+        &self,
+        negated: bool,
+        expr: &Expr,
+        pattern: &Expr,
+        escape_char: Option<&str>,
+        case_insensitive: bool,
+    ) -> Result<Like> {
+        let escape_char = match escape_char.map(|s| s.chars().collect::<Vec<_>>()) {
+            Some(chars) => match chars.as_slice() {
+                [c] => Some(*c),
+                _ => return plan_err!("the escape character must be a single character"),
+            },
+            None => None,
+        };
+        Ok(Like::new(
+            negated,
+            Arc::new(self.sql_to_expr(expr)?),
+            Arc::new(self.sql_to_expr(pattern)?),
+            escape_char,
+            case_insensitive,
+        ))
+    }
+}
+
+/// Checks that function `name` was called with exactly `N` arguments.
+fn function_args<const N: usize>(name: &str, args: Vec<LogicalExpr>) -> Result<[LogicalExpr; N]> {
+    let len = args.len();
+    args.try_into().map_err(|_| {
+        DBError::Plan(format!(
+            "function {} expects exactly {} argument{}, got {}",
+            name,
+            N,
+            if N == 1 { "" } else { "s" },
+            len
+        ))
+    })
+}
+
+/// Maps a SQL type name to the Arrow type used to store it, e.g. `INT` to
+/// `Int32` and `BIGINT` to `Int64`.
+fn sql_to_data_type(data_type: &SqlDataType) -> Result<DataType> {
+    Ok(match data_type {
+        SqlDataType::Boolean | SqlDataType::Bool => DataType::Boolean,
+        SqlDataType::TinyInt(_) => DataType::Int8,
+        SqlDataType::SmallInt(_) | SqlDataType::Int2(_) => DataType::Int16,
+        SqlDataType::Int(_) | SqlDataType::Integer(_) | SqlDataType::Int4(_) => DataType::Int32,
+        SqlDataType::BigInt(_) | SqlDataType::Int8(_) => DataType::Int64,
+        SqlDataType::UnsignedTinyInt(_) => DataType::UInt8,
+        SqlDataType::UnsignedSmallInt(_) | SqlDataType::UnsignedInt2(_) => DataType::UInt16,
+        SqlDataType::UnsignedInt(_)
+        | SqlDataType::UnsignedInteger(_)
+        | SqlDataType::UnsignedInt4(_) => DataType::UInt32,
+        SqlDataType::UnsignedBigInt(_) | SqlDataType::UnsignedInt8(_) => DataType::UInt64,
+        SqlDataType::Real | SqlDataType::Float4 => DataType::Float32,
+        SqlDataType::Float(Some(precision)) if *precision <= 24 => DataType::Float32,
+        SqlDataType::Float(_)
+        | SqlDataType::Double
+        | SqlDataType::DoublePrecision
+        | SqlDataType::Float8 => DataType::Float64,
+        SqlDataType::Char(_)
+        | SqlDataType::Character(_)
+        | SqlDataType::Varchar(_)
+        | SqlDataType::CharVarying(_)
+        | SqlDataType::CharacterVarying(_)
+        | SqlDataType::Text
+        | SqlDataType::String(_) => DataType::Utf8,
+        SqlDataType::Date => DataType::Date32,
+        SqlDataType::Time(precision, TimezoneInfo::None | TimezoneInfo::WithoutTimeZone) => {
+            match precision {
+                Some(precision) if *precision > 6 => DataType::Time64(TimeUnit::Nanosecond),
+                _ => DataType::Time64(TimeUnit::Microsecond),
+            }
+        }
+        SqlDataType::Timestamp(_, TimezoneInfo::None | TimezoneInfo::WithoutTimeZone)
+        | SqlDataType::Datetime(_) => DataType::Timestamp(TimeUnit::Microsecond, None),
+        // Values with a time zone are stored in UTC.
+        SqlDataType::Timestamp(_, TimezoneInfo::WithTimeZone | TimezoneInfo::Tz) => {
+            DataType::Timestamp(TimeUnit::Microsecond, Some("+00:00".into()))
+        }
+        SqlDataType::Interval => DataType::Interval(IntervalUnit::MonthDayNano),
+        SqlDataType::Decimal(info) | SqlDataType::Numeric(info) | SqlDataType::Dec(info) => {
+            // Without a precision, as many digits as fit with 10 after the point.
+            let (precision, scale) = match info {
+                ExactNumberInfo::None => (DECIMAL128_MAX_PRECISION as u64, 10),
+                ExactNumberInfo::Precision(precision) => (*precision, 0),
+                ExactNumberInfo::PrecisionAndScale(precision, scale) => (*precision, *scale),
+            };
+            if precision == 0 || precision > DECIMAL128_MAX_PRECISION as u64 || scale > precision {
+                return plan_err!(
+                    "decimal precision must be between 1 and {} and at least the scale, got \
+                     {}",
+                    DECIMAL128_MAX_PRECISION,
+                    data_type
+                );
+            }
+            DataType::Decimal128(precision as u8, scale as i8)
+        }
+        other => return not_impl_err!("unsupported SQL type: {}", other),
+    })
+}
+
+/// Parses a literal such as `DATE '2024-01-01'` while planning, so that
+/// malformed values are reported before the query runs.
+fn typed_literal(value: &str, data_type: &DataType) -> Result<LogicalExpr> {
+    let options = CastOptions {
+        safe: false,
+        ..Default::default()
+    };
+    let string = ScalarValue::String(Some(value.to_string())).to_array(1);
+    match cast_with_options(&string, data_type, &options) {
+        Ok(array) => Ok(LogicalExpr::Literal(ScalarValue::try_from_array(
+            &array, 0,
+        )?)),
+        Err(_) => plan_err!("cannot parse '{}' as {}", value, data_type),
+    }
+}
+
+/// `INTERVAL '1 day'` and `INTERVAL '1' DAY` are both read as one day.
+fn interval_literal(interval: &Interval) -> Result<LogicalExpr> {
+    let value = match interval.value.as_ref() {
+        Expr::Value(Value::SingleQuotedString(s) | Value::Number(s, _)) => s,
+        _ => return not_impl_err!("unsupported SQL interval: {}", interval),
+    };
+    if interval.last_field.is_some() || interval.fractional_seconds_precision.is_some() {
+        return not_impl_err!("unsupported SQL interval: {}", interval);
+    }
+    let value = match &interval.leading_field {
+        Some(field) => format!("{} {}", value, field),
+        None => value.clone(),
+    };
+    typed_literal(&value, &DataType::Interval(IntervalUnit::MonthDayNano))
+}
+
+/// Returns the `date_part` field `EXTRACT` reads, e.g. `dow` for
+/// `EXTRACT(DAYOFWEEK FROM ts)`.
+fn date_part_field(field: &DateTimeField) -> String {
+    match field {
+        DateTimeField::DayOfWeek => "dow".to_string(),
+        DateTimeField::DayOfYear => "doy".to_string(),
+        DateTimeField::Week(_) => "week".to_string(),
+        DateTimeField::Milliseconds => "millisecond".to_string(),
+        DateTimeField::Microseconds => "microsecond".to_string(),
+        other => other.to_string().to_lowercase(),
+    }
+}
+
+fn parse_value(value: &Value) -> Result<LogicalExpr> {
+    match value {
+        Value::Number(n, _) => parse_number(n),
+        Value::SingleQuotedString(s) => Ok(lit(s.clone())),
+        Value::Boolean(b) => Ok(lit(*b)),
+        Value::Null => Ok(LogicalExpr::Literal(ScalarValue::Null)),
+        other => not_impl_err!("unsupported SQL value: {}", other),
+    }
+}
+
+/// Integers become `Int64` literals, anything else with a decimal point or
+/// exponent becomes `Float64`.
+fn parse_number(n: &str) -> Result<LogicalExpr> {
+    if let Ok(n) = n.parse::<i64>() {
+        return Ok(lit(n));
+    }
+    match n.parse::<f64>() {
+        Ok(n) => Ok(lit(n)),
+        Err(_) => Err(DBError::Parse(format!("cannot parse number '{}'", n))),
+    }
+}
+
+/// Reads a bound of a window frame, whose offset must be a non-negative
+/// integer.
+fn window_frame_bound(bound: &SqlWindowFrameBound) -> Result<WindowFrameBound> {
+    let offset = |offset: &Option<Box<Expr>>| -> Result<Option<u64>> {
+        match offset.as_deref() {
+            None => Ok(None),
+            Some(Expr::Value(Value::Number(n, _))) => n.parse().map(Some).map_err(|_| {
+                DBError::Plan(format!(
+                    "window frame offset must be a non-negative integer, got {}",
+                    n
+                ))
+            }),
+            Some(other) => plan_err!(
+                "window frame offset must be a non-negative integer, got {}",
+                other
+            ),
+        }
+    };
+    Ok(match bound {
+        SqlWindowFrameBound::CurrentRow => WindowFrameBound::CurrentRow,
+        SqlWindowFrameBound::Preceding(n) => WindowFrameBound::Preceding(offset(n)?),
+        SqlWindowFrameBound::Following(n) => WindowFrameBound::Following(offset(n)?),
+    })
+}
+
+fn parse_limit_value(expr: &Expr) -> Result<usize> {
+    match expr {
+        Expr::Value(Value::Number(n, _)) => n.parse().map_err(|_| {
+            DBError::Plan(format!(
+                "LIMIT/OFFSET must be a non-negative integer, got {}",
+                n
+            ))
+        }),
+        other => plan_err!("LIMIT/OFFSET must be a non-negative integer, got {}", other),
+    }
+}
+
+fn parse_binary_op(op: &BinaryOperator) -> Result<Operator> {
+    Ok(match op {
+        BinaryOperator::Eq => Operator::Eq,
+        BinaryOperator::NotEq => Operator::NotEq,
+        BinaryOperator::Lt => Operator::Lt,
+        BinaryOperator::LtEq => Operator::LtEq,
+        BinaryOperator::Gt => Operator::Gt,
+        BinaryOperator::GtEq => Operator::GtEq,
+        BinaryOperator::Plus => Operator::Plus,
+        BinaryOperator::Minus => Operator::Minus,
+        BinaryOperator::Multiply => Operator::Multiply,
+        BinaryOperator::Divide => Operator::Divide,
+        BinaryOperator::Modulo => Operator::Modulo,
+        BinaryOperator::And => Operator::And,
+        BinaryOperator::Or => Operator::Or,
+        BinaryOperator::StringConcat => Operator::StringConcat,
+        other => return not_impl_err!("unsupported SQL operator: {}", other),
+    })
+}
+
+/// Returns the aggregate expressions contained in `expr`.
+fn find_aggregate_exprs(expr: &LogicalExpr) -> Vec<LogicalExpr> {
+    match expr {
+        LogicalExpr::Aggregate(_) => vec![expr.clone()],
+        _ => expr
+            .children()
+            .into_iter()
+            .flat_map(find_aggregate_exprs)
+            .collect(),
+    }
+}
+
+/// Returns the window function calls contained in `expr`.
+fn find_window_exprs(expr: &LogicalExpr) -> Vec<LogicalExpr> {
+    match expr {
+        LogicalExpr::WindowFunction(_) => vec![expr.clone()],
+        _ => expr
+            .children()
+            .into_iter()
+            .flat_map(find_window_exprs)
+            .collect(),
+    }
+}
+
+/// Projects `select_exprs` from `plan`. The window functions they use, and
+/// those of the ORDER BY `sort_keys`, are computed first and then referenced
+/// as columns.
+fn window_projection(
+    plan: LogicalPlan,
+    select_exprs: Vec<LogicalExpr>,
+    sort_keys: &[LogicalExpr],
+) -> Result<LogicalPlan> {
+    let mut window_exprs: Vec<LogicalExpr> = vec![];
+    for expr in select_exprs.iter().chain(sort_keys) {
+        for window in find_window_exprs(expr) {
+            if !window_exprs
+                .iter()
+                .any(|e| e.to_string() == window.to_string())
+            {
+                window_exprs.push(window);
+            }
+        }
+    }
+    if window_exprs.is_empty() {
+        return Ok(LogicalPlan::Projection(Projection::try_new(
+            Arc::new(plan),
+            select_exprs,
+        )?));
+    }
+
+    let window = LogicalPlan::Window(Window::try_new(Arc::new(plan), window_exprs)?);
+    let to_output_column = |expr: &LogicalExpr| -> Option<LogicalExpr> {
+        match expr {
+            LogicalExpr::WindowFunction(_) => Some(LogicalExpr::Column(Column::new(expr.name()))),
+            _ => None,
+        }
+    };
+    let select_exprs = select_exprs
+        .iter()
+        .map(|e| e.transform(&to_output_column))
+        .collect();
+    Ok(LogicalPlan::Projection(Projection::try_new(
+        Arc::new(window),
+        select_exprs,
+    )?))
+}
+
+/// Returns the name of a table alias. Renaming the columns as well, as in
+/// `AS t(a, b)`, is not supported.
+fn table_alias(alias: Option<&TableAlias>) -> Result<Option<String>> {
+    match alias {
+        Some(alias) if !alias.columns.is_empty() => {
+            not_impl_err!("unsupported SQL: column aliases in {}", alias)
+        }
+        Some(alias) => Ok(Some(alias.name.value.clone())),
+        None => Ok(None),
+    }
+}
+
+/// Replaces every computed sub-expression whose output name is a column of
+/// `schema` with a reference to that column.
+fn resolve_columns(expr: &LogicalExpr, schema: &QualifiedSchema) -> LogicalExpr {
+    expr.transform(&|e| match e {
+        LogicalExpr::Column(_) => None,
+        _ => {
+            let column = Column::new(e.name());
+            schema
+                .has_column(&column)
+                .then_some(LogicalExpr::Column(column))
+        }
+    })
+}
+
+/// Ensures every column referenced by `exprs` exists in `schema` and refers
+/// to a single field.
+fn check_columns(exprs: &[LogicalExpr], schema: &QualifiedSchema) -> Result<()> {
+    for column in exprs.iter().flat_map(|e| e.columns()) {
+        schema.index_of_column(column)?;
+    }
+    Ok(())
+}
+
+/// Ensures the expressions evaluated on top of an aggregate only refer to its
+/// group expressions and aggregates.
+fn check_grouped_columns(exprs: &[LogicalExpr], schema: &QualifiedSchema) -> Result<()> {
+    for column in exprs.iter().flat_map(|e| e.columns()) {
+        match schema.index_of_column(column) {
+            Ok(_) => {}
+            Err(DBError::ColumnNotFound { .. }) => {
+                return plan_err!(
+                    "column '{}' must appear in the GROUP BY clause or be used in an aggregate function",
+                    column
+                );
+            }
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datasource::MemDataSource;
+
+    struct TestProvider;
+
+    impl ContextProvider for TestProvider {
+        fn get_table(&self, table: &TableReference) -> Option<Arc<dyn DataSource>> {
+            (table.table() == "t").then(|| MemDataSource::new_arc() as Arc<dyn DataSource>)
+        }
+    }
+
+    fn plan(sql: &str) -> Result<String> {
+        Ok(SqlPlanner::new(&TestProvider).sql_to_plan(sql)?.to_string())
+    }
+
+    #[test]
+    fn test_select_where() -> Result<()> {
+        assert_eq!(
+            plan("SELECT c1, c3 + 1 FROM t WHERE c2 > 5")?,
+            "Projection: c1, c3 + 1\n".to_string()
+                + "\tFilter: c2 > 5\n"
+                + "\t\tScan: t; projection=None\n"
+        );
+        assert_eq!(
+            plan("SELECT * FROM t")?,
+            "Projection: t.c1, t.c2, t.c3\n\tScan: t; projection=None\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_group_by_having() -> Result<()> {
+        assert_eq!(
+            plan("SELECT c1, SUM(c2) + 1, COUNT(*) FROM t GROUP BY c1 HAVING SUM(c2) > 1")?,
+            "Projection: c1, SUM(c2) + 1, COUNT(1)\n".to_string()
+                + "\tFilter: SUM(c2) > 1\n"
+                + "\t\tAggregate: groupBy=[c1], aggr=[SUM(c2), COUNT(1)]\n"
+                + "\t\t\tScan: t; projection=None\n"
+        );
+        assert_eq!(
+            plan("SELECT count(DISTINCT c1) FROM t")?,
+            "Projection: COUNT(DISTINCT c1)\n".to_string()
+                + "\tAggregate: groupBy=[], aggr=[COUNT(DISTINCT c1)]\n"
+                + "\t\tScan: t; projection=None\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_order_by() -> Result<()> {
+        assert_eq!(
+            plan("SELECT c1, c3 + 1 FROM t ORDER BY c3 + 1 DESC, c1")?,
+            "Sort: c3 + 1 DESC NULLS FIRST, c1 ASC NULLS LAST\n".to_string()
+                + "\tProjection: c1, c3 + 1\n"
+                + "\t\tScan: t; projection=None\n"
+        );
+        assert_eq!(
+            plan("SELECT c1 FROM t ORDER BY c2 NULLS FIRST")?,
+            "Projection: c1\n".to_string()
+                + "\tSort: c2 ASC NULLS FIRST\n"
+                + "\t\tScan: t; projection=None\n"
+        );
+        assert_eq!(
+            plan("SELECT c1 FROM t GROUP BY c1 ORDER BY SUM(c2)")?,
+            "Projection: c1\n".to_string()
+                + "\tSort: SUM(c2) ASC NULLS LAST\n"
+                + "\t\tAggregate: groupBy=[c1], aggr=[SUM(c2)]\n"
+                + "\t\t\tScan: t; projection=None\n"
+        );
+        assert!(matches!(
+            plan("SELECT c1 FROM t ORDER BY c4"),
+            Err(DBError::ColumnNotFound { .. })
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_qualified_columns() -> Result<()> {
+        assert_eq!(
+            plan("SELECT t.c1, u.c2 FROM t JOIN t AS u ON u.c1 = t.c1 AND u.c2 > 1")?,
+            "Projection: t.c1, u.c2\n".to_string()
+                + "\tJoin: type=Inner, on=[t.c1 = u.c1], filter=u.c2 > 1\n"
+                + "\t\tScan: t; projection=None\n"
+                + "\t\tScan: t AS u; projection=None\n"
+        );
+        assert_eq!(
+            plan("SELECT u.* FROM t AS u")?,
+            "Projection: u.c1, u.c2, u.c3\n\tScan: t AS u; projection=None\n"
+        );
+        assert_eq!(
+            plan("SELECT s.c1 FROM (SELECT c1, c2 FROM t) AS s WHERE s.c2 > 1")?,
+            "Projection: s.c1\n".to_string()
+                + "\tFilter: s.c2 > 1\n"
+                + "\t\tSubqueryAlias: s\n"
+                + "\t\t\tProjection: c1, c2\n"
+                + "\t\t\t\tScan: t; projection=None\n"
+        );
+
+        let err = plan("SELECT c1 FROM t JOIN t AS u ON t.c1 = u.c1").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "column reference 'c1' is ambiguous, candidates: t.c1, u.c1"
+        );
+        let err = plan("SELECT t.c1 FROM t AS u").unwrap_err();
+        assert!(
+            matches!(&err, DBError::ColumnNotFound { name, candidates } if name == "t.c1" && candidates == &["u.c1", "u.c2", "u.c3"])
+        );
+        let err = plan("SELECT * FROM t JOIN t AS u ON t.c2 > u.c2").unwrap_err();
+        assert!(matches!(err, DBError::NotImplemented(_)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_cast() -> Result<()> {
+        assert_eq!(
+            plan("SELECT CAST(c1 AS INT), c2::BIGINT, TRY_CAST(c3 AS DOUBLE) FROM t")?,
+            "Projection: CAST(c1 AS Int32), CAST(c2 AS Int64), TRY_CAST(c3 AS Float64)\n"
+                .to_string()
+                + "\tScan: t; projection=None\n"
+        );
+        assert_eq!(
+            plan("SELECT c1 FROM t WHERE CAST(c1 AS FLOAT(53)) > 1.5")?,
+            "Projection: c1\n".to_string()
+                + "\tFilter: CAST(c1 AS Float64) > 1.5\n"
+                + "\t\tScan: t; projection=None\n"
+        );
+        assert!(matches!(
+            plan("SELECT CAST(c1 AS BLOB) FROM t"),
+            Err(DBError::NotImplemented(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_conditional() -> Result<()> {
+        assert_eq!(
+            plan("SELECT CASE c2 WHEN 1 THEN 'one' ELSE c1 END, COALESCE(c2, 0) FROM t")?,
+            "Projection: CASE c2 WHEN 1 THEN 'one' ELSE c1 END, COALESCE(c2, 0)\n".to_string()
+                + "\tScan: t; projection=None\n"
+        );
+        assert_eq!(
+            plan("SELECT NULLIF(c1, c2), IF(c1 > 0, c2, c3) FROM t")?,
+            "Projection: CASE WHEN c1 = c2 THEN NULL ELSE c1 END, \
+             CASE WHEN c1 > 0 THEN c2 ELSE c3 END\n"
+                .to_string()
+                + "\tScan: t; projection=None\n"
+        );
+        assert!(matches!(
+            plan("SELECT NULLIF(c1) FROM t"),
+            Err(DBError::Plan(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_predicates() -> Result<()> {
+        assert_eq!(
+            plan(
+                "SELECT -c2, c1 IS NOT NULL FROM t \
+                 WHERE NOT c2 IN (1, 2) AND c3 NOT BETWEEN 1 AND 5 \
+                 AND (c1 IS DISTINCT FROM 'a') AND (c2 > 1) IS NOT TRUE"
+            )?,
+            "Projection: -c2, c1 IS NOT NULL\n".to_string()
+                + "\tFilter: NOT c2 IN (1, 2) AND c3 NOT BETWEEN 1 AND 5 \
+                   AND c1 IS DISTINCT FROM 'a' AND c2 > 1 IS NOT TRUE\n"
+                + "\t\tScan: t; projection=None\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_string_matching() -> Result<()> {
+        assert_eq!(
+            plan(
+                "SELECT regexp_replace(c1, 'a+', 'b') FROM t \
+                 WHERE c1 NOT LIKE 'a#%' ESCAPE '#' AND c1 ILIKE '%x' AND c1 SIMILAR TO 'a|b'"
+            )?,
+            "Projection: REGEXP_REPLACE(c1, 'a+', 'b')\n".to_string()
+                + "\tFilter: c1 NOT LIKE 'a#%' ESCAPE '#' AND c1 ILIKE '%x' AND c1 SIMILAR TO 'a|b'\n"
+                + "\t\tScan: t; projection=None\n"
+        );
+        assert!(matches!(
+            plan("SELECT c1 FROM t WHERE c1 LIKE 'a' ESCAPE '##'"),
+            Err(DBError::Plan(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_string_functions() -> Result<()> {
+        assert_eq!(
+            plan(
+                "SELECT upper(c1) || '!', SUBSTRING(c1 FROM 2 FOR 3), SUBSTR(c1, 2), \
+                 TRIM(LEADING 'x' FROM c1), POSITION('a' IN c1) FROM t"
+            )?,
+            "Projection: UPPER(c1) || '!', SUBSTR(c1, 2, 3), SUBSTR(c1, 2), LTRIM(c1, 'x'), \
+             STRPOS(c1, 'a')\n"
+                .to_string()
+                + "\tScan: t; projection=None\n"
+        );
+        assert!(matches!(
+            plan("SELECT split_part(c1, ',') FROM t"),
+            Err(DBError::Plan(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_math_functions() -> Result<()> {
+        assert_eq!(
+            plan(
+                "SELECT CEIL(c2), FLOOR(c2 / 2), round(c3, -1), greatest(c2, c3, 0), random() \
+                 FROM t"
+            )?,
+            "Projection: CEIL(c2), FLOOR(c2 / 2), ROUND(c3, -1), GREATEST(c2, c3, 0), RANDOM()\n"
+                .to_string()
+                + "\tScan: t; projection=None\n"
+        );
+        assert!(matches!(
+            plan("SELECT power(c2) FROM t"),
+            Err(DBError::Plan(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_temporal() -> Result<()> {
+        assert_eq!(
+            plan(
+                "SELECT EXTRACT(YEAR FROM c1), date_trunc('day', c1), CURRENT_DATE FROM t \
+                 WHERE c1 > TIMESTAMP '2024-01-01 10:00:00' - INTERVAL '1' HOUR"
+            )?,
+            "Projection: DATE_PART('year', c1), DATE_TRUNC('day', c1), CURRENT_DATE()\n"
+                .to_string()
+                + "\tFilter: c1 > 2024-01-01T10:00:00 - 1 hours\n"
+                + "\t\tScan: t; projection=None\n"
+        );
+        assert_eq!(
+            plan("SELECT CAST(c1 AS DATE), c2::TIMESTAMPTZ FROM t")?,
+            "Projection: CAST(c1 AS Date32), CAST(c2 AS Timestamp(Microsecond, Some(\"+00:00\")))\n"
+                .to_string() + "\tScan: t; projection=None\n"
+        );
+        assert!(matches!(
+            plan("SELECT DATE '2024-13-01' FROM t"),
+            Err(DBError::Plan(_))
+        ));
+        assert!(matches!(
+            plan("SELECT INTERVAL '1' YEAR TO MONTH FROM t"),
+            Err(DBError::NotImplemented(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_decimal() -> Result<()> {
+        assert_eq!(
+            plan("SELECT CAST(c1 AS DECIMAL(10, 2)), c2::NUMERIC, c3 * DECIMAL '1.50' FROM t")?,
+            "Projection: CAST(c1 AS Decimal128(10, 2)), CAST(c2 AS Decimal128(38, 10)), c3 * 1.50\n"
+                .to_string() + "\tScan: t; projection=None\n"
+        );
+        assert!(matches!(
+            plan("SELECT CAST(c1 AS DECIMAL(5, 6)) FROM t"),
+            Err(DBError::Plan(_))
+        ));
+        assert!(matches!(
+            plan("SELECT CAST(c1 AS DECIMAL(39)) FROM t"),
+            Err(DBError::Plan(_))
+        ));
+        assert!(matches!(
+            plan("SELECT DECIMAL '1.2.3' FROM t"),
+            Err(DBError::Plan(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_window() -> Result<()> {
+        assert_eq!(
+            plan(
+                "SELECT c1, ROW_NUMBER() OVER (PARTITION BY c1 ORDER BY c2 DESC) AS rn, \
+                 SUM(c3) OVER (ORDER BY c2 ROWS 1 PRECEDING) FROM t"
+            )?,
+            "Projection: c1, ROW_NUMBER() OVER (PARTITION BY c1 ORDER BY c2 DESC NULLS FIRST) AS rn, \
+             SUM(c3) OVER (ORDER BY c2 ASC NULLS LAST ROWS BETWEEN 1 PRECEDING AND CURRENT ROW)\n"
+                .to_string()
+                + "\tWindow: [ROW_NUMBER() OVER (PARTITION BY c1 ORDER BY c2 DESC NULLS FIRST), \
+                   SUM(c3) OVER (ORDER BY c2 ASC NULLS LAST ROWS BETWEEN 1 PRECEDING AND CURRENT ROW)]\n"
+                + "\t\tScan: t; projection=None\n"
+        );
+        // Window functions run after grouping and may order by aggregates.
+        assert_eq!(
+            plan("SELECT c1, RANK() OVER (ORDER BY SUM(c2)) FROM t GROUP BY c1")?,
+            "Projection: c1, RANK() OVER (ORDER BY SUM(c2) ASC NULLS LAST)\n".to_string()
+                + "\tWindow: [RANK() OVER (ORDER BY SUM(c2) ASC NULLS LAST)]\n"
+                + "\t\tAggregate: groupBy=[c1], aggr=[SUM(c2)]\n"
+                + "\t\t\tScan: t; projection=None\n"
+        );
+        assert!(matches!(
+            plan("SELECT c1 FROM t WHERE ROW_NUMBER() OVER () > 1"),
+            Err(DBError::Plan(_))
+        ));
+        assert!(matches!(
+            plan("SELECT SUM(c2) OVER (ROWS BETWEEN CURRENT ROW AND 1 PRECEDING) FROM t"),
+            Err(DBError::Plan(_))
+        ));
+        assert!(matches!(
+            plan("SELECT SUM(c2) OVER (ORDER BY c1 RANGE 1 PRECEDING) FROM t"),
+            Err(DBError::Plan(_))
+        ));
+        assert!(matches!(
+            plan("SELECT NTILE(0) OVER (ORDER BY c2) FROM t"),
+            Err(DBError::Plan(_))
+        ));
+        assert!(matches!(
+            plan("SELECT COUNT(DISTINCT c2) OVER () FROM t"),
+            Err(DBError::NotImplemented(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_limit() -> Result<()> {
+        assert_eq!(
+            plan("SELECT c1 FROM t ORDER BY c2 LIMIT 10 OFFSET 5")?,
+            "Limit: skip=5, fetch=10\n".to_string()
+                + "\tProjection: c1\n"
+                + "\t\tSort: c2 ASC NULLS LAST\n"
+                + "\t\t\tScan: t; projection=None\n"
+        );
+        assert_eq!(
+            plan("SELECT c1 FROM t OFFSET 2")?,
+            "Limit: skip=2, fetch=None\n".to_string()
+                + "\tProjection: c1\n"
+                + "\t\tScan: t; projection=None\n"
+        );
+        assert!(matches!(
+            plan("SELECT c1 FROM t LIMIT -1"),
+            Err(DBError::Plan(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_errors() {
+        let err = plan("SELECT c1 FROM missing").unwrap_err();
+        assert_eq!(err.to_string(), "plan error: table 'missing' not found");
+
+        let err = plan("SELECT c4 FROM t").unwrap_err();
+        assert!(
+            matches!(&err, DBError::ColumnNotFound { name, candidates } if name == "c4" && candidates == &["t.c1", "t.c2", "t.c3"])
+        );
+
+        let err = plan("SELECT c2 FROM t GROUP BY c1").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "plan error: column 'c2' must appear in the GROUP BY clause or be used in an aggregate function"
+        );
+
+        let err = plan("SELECT c1 FROM t UNION SELECT c1 FROM t").unwrap_err();
+        assert!(matches!(err, DBError::NotImplemented(_)));
+        assert!(
+            err.to_string()
+                .starts_with("not implemented: unsupported SQL query")
+        );
+
+        let err = plan("INSERT INTO t VALUES (1)").unwrap_err();
+        assert!(
+            err.to_string()
+                .starts_with("not implemented: unsupported SQL statement")
+        );
+
+        assert!(matches!(plan("SELEC c1 FROM t"), Err(DBError::Parse(_))));
+    }
+}